@@ -0,0 +1,118 @@
+use std::hint::black_box;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+use conways_game_of_life::{Board, Boundary, FixedBoard, Pattern};
+
+/// Sides of the square boards stepped, from one that fits in the cache to one that doesn't
+const SIZES: [usize; 3] = [64, 256, 1024];
+/// Shares of alive cells boards start with, from a sparse board to a crowded one
+const DENSITIES: [f64; 3] = [0.05, 0.25, 0.5];
+
+/// Fills a board with cells alive at random, always the same ones for the same size and density, so every run
+/// measures the same boards
+/// Input: the side of the board, and the share of alive cells
+/// Output: the board, with toroidal edges so cells don't all die off along them
+fn random_board(size: usize, density: f64) -> Board {
+    let mut board = Board::random(size, size, density, 1);
+    board.boundary = Boundary::Toroidal;
+    board
+}
+
+fn stepping(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_board");
+    for size in SIZES {
+        group.throughput(Throughput::Elements((size * size) as u64));
+        for density in DENSITIES {
+            let board = random_board(size, density);
+            //Each step starts from the same board, so the density measured stays the one it's labelled with
+            group.bench_with_input(
+                BenchmarkId::new(format!("{size}x{size}"), density),
+                &board,
+                |b, board| {
+                    b.iter_batched_ref(
+                        || board.clone(),
+                        |board| board.update_board(),
+                        BatchSize::LargeInput,
+                    )
+                },
+            );
+        }
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("step_into");
+    for size in SIZES {
+        group.throughput(Throughput::Elements((size * size) as u64));
+        let board = random_board(size, 0.25);
+        let mut next = Board::new(1, 1);
+        group.bench_function(format!("{size}x{size}"), |b| {
+            b.iter(|| black_box(board.step_into(&mut next)))
+        });
+    }
+    group.finish();
+
+    //Ten generations at once, to compare against ten steps of update_board
+    let mut group = c.benchmark_group("run_generations");
+    for size in SIZES {
+        group.throughput(Throughput::Elements((10 * size * size) as u64));
+        let board = random_board(size, 0.25);
+        group.bench_with_input(format!("{size}x{size}"), &board, |b, board| {
+            b.iter_batched_ref(
+                || board.clone(),
+                |board| board.run_generations(10),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+
+    //The same small board stepped as a board and as a fixed board, to compare them
+    let mut group = c.benchmark_group("fixed_board");
+    group.throughput(Throughput::Elements(16 * 16));
+    let board = random_board(16, 0.25);
+    let fixed_board = FixedBoard::<16, 16>::try_from(&board).unwrap();
+    group.bench_function(BenchmarkId::new("board", "16x16"), |b| {
+        b.iter_batched_ref(
+            || board.clone(),
+            |board| board.update_board(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function(BenchmarkId::new("fixed_board", "16x16"), |b| {
+        b.iter_batched_ref(
+            || fixed_board,
+            |fixed_board| fixed_board.update_board(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for size in SIZES {
+        let board = random_board(size, 0.25);
+        let text = board.to_string();
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(BenchmarkId::new("board", size), &text, |b, text| {
+            b.iter(|| black_box(text.parse::<Board>().unwrap()))
+        });
+        let plaintext = Pattern::from_board(&board).to_plaintext();
+        group.throughput(Throughput::Bytes(plaintext.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("plaintext", size),
+            &plaintext,
+            |b, text| b.iter(|| black_box(Pattern::parse_plaintext(text).unwrap())),
+        );
+        let rle = Pattern::from_board(&board).to_rle();
+        group.throughput(Throughput::Bytes(rle.len() as u64));
+        group.bench_with_input(BenchmarkId::new("rle", size), &rle, |b, text| {
+            b.iter(|| black_box(Pattern::parse_rle(text).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, stepping, parsing);
+criterion_main!(benches);
@@ -0,0 +1,364 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use macroquad::prelude::*;
+
+use crate::census::Census;
+use crate::locale::{tr, tr_with};
+use crate::simulation::Simulation;
+use crate::theme::Theme;
+use crate::tutorial::draw_banner;
+
+/// Shortest period of the boards that unlock Achievement::SlowOscillator
+const SLOW_PERIOD: usize = 5;
+
+/// Cells alive at once that unlock Achievement::Crowd
+const CROWD_POPULATION: usize = 1000;
+
+/// Seconds between looks at the board in view, as finding gliders and cycles goes through the whole board. Boards
+/// that cycle keep doing so, so they're still found
+const LOOK_INTERVAL: f64 = 1.;
+
+/// Seconds between saves of the generations simulated, which keep changing while boards run
+const SAVE_INTERVAL: f64 = 60.;
+
+/// A milestone reached while playing, kept across sessions once unlocked
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Achievement {
+    /// A glider was on the board
+    FirstGlider,
+    /// The board came back to the same generation after SLOW_PERIOD generations or more
+    SlowOscillator,
+    /// CROWD_POPULATION cells were alive at once
+    Crowd,
+    /// 10,000 generations were simulated, over every session
+    TenThousandGenerations,
+    /// A million generations were simulated, over every session
+    MillionGenerations,
+}
+
+/// Every achievement, in the order they're listed in
+pub const ACHIEVEMENTS: [Achievement; 5] = [
+    Achievement::FirstGlider,
+    Achievement::SlowOscillator,
+    Achievement::Crowd,
+    Achievement::TenThousandGenerations,
+    Achievement::MillionGenerations,
+];
+
+impl Achievement {
+    /// Returns the name the achievement is written as in the achievements file
+    fn id(self) -> &'static str {
+        match self {
+            Achievement::FirstGlider => "first_glider",
+            Achievement::SlowOscillator => "slow_oscillator",
+            Achievement::Crowd => "crowd",
+            Achievement::TenThousandGenerations => "ten_thousand_generations",
+            Achievement::MillionGenerations => "million_generations",
+        }
+    }
+
+    /// Returns the key of the name of the achievement
+    fn name(self) -> &'static str {
+        match self {
+            Achievement::FirstGlider => "achievement.first_glider",
+            Achievement::SlowOscillator => "achievement.slow_oscillator",
+            Achievement::Crowd => "achievement.crowd",
+            Achievement::TenThousandGenerations => "achievement.ten_thousand_generations",
+            Achievement::MillionGenerations => "achievement.million_generations",
+        }
+    }
+
+    /// Returns the key of what has to be done to unlock the achievement
+    fn description(self) -> &'static str {
+        match self {
+            Achievement::FirstGlider => "achievement.first_glider.description",
+            Achievement::SlowOscillator => "achievement.slow_oscillator.description",
+            Achievement::Crowd => "achievement.crowd.description",
+            Achievement::TenThousandGenerations => {
+                "achievement.ten_thousand_generations.description"
+            }
+            Achievement::MillionGenerations => "achievement.million_generations.description",
+        }
+    }
+
+    /// Returns how many generations have to be simulated to unlock the achievement, if that's what unlocks it
+    fn generations_needed(self) -> Option<u64> {
+        match self {
+            Achievement::TenThousandGenerations => Some(10_000),
+            Achievement::MillionGenerations => Some(1_000_000),
+            _ => None,
+        }
+    }
+}
+
+/// Achievements unlocked and generations simulated over every session, along with what's needed to notice new ones
+/// on the boards being played
+#[derive(Default)]
+pub struct Achievements {
+    /// Achievements unlocked, in the order they were
+    unlocked: Vec<Achievement>,
+    /// Generations simulated on every board, over every session
+    generations: u64,
+    /// Generation each board was on when last looked at, to count the ones simulated since
+    last_generations: Vec<usize>,
+    census: Census,
+    /// When the board in view was last looked at
+    last_look: f64,
+    /// Whether something changed since the last save, and whether achievements were unlocked since then
+    is_changed: bool,
+    is_unlocked_since_save: bool,
+    /// When the achievements were last saved
+    last_save: f64,
+}
+
+impl Achievements {
+    /// Reads the achievements file, with nothing unlocked if it doesn't exist yet or can't be read. Lines that can't
+    /// be read are skipped
+    /// Input: the path of the file
+    pub fn load(path: &Path) -> Self {
+        let mut achievements = Achievements::default();
+        for line in fs::read_to_string(path).unwrap_or_default().lines() {
+            match line.trim().split_once(' ') {
+                Some(("generations", generations)) => {
+                    achievements.generations = generations.parse().unwrap_or(0);
+                }
+                Some(("unlocked", id)) => {
+                    if let Some(achievement) = ACHIEVEMENTS.iter().find(|known| known.id() == id)
+                        && !achievements.is_unlocked(*achievement)
+                    {
+                        achievements.unlocked.push(*achievement);
+                    }
+                }
+                _ => {}
+            }
+        }
+        achievements
+    }
+
+    /// Writes the achievements file, creating its directory if needed
+    /// Input: the path of the file
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let mut text = format!("generations {}\n", self.generations);
+        for achievement in &self.unlocked {
+            text += &format!("unlocked {}\n", achievement.id());
+        }
+        fs::write(path, text)
+    }
+
+    /// Saves the achievements if they changed: right away when achievements were unlocked, and otherwise every
+    /// SAVE_INTERVAL seconds, as generations are simulated all the time
+    /// Input: the path of the file, and the time, in seconds
+    pub fn save_if_due(&mut self, path: &Path, time: f64) -> io::Result<()> {
+        if !self.is_changed
+            || (!self.is_unlocked_since_save && time - self.last_save < SAVE_INTERVAL)
+        {
+            return Ok(());
+        }
+        self.is_changed = false;
+        self.is_unlocked_since_save = false;
+        self.last_save = time;
+        self.save(path)
+    }
+
+    /// Whether an achievement was unlocked
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+
+    /// Looks at the boards for new achievements, counting the generations simulated on every one of them
+    /// Input: every open board, the index of the one in view, which is the only one looked at for patterns, and the
+    /// time, in seconds
+    /// Output: the achievements unlocked
+    pub fn observe(
+        &mut self,
+        simulations: &[Simulation],
+        current_simulation: usize,
+        time: f64,
+    ) -> Vec<Achievement> {
+        //When boards are opened or closed, which board is which isn't known, so counting starts over
+        if self.last_generations.len() == simulations.len() {
+            for (simulation, last_generation) in simulations.iter().zip(&self.last_generations) {
+                let simulated = simulation.generation.saturating_sub(*last_generation);
+                if simulated > 0 {
+                    self.generations += simulated as u64;
+                    self.is_changed = true;
+                }
+            }
+        }
+        self.last_generations = simulations
+            .iter()
+            .map(|simulation| simulation.generation)
+            .collect();
+
+        let simulation = &simulations[current_simulation];
+        let is_looking = time - self.last_look >= LOOK_INTERVAL;
+        if is_looking {
+            self.last_look = time;
+        }
+        let mut reached = Vec::new();
+        for achievement in ACHIEVEMENTS {
+            if self.is_unlocked(achievement) {
+                continue;
+            }
+            let is_reached = match achievement {
+                Achievement::FirstGlider => {
+                    is_looking && {
+                        self.census.update(&simulation.board);
+                        self.census.count("object.glider") > 0
+                    }
+                }
+                Achievement::SlowOscillator => {
+                    is_looking
+                        && simulation
+                            .period()
+                            .is_some_and(|period| period >= SLOW_PERIOD)
+                }
+                Achievement::Crowd => {
+                    is_looking && simulation.board.population() >= CROWD_POPULATION
+                }
+                _ => achievement
+                    .generations_needed()
+                    .is_some_and(|needed| self.generations >= needed),
+            };
+            if is_reached {
+                reached.push(achievement);
+            }
+        }
+        if !reached.is_empty() {
+            self.unlocked.extend(&reached);
+            self.is_changed = true;
+            self.is_unlocked_since_save = true;
+        }
+        reached
+    }
+}
+
+/// Returns the message shown when an achievement is unlocked
+pub fn unlocked_message(achievement: Achievement) -> String {
+    tr_with("achievement.unlocked", &[&tr(achievement.name())])
+}
+
+/// Shows every achievement, the unlocked ones first, until Escape goes back to the starting menu
+/// Input: the achievements, and the theme to draw with
+/// Output: whether closing the window was asked for
+pub async fn show_achievements(achievements: &Achievements, theme: &Theme) -> bool {
+    loop {
+        if is_quit_requested() {
+            return true;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            return false;
+        }
+        clear_background(theme.background);
+        draw_banner(
+            &tr_with(
+                "achievement.title",
+                &[&achievements.unlocked.len(), &ACHIEVEMENTS.len()],
+            ),
+            theme,
+        );
+        let locked = ACHIEVEMENTS
+            .into_iter()
+            .filter(|achievement| !achievements.is_unlocked(*achievement));
+        for (index, achievement) in achievements
+            .unlocked
+            .iter()
+            .copied()
+            .chain(locked)
+            .enumerate()
+        {
+            let y = 130. + index as f32 * 60.;
+            let is_unlocked = achievements.is_unlocked(achievement);
+            let color = if is_unlocked { theme.text } else { theme.label };
+            let name = if is_unlocked {
+                tr(achievement.name()).to_string()
+            } else {
+                tr_with("achievement.locked", &[&tr(achievement.name())])
+            };
+            draw_text(&name, 40., y, 28., color);
+            let mut description = tr(achievement.description()).to_string();
+            if let Some(needed) = achievement.generations_needed()
+                && !is_unlocked
+            {
+                description += &tr_with(
+                    "achievement.progress",
+                    &[&achievements.generations, &needed],
+                );
+            }
+            draw_text(&description, 60., y + 24., 20., color);
+        }
+        draw_text(
+            tr("achievement.hint"),
+            20.,
+            screen_height() - 10.,
+            18.,
+            theme.text,
+        );
+        next_frame().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Boundary, Pattern};
+
+    #[test]
+    fn achievements_are_unlocked_once_and_kept() {
+        let mut simulations = vec![Simulation::new(40, 40, 0), Simulation::new(10, 10, 0)];
+        let mut achievements = Achievements::default();
+        assert!(achievements.observe(&simulations, 0, 0.).is_empty());
+
+        let glider = Pattern::from_library("Glider").unwrap();
+        simulations[0].board.paste(&glider, (1, 1));
+        for _ in 0..6 {
+            simulations[0].step();
+        }
+        simulations[1].step();
+        assert_eq!(
+            vec![Achievement::FirstGlider],
+            achievements.observe(&simulations, 0, LOOK_INTERVAL)
+        );
+        assert_eq!(7, achievements.generations);
+        assert!(achievements.observe(&simulations, 0, 10.).is_empty());
+
+        //Opening a board doesn't count the generations the others were on as simulated again
+        simulations.push(Simulation::new(10, 10, 0));
+        achievements.observe(&simulations, 2, 11.);
+        simulations[2].step();
+        achievements.observe(&simulations, 2, 12.);
+        assert_eq!(8, achievements.generations);
+
+        achievements.generations = 9_999;
+        simulations[2].step();
+        assert_eq!(
+            vec![Achievement::TenThousandGenerations],
+            achievements.observe(&simulations, 2, 13.)
+        );
+
+        //A glider on a small board with wrapping edges comes back to where it started every 24 generations
+        let mut simulation = Simulation::new(6, 6, 0);
+        simulation.board.boundary = Boundary::Toroidal;
+        simulation.board.paste(&glider, (1, 1));
+        for _ in 0..24 {
+            simulation.step();
+        }
+        assert_eq!(Some(24), simulation.period());
+        assert_eq!(
+            vec![Achievement::SlowOscillator],
+            achievements.observe(&[simulation], 0, 14.)
+        );
+
+        let path = std::env::temp_dir().join(format!("achievements_{}", std::process::id()));
+        achievements.save(&path).unwrap();
+        let loaded = Achievements::load(&path);
+        assert_eq!(achievements.unlocked, loaded.unlocked);
+        assert_eq!(10_000, loaded.generations);
+        fs::remove_file(path).unwrap();
+    }
+}
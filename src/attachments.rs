@@ -0,0 +1,222 @@
+use crate::chat::{ChatControl, TWITCH_CHAT};
+use crate::config::Config;
+#[cfg(feature = "http-api")]
+use crate::control::ControlServer;
+use crate::locale::tr_with;
+use crate::music_output::MusicOutput;
+use crate::network::Session;
+use crate::options::Options;
+#[cfg(feature = "discord")]
+use crate::presence::{Activity, DiscordPresence};
+#[cfg(feature = "discord")]
+use crate::rules::rule_name;
+use crate::simulation::Simulation;
+use crate::streaming::Broadcast;
+use crate::toast::Toasts;
+
+/// What boards are shared with, played on, or driven by outside the window, as asked on the command line. The first
+/// board of each game is shared over the network, read from chat, streamed and driven over HTTP, while the board in
+/// view is played on synthesizers and shown on Discord
+pub struct Attachments {
+    session: Option<Session>,
+    broadcast: Option<Broadcast>,
+    chat: Option<ChatControl>,
+    #[cfg(feature = "http-api")]
+    control_server: Option<ControlServer>,
+    music_output: Option<MusicOutput>,
+    #[cfg(feature = "discord")]
+    presence: Option<DiscordPresence>,
+}
+
+impl Attachments {
+    /// Opens what was asked for on the command line, stopping the game if any of it can't be, as playing without
+    /// it isn't what was asked for
+    /// Input: the options of the command line, the config with the notes synthesizers play, and the toasts telling
+    /// what was opened
+    /// Output: what was opened
+    pub fn open(options: &Options, config: &Config, toasts: &mut Toasts) -> Self {
+        //The first board can be shared over the network, hosted here, joined or watched
+        let session = match (options.host_port, &options.join_address) {
+            (Some(port), _) => match Session::host(options.listen_address(), port) {
+                Ok(session) => {
+                    toasts.push(tr_with("network.hosting", &[&port]));
+                    Some(session)
+                }
+                Err(error) => {
+                    eprintln!("Couldn't share the board on port {port}: {error}");
+                    std::process::exit(1);
+                }
+            },
+            (None, Some(address)) => match Session::join(address) {
+                Ok(session) => Some(session),
+                Err(error) => {
+                    eprintln!("Couldn't join the board at {address}: {error}");
+                    std::process::exit(1);
+                }
+            },
+            (None, None) => match &options.spectate_address {
+                Some(address) => match Session::spectate(address) {
+                    Ok(session) => Some(session),
+                    Err(error) => {
+                        eprintln!("Couldn't watch the board at {address}: {error}");
+                        std::process::exit(1);
+                    }
+                },
+                None => None,
+            },
+        };
+        //It can also be streamed for people to watch, and driven by other programs
+        let broadcast = options.stream_port.map(|port| {
+            match Broadcast::start(options.listen_address(), port) {
+                Ok(broadcast) => {
+                    toasts.push(tr_with("streaming.started", &[&port]));
+                    broadcast
+                }
+                Err(error) => {
+                    eprintln!("Couldn't stream the board on port {port}: {error}");
+                    std::process::exit(1);
+                }
+            }
+        });
+        //Its births and deaths can be played on synthesizers too
+        let music_output = (options.midi.is_some() || options.osc_address.is_some()).then(|| {
+            match MusicOutput::open(
+                config.music_mapping(),
+                options.midi.as_deref(),
+                options.osc_address.as_deref(),
+            ) {
+                Ok(music_output) => music_output,
+                Err(error) => {
+                    eprintln!("Couldn't open the music output: {error}");
+                    std::process::exit(1);
+                }
+            }
+        });
+        let chat = options.chat_channel.as_ref().map(|channel| {
+            let server = options.chat_server.as_deref().unwrap_or(TWITCH_CHAT);
+            match ChatControl::join(server, channel) {
+                Ok(chat) => {
+                    toasts.push(tr_with("chat.joined", &[&channel]));
+                    chat
+                }
+                Err(error) => {
+                    eprintln!("Couldn't read the chat of {channel} on {server}: {error}");
+                    std::process::exit(1);
+                }
+            }
+        });
+        #[cfg(feature = "http-api")]
+        let control_server = options.http_port.map(|port| {
+            match ControlServer::start(options.listen_address(), port) {
+                Ok(server) => {
+                    toasts.push(tr_with("control.started", &[&port]));
+                    server
+                }
+                Err(error) => {
+                    eprintln!("Couldn't answer HTTP requests on port {port}: {error}");
+                    std::process::exit(1);
+                }
+            }
+        });
+        Attachments {
+            session,
+            broadcast,
+            chat,
+            #[cfg(feature = "http-api")]
+            control_server,
+            music_output,
+            #[cfg(feature = "discord")]
+            presence: options
+                .discord_application_id
+                .clone()
+                .map(DiscordPresence::start),
+        }
+    }
+
+    /// Tells whether anything follows the first board, which must then stay open, as new boards are only ever opened
+    /// after it. Event logs, followed files and watched patterns belong to their board, and close along with it
+    pub fn is_attached(&self) -> bool {
+        let is_attached = self.session.is_some() || self.chat.is_some() || self.broadcast.is_some();
+        #[cfg(feature = "http-api")]
+        let is_attached = is_attached || self.control_server.is_some();
+        is_attached
+    }
+
+    /// Shows on Discord that the starting menu is open
+    /// Input: the current time
+    #[cfg(feature = "discord")]
+    pub fn show_menu(&mut self, current_time: f64) {
+        if let Some(presence) = &mut self.presence {
+            presence.show(current_time, Activity::menu);
+        }
+    }
+
+    /// Sends the boards to whatever they're shared with or played on, and applies what came back, dropping what was
+    /// lost along the way
+    /// Input: every open board, the index of the one in view, the toasts telling what happened, and the current time
+    pub fn update(
+        &mut self,
+        simulations: &mut [Simulation],
+        current_simulation: usize,
+        toasts: &mut Toasts,
+        current_time: f64,
+    ) {
+        if let Some(session) = &mut self.session {
+            for notice in session.sync(&mut simulations[0]) {
+                toasts.push(notice);
+            }
+        }
+        //Viewers place cells from chat while the board runs, so no pause is needed
+        if let Some(chat) = &mut self.chat {
+            match chat.update(&mut simulations[0], current_time) {
+                Ok(notices) => {
+                    for notice in notices {
+                        toasts.push(notice);
+                    }
+                }
+                Err(error) => {
+                    toasts.push(tr_with("chat.lost", &[&error]));
+                    self.chat = None;
+                }
+            }
+        }
+        #[cfg(feature = "http-api")]
+        if let Some(control_server) = &self.control_server {
+            control_server.answer(&mut simulations[0]);
+        }
+        if let Some(broadcast) = &mut self.broadcast {
+            for notice in broadcast.send(&simulations[0]) {
+                toasts.push(notice);
+            }
+        }
+        let simulation = &simulations[current_simulation];
+        #[cfg(feature = "discord")]
+        if let Some(presence) = &mut self.presence {
+            presence.show(current_time, || {
+                Activity::board(
+                    rule_name(simulation.rule_index),
+                    simulation.generation,
+                    simulation.board.population(),
+                )
+            });
+        }
+        if let Some(output) = &mut self.music_output
+            && let Err(error) = output.send(&simulation.board, simulation.generation, current_time)
+        {
+            eprintln!("Couldn't send the board as music, the music output is disabled: {error}");
+            self.music_output = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_is_attached_unless_asked_for() {
+        let mut toasts = Toasts::default();
+        let attachments = Attachments::open(&Options::default(), &Config::default(), &mut toasts);
+        assert!(!attachments.is_attached());
+    }
+}
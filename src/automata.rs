@@ -0,0 +1,457 @@
+use macroquad::miniquad::date;
+use macroquad::prelude::*;
+
+use crate::Boundary;
+use crate::button::slider;
+use crate::keys::{Action, KeyBindings};
+use crate::locale::{tr, tr_with};
+use crate::random::{RandomSource, SplitMix64};
+use crate::simulation::SPEEDS;
+use crate::theme::Theme;
+use crate::tutorial::draw_banner;
+
+/// States of the cells of a forest fire
+const EMPTY: u8 = 0;
+const TREE: u8 = 1;
+const BURNING: u8 = 2;
+
+/// States of the cells of an epidemic
+const SUSCEPTIBLE: u8 = 0;
+const INFECTED: u8 = 1;
+const RECOVERED: u8 = 2;
+
+/// Share of the cells of a forest fire that start with a tree
+const INITIAL_TREES: f64 = 0.5;
+
+/// Cells of an epidemic infected at the start
+const INITIAL_INFECTED: usize = 5;
+
+/// Colours of the states of the cells, in the order of their values. The first state of each model is drawn with
+/// the colour of dead cells instead
+const FOREST_COLORS: [Color; 3] = [
+    BLANK,
+    Color::new(0.13, 0.55, 0.13, 1.),
+    Color::new(1., 0.45, 0., 1.),
+];
+const EPIDEMIC_COLORS: [Color; 3] = [
+    BLANK,
+    Color::new(0.85, 0.1, 0.1, 1.),
+    Color::new(0.45, 0.55, 0.75, 1.),
+];
+
+/// Width of the panel with the sliders, on the right of the window, in pixels
+const PANEL_WIDTH: f32 = 260.;
+
+/// Space left above the grid for the banner with the counts of cells, in pixels
+const BANNER_MARGIN: f32 = 100.;
+
+/// A probabilistic automaton whose cells have three states, with the chances that drive it
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Model {
+    /// Trees grow on empty cells and catch fire from burning neighbours or from lightning, and burning cells burn
+    /// down to empty ones
+    ForestFire {
+        /// Chance of a tree growing on an empty cell on each generation
+        growth: f64,
+        /// Chance of a tree being struck by lightning on each generation
+        lightning: f64,
+    },
+    /// Susceptible cells catch the infection from infected neighbours, infected ones recover, and recovered ones lose
+    /// their immunity
+    Epidemic {
+        /// Chance of each infected neighbour passing on the infection on each generation
+        infection: f64,
+        /// Chance of an infected cell recovering on each generation
+        recovery: f64,
+        /// Chance of a recovered cell becoming susceptible again on each generation
+        immunity_loss: f64,
+    },
+}
+
+/// Automata that can be started from the starting menu, with the chances they start with
+pub const AUTOMATA: [Model; 2] = [Model::forest_fire(), Model::epidemic()];
+
+impl Model {
+    /// Returns a forest fire with trees growing slowly and rare lightning
+    pub const fn forest_fire() -> Self {
+        Model::ForestFire {
+            growth: 0.01,
+            lightning: 0.0001,
+        }
+    }
+
+    /// Returns an epidemic that spreads quickly, with an immunity that lasts a while
+    pub const fn epidemic() -> Self {
+        Model::Epidemic {
+            infection: 0.3,
+            recovery: 0.1,
+            immunity_loss: 0.01,
+        }
+    }
+
+    /// Returns the key of the name of the model
+    pub fn name(self) -> &'static str {
+        match self {
+            Model::ForestFire { .. } => "automata.forest_fire",
+            Model::Epidemic { .. } => "automata.epidemic",
+        }
+    }
+
+    /// Returns the state infected or burning cells are in, which spreads to their neighbours
+    fn spreading(self) -> u8 {
+        match self {
+            Model::ForestFire { .. } => BURNING,
+            Model::Epidemic { .. } => INFECTED,
+        }
+    }
+
+    /// Returns the chances that can be changed with sliders, with the key of their name and their highest value
+    fn parameters(&mut self) -> Vec<(&'static str, &mut f64, f64)> {
+        match self {
+            Model::ForestFire { growth, lightning } => vec![
+                ("automata.growth", growth, 0.1),
+                ("automata.lightning", lightning, 0.001),
+            ],
+            Model::Epidemic {
+                infection,
+                recovery,
+                immunity_loss,
+            } => vec![
+                ("automata.infection", infection, 1.),
+                ("automata.recovery", recovery, 1.),
+                ("automata.immunity_loss", immunity_loss, 0.1),
+            ],
+        }
+    }
+
+    /// Works out the next state of a cell
+    /// Input: the state of the cell, how many of its neighbours are burning or infected, and a random number between
+    /// 0 and 1
+    fn next_state(self, state: u8, spreading_neighbours: usize, chance: f64) -> u8 {
+        match (self, state) {
+            (Model::ForestFire { growth, .. }, EMPTY) if chance < growth => TREE,
+            (Model::ForestFire { lightning, .. }, TREE)
+                if spreading_neighbours > 0 || chance < lightning =>
+            {
+                BURNING
+            }
+            (Model::ForestFire { .. }, BURNING) => EMPTY,
+            (Model::Epidemic { infection, .. }, SUSCEPTIBLE) => {
+                //Each infected neighbour is a separate chance of catching the infection
+                let escape = (1. - infection).powi(spreading_neighbours as i32);
+                if chance < 1. - escape {
+                    INFECTED
+                } else {
+                    SUSCEPTIBLE
+                }
+            }
+            (Model::Epidemic { recovery, .. }, INFECTED) if chance < recovery => RECOVERED,
+            (Model::Epidemic { immunity_loss, .. }, RECOVERED) if chance < immunity_loss => {
+                SUSCEPTIBLE
+            }
+            _ => state,
+        }
+    }
+}
+
+/// A grid of cells following a model, stepped with random numbers from a seed
+pub struct Automaton {
+    pub model: Model,
+    width: usize,
+    length: usize,
+    boundary: Boundary,
+    /// State of each cell, row by row
+    cells: Vec<u8>,
+    random: SplitMix64,
+    pub generation: usize,
+}
+
+impl Automaton {
+    /// Starts an automaton: forests with trees on half of the cells, and epidemics with INITIAL_INFECTED cells
+    /// infected
+    /// Input: the model, the width and length of the grid, what lies past its edges, and the seed of the random
+    /// numbers
+    pub fn new(model: Model, width: usize, length: usize, boundary: Boundary, seed: u64) -> Self {
+        let mut random = SplitMix64::new(seed);
+        let mut cells = vec![0; width * length];
+        match model {
+            Model::ForestFire { .. } => {
+                for cell in &mut cells {
+                    if random.next_f64() < INITIAL_TREES {
+                        *cell = TREE;
+                    }
+                }
+            }
+            Model::Epidemic { .. } => {
+                for _ in 0..INITIAL_INFECTED {
+                    let index = (random.next_u64() % cells.len() as u64) as usize;
+                    cells[index] = INFECTED;
+                }
+            }
+        }
+        Automaton {
+            model,
+            width,
+            length,
+            boundary,
+            cells,
+            random,
+            generation: 0,
+        }
+    }
+
+    /// Returns the state of a cell
+    fn state(&self, x: usize, y: usize) -> u8 {
+        self.cells[y * self.width + x]
+    }
+
+    /// Sets a cell on fire, or infects it
+    pub fn ignite(&mut self, x: usize, y: usize) {
+        self.cells[y * self.width + x] = self.model.spreading();
+    }
+
+    /// Counts the cells in a state
+    pub fn count(&self, state: u8) -> usize {
+        self.cells.iter().filter(|cell| **cell == state).count()
+    }
+
+    /// Counts the neighbours of a cell that are burning or infected, out of the eight around it
+    fn spreading_neighbours(&self, x: usize, y: usize) -> usize {
+        let spreading = self.model.spreading();
+        let mut count = 0;
+        for y_offset in -1..=1_isize {
+            for x_offset in -1..=1_isize {
+                if x_offset == 0 && y_offset == 0 {
+                    continue;
+                }
+                let (neighbour_x, neighbour_y) = (x as isize + x_offset, y as isize + y_offset);
+                let (neighbour_x, neighbour_y) = match self.boundary {
+                    Boundary::Toroidal => (
+                        neighbour_x.rem_euclid(self.width as isize),
+                        neighbour_y.rem_euclid(self.length as isize),
+                    ),
+                    Boundary::Bounded => (neighbour_x, neighbour_y),
+                };
+                if (0..self.width as isize).contains(&neighbour_x)
+                    && (0..self.length as isize).contains(&neighbour_y)
+                    && self.state(neighbour_x as usize, neighbour_y as usize) == spreading
+                {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Advances every cell one generation at once
+    pub fn step(&mut self) {
+        let mut next = Vec::with_capacity(self.cells.len());
+        for y in 0..self.length {
+            for x in 0..self.width {
+                let chance = self.random.next_f64();
+                next.push(self.model.next_state(
+                    self.state(x, y),
+                    self.spreading_neighbours(x, y),
+                    chance,
+                ));
+            }
+        }
+        self.cells = next;
+        self.generation += 1;
+    }
+
+    /// Draws every cell on an image with one pixel per cell
+    /// Input: the image, as big as the grid, and the colour of the first state of the model
+    fn paint(&self, image: &mut Image, first_color: Color) {
+        let colors = match self.model {
+            Model::ForestFire { .. } => FOREST_COLORS,
+            Model::Epidemic { .. } => EPIDEMIC_COLORS,
+        };
+        for (index, cell) in self.cells.iter().enumerate() {
+            let color = match cell {
+                0 => first_color,
+                state => colors[*state as usize],
+            };
+            image.set_pixel(
+                (index % self.width) as u32,
+                (index / self.width) as u32,
+                color,
+            );
+        }
+    }
+}
+
+/// Runs an automaton on the window until Escape goes back to the starting menu, with sliders on the right for its
+/// chances. The keys of the game pause it and change its speed, clicking on a cell sets it on fire or infects it,
+/// and Enter starts it over
+/// Input: the model, the width and length of the grid, what lies past its edges, the theme to draw with, the keys of
+/// the game and the index of the speed the grid starts at, out of SPEEDS
+/// Output: whether closing the window was asked for
+pub async fn run_automaton(
+    model: Model,
+    (width, length): (usize, usize),
+    boundary: Boundary,
+    theme: &Theme,
+    keys: &KeyBindings,
+    mut speed_index: usize,
+) -> bool {
+    let seed = || date::now().to_bits();
+    let mut automaton = Automaton::new(model, width, length, boundary, seed());
+    let mut image = Image::gen_image_color(width as u16, length as u16, theme.dead);
+    let texture = Texture2D::from_image(&image);
+    texture.set_filter(FilterMode::Nearest);
+    let mut is_paused = false;
+    let mut last_update = get_time();
+    loop {
+        if is_quit_requested() {
+            return true;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            return false;
+        }
+        if is_key_pressed(KeyCode::Enter) {
+            automaton = Automaton::new(automaton.model, width, length, boundary, seed());
+        }
+        if keys.is_pressed(Action::Pause) {
+            is_paused = !is_paused;
+        }
+        if keys.is_pressed(Action::Slower) {
+            speed_index = speed_index.saturating_sub(1);
+        }
+        if keys.is_pressed(Action::Faster) {
+            speed_index = (speed_index + 1).min(SPEEDS.len() - 1);
+        }
+        if !is_paused && get_time() - last_update >= SPEEDS[speed_index].1 {
+            last_update = get_time();
+            automaton.step();
+        }
+
+        //The grid is as big as fits between the banner and the panel, centred
+        let area = Rect::new(
+            0.,
+            BANNER_MARGIN,
+            screen_width() - PANEL_WIDTH,
+            screen_height() - BANNER_MARGIN - 30.,
+        );
+        let cell_size = (area.w / width as f32).min(area.h / length as f32);
+        let corner = (
+            (area.w - cell_size * width as f32) / 2.,
+            area.y + (area.h - cell_size * length as f32) / 2.,
+        );
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let (mouse_x, mouse_y) = mouse_position();
+            let (x, y) = (
+                ((mouse_x - corner.0) / cell_size).floor(),
+                ((mouse_y - corner.1) / cell_size).floor(),
+            );
+            if x >= 0. && y >= 0. && (x as usize) < width && (y as usize) < length {
+                automaton.ignite(x as usize, y as usize);
+            }
+        }
+
+        clear_background(theme.background);
+        automaton.paint(&mut image, theme.dead);
+        texture.update(&image);
+        draw_texture_ex(
+            &texture,
+            corner.0,
+            corner.1,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(cell_size * width as f32, cell_size * length as f32)),
+                ..Default::default()
+            },
+        );
+        for (index, (name, value, max)) in automaton.model.parameters().into_iter().enumerate() {
+            let label = tr_with(name, &[&format!("{value:.4}")]);
+            let rect = Rect::new(
+                screen_width() - PANEL_WIDTH + 20.,
+                BANNER_MARGIN + 40. + index as f32 * 60.,
+                PANEL_WIDTH - 40.,
+                20.,
+            );
+            slider(&label, value, (0., max), rect, theme);
+        }
+        let banner = match automaton.model {
+            Model::ForestFire { .. } => tr_with(
+                "automata.forest_fire.counts",
+                &[
+                    &automaton.generation,
+                    &automaton.count(TREE),
+                    &automaton.count(BURNING),
+                ],
+            ),
+            Model::Epidemic { .. } => tr_with(
+                "automata.epidemic.counts",
+                &[
+                    &automaton.generation,
+                    &automaton.count(SUSCEPTIBLE),
+                    &automaton.count(INFECTED),
+                    &automaton.count(RECOVERED),
+                ],
+            ),
+        };
+        draw_banner(&banner, theme);
+        draw_text(
+            tr("automata.hint"),
+            20.,
+            screen_height() - 10.,
+            18.,
+            theme.text,
+        );
+        next_frame().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fires_and_infections_spread_to_neighbours() {
+        //Without growth or lightning, a fire only spreads along the trees and burns them down
+        let still = Model::ForestFire {
+            growth: 0.,
+            lightning: 0.,
+        };
+        let mut forest = Automaton::new(still, 5, 1, Boundary::Bounded, 0);
+        forest.cells = vec![TREE, TREE, EMPTY, TREE, TREE];
+        forest.ignite(0, 0);
+        forest.step();
+        assert_eq!(vec![EMPTY, BURNING, EMPTY, TREE, TREE], forest.cells);
+        forest.step();
+        assert_eq!(vec![EMPTY, EMPTY, EMPTY, TREE, TREE], forest.cells);
+        assert_eq!(2, forest.count(TREE));
+
+        //Certain infections and recoveries move as a wave, and edges that wrap around carry it across
+        let certain = Model::Epidemic {
+            infection: 1.,
+            recovery: 1.,
+            immunity_loss: 0.,
+        };
+        let mut epidemic = Automaton::new(certain, 5, 1, Boundary::Toroidal, 0);
+        epidemic.cells = vec![SUSCEPTIBLE; 5];
+        epidemic.ignite(0, 0);
+        epidemic.step();
+        assert_eq!(
+            vec![RECOVERED, INFECTED, SUSCEPTIBLE, SUSCEPTIBLE, INFECTED],
+            epidemic.cells
+        );
+        epidemic.step();
+        assert_eq!(
+            vec![RECOVERED, RECOVERED, INFECTED, INFECTED, RECOVERED],
+            epidemic.cells
+        );
+
+        let mut model = Model::epidemic();
+        for (_, value, max) in model.parameters() {
+            assert!(*value <= max);
+            *value = 0.;
+        }
+        assert_eq!(SUSCEPTIBLE, model.next_state(SUSCEPTIBLE, 8, 0.));
+        assert_eq!(
+            INITIAL_INFECTED,
+            Automaton::new(Model::epidemic(), 100, 100, Boundary::Bounded, 3).count(INFECTED)
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use macroquad::miniquad::date;
+use macroquad::prelude::*;
+
+use crate::Board;
+use crate::locale::{tr, tr_with};
+use crate::render::{BoardTexture, draw_board_texture};
+use crate::theme::Theme;
+use crate::tutorial::draw_banner;
+
+/// Seconds spent stepping the board before the result is given
+const BENCHMARK_SECONDS: f64 = 10.;
+
+/// Seconds of each frame spent stepping the board, leaving the rest to drawing it so the window keeps responding
+const FRAME_STEPPING: f64 = 0.012;
+
+/// Share of the cells of the board alive at the start, and the seed they're picked with, the same on every run so
+/// runs can be compared
+const BENCHMARK_DENSITY: f64 = 0.35;
+const BENCHMARK_SEED: u64 = 1;
+
+/// Space left above the board for the banner with the result, in pixels
+const BANNER_MARGIN: f32 = 100.;
+
+/// Measures how fast a board steps on this computer, stepping a random soup for BENCHMARK_SECONDS seconds
+pub struct Benchmark {
+    pub board: Board,
+    pub generations: usize,
+    /// Seconds spent stepping the board so far
+    stepping_time: f64,
+}
+
+impl Benchmark {
+    /// Starts a benchmark on a random soup of the size of a board
+    /// Input: the board, with the rule and edges the soup follows
+    pub fn new(board: &Board) -> Self {
+        let mut soup = Board::random(
+            board.width(),
+            board.length(),
+            BENCHMARK_DENSITY,
+            BENCHMARK_SEED,
+        );
+        soup.rule = board.rule;
+        soup.boundary = board.boundary;
+        Benchmark {
+            board: soup,
+            generations: 0,
+            stepping_time: 0.,
+        }
+    }
+
+    /// Steps the board for a while, unless the benchmark is over
+    /// Input: the seconds to step the board for, which a generation started before they run out can go over
+    pub fn run_for(&mut self, seconds: f64) {
+        let start = date::now();
+        while !self.is_over() && date::now() - start < seconds {
+            self.board.update_board();
+            self.generations += 1;
+        }
+        self.stepping_time += date::now() - start;
+    }
+
+    /// Whether the board was stepped for BENCHMARK_SECONDS seconds
+    pub fn is_over(&self) -> bool {
+        self.stepping_time >= BENCHMARK_SECONDS
+    }
+
+    /// Returns how many generations were computed each second so far
+    pub fn generations_per_second(&self) -> f64 {
+        if self.stepping_time > 0. {
+            self.generations as f64 / self.stepping_time
+        } else {
+            0.
+        }
+    }
+}
+
+/// Runs benchmarks on the window until Escape goes back to the starting menu, drawing the board as it steps. Enter
+/// runs the benchmark again
+/// Input: the board whose size, rule and edges are measured, and the theme to draw with
+/// Output: whether closing the window was asked for
+pub async fn run_benchmark(board: Board, theme: &Theme) -> bool {
+    let mut benchmark = Benchmark::new(&board);
+    let mut board_texture = BoardTexture::default();
+    loop {
+        if is_quit_requested() {
+            return true;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            return false;
+        }
+        if benchmark.is_over() && is_key_pressed(KeyCode::Enter) {
+            benchmark = Benchmark::new(&board);
+        }
+        benchmark.run_for(FRAME_STEPPING);
+
+        //The board is as big as fits under the banner, centred
+        let (width, length) = (benchmark.board.width(), benchmark.board.length());
+        let cell_size =
+            (screen_width() / width as f32).min((screen_height() - BANNER_MARGIN) / length as f32);
+        let corner = (
+            (screen_width() - cell_size * width as f32) / 2.,
+            BANNER_MARGIN + (screen_height() - BANNER_MARGIN - cell_size * length as f32) / 2.,
+        );
+        clear_background(theme.background);
+        let texture = board_texture.update(&benchmark.board, theme);
+        draw_board_texture(
+            &benchmark.board,
+            texture,
+            corner,
+            cell_size,
+            Rect::new(
+                corner.0,
+                corner.1,
+                cell_size * width as f32,
+                cell_size * length as f32,
+            ),
+        );
+        let per_second = benchmark.generations_per_second();
+        let cells_per_second = per_second * (width * length) as f64;
+        let key = if benchmark.is_over() {
+            "benchmark.result"
+        } else {
+            "benchmark.running"
+        };
+        draw_banner(
+            &tr_with(
+                key,
+                &[
+                    &benchmark.generations,
+                    &format!("{per_second:.0}"),
+                    &format!("{:.1}", cells_per_second / 1e6),
+                ],
+            ),
+            theme,
+        );
+        draw_text(
+            tr("benchmark.hint"),
+            20.,
+            screen_height() - 10.,
+            18.,
+            theme.text,
+        );
+        next_frame().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn benchmarks_step_the_same_soup_until_their_time_is_up() {
+        let mut board = Board::new(20, 10);
+        board.boundary = crate::Boundary::Toroidal;
+        let benchmark = Benchmark::new(&board);
+        assert_eq!(Benchmark::new(&board).board, benchmark.board);
+        assert_eq!(crate::Boundary::Toroidal, benchmark.board.boundary);
+        assert!(benchmark.board.population() > 0);
+        assert_eq!(0., benchmark.generations_per_second());
+
+        let mut benchmark = Benchmark::new(&Board::new(8, 8));
+        benchmark.run_for(0.01);
+        assert!(benchmark.generations > 0);
+        assert!(!benchmark.is_over());
+        benchmark.stepping_time = BENCHMARK_SECONDS;
+        let generations = benchmark.generations;
+        benchmark.run_for(0.01);
+        assert_eq!(generations, benchmark.generations);
+    }
+}
@@ -0,0 +1,1391 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::GolError;
+use crate::geometry::{Point, Rect};
+use crate::neighborhood::{Moore, Neighborhood};
+use crate::patterns::Pattern;
+use crate::random::{RandomSource, SplitMix64};
+use crate::rules::{LifeLikeRule, NeighborCounts, Rule};
+
+/// Most cells a board or pattern can have, 16384 by 16384. Boards keep two copies of their cells, so this keeps them
+/// well within memory, and patterns read from files can't ask for more than that
+pub const MAX_CELLS: usize = 1 << 28;
+
+/// State of a single cell of a board
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum CellState {
+    Alive,
+    Dead,
+}
+
+/// What lies past the edges of a board
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Boundary {
+    /// Cells past the edges are always dead
+    Bounded,
+    /// The edges wrap around, so cells on one edge are neighbours of the ones on the opposite edge
+    Toroidal,
+}
+
+impl Boundary {
+    /// Finds the cell at some coordinates that can be past the edges of a grid, wrapping them around if it's toroidal
+    /// Input: the coordinates, which can be negative, and the width and length of the grid
+    /// Output: the coordinates of the cell inside the grid, or None if they're past the edges of a bounded grid or the
+    /// grid has no cells to wrap around to
+    #[inline]
+    pub(crate) fn wrap(self, x: isize, y: isize, (width, length): (usize, usize)) -> Option<Point> {
+        match self {
+            Boundary::Bounded => {
+                (x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < length)
+                    .then_some(Point::new(x as usize, y as usize))
+            }
+            Boundary::Toroidal if width == 0 || length == 0 => None,
+            Boundary::Toroidal => Some(Point::new(
+                x.rem_euclid(width as isize) as usize,
+                y.rem_euclid(length as isize) as usize,
+            )),
+        }
+    }
+}
+
+/// Counts the alive and dead neighbours of a cell, among cells laid out row by row. Boards and fixed boards both step
+/// with it, so they count neighbours the same way. Neighbours past the edges of bounded grids are dead
+/// Input: the cells, the width and length of the grid, its edges, the coordinates of the cell, and the offsets of its
+/// neighbours
+/// Output: how many of them are alive, and how many dead
+#[inline]
+pub(crate) fn count_neighbours(
+    cells: &[CellState],
+    (width, length): (usize, usize),
+    boundary: Boundary,
+    (x, y): (usize, usize),
+    offsets: &[(isize, isize)],
+) -> NeighborCounts {
+    let mut alive_neighbours = 0;
+    for (x_offset, y_offset) in offsets {
+        let (x_neighbour, y_neighbour) = (x as isize + x_offset, y as isize + y_offset);
+        //Most neighbours are inside the grid, and only the ones past its edges need wrapping around
+        let neighbour = if x_neighbour >= 0
+            && y_neighbour >= 0
+            && (x_neighbour as usize) < width
+            && (y_neighbour as usize) < length
+        {
+            Some(Point::new(x_neighbour as usize, y_neighbour as usize))
+        } else {
+            boundary.wrap(x_neighbour, y_neighbour, (width, length))
+        };
+        if let Some(neighbour) = neighbour
+            && cells[neighbour.y * width + neighbour.x] == CellState::Alive
+        {
+            alive_neighbours += 1;
+        }
+    }
+    NeighborCounts {
+        alive: alive_neighbours,
+        dead: offsets.len() - alive_neighbours,
+    }
+}
+
+/// How the cells of a board blitted onto another are combined with the cells already there
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlitMode {
+    /// Cells take the state of the ones blitted over them, alive or dead
+    Overwrite,
+    /// Cells come to life where the ones blitted over them are alive, and are left as they were elsewhere
+    Or,
+    /// Cells swap their state where the ones blitted over them are alive, and are left as they were elsewhere
+    Xor,
+}
+
+/// How many cells changed state during a generation
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StepStats {
+    pub births: usize,
+    pub deaths: usize,
+}
+
+/// A cell that changed state during a generation, given by its column and row
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum CellEvent {
+    Birth { x: usize, y: usize },
+    Death { x: usize, y: usize },
+}
+
+/// A grid of cells that are updated all at once, one generation at a time. Cells are indexed by their column and row,
+/// counting from 0 at the top left corner
+///
+/// Boards are `Send` and `Sync`, so they can be read from several threads at once. To keep reading a board while its
+/// next generation is computed, it can be stepped into another board with [`Board::step_into`]. Snapshots to hand to
+/// other threads are taken by cloning it, which copies both of its buffers of cells, so they take time and memory in
+/// proportion to its width times its height, and are best kept for when a copy is needed rather than taken every frame
+///
+/// ```
+/// use conways_game_of_life::{Board, Boundary, CellState};
+///
+/// // A blinker goes from a row of three cells to a column, and back
+/// let mut board = Board::new(5, 5);
+/// board.boundary = Boundary::Toroidal;
+/// for x in 1..4 {
+///     board.toggle_cell_state((x, 2));
+/// }
+/// let stats = board.update_board();
+/// assert_eq!((2, 2), (stats.births, stats.deaths));
+/// assert_eq!(CellState::Alive, board[(2, 1)]);
+/// assert_eq!(CellState::Dead, board[(1, 2)]);
+/// assert_eq!(3, board.population());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Board {
+    pub(crate) board: Vec<CellState>,
+    pub(crate) old_board: Vec<CellState>,
+    pub(crate) width: usize,
+    pub(crate) length: usize,
+    /// Rule the cells follow on every generation
+    pub rule: LifeLikeRule,
+    /// What lies past the edges of the board
+    pub boundary: Boundary,
+    /// Which cells around each cell count as its neighbours
+    pub neighborhood: Arc<dyn Neighborhood>,
+    /// Births and deaths since they were last drained, when they are being recorded
+    events: Option<Vec<CellEvent>>,
+}
+
+impl Board {
+    /// Creates a new board from scratch. All the cells start dead by default.
+    /// Output: A game of life board
+    /// The board follows the standard rules, B3/S23, counting the eight cells around each cell as its neighbours
+    pub fn new(width: usize, length: usize) -> Self {
+        let board = vec![CellState::Dead; width * length];
+        let old_board = board.clone();
+        Board {
+            board,
+            old_board,
+            width,
+            length,
+            rule: LifeLikeRule::conway(),
+            boundary: Boundary::Bounded,
+            neighborhood: Arc::new(Moore),
+            events: None,
+        }
+    }
+
+    /// Creates a new board from scratch like new does, checking its size first
+    /// Output: the board, or an error if it would have no cells, or more than MAX_CELLS
+    pub fn try_new(width: usize, length: usize) -> Result<Self, GolError> {
+        match width.checked_mul(length) {
+            Some(cells) if cells > 0 && cells <= MAX_CELLS => Ok(Board::new(width, length)),
+            _ => Err(GolError::InvalidDimensions { width, length }),
+        }
+    }
+
+    /// Creates a board with cells alive at random, such as to start from a soup. The same seed always gives the same
+    /// board, so random boards can be shared and run again
+    /// Input: the width and length of the board, the share of cells that are alive, from 0 to 1, and the seed
+    /// Output: the board, following the standard rules with bounded edges
+    pub fn random(width: usize, length: usize, density: f64, seed: u64) -> Self {
+        Self::random_with(width, length, density, &mut SplitMix64::new(seed))
+    }
+
+    /// Creates a board with cells alive at random like random does, taking the random numbers from any source
+    /// Input: the width and length of the board, the share of cells that are alive, from 0 to 1, and where the random
+    /// numbers come from, one for each cell, row by row
+    /// Output: the board, following the standard rules with bounded edges
+    pub fn random_with(
+        width: usize,
+        length: usize,
+        density: f64,
+        source: &mut impl RandomSource,
+    ) -> Self {
+        let mut board = Board::new(width, length);
+        for cell in &mut board.board {
+            if source.next_f64() < density {
+                *cell = CellState::Alive;
+            }
+        }
+        board.old_board.clone_from(&board.board);
+        board
+    }
+
+    /// Swaps a specific position in an already existing board.
+    /// Input: a mutable reference to the board, and the cell to update, as a point or a column and row
+    /// NOT the cell udpate function, this one is intended to be used for the user to manually flip the states of cells before the game starts
+    pub fn toggle_cell_state(&mut self, cell: impl Into<Point>) {
+        let cell = cell.into();
+        match self[cell] {
+            CellState::Alive => self[cell] = CellState::Dead,
+            CellState::Dead => self[cell] = CellState::Alive,
+        }
+    }
+
+    /// Updates the states of every cell in the board, following the rule of the board
+    /// Output: how many cells were born and how many died
+    pub fn update_board(&mut self) -> StepStats {
+        let rule = self.rule;
+        self.update_board_with(&rule)
+    }
+
+    /// Updates the states of every cell in the board, following any rule instead of the one of the board
+    /// Input: the rule
+    /// Output: how many cells were born and how many died
+    pub fn update_board_with<R: Rule + ?Sized>(&mut self, rule: &R) -> StepStats {
+        self.old_board.clone_from(&self.board);
+        //The cells are taken out of the board while they're written, as the board is read to count neighbours
+        let mut cells = std::mem::take(&mut self.board);
+        self.step_cells(rule, &self.old_board, &mut cells);
+        self.board = cells;
+        let stats = self.record_changes();
+        #[cfg(feature = "check-invariants")]
+        self.check_invariants(rule, stats);
+        stats
+    }
+
+    /// Advances the board several generations at once, following its rule. As the generations in between aren't kept,
+    /// the cells are stepped back and forth between the board's two generations without copying them, unless events are
+    /// being recorded or invariants checked, which need every generation
+    /// Input: how many generations to advance
+    /// Output: how many cells were born and how many died, over all the generations
+    ///
+    /// ```
+    /// use conways_game_of_life::Board;
+    ///
+    /// // A blinker is back where it started after any even number of generations
+    /// let mut board: Board = ".....\n..#..\n..#..\n..#..\n.....".parse().unwrap();
+    /// let stats = board.run_generations(100);
+    /// assert_eq!((200, 200), (stats.births, stats.deaths));
+    /// assert_eq!(board, ".....\n..#..\n..#..\n..#..\n.....".parse().unwrap());
+    /// ```
+    pub fn run_generations(&mut self, generations: usize) -> StepStats {
+        let rule = self.rule;
+        let mut total = StepStats::default();
+        if self.events.is_some() || cfg!(feature = "check-invariants") {
+            for _ in 0..generations {
+                let stats = self.update_board_with(&rule);
+                total.births += stats.births;
+                total.deaths += stats.deaths;
+            }
+            return total;
+        }
+        //The cells are taken out of the board while they're stepped, as the board is read to count neighbours
+        let mut cells = std::mem::take(&mut self.board);
+        let mut next_cells = std::mem::take(&mut self.old_board);
+        for _ in 0..generations {
+            self.step_cells(&rule, &cells, &mut next_cells);
+            for (cell, next_cell) in cells.iter().zip(&next_cells) {
+                match (cell, next_cell) {
+                    (CellState::Dead, CellState::Alive) => total.births += 1,
+                    (CellState::Alive, CellState::Dead) => total.deaths += 1,
+                    _ => {}
+                }
+            }
+            std::mem::swap(&mut cells, &mut next_cells);
+        }
+        //After the last swap, the cells before it are the previous generation
+        self.board = cells;
+        self.old_board = next_cells;
+        total
+    }
+
+    /// Computes the next generation of the board on another board, leaving this one as it is. As this board is only
+    /// read, other threads can keep reading it while its next generation is computed, and the other board's cells are
+    /// reused instead of allocating new ones on every generation, as cloning the board for each one would
+    /// Input: the board to write the next generation to, which takes the size, rule, edges and neighbourhood of this one
+    /// Output: how many cells were born and how many died
+    ///
+    /// ```
+    /// use std::thread;
+    ///
+    /// use conways_game_of_life::Board;
+    ///
+    /// let mut board: Board = ".....\n..#..\n..#..\n..#..\n.....".parse().unwrap();
+    /// let mut next = Board::new(1, 1);
+    /// for _ in 0..4 {
+    ///     thread::scope(|scope| {
+    ///         // The board can be read, like to draw it, while its next generation is computed
+    ///         let population = scope.spawn(|| board.population());
+    ///         let stats = board.step_into(&mut next);
+    ///         assert_eq!(3, population.join().unwrap());
+    ///         assert_eq!((2, 2), (stats.births, stats.deaths));
+    ///     });
+    ///     std::mem::swap(&mut board, &mut next);
+    /// }
+    /// assert_eq!(board, ".....\n..#..\n..#..\n..#..\n.....".parse().unwrap());
+    /// ```
+    pub fn step_into(&self, next: &mut Board) -> StepStats {
+        next.width = self.width;
+        next.length = self.length;
+        next.rule = self.rule;
+        next.boundary = self.boundary;
+        next.neighborhood = Arc::clone(&self.neighborhood);
+        next.old_board.clone_from(&self.board);
+        next.board.clone_from(&self.board);
+        self.step_cells(&self.rule, &self.board, &mut next.board);
+        let stats = next.record_changes();
+        #[cfg(feature = "check-invariants")]
+        next.check_invariants(&self.rule, stats);
+        stats
+    }
+
+    /// Computes the next generation of some cells laid out like the ones of the board
+    /// Input: the rule to follow, the cells of the current generation, and where to write the next one
+    fn step_cells<R: Rule + ?Sized>(
+        &self,
+        rule: &R,
+        cells: &[CellState],
+        next_cells: &mut [CellState],
+    ) {
+        //The offsets are looked up once, instead of once per cell
+        let offsets = self.neighborhood.offsets();
+        for x in 0..self.width {
+            for y in 0..self.length {
+                let index = y * self.width + x;
+                let neighbours = self.count_neighbours(cells, x, y, offsets);
+                //Change the cell state according to the number of neighbours
+                next_cells[index] = rule.next_state(cells[index], &neighbours);
+            }
+        }
+    }
+
+    /// Counts the cells born and dead between the previous generation and the current one, recording them as events
+    /// if they're being recorded
+    /// Output: how many cells were born and how many died
+    fn record_changes(&mut self) -> StepStats {
+        let mut stats = StepStats::default();
+        for (index, (old_cell, cell)) in self.old_board.iter().zip(&self.board).enumerate() {
+            let Point { x, y } = self.point_at(index);
+            let event = match (old_cell, cell) {
+                (CellState::Dead, CellState::Alive) => {
+                    stats.births += 1;
+                    CellEvent::Birth { x, y }
+                }
+                (CellState::Alive, CellState::Dead) => {
+                    stats.deaths += 1;
+                    CellEvent::Death { x, y }
+                }
+                _ => continue,
+            };
+            if let Some(events) = &mut self.events {
+                events.push(event);
+            }
+        }
+        stats
+    }
+
+    /// Panics if a step left the board in a state it can never reach, to catch mistakes in the engine as soon as they
+    /// happen. Only built with the check-invariants feature, as it goes through the whole board again on every step
+    /// Input: the rule the board was stepped with, and the births and deaths the step counted
+    #[cfg(feature = "check-invariants")]
+    fn check_invariants<R: Rule + ?Sized>(&self, rule: &R, stats: StepStats) {
+        let cells = self.width * self.length;
+        assert_eq!(
+            cells,
+            self.board.len(),
+            "the board has lost or gained cells"
+        );
+        assert_eq!(
+            cells,
+            self.old_board.len(),
+            "the previous generation has lost or gained cells"
+        );
+
+        let (old_population, population) = (
+            self.old_board
+                .iter()
+                .filter(|cell| **cell == CellState::Alive)
+                .count(),
+            self.population(),
+        );
+        assert_eq!(
+            old_population + stats.births - stats.deaths,
+            population,
+            "the births and deaths counted don't add up to the population"
+        );
+
+        //Rules that never bring cells to life can't make the population grow, and rules that never kill them can't
+        //make it shrink, whatever the neighbourhood
+        let neighbours = self.neighborhood.offsets().len();
+        let can_change = |from, to| {
+            (0..=neighbours).any(|alive| {
+                let counts = NeighborCounts {
+                    alive,
+                    dead: neighbours - alive,
+                };
+                rule.next_state(from, &counts) == to
+            })
+        };
+        assert!(
+            stats.births == 0 || can_change(CellState::Dead, CellState::Alive),
+            "cells were born under a rule with no births"
+        );
+        assert!(
+            stats.deaths == 0 || can_change(CellState::Alive, CellState::Dead),
+            "cells died under a rule where they always survive"
+        );
+    }
+
+    /// Starts or stops recording every birth and death on each generation, so sound, logs or other observers can
+    /// follow the board without comparing generations themselves. Stopping drops the events that weren't drained
+    /// Input: whether events are recorded
+    pub fn record_events(&mut self, is_recording: bool) {
+        match (is_recording, &self.events) {
+            (true, None) => self.events = Some(Vec::new()),
+            (false, _) => self.events = None,
+            _ => {}
+        }
+    }
+
+    /// Takes the births and deaths recorded since the last time they were drained
+    /// Output: the events, in the order the generations happened, and by row within each generation. There are none
+    /// if events aren't being recorded
+    ///
+    /// ```
+    /// use conways_game_of_life::{Board, CellEvent};
+    ///
+    /// let mut board: Board = ".#.\n.#.\n.#.".parse().unwrap();
+    /// board.record_events(true);
+    /// board.update_board();
+    /// let events: Vec<_> = board.drain_events().collect();
+    /// assert_eq!(4, events.len());
+    /// assert_eq!(CellEvent::Death { x: 1, y: 0 }, events[0]);
+    /// assert!(events.contains(&CellEvent::Birth { x: 0, y: 1 }));
+    /// assert_eq!(0, board.drain_events().count());
+    /// ```
+    pub fn drain_events(&mut self) -> impl Iterator<Item = CellEvent> + '_ {
+        self.events.iter_mut().flat_map(|events| events.drain(..))
+    }
+
+    /// Runs a copy of the board, leaving this one as it is
+    /// Output: an endless iterator over the following generations, each one as a board of its own
+    ///
+    /// ```
+    /// use conways_game_of_life::{Board, Pattern, Point};
+    ///
+    /// let blinker = Pattern::from_library("Blinker").unwrap();
+    /// let mut board = Board::new(10, 10);
+    /// board.paste(&blinker, (3, 3));
+    /// for generation in board.generations().take(100) {
+    ///     assert_eq!(3, generation.population());
+    /// }
+    /// // The board itself is still on its first generation
+    /// assert_eq!(Some(Point::new(3, 3)), board.find_pattern(&blinker));
+    /// ```
+    pub fn generations(&self) -> impl Iterator<Item = Board> + use<> {
+        let mut board = self.clone();
+        std::iter::from_fn(move || {
+            board.update_board();
+            Some(board.clone())
+        })
+    }
+
+    /// Returns how many columns the board has
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns how many rows the board has
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Counts the alive cells in the board
+    pub fn population(&self) -> usize {
+        self.live_cells().count()
+    }
+
+    /// Goes through the alive cells of the board, so code looking at them doesn't have to index every cell itself
+    /// Output: the coordinates of every alive cell, row by row
+    ///
+    /// ```
+    /// use conways_game_of_life::{Board, Point};
+    ///
+    /// let mut board = Board::new(4, 3);
+    /// board.toggle_cell_state((2, 0));
+    /// board.toggle_cell_state((1, 2));
+    /// assert_eq!(
+    ///     vec![Point::new(2, 0), Point::new(1, 2)],
+    ///     board.live_cells().collect::<Vec<_>>()
+    /// );
+    /// ```
+    pub fn live_cells(&self) -> impl Iterator<Item = Point> + '_ {
+        self.board
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| **cell == CellState::Alive)
+            .map(|(index, _)| self.point_at(index))
+    }
+
+    /// Finds the smallest rectangle holding every alive cell
+    /// Output: the rectangle, if any cell is alive
+    pub fn live_bounds(&self) -> Option<Rect> {
+        let mut live_cells = self.live_cells();
+        let first = live_cells.next()?;
+        Some(
+            live_cells.fold(Rect::from_corners(first, first), |bounds, cell| {
+                Rect::from_corners(
+                    Point::new(bounds.x.min(cell.x), bounds.y.min(cell.y)),
+                    Point::new(
+                        (bounds.x + bounds.width - 1).max(cell.x),
+                        (bounds.y + bounds.height - 1).max(cell.y),
+                    ),
+                )
+            }),
+        )
+    }
+
+    /// Finds the cells whose state is different on another board of the same size
+    /// Input: the board to compare against
+    /// Output: the coordinates of every cell that differs, row by row
+    pub(crate) fn differences<'a>(&'a self, other: &'a Board) -> impl Iterator<Item = Point> + 'a {
+        self.board
+            .iter()
+            .zip(&other.board)
+            .enumerate()
+            .filter(|(_, (cell, other_cell))| cell != other_cell)
+            .map(|(index, _)| self.point_at(index))
+    }
+
+    /// Finds the coordinates of a cell from its position in the cells of the board, which go row by row
+    fn point_at(&self, index: usize) -> Point {
+        Point::new(index % self.width, index / self.width)
+    }
+
+    /// Looks for an isolated copy of a pattern: it has to match exactly, with only dead cells around it
+    /// Input: the pattern to look for
+    /// Output: the coordinates of the top left corner of the first copy found, row by row
+    pub fn find_pattern(&self, pattern: &Pattern) -> Option<Point> {
+        if pattern.width > self.width || pattern.height > self.length {
+            return None;
+        }
+        Rect::new(
+            0,
+            0,
+            self.width - pattern.width + 1,
+            self.length - pattern.height + 1,
+        )
+        .points()
+        .find(|corner| self.matches_pattern_at(pattern, corner.x, corner.y))
+    }
+
+    fn matches_pattern_at(&self, pattern: &Pattern, x: usize, y: usize) -> bool {
+        // The pattern is checked along with a one cell border around it, which has to be dead
+        let x_range = x.saturating_sub(1)..(x + pattern.width + 1).min(self.width);
+        let y_range = y.saturating_sub(1)..(y + pattern.height + 1).min(self.length);
+        for board_y in y_range {
+            for board_x in x_range.clone() {
+                let inside_pattern = (x..x + pattern.width).contains(&board_x)
+                    && (y..y + pattern.height).contains(&board_y);
+                let expected = if inside_pattern {
+                    pattern.get((board_x - x, board_y - y))
+                } else {
+                    CellState::Dead
+                };
+                if self[(board_x, board_y)] != expected {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Copies every cell of a pattern onto the board, cutting the ones that would go past its edges
+    /// Input: the pattern, and the coordinates of the cell its top left corner goes on
+    pub fn paste(&mut self, pattern: &Pattern, corner: impl Into<Point>) {
+        let Point { x, y } = corner.into();
+        for pattern_y in 0..pattern.height.min(self.length.saturating_sub(y)) {
+            for pattern_x in 0..pattern.width.min(self.width.saturating_sub(x)) {
+                self[(x + pattern_x, y + pattern_y)] = pattern.get((pattern_x, pattern_y));
+            }
+        }
+    }
+
+    /// Copies the cells of a rectangle of the board onto a board of their own, such as to copy them elsewhere. Cells of
+    /// the rectangle past the edges of the board wrap around on toroidal boards, and are dead on bounded ones
+    /// Input: the rectangle
+    /// Output: a board the size of the rectangle, with the rule and neighbourhood of this one and bounded edges
+    ///
+    /// ```
+    /// use conways_game_of_life::{BlitMode, Board, Rect};
+    ///
+    /// let mut board: Board = "...\n.##\n.#.".parse().unwrap();
+    /// let corner = board.extract(Rect::new(1, 1, 2, 2));
+    /// assert_eq!("##\n#.", corner.to_string());
+    ///
+    /// // Blitting with XOR swaps the cells the copy has alive, so doing it twice puts the board back as it was
+    /// board.blit(&corner, (0, 0), BlitMode::Xor);
+    /// assert_eq!("##.\n###\n.#.", board.to_string());
+    /// board.blit(&corner, (0, 0), BlitMode::Xor);
+    /// assert_eq!("...\n.##\n.#.", board.to_string());
+    /// ```
+    pub fn extract(&self, rect: Rect) -> Board {
+        let mut extracted = Board::new(rect.width, rect.height);
+        extracted.rule = self.rule;
+        extracted.neighborhood = Arc::clone(&self.neighborhood);
+        for point in rect.points() {
+            if let Some(cell) = self.wrap_coordinates(point.x as isize, point.y as isize) {
+                extracted[(point.x - rect.x, point.y - rect.y)] = self[cell];
+            }
+        }
+        extracted.old_board.clone_from(&extracted.board);
+        extracted
+    }
+
+    /// Copies the cells of another board onto this one, combining them with the cells already there, and cutting the
+    /// ones that would go past its edges
+    /// Input: the board to copy, such as one extracted from a board, the coordinates of the cell its top left corner
+    /// goes on, and how its cells are combined with the ones under them
+    pub fn blit(&mut self, source: &Board, corner: impl Into<Point>, mode: BlitMode) {
+        let Point { x, y } = corner.into();
+        for source_y in 0..source.length.min(self.length.saturating_sub(y)) {
+            for source_x in 0..source.width.min(self.width.saturating_sub(x)) {
+                let (cell, source_cell) = (
+                    &mut self.board[(y + source_y) * self.width + x + source_x],
+                    source[(source_x, source_y)],
+                );
+                *cell = match (mode, source_cell) {
+                    (BlitMode::Overwrite, _) => source_cell,
+                    (BlitMode::Or | BlitMode::Xor, CellState::Dead) => *cell,
+                    (BlitMode::Or, CellState::Alive) => CellState::Alive,
+                    (BlitMode::Xor, CellState::Alive) => match *cell {
+                        CellState::Alive => CellState::Dead,
+                        CellState::Dead => CellState::Alive,
+                    },
+                };
+            }
+        }
+    }
+
+    /// Turns the board a quarter turn clockwise, so its rows become columns
+    /// Output: the board turned a quarter, with its width and length swapped
+    ///
+    /// ```
+    /// use conways_game_of_life::Board;
+    ///
+    /// let board: Board = ".#.\n..#\n###".parse().unwrap();
+    /// assert_eq!("#..\n#.#\n##.", board.rotate90().to_string());
+    /// assert_eq!("###\n#..\n.#.", board.rotate180().to_string());
+    /// assert_eq!(".#.\n#..\n###", board.mirror_x().to_string());
+    /// assert_eq!("###\n..#\n.#.", board.mirror_y().to_string());
+    /// assert_eq!("..#\n#.#\n.##", board.transpose().to_string());
+    /// ```
+    pub fn rotate90(&self) -> Board {
+        self.transformed(self.length, self.width, |Point { x, y }| {
+            Point::new(y, self.length - 1 - x)
+        })
+    }
+
+    /// Turns the board half a turn, so its top left corner ends up at the bottom right
+    /// Output: the turned board
+    pub fn rotate180(&self) -> Board {
+        self.transformed(self.width, self.length, |Point { x, y }| {
+            Point::new(self.width - 1 - x, self.length - 1 - y)
+        })
+    }
+
+    /// Mirrors the board from left to right, reversing the order of its columns
+    /// Output: the mirrored board
+    pub fn mirror_x(&self) -> Board {
+        self.transformed(self.width, self.length, |Point { x, y }| {
+            Point::new(self.width - 1 - x, y)
+        })
+    }
+
+    /// Mirrors the board from top to bottom, reversing the order of its rows
+    /// Output: the mirrored board
+    pub fn mirror_y(&self) -> Board {
+        self.transformed(self.width, self.length, |Point { x, y }| {
+            Point::new(x, self.length - 1 - y)
+        })
+    }
+
+    /// Mirrors the board along the diagonal from its top left corner, swapping its rows and columns
+    /// Output: the board mirrored along its diagonal, with its width and length swapped
+    pub fn transpose(&self) -> Board {
+        self.transformed(self.length, self.width, |Point { x, y }| Point::new(y, x))
+    }
+
+    /// Builds a board with the cells of this one moved around, keeping its rule, edges and neighbourhood
+    /// Input: the size of the new board, and the cell of this board each of its cells is copied from
+    /// Output: the new board
+    fn transformed(&self, width: usize, length: usize, source: impl Fn(Point) -> Point) -> Board {
+        let mut transformed = Board::new(width, length);
+        transformed.rule = self.rule;
+        transformed.boundary = self.boundary;
+        transformed.neighborhood = Arc::clone(&self.neighborhood);
+        for (index, cell) in transformed.board.iter_mut().enumerate() {
+            *cell = self[source(Point::new(index % width, index / width))];
+        }
+        transformed.old_board.clone_from(&transformed.board);
+        transformed
+    }
+
+    /// Finds the cell at some coordinates that can be past the edges of the board, wrapping them around if the board is toroidal
+    /// Input: the coordinates, which can be negative
+    /// Output: the coordinates of the cell inside the board, or None if they're past the edges of a bounded board
+    pub(crate) fn wrap_coordinates(&self, x: isize, y: isize) -> Option<Point> {
+        self.boundary.wrap(x, y, (self.width, self.length))
+    }
+
+    /// Counts the alive neighbours of a cell on the current generation, the same way the board does when stepping, so
+    /// tools looking at the board can count them without knowing its edges or neighbourhood
+    /// Input: the cell, as a point or a column and row
+    /// Output: how many of its neighbours are alive, up to 255
+    ///
+    /// ```
+    /// use conways_game_of_life::{Board, Boundary};
+    ///
+    /// let mut board: Board = "#..\n.#.\n..#".parse().unwrap();
+    /// assert_eq!(2, board.alive_neighbours((1, 1)));
+    /// assert_eq!(1, board.alive_neighbours((0, 0)));
+    /// board.boundary = Boundary::Toroidal;
+    /// assert_eq!(2, board.alive_neighbours((0, 0)));
+    /// assert_eq!(vec![2, 3, 3], board.alive_neighbours_in_row(0).collect::<Vec<_>>());
+    /// ```
+    pub fn alive_neighbours(&self, cell: impl Into<Point>) -> u8 {
+        let Point { x, y } = cell.into();
+        let counts = self.count_neighbours(&self.board, x, y, self.neighborhood.offsets());
+        counts.alive.min(u8::MAX as usize) as u8
+    }
+
+    /// Counts the alive neighbours of every cell of a row like alive_neighbours does, looking up the neighbourhood only
+    /// once for the whole row
+    /// Input: the row
+    /// Output: how many alive neighbours each cell of the row has, from left to right
+    pub fn alive_neighbours_in_row(&self, y: usize) -> impl Iterator<Item = u8> + '_ {
+        let offsets = self.neighborhood.offsets();
+        (0..self.width).map(move |x| {
+            let counts = self.count_neighbours(&self.board, x, y, offsets);
+            counts.alive.min(u8::MAX as usize) as u8
+        })
+    }
+
+    /// Counts the alive and dead neighbours of a cell. Neighbours past the edges of bounded boards are dead
+    /// Input: the cells to count on, laid out like the ones of the board, the coordinates of the cell, and the offsets
+    /// of its neighbours
+    /// Output: how many of them were alive, and how many dead
+    fn count_neighbours(
+        &self,
+        cells: &[CellState],
+        x: usize,
+        y: usize,
+        offsets: &[(isize, isize)],
+    ) -> NeighborCounts {
+        count_neighbours(
+            cells,
+            (self.width, self.length),
+            self.boundary,
+            (x, y),
+            offsets,
+        )
+    }
+}
+
+impl Index<(usize, usize)> for Board {
+    type Output = CellState;
+
+    /// Returns the status of a cell, given its coordinates
+    /// Input: x and y coordinates of the cell
+    /// Output: whether the cell is dead or alive
+    fn index(&self, (x, y): (usize, usize)) -> &CellState {
+        &self.board[y * self.width + x]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Board {
+    /// Returns a mutable reference to the status of a cell, given its coordinates
+    /// Input: x and y coordinates of the cell
+    /// Output: a mutable reference to the cell state
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut CellState {
+        &mut self.board[y * self.width + x]
+    }
+}
+
+impl Index<Point> for Board {
+    type Output = CellState;
+
+    fn index(&self, point: Point) -> &CellState {
+        &self[(point.x, point.y)]
+    }
+}
+
+impl IndexMut<Point> for Board {
+    fn index_mut(&mut self, point: Point) -> &mut CellState {
+        &mut self[(point.x, point.y)]
+    }
+}
+
+/// Boards are equal when they have the same size and the same cells alive, whatever rule, edges or neighbourhood they
+/// follow, so generations can be compared to find cycles, or kept in sets
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use conways_game_of_life::{Board, Boundary};
+///
+/// let mut board: Board = "....\n.##.\n.#..\n....".parse().unwrap();
+/// board.boundary = Boundary::Toroidal;
+/// let mut seen = HashSet::new();
+/// // The cell that's born completes a block, which then stays the same
+/// while seen.insert(board.clone()) {
+///     board.update_board();
+/// }
+/// assert_eq!(2, seen.len());
+/// assert_eq!(board, "....\n.##.\n.##.\n....".parse().unwrap());
+/// ```
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width && self.length == other.length && self.board == other.board
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width.hash(state);
+        self.length.hash(state);
+        self.board.hash(state);
+    }
+}
+
+impl fmt::Display for Board {
+    /// Writes the board as rows of text, with a # for each alive cell and a . for each dead one
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, row) in self.board.chunks(self.width.max(1)).enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                let symbol = match cell {
+                    CellState::Alive => '#',
+                    CellState::Dead => '.',
+                };
+                write!(f, "{symbol}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Board {
+    type Err = GolError;
+
+    /// Reads a board written as rows of # and ., as it's displayed. Blank lines and the spaces around each row are
+    /// skipped, so boards can be written indented in the code. The board follows the standard rules, with bounded edges
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|row| !row.is_empty())
+            .collect();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut board = Board::try_new(width, rows.len())?;
+        for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err(GolError::Parse(format!(
+                    "row {} has {} cells, but the first one has {width}",
+                    y + 1,
+                    row.chars().count()
+                )));
+            }
+            for (x, symbol) in row.chars().enumerate() {
+                board[(x, y)] = match symbol {
+                    '#' => CellState::Alive,
+                    '.' => CellState::Dead,
+                    _ => {
+                        return Err(GolError::Parse(format!(
+                            "unexpected '{symbol}' in row {}",
+                            y + 1
+                        )));
+                    }
+                };
+            }
+        }
+        board.old_board = board.board.clone();
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::neighborhood::VonNeumann;
+
+    #[test]
+    fn dead_cell_with_two_alive_neighbours_stays_dead() {
+        let mut board = Board::new(3, 3);
+        board.toggle_cell_state((0, 0));
+        board.toggle_cell_state((0, 1));
+
+        board.update_board();
+        assert_eq!(CellState::Dead, board[(1, 1)]);
+    }
+
+    #[test]
+    fn dead_cell_with_three_alive_neighbours_revives() {
+        let mut board = Board::new(3, 3);
+        board.toggle_cell_state((0, 0));
+        board.toggle_cell_state((0, 1));
+        board.toggle_cell_state((1, 0));
+
+        board.update_board();
+        assert_eq!(CellState::Alive, board[(1, 1)]);
+    }
+
+    #[test]
+    fn alive_cell_with_two_alive_neighbours_stays_alive() {
+        let mut board = Board::new(3, 3);
+        board.toggle_cell_state((0, 0));
+        board.toggle_cell_state((0, 1));
+        board.toggle_cell_state((1, 0));
+
+        board.update_board();
+        assert_eq!(CellState::Alive, board[(1, 0)]);
+    }
+
+    #[test]
+    fn alive_cell_with_three_alive_neighbours_stays_alive() {
+        let mut board = Board::new(3, 3);
+        board.toggle_cell_state((0, 0));
+        board.toggle_cell_state((0, 1));
+        board.toggle_cell_state((1, 0));
+        board.toggle_cell_state((1, 1));
+
+        board.update_board();
+        assert_eq!(CellState::Alive, board[(1, 0)]);
+    }
+
+    #[test]
+    fn alive_cell_with_four_alive_neighbours_dies() {
+        let mut board = Board::new(4, 4);
+        board.toggle_cell_state((1, 1));
+        board.toggle_cell_state((0, 1));
+        board.toggle_cell_state((0, 2));
+        board.toggle_cell_state((1, 0));
+        board.toggle_cell_state((2, 0));
+
+        board.update_board();
+        assert_eq!(CellState::Dead, board[(1, 1)]);
+    }
+
+    #[test]
+    fn alive_cell_with_one_alive_neighbour_dies() {
+        let mut board = Board::new(3, 3);
+        board.toggle_cell_state((1, 1));
+        board.toggle_cell_state((1, 0));
+
+        board.update_board();
+        assert_eq!(CellState::Dead, board[(1, 1)]);
+    }
+
+    #[test]
+    fn dead_cell_with_six_alive_neighbours_revives_under_highlife() {
+        let mut board = Board::new(3, 3);
+        board.rule = "B36/S23".parse().unwrap();
+        for (x, y) in [(0, 0), (1, 0), (2, 0), (0, 2), (1, 2), (2, 2)] {
+            board.toggle_cell_state((x, y));
+        }
+
+        board.update_board();
+        assert_eq!(CellState::Alive, board[(1, 1)]);
+    }
+
+    #[test]
+    fn update_board_counts_births_and_deaths() {
+        let mut board: Board = "
+            .#.
+            .#.
+            .#.
+        "
+        .parse()
+        .unwrap();
+
+        let stats = board.update_board();
+        assert_eq!(
+            StepStats {
+                births: 2,
+                deaths: 2
+            },
+            stats
+        );
+        assert_eq!(3, board.population());
+    }
+
+    #[test]
+    fn find_pattern_only_finds_isolated_copies() {
+        let blinker = Pattern::from_library("Blinker").unwrap();
+        let mut board = Board::new(5, 3);
+        board.toggle_cell_state((1, 1));
+        board.toggle_cell_state((2, 1));
+        board.toggle_cell_state((3, 1));
+        assert_eq!(Some(Point::new(1, 1)), board.find_pattern(&blinker));
+
+        board.toggle_cell_state((4, 1));
+        assert_eq!(None, board.find_pattern(&blinker));
+    }
+
+    #[test]
+    fn toroidal_board_counts_neighbours_across_edges() {
+        let mut board: Board = "
+            .....
+            #....
+            #....
+            #....
+            .....
+        "
+        .parse()
+        .unwrap();
+        board.boundary = Boundary::Toroidal;
+
+        board.update_board();
+        assert_eq!(".....\n.....\n##..#\n.....\n.....", board.to_string());
+    }
+
+    #[test]
+    fn pasted_patterns_are_cut_at_the_edges() {
+        let mut board = Board::new(3, 3);
+        board.paste(&Pattern::from_library("Block").unwrap(), (2, 1));
+        assert_eq!(2, board.population());
+        assert_eq!(CellState::Alive, board[(2, 1)]);
+        assert_eq!(CellState::Alive, board[(2, 2)]);
+    }
+
+    #[test]
+    fn generations_follow_each_other_without_changing_the_board() {
+        let mut board = Board::new(6, 6);
+        board.paste(&Pattern::from_library("Block").unwrap(), (1, 1));
+        board.toggle_cell_state((4, 4));
+        let generations: Vec<_> = board.generations().take(3).collect();
+        assert_eq!(5, board.population());
+        assert_eq!(
+            vec![4, 4, 4],
+            generations
+                .iter()
+                .map(Board::population)
+                .collect::<Vec<_>>()
+        );
+        let mut stepped = board.clone();
+        stepped.update_board();
+        assert_eq!(stepped, generations[0]);
+    }
+
+    #[test]
+    fn boards_are_read_as_they_are_displayed() {
+        let board: Board = "
+            .#..
+            ..#.
+            ###.
+        "
+        .parse()
+        .unwrap();
+        assert_eq!((4, 3), (board.width(), board.length()));
+        assert_eq!(5, board.population());
+        assert_eq!(".#..\n..#.\n###.", board.to_string());
+        assert_eq!(board, board.to_string().parse().unwrap());
+
+        assert!("".parse::<Board>().is_err());
+        assert!("##\n#".parse::<Board>().is_err());
+        assert!("#o".parse::<Board>().is_err());
+    }
+
+    #[test]
+    fn boards_are_read_from_several_threads_while_stepping() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Board>();
+
+        let mut board = Board::new(12, 12);
+        board.boundary = Boundary::Toroidal;
+        board.paste(&Pattern::from_library("Glider").unwrap(), (1, 1));
+        let expected: Vec<_> = board.generations().take(8).collect();
+        let mut next = Board::new(1, 1);
+        for generation in &expected {
+            std::thread::scope(|scope| {
+                let readers: Vec<_> = (0..4)
+                    .map(|_| scope.spawn(|| (board.population(), board.clone())))
+                    .collect();
+                board.step_into(&mut next);
+                for reader in readers {
+                    let (population, snapshot) = reader.join().unwrap();
+                    assert_eq!(5, population);
+                    assert_eq!(board, snapshot);
+                }
+            });
+            assert_eq!(*generation, next);
+            std::mem::swap(&mut board, &mut next);
+        }
+    }
+
+    #[test]
+    fn boards_are_equal_by_size_and_cells() {
+        let mut board: Board = ".#.\n.#.".parse().unwrap();
+        let mut other = board.clone();
+        other.rule = "B36/S23".parse().unwrap();
+        other.boundary = Boundary::Toroidal;
+        assert_eq!(board, other);
+
+        //The same cells, in rows of a different width
+        let reshaped: Board = ".#\n..\n#.".parse().unwrap();
+        assert_ne!(board, reshaped);
+        board.toggle_cell_state((0, 0));
+        assert_ne!(board, other);
+    }
+
+    #[test]
+    fn boards_without_cells_or_too_big_are_rejected() {
+        assert!(Board::try_new(3, 2).is_ok());
+        assert!(matches!(
+            Board::try_new(0, 5),
+            Err(GolError::InvalidDimensions {
+                width: 0,
+                length: 5
+            })
+        ));
+        assert!(Board::try_new(usize::MAX, 2).is_err());
+        assert!(Board::try_new(MAX_CELLS, 2).is_err());
+    }
+
+    #[test]
+    fn live_bounds_hold_every_alive_cell() {
+        let mut board = Board::new(10, 8);
+        assert_eq!(None, board.live_bounds());
+        board.toggle_cell_state((6, 2));
+        board.toggle_cell_state((3, 5));
+        board.toggle_cell_state((4, 1));
+        assert_eq!(Some(Rect::new(3, 1, 4, 5)), board.live_bounds());
+    }
+
+    #[test]
+    fn wrap_coordinates_depends_on_boundary() {
+        let mut board = Board::new(4, 3);
+        assert_eq!(None, board.wrap_coordinates(-1, 0));
+        assert_eq!(Some(Point::new(3, 2)), board.wrap_coordinates(3, 2));
+
+        board.boundary = Boundary::Toroidal;
+        assert_eq!(Some(Point::new(3, 0)), board.wrap_coordinates(-1, 3));
+    }
+
+    #[test]
+    fn extracted_cells_are_blitted_back_in_every_mode() {
+        let mut board: Board = "
+            ##..
+            #...
+            ..##
+        "
+        .parse()
+        .unwrap();
+        //Cells past the edges are dead on bounded boards, and wrap around on toroidal ones
+        assert_eq!("..\n#.", board.extract(Rect::new(3, 1, 2, 2)).to_string());
+        board.boundary = Boundary::Toroidal;
+        let stamp = board.extract(Rect::new(3, 1, 2, 2));
+        assert_eq!(".#\n#.", stamp.to_string());
+        assert_eq!(Boundary::Bounded, stamp.boundary);
+        assert_eq!(0, board.extract(Rect::new(0, 0, 0, 3)).population());
+
+        let mut overwritten = board.clone();
+        overwritten.blit(&stamp, (0, 0), BlitMode::Overwrite);
+        assert_eq!(".#..\n#...\n..##", overwritten.to_string());
+        let mut added = board.clone();
+        added.blit(&stamp, (0, 0), BlitMode::Or);
+        assert_eq!("##..\n#...\n..##", added.to_string());
+        let mut swapped = board.clone();
+        swapped.blit(&stamp, (2, 1), BlitMode::Xor);
+        assert_eq!("##..\n#..#\n...#", swapped.to_string());
+        //Cells blitted past the edges are cut
+        swapped.blit(&stamp, (3, 2), BlitMode::Overwrite);
+        assert_eq!("##..\n#..#\n....", swapped.to_string());
+    }
+
+    #[test]
+    fn transformations_compose_like_turns_and_mirrors() {
+        let mut board: Board = "
+            ##...
+            #..#.
+        "
+        .parse()
+        .unwrap();
+        board.rule = LifeLikeRule::preset(1);
+        let turned = board.rotate90();
+        assert_eq!((2, 5), (turned.width(), turned.length()));
+        assert_eq!("##\n.#\n..\n#.\n..", turned.to_string());
+        assert_eq!(board.rule, turned.rule);
+
+        assert_eq!(board, turned.rotate90().rotate90().rotate90());
+        assert_eq!(board.rotate180(), turned.rotate90());
+        assert_eq!(board.rotate180(), board.mirror_x().mirror_y());
+        assert_eq!(board.transpose(), turned.mirror_x());
+        assert_eq!(board, board.mirror_y().mirror_y());
+
+        //Extracted parts of boards are boards too
+        let corner = board.extract(Rect::new(0, 0, 2, 2));
+        assert_eq!("##\n.#", corner.mirror_x().to_string());
+    }
+
+    #[test]
+    fn running_generations_matches_stepping_one_by_one() {
+        let mut board: Board = "
+            .#......
+            ..#.....
+            ###...#.
+            ......#.
+            ......#.
+        "
+        .parse()
+        .unwrap();
+        board.boundary = Boundary::Toroidal;
+        let mut stepped = board.clone();
+        let mut expected = StepStats::default();
+        for _ in 0..13 {
+            let stats = stepped.update_board();
+            expected.births += stats.births;
+            expected.deaths += stats.deaths;
+        }
+        assert_eq!(expected, board.run_generations(13));
+        assert_eq!(stepped, board);
+        assert_eq!(stepped.old_board, board.old_board);
+        assert_eq!(StepStats::default(), board.run_generations(0));
+        assert_eq!(stepped.old_board, board.old_board);
+    }
+
+    #[test]
+    fn neighbour_counts_follow_the_neighbourhood() {
+        let mut board: Board = "
+            ###
+            #.#
+            ###
+        "
+        .parse()
+        .unwrap();
+        assert_eq!(8, board.alive_neighbours((1, 1)));
+        assert_eq!(2, board.alive_neighbours((0, 0)));
+        board.neighborhood = Arc::new(VonNeumann);
+        assert_eq!(4, board.alive_neighbours((1, 1)));
+        assert_eq!(
+            vec![2, 2, 2],
+            board.alive_neighbours_in_row(2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn toroidal_boards_without_cells_have_nothing_to_wrap_to() {
+        for (width, length) in [(0, 5), (5, 0), (0, 0)] {
+            let mut board = Board::new(width, length);
+            board.boundary = Boundary::Toroidal;
+            assert_eq!(None, board.wrap_coordinates(-1, 3));
+            assert_eq!(StepStats::default(), board.update_board());
+        }
+    }
+
+    /// Steps a grid of cells the plainest way there is, looking at every neighbour of every cell, for the engine to be
+    /// checked against
+    /// Input: the cells, row by row, the counts of alive neighbours cells are born and survive with, and the edges
+    /// Output: the cells of the next generation
+    fn reference_step(
+        cells: &[Vec<bool>],
+        birth: &[bool; 9],
+        survival: &[bool; 9],
+        boundary: Boundary,
+    ) -> Vec<Vec<bool>> {
+        let (length, width) = (cells.len() as isize, cells[0].len() as isize);
+        let is_alive = |x: isize, y: isize| match boundary {
+            Boundary::Bounded => {
+                (0..width).contains(&x) && (0..length).contains(&y) && cells[y as usize][x as usize]
+            }
+            Boundary::Toroidal => {
+                cells[y.rem_euclid(length) as usize][x.rem_euclid(width) as usize]
+            }
+        };
+        (0..length)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let mut alive = 0;
+                        for y_offset in -1..=1 {
+                            for x_offset in -1..=1 {
+                                if (x_offset, y_offset) != (0, 0)
+                                    && is_alive(x + x_offset, y + y_offset)
+                                {
+                                    alive += 1;
+                                }
+                            }
+                        }
+                        if is_alive(x, y) {
+                            survival[alive]
+                        } else {
+                            birth[alive]
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Writes the counts of alive neighbours a rule applies to as they are in rulestrings
+    fn rulestring_counts(counts: &[bool; 9]) -> String {
+        (0..9)
+            .filter(|&alive| counts[alive])
+            .map(|alive| alive.to_string())
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn boards_step_like_the_reference(
+            cells in (1..12usize, 1..12usize).prop_flat_map(|(width, length)| {
+                prop::collection::vec(prop::collection::vec(any::<bool>(), width), length)
+            }),
+            birth in any::<[bool; 9]>(),
+            survival in any::<[bool; 9]>(),
+            is_toroidal in any::<bool>(),
+            generations in 1..5usize,
+        ) {
+            let boundary = if is_toroidal { Boundary::Toroidal } else { Boundary::Bounded };
+            let mut board = Board::new(cells[0].len(), cells.len());
+            board.boundary = boundary;
+            board.rule = format!("B{}/S{}", rulestring_counts(&birth), rulestring_counts(&survival))
+                .parse()
+                .unwrap();
+            for (y, row) in cells.iter().enumerate() {
+                for (x, _) in row.iter().enumerate().filter(|(_, is_alive)| **is_alive) {
+                    board.toggle_cell_state((x, y));
+                }
+            }
+            let mut next = Board::new(1, 1);
+
+            let mut expected = cells;
+            for _ in 0..generations {
+                board.step_into(&mut next);
+                let old_population = board.population();
+                let stats = board.update_board();
+                expected = reference_step(&expected, &birth, &survival, boundary);
+                for (y, row) in expected.iter().enumerate() {
+                    for (x, is_alive) in row.iter().enumerate() {
+                        let state = if *is_alive { CellState::Alive } else { CellState::Dead };
+                        prop_assert_eq!(state, board[(x, y)]);
+                    }
+                }
+                prop_assert_eq!(&next, &board);
+                prop_assert_eq!(old_population + stats.births - stats.deaths, board.population());
+            }
+        }
+    }
+}
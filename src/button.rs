@@ -0,0 +1,97 @@
+use macroquad::prelude::*;
+
+use crate::text::{draw_text, measure_text};
+use crate::theme::Theme;
+
+/// Space left between buttons placed side by side, as a share of the width of each one
+const BUTTON_GAP_SHARE: f32 = 0.05;
+
+/// Splits an area into a row of buttons of the same size, with a small gap between them
+/// Input: the area the row takes, how many buttons there are, and the index of the button
+/// Output: the screen rectangle of the button
+pub fn row_rect(area: Rect, count: usize, index: usize) -> Rect {
+    let slot = area.w / count as f32;
+    let gap = slot * BUTTON_GAP_SHARE;
+    Rect::new(
+        area.x + index as f32 * slot + gap / 2.,
+        area.y,
+        slot - gap,
+        area.h,
+    )
+}
+
+/// Draws a button with a label in it, highlighted while selected or with the mouse over it
+/// Input: the text of the button, its screen rectangle, the font size of the text, whether it's selected, and the
+/// theme to draw it with
+/// Output: whether the button was clicked on this frame
+pub fn button(label: &str, rect: Rect, font_size: f32, is_selected: bool, theme: &Theme) -> bool {
+    let (mouse_x, mouse_y) = mouse_position();
+    let is_hovered = rect.contains(vec2(mouse_x, mouse_y));
+    if is_selected || is_hovered {
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme.highlight);
+    }
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1., theme.label);
+    //The label is centered in the button
+    let size = measure_text(label, None, font_size as u16, 1.);
+    draw_text(
+        label,
+        rect.x + (rect.w - size.width) / 2.,
+        rect.y + (rect.h + size.offset_y) / 2.,
+        font_size,
+        theme.text,
+    );
+    is_hovered && is_mouse_button_pressed(MouseButton::Left)
+}
+
+/// Works out the value of a slider at a horizontal position, the lowest one at its left end and the highest at its right
+/// Input: the lowest and highest values, the screen rectangle of the slider, and the position
+/// Output: the value, within the range
+fn slider_value((low, high): (f64, f64), rect: Rect, x: f32) -> f64 {
+    let share = ((x - rect.x) / rect.w).clamp(0., 1.);
+    low + (high - low) * share as f64
+}
+
+/// Draws a slider with its label over it, moving its knob to where it's clicked or dragged
+/// Input: the label, which should include the value, the value, the lowest and highest values, the screen rectangle
+/// of the slider, and the theme to draw it with
+/// Output: whether the value changed on this frame
+pub fn slider(label: &str, value: &mut f64, range: (f64, f64), rect: Rect, theme: &Theme) -> bool {
+    draw_text(label, rect.x, rect.y - 4., 18., theme.text);
+    let track_y = rect.y + rect.h / 2.;
+    draw_line(rect.x, track_y, rect.right(), track_y, 2., theme.label);
+    let share = ((*value - range.0) / (range.1 - range.0)).clamp(0., 1.) as f32;
+    draw_circle(rect.x + share * rect.w, track_y, rect.h / 3., theme.text);
+
+    let (mouse_x, mouse_y) = mouse_position();
+    if is_mouse_button_down(MouseButton::Left) && rect.contains(vec2(mouse_x, mouse_y)) {
+        let dragged = slider_value(range, rect, mouse_x);
+        let is_changed = dragged != *value;
+        *value = dragged;
+        return is_changed;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buttons_in_a_row_fill_it_without_overlapping() {
+        let area = Rect::new(10., 20., 300., 30.);
+        for index in 1..3 {
+            assert!(row_rect(area, 3, index - 1).right() < row_rect(area, 3, index).x);
+        }
+        assert!(row_rect(area, 3, 0).x > area.x);
+        assert!(row_rect(area, 3, 2).right() < area.right());
+        assert_eq!(area.h, row_rect(area, 3, 1).h);
+    }
+
+    #[test]
+    fn sliders_map_their_width_onto_their_range() {
+        let rect = Rect::new(100., 0., 200., 20.);
+        assert_eq!(0.5, slider_value((0., 1.), rect, 200.));
+        assert_eq!(2., slider_value((2., 4.), rect, 50.));
+        assert_eq!(4., slider_value((2., 4.), rect, 900.));
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use crate::patterns::Pattern;
+use crate::{Board, CellState};
+
+/// Objects counted by the census, with the key of their name, one of their phases in plaintext format and how many generations it takes
+/// them to go back to it. Objects that split into separate groups of cells on some phases, such as the toad, aren't
+/// counted, as they can't be recognised on every generation. The same goes for the lightweight spaceship
+const OBJECTS: [(&str, &str, usize); 7] = [
+    ("object.block", "OO\nOO", 1),
+    ("object.beehive", ".OO.\nO..O\n.OO.", 1),
+    ("object.loaf", ".OO.\nO..O\n.O.O\n..O.", 1),
+    ("object.boat", "OO.\nO.O\n.O.", 1),
+    ("object.tub", ".O.\nO.O\n.O.", 1),
+    ("object.blinker", "OOO", 2),
+    ("object.glider", ".O.\n..O\nOOO", 4),
+];
+
+/// Cells of a group of alive cells, relative to the top left corner of the smallest rectangle around them, in order
+type Shape = Vec<(isize, isize)>;
+
+/// Counts of the objects on a board, recognised by the shapes of the groups of touching alive cells
+pub struct Census {
+    /// Every phase of every object, in every orientation, with the index of the object it belongs to
+    shapes: HashMap<Shape, usize>,
+    /// Cells of the board the census was last taken on
+    cells: Vec<CellState>,
+    /// How many copies of each object there are, in the order of OBJECTS
+    counts: [usize; OBJECTS.len()],
+    /// How many groups of cells aren't any of the objects
+    others: usize,
+}
+
+impl Default for Census {
+    fn default() -> Self {
+        let mut shapes = HashMap::new();
+        for (index, (_, cells, period)) in OBJECTS.iter().enumerate() {
+            let pattern = Pattern::parse_plaintext(cells).expect("census objects are valid");
+            //Each phase is found by running the object, with room around it for spaceships to move
+            let margin = 4;
+            let mut board = Board::new(pattern.width + 2 * margin, pattern.height + 2 * margin);
+            board.paste(&pattern, (margin, margin));
+            for _ in 0..*period {
+                //Every phase of the objects counted is a single group of cells
+                let [group] = groups(&board)
+                    .try_into()
+                    .expect("census objects stay in one piece");
+                for shape in orientations(&group) {
+                    shapes.insert(shape, index);
+                }
+                board.update_board();
+            }
+        }
+        Census {
+            shapes,
+            cells: Vec::new(),
+            counts: [0; OBJECTS.len()],
+            others: 0,
+        }
+    }
+}
+
+impl Census {
+    /// Takes the census of a board again if it changed since the last time
+    pub fn update(&mut self, board: &Board) {
+        if self.cells == board.board {
+            return;
+        }
+        self.counts = [0; OBJECTS.len()];
+        self.others = 0;
+        for group in groups(board) {
+            match self.shapes.get(&normalize(group)) {
+                Some(index) => self.counts[*index] += 1,
+                None => self.others += 1,
+            }
+        }
+        self.cells.clone_from(&board.board);
+    }
+
+    /// Returns how many copies of an object there are
+    /// Input: the key of the name of the object
+    pub fn count(&self, name: &str) -> usize {
+        self.counts()
+            .find(|(object, _)| *object == name)
+            .map_or(0, |(_, count)| count)
+    }
+
+    /// Returns the key of the name of every object with how many copies of it there are, followed by the count of other groups
+    pub fn counts(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        OBJECTS
+            .iter()
+            .zip(self.counts)
+            .map(|((name, _, _), count)| (*name, count))
+            .chain([("object.other", self.others)])
+    }
+}
+
+/// Splits the alive cells of a board into groups of cells touching each other, diagonally too. On toroidal boards,
+/// groups continue across the edges
+/// Output: the cells of each group, with coordinates that go past the edges for groups that wrap around
+fn groups(board: &Board) -> Vec<Vec<(isize, isize)>> {
+    let mut is_visited = vec![false; board.board.len()];
+    let mut groups = Vec::new();
+    for start in 0..board.board.len() {
+        if is_visited[start] || board.board[start] == CellState::Dead {
+            continue;
+        }
+        is_visited[start] = true;
+        let mut group = Vec::new();
+        let mut pending = vec![(
+            (start % board.width) as isize,
+            (start / board.width) as isize,
+        )];
+        while let Some((x, y)) = pending.pop() {
+            group.push((x, y));
+            for (delta_x, delta_y) in NEIGHBOUR_OFFSETS {
+                let (neighbour_x, neighbour_y) = (x + delta_x, y + delta_y);
+                let Some(cell) = board.wrap_coordinates(neighbour_x, neighbour_y) else {
+                    continue;
+                };
+                let index = cell.y * board.width + cell.x;
+                if !is_visited[index] && board.board[index] == CellState::Alive {
+                    is_visited[index] = true;
+                    pending.push((neighbour_x, neighbour_y));
+                }
+            }
+        }
+        groups.push(group);
+    }
+    groups
+}
+
+const NEIGHBOUR_OFFSETS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Moves a group of cells so the smallest rectangle around it starts at 0, 0, and sorts them, so groups with the same
+/// shape are equal wherever they are
+fn normalize(mut cells: Vec<(isize, isize)>) -> Shape {
+    let min_x = cells.iter().map(|(x, _)| *x).min().unwrap_or(0);
+    let min_y = cells.iter().map(|(_, y)| *y).min().unwrap_or(0);
+    for (x, y) in &mut cells {
+        *x -= min_x;
+        *y -= min_y;
+    }
+    cells.sort_unstable();
+    cells
+}
+
+/// Finds the shapes of a group of cells rotated and mirrored in every way
+fn orientations(cells: &[(isize, isize)]) -> Vec<Shape> {
+    let mut shapes = Vec::new();
+    for is_swapped in [false, true] {
+        for (sign_x, sign_y) in [(1, 1), (-1, 1), (1, -1), (-1, -1)] {
+            let transformed = cells.iter().map(|&(x, y)| {
+                let (x, y) = if is_swapped { (y, x) } else { (x, y) };
+                (x * sign_x, y * sign_y)
+            });
+            shapes.push(normalize(transformed.collect()));
+        }
+    }
+    shapes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Boundary;
+
+    fn counts_of(census: &Census) -> Vec<(&'static str, usize)> {
+        census.counts().filter(|(_, count)| *count > 0).collect()
+    }
+
+    #[test]
+    fn objects_are_counted_in_any_orientation_and_phase() {
+        let mut board = Board::new(20, 20);
+        board.paste(&Pattern::from_library("Glider").unwrap(), (1, 1));
+        board.paste(&Pattern::from_library("Block").unwrap(), (10, 1));
+        board.paste(&Pattern::parse_plaintext("O\nO\nO").unwrap(), (15, 10));
+        board.paste(&Pattern::parse_plaintext("OO\nOO\nO.").unwrap(), (5, 15));
+        let mut census = Census::default();
+        census.update(&board);
+        assert_eq!(
+            vec![
+                ("object.block", 1),
+                ("object.blinker", 1),
+                ("object.glider", 1),
+                ("object.other", 1)
+            ],
+            counts_of(&census)
+        );
+
+        board.update_board();
+        census.update(&board);
+        assert_eq!(Some(("object.glider", 1)), census.counts().nth(6));
+        assert_eq!(Some(("object.blinker", 1)), census.counts().nth(5));
+    }
+
+    #[test]
+    fn groups_continue_across_the_edges_of_toroidal_boards() {
+        let mut board = Board::new(6, 6);
+        board.boundary = Boundary::Toroidal;
+        board.toggle_cell_state((5, 2));
+        board.toggle_cell_state((0, 2));
+        board.toggle_cell_state((1, 2));
+        let mut census = Census::default();
+        census.update(&board);
+        assert_eq!(vec![("object.blinker", 1)], counts_of(&census));
+    }
+}
@@ -0,0 +1,539 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use macroquad::prelude::*;
+
+use crate::locale::{tr, tr_with};
+use crate::simulation::SPEEDS;
+use crate::theme::Theme;
+use crate::tutorial::draw_banner;
+use crate::{Board, Boundary, CellState, Point};
+
+/// Cells the player can place before the board runs
+const CELL_BUDGET: usize = 12;
+
+/// Population the board has to stay above, generation after generation, for the run to go on
+const POPULATION_THRESHOLD: usize = 8;
+
+/// Generations after which a run ends on its own, as the best score there is
+const MAX_CHALLENGE_GENERATIONS: usize = 5000;
+
+/// High scores kept for each kind of board
+const HIGH_SCORES_KEPT: usize = 5;
+
+/// Width and height of the board of daily challenges, the same for everyone
+const DAILY_SIZE: (usize, usize) = (64, 48);
+
+/// Share of the cells of the daily soup that start alive
+const DAILY_DENSITY: f64 = 0.3;
+
+/// Cells of the daily soup the player can change before it runs
+const DAILY_EDITS: usize = 10;
+
+/// Generations the daily soup runs for before its alive cells are counted
+const DAILY_GENERATIONS: usize = 1000;
+
+/// Space left above the board for the banner with the budget and the score, in pixels
+const BANNER_MARGIN: f32 = 100.;
+
+/// What a survival challenge is doing
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Phase {
+    /// The player is placing cells
+    Seeding,
+    /// The board is running, with the population still above the threshold
+    Running,
+    /// The run ended, with the generations the population stayed above the threshold as the score
+    Over,
+}
+
+/// Which challenge is played
+#[derive(Clone, PartialEq, Debug)]
+pub enum Kind {
+    /// The player places up to CELL_BUDGET cells on an empty board, and scores the generations the population then
+    /// stays above POPULATION_THRESHOLD. Boards that settle into still lifes or blinkers would stay there forever, so
+    /// the run ends once the board repeats, and otherwise after MAX_CHALLENGE_GENERATIONS generations
+    Survival,
+    /// The random soup of a day, the same for everyone playing on that day. The player changes up to DAILY_EDITS of
+    /// its cells, and scores the cells alive after DAILY_GENERATIONS generations
+    Daily {
+        /// The day, written as 2024-02-29
+        date: String,
+        soup: Board,
+    },
+}
+
+/// A challenge played on a board, scored once the board ran
+pub struct Challenge {
+    pub kind: Kind,
+    pub board: Board,
+    pub phase: Phase,
+    pub generation: usize,
+    /// The two generations before the current one, to tell when the board repeats
+    recent: [Option<Board>; 2],
+}
+
+impl Challenge {
+    /// Starts a challenge on a board
+    /// Input: the board, with the rule and edges it follows, which is emptied
+    pub fn new(mut board: Board) -> Self {
+        for cell in board.live_cells().collect::<Vec<_>>() {
+            board[cell] = CellState::Dead;
+        }
+        Challenge {
+            kind: Kind::Survival,
+            board,
+            phase: Phase::Seeding,
+            generation: 0,
+            recent: [None, None],
+        }
+    }
+
+    /// Starts the daily challenge of a day, on a soup seeded with the day so it's the same everywhere
+    /// Input: the day, counted in days since the first of January of 1970
+    pub fn daily(day: u64) -> Self {
+        let mut soup = Board::random(DAILY_SIZE.0, DAILY_SIZE.1, DAILY_DENSITY, day);
+        soup.boundary = Boundary::Toroidal;
+        Challenge {
+            kind: Kind::Daily {
+                date: date_name(day),
+                soup: soup.clone(),
+            },
+            board: soup,
+            phase: Phase::Seeding,
+            generation: 0,
+            recent: [None, None],
+        }
+    }
+
+    /// Returns how many more cells the player can place, or change on the daily soup
+    pub fn cells_left(&self) -> usize {
+        match &self.kind {
+            Kind::Survival => CELL_BUDGET.saturating_sub(self.board.population()),
+            Kind::Daily { soup, .. } => {
+                DAILY_EDITS.saturating_sub(self.board.differences(soup).count())
+            }
+        }
+    }
+
+    /// Places a cell, or takes back one already placed, while seeding. Cells of the daily soup are changed the same
+    /// way, whether they're alive or dead
+    /// Output: whether the cell changed
+    pub fn toggle(&mut self, cell: Point) -> bool {
+        let is_unchanged = match &self.kind {
+            Kind::Survival => self.board[cell] == CellState::Dead,
+            Kind::Daily { soup, .. } => self.board[cell] == soup[cell],
+        };
+        if self.phase != Phase::Seeding || (is_unchanged && self.cells_left() == 0) {
+            return false;
+        }
+        self.board.toggle_cell_state(cell);
+        true
+    }
+
+    /// Starts running the board, if cells were placed on it or it's the daily soup
+    pub fn start(&mut self) {
+        if self.phase == Phase::Seeding
+            && (self.board.population() > 0 || self.kind != Kind::Survival)
+        {
+            self.phase = Phase::Running;
+        }
+    }
+
+    /// Returns the score of the run so far: the generations survived, or the cells alive on the daily soup
+    pub fn score(&self) -> usize {
+        match self.kind {
+            Kind::Survival => self.generation,
+            Kind::Daily { .. } => self.board.population(),
+        }
+    }
+
+    /// Advances the board one generation, ending the run once the population falls to the threshold or the board
+    /// repeats
+    pub fn step(&mut self) {
+        if self.phase != Phase::Running {
+            return;
+        }
+        if let Kind::Daily { .. } = self.kind {
+            self.board.update_board();
+            self.generation += 1;
+            if self.generation >= DAILY_GENERATIONS {
+                self.phase = Phase::Over;
+            }
+            return;
+        }
+        let previous = self.board.clone();
+        self.board.update_board();
+        let is_repeating = self.board == previous
+            || self.recent[1].as_ref() == Some(&self.board)
+            || self.recent[0].as_ref() == Some(&self.board);
+        self.recent = [self.recent[1].take(), Some(previous)];
+        if self.board.population() <= POPULATION_THRESHOLD || is_repeating {
+            self.phase = Phase::Over;
+            return;
+        }
+        self.generation += 1;
+        if self.generation >= MAX_CHALLENGE_GENERATIONS {
+            self.phase = Phase::Over;
+        }
+    }
+
+    /// Describes the kind of board the challenge is played on, as high scores are only compared between the same kind
+    pub fn setup(&self) -> String {
+        if let Kind::Daily { date, .. } = &self.kind {
+            return format!("daily {date}");
+        }
+        let boundary = match self.board.boundary {
+            Boundary::Bounded => "bounded",
+            Boundary::Toroidal => "toroidal",
+        };
+        format!(
+            "{}x{} {} {boundary}",
+            self.board.width(),
+            self.board.length(),
+            self.board.rule
+        )
+    }
+}
+
+/// Returns the day it is, in UTC so it's the same day everywhere
+/// Output: the day, counted in days since the first of January of 1970
+pub fn today() -> u64 {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    since_epoch.as_secs() / (24 * 60 * 60)
+}
+
+/// Writes a day as its date, such as 2024-02-29
+/// Input: the day, counted in days since the first of January of 1970
+fn date_name(day: u64) -> String {
+    //Howard Hinnant's algorithm, counting from the first of March of year 0 so leap days end the years
+    let days = day as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = era * 400 + year_of_era + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day_of_month:02}")
+}
+
+/// Best scores of the challenges, kept in a file with a "generations setup" line per score, where the setup
+/// tells the size, rule and edges of the board it was scored on
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct HighScores {
+    scores: Vec<(usize, String)>,
+}
+
+impl HighScores {
+    /// Reads the high scores file, with no scores if it doesn't exist yet or can't be read. Lines that can't be read
+    /// are skipped
+    /// Input: the path of the file
+    pub fn load(path: &Path) -> Self {
+        let scores = fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let (generations, setup) = line.trim().split_once(' ')?;
+                Some((generations.parse().ok()?, setup.to_string()))
+            })
+            .collect();
+        HighScores { scores }
+    }
+
+    /// Writes the high scores file, creating its directory if needed
+    /// Input: the path of the file
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let text: String = self
+            .scores
+            .iter()
+            .map(|(generations, setup)| format!("{generations} {setup}\n"))
+            .collect();
+        fs::write(path, text)
+    }
+
+    /// Returns the best scores of a kind of board, from the highest
+    pub fn best(&self, setup: &str) -> Vec<usize> {
+        let mut best: Vec<_> = self
+            .scores
+            .iter()
+            .filter(|(_, scored_setup)| scored_setup == setup)
+            .map(|(generations, _)| *generations)
+            .collect();
+        best.sort_unstable_by(|a, b| b.cmp(a));
+        best
+    }
+
+    /// Adds a score, if it's one of the best HIGH_SCORES_KEPT of its kind of board
+    /// Input: the score, and the kind of board it was scored on
+    /// Output: the position of the score among the best ones, from 1, if it's one of them
+    pub fn add(&mut self, generations: usize, setup: &str) -> Option<usize> {
+        let best = self.best(setup);
+        let rank = best.iter().filter(|score| **score >= generations).count();
+        if rank >= HIGH_SCORES_KEPT {
+            return None;
+        }
+        self.scores.push((generations, setup.to_string()));
+        //The lowest scores of the kind of board are dropped once there are too many
+        if best.len() >= HIGH_SCORES_KEPT {
+            let lowest = best[HIGH_SCORES_KEPT - 1];
+            if let Some(index) = self
+                .scores
+                .iter()
+                .position(|(score, scored_setup)| scored_setup == setup && *score == lowest)
+            {
+                self.scores.remove(index);
+            }
+        }
+        Some(rank + 1)
+    }
+}
+
+/// Runs challenges on the window until Escape goes back to the starting menu. The player clicks on the board to
+/// place or change cells and presses Enter to run it, or to skip to the end of the run. Enter starts a new challenge
+/// of the same kind once one is over, and D switches between the survival challenge and the daily one
+/// Input: the board challenges are played on, with the rule and edges they follow, the theme to draw with, the index
+/// of the speed the board runs at, out of SPEEDS, and the path of the high scores file, if it's known
+/// Output: whether closing the window was asked for
+pub async fn run_challenge(
+    board: Board,
+    theme: &Theme,
+    speed_index: usize,
+    high_scores_path: Option<PathBuf>,
+) -> bool {
+    let mut high_scores = high_scores_path
+        .as_deref()
+        .map(HighScores::load)
+        .unwrap_or_default();
+    let mut challenge = Challenge::new(board.clone());
+    //Position of the last score among the best ones, if it's one of them
+    let mut rank = None;
+    let mut last_update = get_time();
+    loop {
+        if is_quit_requested() {
+            return true;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            return false;
+        }
+        let was_over = challenge.phase == Phase::Over;
+        if is_key_pressed(KeyCode::Enter) {
+            match challenge.phase {
+                Phase::Seeding => challenge.start(),
+                Phase::Running => {
+                    while challenge.phase == Phase::Running {
+                        challenge.step();
+                    }
+                }
+                Phase::Over => {
+                    challenge = match challenge.kind {
+                        Kind::Survival => Challenge::new(board.clone()),
+                        Kind::Daily { .. } => Challenge::daily(today()),
+                    };
+                    rank = None;
+                }
+            }
+        }
+        if challenge.phase != Phase::Running && is_key_pressed(KeyCode::D) {
+            challenge = match challenge.kind {
+                Kind::Survival => Challenge::daily(today()),
+                Kind::Daily { .. } => Challenge::new(board.clone()),
+            };
+            rank = None;
+        }
+        if challenge.phase == Phase::Running && get_time() - last_update >= SPEEDS[speed_index].1 {
+            last_update = get_time();
+            challenge.step();
+        }
+        if !was_over && challenge.phase == Phase::Over {
+            rank = high_scores.add(challenge.score(), &challenge.setup());
+            if let (Some(_), Some(path)) = (rank, &high_scores_path)
+                && let Err(error) = high_scores.save(path)
+            {
+                eprintln!("Couldn't save the high scores: {error}");
+            }
+        }
+
+        //The board is as big as fits under the banner, centred
+        let (width, length) = (challenge.board.width(), challenge.board.length());
+        let cell_size =
+            (screen_width() / width as f32).min((screen_height() - BANNER_MARGIN) / length as f32);
+        let corner = (
+            (screen_width() - cell_size * width as f32) / 2.,
+            BANNER_MARGIN + (screen_height() - BANNER_MARGIN - cell_size * length as f32) / 2.,
+        );
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let (mouse_x, mouse_y) = mouse_position();
+            let (x, y) = (
+                ((mouse_x - corner.0) / cell_size).floor(),
+                ((mouse_y - corner.1) / cell_size).floor(),
+            );
+            if x >= 0. && y >= 0. && (x as usize) < width && (y as usize) < length {
+                challenge.toggle(Point::new(x as usize, y as usize));
+            }
+        }
+
+        clear_background(theme.background);
+        draw_rectangle(
+            corner.0,
+            corner.1,
+            cell_size * width as f32,
+            cell_size * length as f32,
+            theme.dead,
+        );
+        for cell in challenge.board.live_cells() {
+            draw_rectangle(
+                corner.0 + cell.x as f32 * cell_size,
+                corner.1 + cell.y as f32 * cell_size,
+                cell_size,
+                cell_size,
+                theme.alive,
+            );
+        }
+        let score = challenge.score();
+        let banner = match (&challenge.kind, challenge.phase) {
+            (Kind::Survival, Phase::Seeding) => tr_with(
+                "challenge.seeding",
+                &[&challenge.cells_left(), &POPULATION_THRESHOLD],
+            ),
+            (Kind::Daily { date, .. }, Phase::Seeding) => tr_with(
+                "challenge.daily_seeding",
+                &[date, &challenge.cells_left(), &DAILY_GENERATIONS],
+            ),
+            (_, Phase::Running) => tr_with(
+                "challenge.running",
+                &[&challenge.generation, &challenge.board.population()],
+            ),
+            (Kind::Survival, Phase::Over) => match rank {
+                Some(rank) => tr_with("challenge.high_score", &[&score, &rank]),
+                None => tr_with("challenge.over", &[&score]),
+            },
+            (Kind::Daily { .. }, Phase::Over) => match rank {
+                Some(rank) => tr_with("challenge.daily_high_score", &[&score, &rank]),
+                None => tr_with("challenge.daily_over", &[&score]),
+            },
+        };
+        draw_banner(&banner, theme);
+        let best: Vec<_> = high_scores
+            .best(&challenge.setup())
+            .iter()
+            .map(usize::to_string)
+            .collect();
+        if !best.is_empty() {
+            draw_text(
+                &tr_with("challenge.best", &[&best.join(", ")]),
+                20.,
+                screen_height() - 30.,
+                18.,
+                theme.text,
+            );
+        }
+        draw_text(
+            tr("challenge.hint"),
+            20.,
+            screen_height() - 10.,
+            18.,
+            theme.text,
+        );
+        next_frame().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_end_when_the_population_falls_or_the_board_repeats() {
+        let mut challenge = Challenge::new(Board::new(40, 40));
+        //An R-pentomino keeps changing for over a thousand generations, but only has five cells to start with
+        for (x, y) in [(20, 19), (21, 19), (19, 20), (20, 20), (20, 21)] {
+            assert!(challenge.toggle(Point::new(x, y)));
+        }
+        assert_eq!(CELL_BUDGET - 5, challenge.cells_left());
+        challenge.start();
+        challenge.step();
+        assert_eq!(Phase::Over, challenge.phase);
+        assert_eq!(0, challenge.generation);
+
+        //Three blinkers stay above the threshold, but repeat every other generation
+        let mut challenge = Challenge::new(Board::new(20, 20));
+        for row in [2, 8, 14] {
+            for x in 5..8 {
+                challenge.toggle(Point::new(x, row));
+            }
+        }
+        assert!(challenge.toggle(Point::new(15, 15)));
+        assert!(challenge.toggle(Point::new(16, 16)));
+        assert!(challenge.toggle(Point::new(17, 17)));
+        assert!(!challenge.toggle(Point::new(18, 18)));
+        challenge.start();
+        while challenge.phase == Phase::Running {
+            challenge.step();
+        }
+        assert_eq!(3, challenge.generation);
+    }
+
+    #[test]
+    fn the_daily_challenge_is_the_same_all_day() {
+        assert_eq!("1970-01-01", date_name(0));
+        assert_eq!("2024-02-29", date_name(19_782));
+        assert_eq!("2026-10-16", date_name(20_742));
+
+        let mut challenge = Challenge::daily(20_742);
+        assert_eq!("daily 2026-10-16", challenge.setup());
+        assert_eq!(Challenge::daily(20_742).board, challenge.board);
+        assert_ne!(Challenge::daily(20_743).board, challenge.board);
+
+        //Changing a cell back gives its change back
+        assert!(challenge.toggle(Point::new(0, 0)));
+        assert_eq!(DAILY_EDITS - 1, challenge.cells_left());
+        assert!(challenge.toggle(Point::new(0, 0)));
+        assert_eq!(DAILY_EDITS, challenge.cells_left());
+        for x in 0..DAILY_EDITS {
+            assert!(challenge.toggle(Point::new(x, 1)));
+        }
+        assert!(!challenge.toggle(Point::new(0, 2)));
+        assert!(challenge.toggle(Point::new(0, 1)));
+
+        challenge.start();
+        while challenge.phase == Phase::Running {
+            challenge.step();
+        }
+        assert_eq!(DAILY_GENERATIONS, challenge.generation);
+        assert_eq!(challenge.board.population(), challenge.score());
+    }
+
+    #[test]
+    fn only_the_best_scores_of_each_board_are_kept() {
+        let mut high_scores = HighScores::default();
+        for score in [10, 50, 30, 20, 40] {
+            assert!(high_scores.add(score, "20x20 B3/S23 bounded").is_some());
+        }
+        assert_eq!(Some(1), high_scores.add(60, "20x20 B3/S23 bounded"));
+        assert_eq!(None, high_scores.add(5, "20x20 B3/S23 bounded"));
+        assert_eq!(Some(1), high_scores.add(5, "30x30 B3/S23 bounded"));
+        assert_eq!(
+            vec![60, 50, 40, 30, 20],
+            high_scores.best("20x20 B3/S23 bounded")
+        );
+
+        let path = std::env::temp_dir().join(format!("high_scores_{}", std::process::id()));
+        high_scores.save(&path).unwrap();
+        assert_eq!(high_scores, HighScores::load(&path));
+        fs::remove_file(path).unwrap();
+    }
+}
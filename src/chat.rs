@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+
+use crate::locale::tr_with;
+use crate::network::CONNECT_TIMEOUT;
+use crate::simulation::Simulation;
+use crate::{CellState, Pattern, Point};
+
+/// Server chat is read from when no other one is given, Twitch's, which lets anyone read channels without logging in
+pub const TWITCH_CHAT: &str = "irc.chat.twitch.tv:6667";
+
+/// Seconds each viewer has to wait between two placements
+const VIEWER_COOLDOWN: f64 = 5.;
+
+/// Most placements taken from the whole chat in PLACEMENT_WINDOW seconds, so a busy chat can't bury the board
+const MAX_PLACEMENTS: usize = 10;
+/// Seconds placements are counted over for MAX_PLACEMENTS
+const PLACEMENT_WINDOW: f64 = 10.;
+
+/// Something a viewer asked to place on the board, by typing "!cell 10 12" or "!spawn glider 10 12" in chat
+#[derive(Clone, PartialEq, Debug)]
+enum Placement {
+    Cell(Point),
+    Pattern(Pattern, Point),
+}
+
+impl Placement {
+    /// Reads a placement from a chat message. Messages that aren't placements, like most of the chat, give nothing
+    /// Input: the message
+    /// Output: the placement, if the message is one
+    fn parse(message: &str) -> Option<Self> {
+        let words: Vec<_> = message.split_whitespace().collect();
+        let (command, rest) = words.split_first()?;
+        let [name @ .., x, y] = rest else {
+            return None;
+        };
+        let corner = Point::new(x.parse().ok()?, y.parse().ok()?);
+        match (command.to_lowercase().as_str(), name) {
+            ("!cell", []) => Some(Placement::Cell(corner)),
+            //Names can have several words, like "lightweight spaceship"
+            ("!spawn", [_, ..]) => Some(Placement::Pattern(
+                Pattern::from_library(&name.join(" "))?,
+                corner,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Places the cell or pattern on the board, and on the comparison board if there is one. Parts past the edges of
+    /// the board are cut
+    fn apply(&self, simulation: &mut Simulation) {
+        let board = &simulation.board;
+        match self {
+            Placement::Cell(cell) => {
+                if cell.x < board.width()
+                    && cell.y < board.length()
+                    && board[*cell] == CellState::Dead
+                {
+                    simulation.toggle_cell_state(*cell);
+                }
+            }
+            Placement::Pattern(pattern, corner) => {
+                if corner.x < board.width() && corner.y < board.length() {
+                    simulation.board.paste(pattern, *corner);
+                    if let Some(comparison_board) = &mut simulation.comparison_board {
+                        comparison_board.paste(pattern, *corner);
+                    }
+                    simulation.mark_edited();
+                }
+            }
+        }
+    }
+}
+
+/// Keeps chat from placing too much too fast: each viewer waits VIEWER_COOLDOWN seconds between placements, and only
+/// MAX_PLACEMENTS are taken from everyone in PLACEMENT_WINDOW seconds
+#[derive(Default)]
+struct RateLimiter {
+    /// When each viewer last placed something, for the viewers still cooling down
+    last_placements: HashMap<String, f64>,
+    /// When the placements taken in the last PLACEMENT_WINDOW seconds were
+    recent: Vec<f64>,
+}
+
+impl RateLimiter {
+    /// Tells whether a viewer can place something now, counting it as placed if so
+    /// Input: the name of the viewer, and the time, in seconds
+    fn allow(&mut self, viewer: &str, time: f64) -> bool {
+        self.recent
+            .retain(|placed| time - placed < PLACEMENT_WINDOW);
+        //Viewers are forgotten once they can place again, so a busy chat doesn't fill the memory over a long stream
+        self.last_placements
+            .retain(|_, placed| time - *placed < VIEWER_COOLDOWN);
+        let is_cooling_down = self
+            .last_placements
+            .get(viewer)
+            .is_some_and(|placed| time - placed < VIEWER_COOLDOWN);
+        if is_cooling_down || self.recent.len() >= MAX_PLACEMENTS {
+            return false;
+        }
+        self.recent.push(time);
+        self.last_placements.insert(viewer.to_string(), time);
+        true
+    }
+}
+
+/// Reads the chat of a stream, IRC or Twitch, placing on the board the cells and patterns viewers ask for
+pub struct ChatControl {
+    stream: TcpStream,
+    channel: String,
+    incoming: Vec<u8>,
+    limiter: RateLimiter,
+}
+
+impl ChatControl {
+    /// Joins the chat of a channel, without logging in
+    /// Input: the address of the IRC server, such as TWITCH_CHAT, and the channel, with or without its #
+    /// Output: the connection, or why the server couldn't be reached
+    pub fn join(address: &str, channel: &str) -> io::Result<Self> {
+        let socket_address = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "the address has no host"))?;
+        let mut stream = TcpStream::connect_timeout(&socket_address, CONNECT_TIMEOUT)?;
+        let channel = format!("#{}", channel.trim_start_matches('#').to_lowercase());
+        //Twitch lets anyone whose nickname starts with justinfan read chat without a password
+        write!(
+            stream,
+            "NICK justinfan{}\r\nJOIN {channel}\r\n",
+            std::process::id()
+        )?;
+        stream.set_nonblocking(true)?;
+        Ok(ChatControl {
+            stream,
+            channel,
+            incoming: Vec::new(),
+            limiter: RateLimiter::default(),
+        })
+    }
+
+    /// Reads the messages that arrived since the last call, placing what they ask for on the board
+    /// Input: the simulation running the board, and the time, in seconds
+    /// Output: messages about what viewers placed, to show, or an error if the connection was lost
+    pub fn update(&mut self, simulation: &mut Simulation, time: f64) -> io::Result<Vec<String>> {
+        let mut buffer = [0; 4096];
+        loop {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(read) => self.incoming.extend(&buffer[..read]),
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        let mut notices = Vec::new();
+        while let Some(end) = self.incoming.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.incoming.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end();
+            //Servers close the connections that don't answer their pings
+            if let Some(server) = line.strip_prefix("PING ") {
+                self.stream
+                    .write_all(format!("PONG {server}\r\n").as_bytes())?;
+                continue;
+            }
+            let Some((viewer, message)) = chat_message(line, &self.channel) else {
+                continue;
+            };
+            if let Some(placement) = Placement::parse(message)
+                && self.limiter.allow(viewer, time)
+            {
+                placement.apply(simulation);
+                notices.push(tr_with("chat.placed", &[&viewer, &message.trim()]));
+            }
+        }
+        Ok(notices)
+    }
+}
+
+/// Reads who said what from a line sent by an IRC server, such as ":ana!ana@ana.tmi.twitch.tv PRIVMSG #channel :hi"
+/// Input: the line, and the channel chat is read from
+/// Output: the nickname of the viewer and the message, if the line is a message sent to the channel
+fn chat_message<'a>(line: &'a str, channel: &str) -> Option<(&'a str, &'a str)> {
+    let (prefix, rest) = line.strip_prefix(':')?.split_once(' ')?;
+    let (target, message) = rest.strip_prefix("PRIVMSG ")?.split_once(" :")?;
+    let viewer = prefix.split('!').next()?;
+    (target == channel).then_some((viewer, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chat_places_cells_and_patterns_within_limits() {
+        assert_eq!(
+            Some(("ana", "!cell 3 4")),
+            chat_message(
+                ":ana!ana@ana.tmi.twitch.tv PRIVMSG #life :!cell 3 4",
+                "#life"
+            )
+        );
+        assert_eq!(
+            None,
+            chat_message(":ana!ana@host PRIVMSG #other :!cell 3 4", "#life")
+        );
+        assert_eq!(
+            None,
+            chat_message(":tmi.twitch.tv 001 justinfan1 :Welcome", "#life")
+        );
+
+        assert_eq!(
+            Some(Placement::Cell(Point::new(3, 4))),
+            Placement::parse("!cell 3 4")
+        );
+        assert_eq!(
+            Some(Placement::Pattern(
+                Pattern::from_library("Lightweight spaceship").unwrap(),
+                Point::new(1, 2)
+            )),
+            Placement::parse("!SPAWN lightweight spaceship 1 2")
+        );
+        assert_eq!(None, Placement::parse("!spawn dragon 1 2"));
+        assert_eq!(None, Placement::parse("!cell 3"));
+        assert_eq!(None, Placement::parse("nice glider"));
+
+        let mut simulation = Simulation::new(6, 6, 0);
+        Placement::parse("!spawn glider 1 1")
+            .unwrap()
+            .apply(&mut simulation);
+        Placement::parse("!cell 5 5")
+            .unwrap()
+            .apply(&mut simulation);
+        Placement::parse("!cell 9 9")
+            .unwrap()
+            .apply(&mut simulation);
+        assert_eq!(
+            "......\n..#...\n...#..\n.###..\n......\n.....#",
+            simulation.board.to_string()
+        );
+
+        let mut limiter = RateLimiter::default();
+        assert!(limiter.allow("ana", 0.));
+        assert!(!limiter.allow("ana", 1.));
+        assert!(limiter.allow("ana", VIEWER_COOLDOWN));
+        for viewer in 0..MAX_PLACEMENTS - 2 {
+            assert!(limiter.allow(&viewer.to_string(), 6.));
+        }
+        assert!(!limiter.allow("bob", 7.));
+        assert!(limiter.allow("bob", PLACEMENT_WINDOW + 0.5));
+        //Viewers who can place again are forgotten
+        assert!(limiter.allow("carol", 2. * PLACEMENT_WINDOW));
+        assert_eq!(1, limiter.last_placements.len());
+    }
+}
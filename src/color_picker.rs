@@ -0,0 +1,165 @@
+use macroquad::color::hsl_to_rgb;
+use macroquad::prelude::*;
+
+use crate::config::{Config, format_hex_color};
+use crate::locale::tr;
+use crate::text::draw_text;
+use crate::theme::Theme;
+
+/// Colours that can be picked, with the key of the name shown for each one
+const TARGETS: [&str; 3] = ["picker.alive", "picker.dead", "picker.background"];
+
+/// Size of the grid of colours to pick from. Each column is a hue, and each row a lightness, except the last row,
+/// which goes from black to white
+const PALETTE_COLUMNS: usize = 12;
+const PALETTE_ROWS: usize = 6;
+
+/// Returns the colour at a position of the palette
+/// Input: the column and row of the colour
+pub fn palette_color(column: usize, row: usize) -> Color {
+    if row == PALETTE_ROWS - 1 {
+        let lightness = column as f32 / (PALETTE_COLUMNS - 1) as f32;
+        return Color::new(lightness, lightness, lightness, 1.);
+    }
+    hsl_to_rgb(
+        column as f32 / PALETTE_COLUMNS as f32,
+        0.8,
+        (row + 1) as f32 / PALETTE_ROWS as f32,
+    )
+}
+
+/// Finds the colour of the palette under a point of the screen
+/// Input: the screen rectangle of the palette, and the point
+/// Output: the column and row of the colour, if the point is over the palette
+pub fn palette_cell_at(palette: Rect, (x, y): (f32, f32)) -> Option<(usize, usize)> {
+    if !palette.contains(vec2(x, y)) {
+        return None;
+    }
+    let column = ((x - palette.x) / palette.w * PALETTE_COLUMNS as f32) as usize;
+    let row = ((y - palette.y) / palette.h * PALETTE_ROWS as f32) as usize;
+    Some((column.min(PALETTE_COLUMNS - 1), row.min(PALETTE_ROWS - 1)))
+}
+
+/// Small screen to pick the colours of the cells and the background, over the ones of the theme
+#[derive(Default)]
+pub struct ColorPicker {
+    pub is_open: bool,
+    /// Index of the colour being picked, out of TARGETS
+    target: usize,
+}
+
+impl ColorPicker {
+    /// Handles the input of the picker and draws it over an area of the screen
+    /// Input: the config the picked colours are stored in, the theme they replace, and the area to draw in
+    /// Output: whether any colour changed
+    pub fn update(&mut self, config: &mut Config, theme: &Theme, area: Rect) -> bool {
+        if is_key_pressed(KeyCode::Down) {
+            self.target = (self.target + 1) % TARGETS.len();
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.target = (self.target + TARGETS.len() - 1) % TARGETS.len();
+        }
+        if is_key_pressed(KeyCode::O) || is_key_pressed(KeyCode::Escape) {
+            self.is_open = false;
+        }
+
+        let picked = match self.target {
+            0 => &mut config.alive,
+            1 => &mut config.dead,
+            _ => &mut config.background,
+        };
+        let mut changed = false;
+        //Resetting goes back to the colour of the theme
+        if is_key_pressed(KeyCode::R) && picked.is_some() {
+            *picked = None;
+            changed = true;
+        }
+        let palette = Rect::new(
+            area.x + area.w / 10.,
+            area.y + area.h * 0.45,
+            area.w * 0.8,
+            area.h * 0.4,
+        );
+        if is_mouse_button_pressed(MouseButton::Left)
+            && let Some((column, row)) = palette_cell_at(palette, mouse_position())
+        {
+            *picked = Some(palette_color(column, row));
+            changed = true;
+        }
+
+        let theme = config.apply(theme);
+        clear_background(theme.background);
+        draw_text(
+            tr("picker.title"),
+            area.x + area.w / 10.,
+            area.y + area.h / 10.,
+            40.,
+            theme.text,
+        );
+        let colors = [theme.alive, theme.dead, theme.background];
+        for (index, (name, color)) in TARGETS.iter().zip(colors).enumerate() {
+            let y = area.y + area.h * (0.18 + 0.07 * index as f32);
+            if index == self.target {
+                draw_rectangle(
+                    area.x + area.w / 10. - 10.,
+                    y - 24.,
+                    area.w * 0.5,
+                    32.,
+                    theme.highlight,
+                );
+            }
+            draw_rectangle(area.x + area.w / 10., y - 20., 24., 24., color);
+            draw_rectangle_lines(area.x + area.w / 10., y - 20., 24., 24., 2., theme.text);
+            draw_text(
+                &format!("{}: {}", tr(name), format_hex_color(color)),
+                area.x + area.w / 10. + 36.,
+                y,
+                24.,
+                theme.text,
+            );
+        }
+        let cell_width = palette.w / PALETTE_COLUMNS as f32;
+        let cell_height = palette.h / PALETTE_ROWS as f32;
+        for column in 0..PALETTE_COLUMNS {
+            for row in 0..PALETTE_ROWS {
+                draw_rectangle(
+                    palette.x + column as f32 * cell_width,
+                    palette.y + row as f32 * cell_height,
+                    cell_width,
+                    cell_height,
+                    palette_color(column, row),
+                );
+            }
+        }
+        draw_text(
+            tr("picker.hint"),
+            area.x + area.w / 40.,
+            area.y + area.h * 0.93,
+            18.,
+            theme.text,
+        );
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_palette_row_goes_from_black_to_white() {
+        assert_eq!(BLACK.to_vec(), palette_color(0, PALETTE_ROWS - 1).to_vec());
+        assert_eq!(
+            WHITE.to_vec(),
+            palette_color(PALETTE_COLUMNS - 1, PALETTE_ROWS - 1).to_vec()
+        );
+    }
+
+    #[test]
+    fn palette_cells_are_found_under_the_cursor() {
+        let palette = Rect::new(100., 100., 120., 60.);
+        assert_eq!(Some((0, 0)), palette_cell_at(palette, (100., 100.)));
+        assert_eq!(Some((11, 5)), palette_cell_at(palette, (219., 159.)));
+        assert_eq!(None, palette_cell_at(palette, (99., 120.)));
+    }
+}
@@ -0,0 +1,445 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use macroquad::color::Color;
+use macroquad::input::KeyCode;
+
+use crate::Boundary;
+use crate::keys::{Action, key_name, parse_key};
+use crate::locale::Language;
+use crate::music_output::{Axis, MusicMapping, Scale};
+use crate::render::CellShape;
+use crate::settings::{MAX_UI_SCALE, MIN_UI_SCALE};
+use crate::theme::Theme;
+
+/// Settings kept between sessions, in a file with one "key = value" line per setting
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Config {
+    /// Name of the colour theme
+    pub theme: Option<String>,
+    /// Colours picked by the user, replacing the ones of the theme
+    pub alive: Option<Color>,
+    pub dead: Option<Color>,
+    pub background: Option<Color>,
+    /// Name of the palette of the highlights drawn over the board
+    pub palette: Option<String>,
+    /// Index of the speed new boards start with, out of SPEEDS
+    pub speed: Option<usize>,
+    /// Whether lines are drawn between cells
+    pub show_grid: Option<bool>,
+    /// Whether rulers with the indices of rows and columns are drawn along the edges of the board
+    pub show_rulers: Option<bool>,
+    /// Shape alive cells are drawn with
+    pub cell_shape: Option<CellShape>,
+    /// What lies past the edges of new boards
+    pub boundary: Option<Boundary>,
+    /// Volume of the sounds, from 0 to 100
+    pub volume: Option<usize>,
+    /// Whether the ambient track plays
+    pub ambient: Option<bool>,
+    /// How large text is drawn, in percent
+    pub ui_scale: Option<usize>,
+    /// Whether text is drawn larger
+    pub large_text: Option<bool>,
+    /// Whether alive cells are outlined
+    pub cell_outlines: Option<bool>,
+    /// Whether animations are left out
+    pub reduced_motion: Option<bool>,
+    /// Whether the scripts of the scripts directory run on each generation
+    pub scripts: Option<bool>,
+    /// Language the game is shown in
+    pub language: Option<Language>,
+    /// Which way the board is split into the bands played as notes by --midi and --osc
+    pub music_axis: Option<Axis>,
+    /// Scale the bands are played with
+    pub music_scale: Option<Scale>,
+    /// MIDI note of the lowest band
+    pub music_root: Option<u8>,
+    /// MIDI channels births and deaths are played on, from 1 to 16
+    pub birth_channel: Option<u8>,
+    pub death_channel: Option<u8>,
+    /// Width and height of the window when the game was last closed, in logical pixels
+    pub window_size: Option<(u32, u32)>,
+    /// Position of the top left corner of the window when the game was last closed
+    pub window_position: Option<(u32, u32)>,
+    /// Actions bound to other keys than their default ones, with their keys
+    pub keys: Vec<(Action, KeyCode)>,
+}
+
+impl Config {
+    /// Finds where the config file goes, following the conventions of each platform
+    /// Output: the path of the file, if the directory for it is known
+    pub fn path() -> Option<PathBuf> {
+        Some(Self::directory()?.join("config"))
+    }
+
+    /// Finds the directory of the config file, where anything else kept between sessions goes too
+    /// Output: the path of the directory, if it's known
+    pub fn directory() -> Option<PathBuf> {
+        let directory = if cfg!(windows) {
+            PathBuf::from(std::env::var_os("APPDATA")?)
+        } else if let Some(directory) = std::env::var_os("XDG_CONFIG_HOME") {
+            PathBuf::from(directory)
+        } else {
+            PathBuf::from(std::env::var_os("HOME")?).join(".config")
+        };
+        Some(directory.join("conways-game-of-life"))
+    }
+
+    /// Reads the config file, with every setting unset if it doesn't exist yet
+    /// Input: the path of the file
+    /// Output: the config, or a message explaining why it couldn't be read
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                Self::parse(&contents).map_err(|message| format!("{}: {message}", path.display()))
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(error) => Err(format!("couldn't read {}: {error}", path.display())),
+        }
+    }
+
+    /// Writes the config file, creating its directory if needed
+    /// Input: the path of the file
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(path, self.to_text())
+    }
+
+    /// Parses the contents of a config file. Empty lines and lines starting with '#' are ignored
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut config = Config::default();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(format!("expected 'key = value', found '{line}'"))?;
+            let value = value.trim();
+            match key.trim() {
+                "theme" => config.theme = Some(value.to_string()),
+                "alive" => config.alive = Some(parse_hex_color(value)?),
+                "dead" => config.dead = Some(parse_hex_color(value)?),
+                "background" => config.background = Some(parse_hex_color(value)?),
+                "palette" => config.palette = Some(value.to_string()),
+                "speed" => {
+                    config.speed = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("invalid speed '{value}'"))?,
+                    )
+                }
+                "grid" => {
+                    config.show_grid = Some(value.parse().map_err(|_| {
+                        format!("invalid grid setting '{value}', expected true or false")
+                    })?)
+                }
+                "rulers" => {
+                    config.show_rulers = Some(value.parse().map_err(|_| {
+                        format!("invalid rulers setting '{value}', expected true or false")
+                    })?)
+                }
+                "cell_shape" => config.cell_shape = Some(value.parse()?),
+                "edges" => {
+                    config.boundary = Some(match value {
+                        "bounded" => Boundary::Bounded,
+                        "wrap" => Boundary::Toroidal,
+                        _ => {
+                            return Err(format!(
+                                "invalid edges '{value}', expected bounded or wrap"
+                            ));
+                        }
+                    })
+                }
+                "volume" => {
+                    config.volume = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|volume| *volume <= 100)
+                            .ok_or(format!("invalid volume '{value}', expected 0 to 100"))?,
+                    )
+                }
+                "ambient" => {
+                    config.ambient = Some(value.parse().map_err(|_| {
+                        format!("invalid ambient setting '{value}', expected true or false")
+                    })?)
+                }
+                "ui_scale" => config.ui_scale = Some(
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|ui_scale| (MIN_UI_SCALE..=MAX_UI_SCALE).contains(ui_scale))
+                        .ok_or(format!(
+                            "invalid UI scale '{value}', expected {MIN_UI_SCALE} to {MAX_UI_SCALE}"
+                        ))?,
+                ),
+                "large_text" => {
+                    config.large_text = Some(value.parse().map_err(|_| {
+                        format!("invalid large text setting '{value}', expected true or false")
+                    })?)
+                }
+                "cell_outlines" => {
+                    config.cell_outlines = Some(value.parse().map_err(|_| {
+                        format!("invalid cell outlines setting '{value}', expected true or false")
+                    })?)
+                }
+                "reduced_motion" => {
+                    config.reduced_motion = Some(value.parse().map_err(|_| {
+                        format!("invalid reduced motion setting '{value}', expected true or false")
+                    })?)
+                }
+                "scripts" => {
+                    config.scripts = Some(value.parse().map_err(|_| {
+                        format!("invalid scripts setting '{value}', expected true or false")
+                    })?)
+                }
+                "language" => config.language = Some(value.parse()?),
+                "music_axis" => config.music_axis = Some(value.parse()?),
+                "music_scale" => config.music_scale = Some(value.parse()?),
+                "music_root" => {
+                    config.music_root = Some(value.parse().ok().filter(|note| *note <= 127).ok_or(
+                        format!("invalid music root '{value}', expected a MIDI note from 0 to 127"),
+                    )?)
+                }
+                "birth_channel" => config.birth_channel = Some(parse_channel(value)?),
+                "death_channel" => config.death_channel = Some(parse_channel(value)?),
+                "window_size" => {
+                    config.window_size = Some(
+                        parse_pair(value, 'x')
+                            .filter(|(width, height)| *width > 0 && *height > 0)
+                            .ok_or(format!(
+                                "invalid window size '{value}', expected one like 1280x720"
+                            ))?,
+                    )
+                }
+                "window_position" => {
+                    config.window_position = Some(parse_pair(value, ',').ok_or(format!(
+                        "invalid window position '{value}', expected one like 100,50"
+                    ))?)
+                }
+                key if key.starts_with("key.") => {
+                    let action = key.trim_start_matches("key.").parse()?;
+                    config.keys.push((action, parse_key(value)?));
+                }
+                key => return Err(format!("unknown setting '{key}'")),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Writes the settings that are set in the format of the config file
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        if let Some(theme) = &self.theme {
+            writeln!(text, "theme = {theme}").unwrap();
+        }
+        let colors = [
+            ("alive", self.alive),
+            ("dead", self.dead),
+            ("background", self.background),
+        ];
+        for (key, color) in colors {
+            if let Some(color) = color {
+                writeln!(text, "{key} = {}", format_hex_color(color)).unwrap();
+            }
+        }
+        if let Some(palette) = &self.palette {
+            writeln!(text, "palette = {palette}").unwrap();
+        }
+        if let Some(speed) = self.speed {
+            writeln!(text, "speed = {speed}").unwrap();
+        }
+        if let Some(show_grid) = self.show_grid {
+            writeln!(text, "grid = {show_grid}").unwrap();
+        }
+        if let Some(show_rulers) = self.show_rulers {
+            writeln!(text, "rulers = {show_rulers}").unwrap();
+        }
+        if let Some(cell_shape) = self.cell_shape {
+            writeln!(text, "cell_shape = {cell_shape}").unwrap();
+        }
+        if let Some(boundary) = self.boundary {
+            let edges = match boundary {
+                Boundary::Bounded => "bounded",
+                Boundary::Toroidal => "wrap",
+            };
+            writeln!(text, "edges = {edges}").unwrap();
+        }
+        if let Some(volume) = self.volume {
+            writeln!(text, "volume = {volume}").unwrap();
+        }
+        if let Some(ambient) = self.ambient {
+            writeln!(text, "ambient = {ambient}").unwrap();
+        }
+        if let Some(ui_scale) = self.ui_scale {
+            writeln!(text, "ui_scale = {ui_scale}").unwrap();
+        }
+        if let Some(large_text) = self.large_text {
+            writeln!(text, "large_text = {large_text}").unwrap();
+        }
+        if let Some(cell_outlines) = self.cell_outlines {
+            writeln!(text, "cell_outlines = {cell_outlines}").unwrap();
+        }
+        if let Some(reduced_motion) = self.reduced_motion {
+            writeln!(text, "reduced_motion = {reduced_motion}").unwrap();
+        }
+        if let Some(scripts) = self.scripts {
+            writeln!(text, "scripts = {scripts}").unwrap();
+        }
+        if let Some(language) = self.language {
+            writeln!(text, "language = {language}").unwrap();
+        }
+        if let Some(music_axis) = self.music_axis {
+            writeln!(text, "music_axis = {music_axis}").unwrap();
+        }
+        if let Some(music_scale) = self.music_scale {
+            writeln!(text, "music_scale = {music_scale}").unwrap();
+        }
+        if let Some(music_root) = self.music_root {
+            writeln!(text, "music_root = {music_root}").unwrap();
+        }
+        if let Some(birth_channel) = self.birth_channel {
+            writeln!(text, "birth_channel = {birth_channel}").unwrap();
+        }
+        if let Some(death_channel) = self.death_channel {
+            writeln!(text, "death_channel = {death_channel}").unwrap();
+        }
+        if let Some((width, height)) = self.window_size {
+            writeln!(text, "window_size = {width}x{height}").unwrap();
+        }
+        if let Some((x, y)) = self.window_position {
+            writeln!(text, "window_position = {x},{y}").unwrap();
+        }
+        for (action, key) in &self.keys {
+            writeln!(text, "key.{} = {}", action.name(), key_name(*key)).unwrap();
+        }
+        text
+    }
+
+    /// Returns how births and deaths are turned into notes, with the defaults of what isn't set
+    pub fn music_mapping(&self) -> MusicMapping {
+        let default = MusicMapping::default();
+        MusicMapping {
+            axis: self.music_axis.unwrap_or(default.axis),
+            scale: self.music_scale.unwrap_or(default.scale),
+            root: self.music_root.unwrap_or(default.root),
+            birth_channel: self.birth_channel.unwrap_or(default.birth_channel),
+            death_channel: self.death_channel.unwrap_or(default.death_channel),
+        }
+    }
+
+    /// Replaces the colours of a theme with the ones picked by the user
+    /// Input: the theme
+    /// Output: the theme with the picked colours
+    pub fn apply(&self, theme: &Theme) -> Theme {
+        Theme {
+            alive: self.alive.unwrap_or(theme.alive),
+            dead: self.dead.unwrap_or(theme.dead),
+            background: self.background.unwrap_or(theme.background),
+            ..*theme
+        }
+    }
+}
+
+/// Parses a colour written as "#rrggbb", with the '#' being optional
+pub fn parse_hex_color(text: &str) -> Result<Color, String> {
+    let digits = text.strip_prefix('#').unwrap_or(text);
+    if digits.len() != 6 || !digits.chars().all(|digit| digit.is_ascii_hexdigit()) {
+        return Err(format!(
+            "invalid colour '{text}', expected one like #1a2b3c"
+        ));
+    }
+    Ok(Color::from_hex(u32::from_str_radix(digits, 16).unwrap()))
+}
+
+/// Parses two numbers written with a separator between them, like the size or position of the window
+/// Input: the text, and the separator
+/// Output: both numbers, if the text has them
+fn parse_pair(text: &str, separator: char) -> Option<(u32, u32)> {
+    let (first, second) = text.split_once(separator)?;
+    Some((first.trim().parse().ok()?, second.trim().parse().ok()?))
+}
+
+/// Parses a MIDI channel, numbered from 1 to 16
+fn parse_channel(text: &str) -> Result<u8, String> {
+    text.parse()
+        .ok()
+        .filter(|channel| (1..=16).contains(channel))
+        .ok_or(format!("invalid MIDI channel '{text}', expected 1 to 16"))
+}
+
+/// Writes a colour as "#rrggbb"
+pub fn format_hex_color(color: Color) -> String {
+    let [r, g, b] = [color.r, color.g, color.b].map(|channel| (channel * 255.).round() as u8);
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configs_are_saved_and_read_back() {
+        let config = Config {
+            theme: Some("Dark".to_string()),
+            alive: Some(Color::from_hex(0x12ab34)),
+            dead: None,
+            background: Some(Color::from_hex(0xffffff)),
+            palette: Some("tritanopia".to_string()),
+            speed: Some(3),
+            show_grid: Some(true),
+            show_rulers: Some(false),
+            cell_shape: Some(CellShape::Circle),
+            boundary: Some(Boundary::Toroidal),
+            volume: Some(30),
+            ambient: Some(true),
+            ui_scale: Some(150),
+            large_text: Some(true),
+            cell_outlines: None,
+            reduced_motion: Some(false),
+            scripts: Some(true),
+            language: Some(Language::Spanish),
+            music_axis: Some(Axis::Rows),
+            music_scale: Some(Scale::Minor),
+            music_root: Some(36),
+            birth_channel: Some(10),
+            death_channel: None,
+            window_size: Some((1280, 720)),
+            window_position: Some((100, 50)),
+            keys: vec![(Action::Pause, KeyCode::P), (Action::Watch, KeyCode::Space)],
+        };
+        assert_eq!(Ok(config.clone()), Config::parse(&config.to_text()));
+    }
+
+    #[test]
+    fn hex_colours_need_six_digits() {
+        assert_eq!(Ok(Color::from_hex(0x00ff80)), parse_hex_color("00FF80"));
+        assert_eq!("#00ff80", format_hex_color(Color::from_hex(0x00ff80)));
+        assert!(parse_hex_color("#fff").is_err());
+        assert!(parse_hex_color("#gggggg").is_err());
+    }
+
+    #[test]
+    fn invalid_lines_are_rejected() {
+        assert!(Config::parse("# comment\n\ntheme = Dark").is_ok());
+        assert!(Config::parse("theme Dark").is_err());
+        assert!(Config::parse("zoom = 2").is_err());
+        assert!(Config::parse("speed = fast").is_err());
+        assert!(Config::parse("volume = 101").is_err());
+        assert!(Config::parse("ui_scale = 20").is_err());
+        assert!(Config::parse("large_text = yes").is_err());
+        assert!(Config::parse("music_root = 128").is_err());
+        assert!(Config::parse("birth_channel = 0").is_err());
+        assert!(Config::parse("death_channel = 17").is_err());
+        assert!(Config::parse("window_size = 0x720").is_err());
+        assert!(Config::parse("window_position = -10,50").is_err());
+        assert!(Config::parse("key.pause = Escape").is_err());
+        assert!(Config::parse("key.jump = J").is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use std::io::{self, Read};
+use std::net::IpAddr;
+
+use serde::Deserialize;
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::formats::{PatternMeta, write_rle};
+use crate::simulation::Simulation;
+use crate::{CellState, Pattern, Point};
+
+/// Most generations a single request can step the board by. Requests are answered in the game loop, so this keeps
+/// the game from freezing on big boards
+const MAX_STEPS: usize = 1000;
+
+/// Longest body a request can have, in bytes, so a request can't fill the memory of the game
+const MAX_BODY_LENGTH: u64 = 1 << 20;
+
+/// A cell to set, as sent to POST /cells
+#[derive(Deserialize)]
+struct CellChange {
+    x: usize,
+    y: usize,
+    state: CellState,
+}
+
+/// What a request is answered with: its status code, the type of its body, and the body
+struct Answer {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Answer {
+    fn json(status: u16, body: serde_json::Value) -> Self {
+        Answer {
+            status,
+            content_type: "application/json",
+            body: body.to_string(),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Answer::json(status, json!({ "error": message.into() }))
+    }
+}
+
+/// Lets other programs, like scripts or home automation, read and drive the board over HTTP:
+///
+/// - GET /board: the board as JSON, with its generation, whether it's paused and its population
+/// - GET /board.rle: the board as an RLE pattern, with its rule
+/// - POST /cells: sets the cells of a JSON list like `[{"x": 3, "y": 4, "state": "alive"}]`
+/// - POST /step?generations=N: steps the board N generations, 1 if left out
+/// - POST /pause and POST /resume: pause and resume the board
+///
+/// Every POST is answered with the board as GET /board gives it
+pub struct ControlServer {
+    server: Server,
+}
+
+impl ControlServer {
+    /// Starts answering requests on a port
+    /// Input: the address of the network interface to listen on, like 127.0.0.1 to only answer this computer, and
+    /// the port
+    /// Output: the server, or why the port couldn't be listened on
+    pub fn start(address: IpAddr, port: u16) -> io::Result<Self> {
+        let server = Server::http((address, port)).map_err(io::Error::other)?;
+        Ok(ControlServer { server })
+    }
+
+    /// Answers every request that arrived since the last call, without waiting for more
+    /// Input: the simulation running the board
+    pub fn answer(&self, simulation: &mut Simulation) {
+        while let Ok(Some(request)) = self.server.try_recv() {
+            answer_request(request, simulation);
+        }
+    }
+}
+
+/// Reads a request, does what it asks and answers it. Requests that can't be answered anymore are dropped
+/// Input: the request, and the simulation running the board
+fn answer_request(mut request: Request, simulation: &mut Simulation) {
+    let mut body = String::new();
+    let mut reader = request.as_reader().take(MAX_BODY_LENGTH + 1);
+    let answer = match reader.read_to_string(&mut body) {
+        Ok(length) if length as u64 > MAX_BODY_LENGTH => Answer::error(
+            413,
+            format!("the body is longer than {MAX_BODY_LENGTH} bytes"),
+        ),
+        Ok(_) => handle(request.method(), request.url(), &body, simulation),
+        Err(_) => Answer::error(400, "the body isn't text"),
+    };
+    let header = Header::from_bytes("Content-Type", answer.content_type).unwrap();
+    let response = Response::from_string(answer.body)
+        .with_status_code(answer.status)
+        .with_header(header);
+    let _ = request.respond(response);
+}
+
+/// Works out the answer to a request, changing the board as it asks
+/// Input: the method of the request, its URL, its body, and the simulation running the board
+/// Output: the answer
+fn handle(method: &Method, url: &str, body: &str, simulation: &mut Simulation) -> Answer {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let parameter = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find_map(|(key, value)| (key == name).then_some(value))
+    };
+    match (method, path) {
+        (Method::Get, "/board") => Answer::json(200, status(simulation)),
+        (Method::Get, "/board.rle") => {
+            let meta = PatternMeta {
+                rule: Some(simulation.board.rule.to_string()),
+                ..PatternMeta::default()
+            };
+            Answer {
+                status: 200,
+                content_type: "text/plain",
+                body: write_rle(&Pattern::from_board(&simulation.board), &meta),
+            }
+        }
+        (Method::Post, "/cells") => {
+            let changes: Vec<CellChange> = match serde_json::from_str(body) {
+                Ok(changes) => changes,
+                Err(error) => return Answer::error(400, format!("invalid cells: {error}")),
+            };
+            let board = &simulation.board;
+            if let Some(change) = changes
+                .iter()
+                .find(|change| change.x >= board.width() || change.y >= board.length())
+            {
+                return Answer::error(
+                    400,
+                    format!("({}, {}) is outside the board", change.x, change.y),
+                );
+            }
+            for change in changes {
+                if simulation.board[Point::new(change.x, change.y)] != change.state {
+                    simulation.toggle_cell_state(Point::new(change.x, change.y));
+                }
+            }
+            Answer::json(200, status(simulation))
+        }
+        (Method::Post, "/step") => {
+            let generations = match parameter("generations").map(str::parse::<usize>) {
+                None => 1,
+                Some(Ok(generations)) if generations <= MAX_STEPS => generations,
+                Some(_) => {
+                    return Answer::error(
+                        400,
+                        format!("generations has to be a number up to {MAX_STEPS}"),
+                    );
+                }
+            };
+            //Boards joined or watched over the network are stepped by the game running them
+            if simulation.is_remote {
+                return Answer::error(409, "the board is run by another game");
+            }
+            if generations > 0 {
+                simulation.step_by(generations);
+            }
+            Answer::json(200, status(simulation))
+        }
+        (Method::Post, "/pause" | "/resume") => {
+            simulation.is_paused = path == "/pause";
+            Answer::json(200, status(simulation))
+        }
+        (_, "/board" | "/board.rle" | "/cells" | "/step" | "/pause" | "/resume") => {
+            Answer::error(405, format!("{path} doesn't take {method} requests"))
+        }
+        _ => Answer::error(404, format!("there's nothing at {path}")),
+    }
+}
+
+/// Describes the board, with its generation, whether it's paused and its population
+fn status(simulation: &Simulation) -> serde_json::Value {
+    json!({
+        "generation": simulation.generation,
+        "is_paused": simulation.is_paused,
+        "population": simulation.board.population(),
+        "board": simulation.board,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_drive_the_board() {
+        let mut simulation = Simulation::new(5, 5, 0);
+        let answer = handle(
+            &Method::Post,
+            "/cells",
+            r#"[{"x": 1, "y": 2, "state": "alive"}, {"x": 2, "y": 2, "state": "alive"},
+                {"x": 3, "y": 2, "state": "alive"}]"#,
+            &mut simulation,
+        );
+        assert_eq!(200, answer.status);
+        assert_eq!(3, simulation.board.population());
+
+        let answer = handle(&Method::Post, "/step?generations=3", "", &mut simulation);
+        let status: serde_json::Value = serde_json::from_str(&answer.body).unwrap();
+        assert_eq!(3, status["generation"]);
+        assert_eq!(
+            json!([".....", "..#..", "..#..", "..#..", "....."]),
+            status["board"]["rows"]
+        );
+        assert_eq!(
+            "x = 5, y = 5, rule = B3/S23\n$2bo$2bo$2bo!\n",
+            handle(&Method::Get, "/board.rle", "", &mut simulation).body
+        );
+
+        handle(&Method::Post, "/resume", "", &mut simulation);
+        assert!(!simulation.is_paused);
+        handle(&Method::Post, "/pause", "", &mut simulation);
+        assert!(simulation.is_paused);
+
+        let status = |url: &str, body: &str, simulation: &mut Simulation| {
+            handle(&Method::Post, url, body, simulation).status
+        };
+        assert_eq!(
+            400,
+            status(
+                "/cells",
+                r#"[{"x": 5, "y": 0, "state": "alive"}]"#,
+                &mut simulation
+            )
+        );
+        assert_eq!(400, status("/cells", "alive", &mut simulation));
+        assert_eq!(400, status("/step?generations=many", "", &mut simulation));
+        assert_eq!(400, status("/step?generations=10000", "", &mut simulation));
+        assert_eq!(404, status("/jump", "", &mut simulation));
+        assert_eq!(
+            405,
+            handle(&Method::Delete, "/board", "", &mut simulation).status
+        );
+        simulation.is_remote = true;
+        assert_eq!(409, status("/step", "", &mut simulation));
+    }
+}
@@ -0,0 +1,186 @@
+use macroquad::prelude::*;
+
+use crate::lessons::LESSONS;
+use crate::patterns::LIBRARY;
+use crate::rules::LifeLikeRule;
+use crate::theme::Theme;
+use crate::{Board, Boundary, Pattern};
+
+/// Seconds without input on the starting menu before the demo starts
+const IDLE_SECONDS: f64 = 8.;
+
+/// Seconds the demo takes to fade in
+const FADE_SECONDS: f64 = 2.;
+
+/// How see-through the demo is once faded in, so the menu stays readable over it
+const DEMO_OPACITY: f32 = 0.25;
+
+/// Size of the cells of the demo, in pixels. The board is as big as fits in the window
+const DEMO_CELL_SIZE: f32 = 10.;
+
+/// Seconds between generations of the demo
+const DEMO_STEP_TIME: f64 = 0.08;
+
+/// Generations each pattern runs for before the next one replaces it
+const DEMO_GENERATIONS: usize = 300;
+
+/// Returns the patterns the demo cycles through: the ones of the lessons, then the ones of the library
+fn demo_patterns() -> Vec<Pattern> {
+    LESSONS
+        .iter()
+        .map(|lesson| lesson.pattern())
+        .chain(
+            LIBRARY.iter().map(|(_, cells)| {
+                Pattern::parse_plaintext(cells).expect("library patterns are valid")
+            }),
+        )
+        .collect()
+}
+
+/// Famous patterns running behind the starting menu while nobody uses it, following the rule picked on it so they also
+/// preview it
+pub struct Demo {
+    patterns: Vec<Pattern>,
+    /// Index of the pattern running, out of patterns
+    pattern_index: usize,
+    board: Board,
+    generation: usize,
+    /// Width and length the board was asked to have, which it's bigger than if the pattern doesn't fit
+    size: (usize, usize),
+    /// Rule the board follows. Picking another one starts the pattern over
+    rule: LifeLikeRule,
+    /// When the menu was last used, and when the board last stepped, in seconds
+    last_input: f64,
+    last_step: f64,
+    /// Where the mouse was on the last frame, to notice it moving
+    last_mouse: Vec2,
+}
+
+impl Demo {
+    /// Creates the demo, waiting for the menu to be idle
+    /// Input: the time, in seconds
+    pub fn new(time: f64) -> Self {
+        Demo {
+            patterns: demo_patterns(),
+            pattern_index: 0,
+            board: Board::new(1, 1),
+            generation: 0,
+            size: (1, 1),
+            rule: LifeLikeRule::conway(),
+            last_input: time,
+            last_step: time,
+            last_mouse: Vec2::ZERO,
+        }
+    }
+
+    /// Starts a pattern over in the centre of an empty board whose edges wrap around
+    /// Input: the index of the pattern, out of patterns, and the width and length of the board
+    fn start(&mut self, pattern_index: usize, (width, length): (usize, usize)) {
+        let pattern = &self.patterns[pattern_index];
+        let mut board = Board::new(width.max(pattern.width), length.max(pattern.height));
+        board.rule = self.rule;
+        board.boundary = Boundary::Toroidal;
+        let corner = (
+            (board.width() - pattern.width) / 2,
+            (board.length() - pattern.height) / 2,
+        );
+        board.paste(pattern, corner);
+        self.pattern_index = pattern_index;
+        self.board = board;
+        self.size = (width, length);
+        self.generation = 0;
+    }
+
+    /// Steps the board one generation, moving on to the next pattern once it ran for DEMO_GENERATIONS generations or
+    /// died out. The pattern starts over instead if the rule or the size of the board changed
+    /// Input: the rule picked, and the width and length of the board
+    fn step(&mut self, rule: LifeLikeRule, size: (usize, usize)) {
+        if rule != self.rule || size != self.size {
+            self.rule = rule;
+            self.start(self.pattern_index, size);
+            return;
+        }
+        self.board.update_board();
+        self.generation += 1;
+        if self.generation >= DEMO_GENERATIONS || self.board.population() == 0 {
+            self.start((self.pattern_index + 1) % self.patterns.len(), size);
+        }
+    }
+
+    /// Draws the demo behind the menu once it has been idle for IDLE_SECONDS seconds, fading it in, and hides it as
+    /// soon as a key is pressed or the mouse moves
+    /// Input: the rule picked on the menu, the theme to draw with, and the time, in seconds
+    pub fn update(&mut self, rule: LifeLikeRule, theme: &Theme, time: f64) {
+        let mouse = Vec2::from(mouse_position());
+        if mouse != self.last_mouse
+            || !get_keys_down().is_empty()
+            || is_mouse_button_down(MouseButton::Left)
+            || mouse_wheel() != (0., 0.)
+        {
+            self.last_input = time;
+            self.last_mouse = mouse;
+        }
+        let size = (
+            ((screen_width() / DEMO_CELL_SIZE) as usize).max(1),
+            ((screen_height() / DEMO_CELL_SIZE) as usize).max(1),
+        );
+        let idle_time = time - self.last_input - IDLE_SECONDS;
+        if idle_time < 0. {
+            //The demo starts with the first pattern again next time
+            if self.pattern_index != 0 || self.generation != 0 {
+                self.rule = rule;
+                self.start(0, size);
+            }
+            return;
+        }
+        if time - self.last_step >= DEMO_STEP_TIME {
+            self.last_step = time;
+            self.step(rule, size);
+        }
+        let color = Color {
+            a: DEMO_OPACITY * (idle_time / FADE_SECONDS).min(1.) as f32,
+            ..theme.alive
+        };
+        for cell in self.board.live_cells() {
+            draw_rectangle(
+                cell.x as f32 * DEMO_CELL_SIZE,
+                cell.y as f32 * DEMO_CELL_SIZE,
+                DEMO_CELL_SIZE,
+                DEMO_CELL_SIZE,
+                color,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_demo_cycles_patterns_under_the_rule_picked() {
+        let mut demo = Demo::new(0.);
+        demo.start(0, (40, 30));
+        assert!(demo.board.population() > 0);
+        for _ in 0..DEMO_GENERATIONS {
+            demo.step(LifeLikeRule::conway(), (40, 30));
+        }
+        assert_eq!(1, demo.pattern_index);
+        assert_eq!(0, demo.generation);
+
+        //Picking another rule starts the pattern over under it
+        demo.step(LifeLikeRule::conway(), (40, 30));
+        assert_eq!(1, demo.generation);
+        let highlife = LifeLikeRule::preset(1);
+        demo.step(highlife, (40, 30));
+        assert_eq!(0, demo.generation);
+        assert_eq!(highlife, demo.board.rule);
+
+        //Patterns bigger than the window still get a board they fit on
+        demo.start(1, (5, 5));
+        assert!(demo.board.width() >= demo.patterns[1].width);
+        assert!(demo.board.length() >= demo.patterns[1].height);
+        demo.step(highlife, (5, 5));
+        assert_eq!(1, demo.generation);
+    }
+}
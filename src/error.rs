@@ -0,0 +1,48 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// What can go wrong when using the simulation as a library
+#[derive(Debug)]
+pub enum GolError {
+    /// A pattern or board written as text couldn't be read, with a message explaining why
+    Parse(String),
+    /// A rulestring isn't in B/S notation, with a message explaining why
+    InvalidRule(String),
+    /// A board would have no cells, or more than fit in memory
+    InvalidDimensions { width: usize, length: usize },
+    /// A board counts other cells than the eight around each cell as neighbours, which fixed boards can't
+    UnsupportedNeighborhood,
+    /// A file couldn't be read or written
+    Io { path: PathBuf, source: io::Error },
+    /// Something couldn't be written to an output that isn't a file, like the terminal
+    Output(io::Error),
+}
+
+impl fmt::Display for GolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GolError::Parse(message) | GolError::InvalidRule(message) => write!(f, "{message}"),
+            GolError::InvalidDimensions { width, length } => {
+                write!(f, "a board can't be {width} by {length} cells")
+            }
+            GolError::UnsupportedNeighborhood => {
+                write!(
+                    f,
+                    "fixed boards only count the eight cells around each cell as neighbours"
+                )
+            }
+            GolError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            GolError::Output(source) => write!(f, "{source}"),
+        }
+    }
+}
+
+impl std::error::Error for GolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GolError::Io { source, .. } | GolError::Output(source) => Some(source),
+            _ => None,
+        }
+    }
+}
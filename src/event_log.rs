@@ -0,0 +1,115 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::CellEvent;
+
+/// Format of the lines written to the event log
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum LogFormat {
+    Csv,
+    JsonLines,
+}
+
+/// Writes every birth and death of a board to a file, one event per line, for external analysis tools
+pub struct EventLog {
+    writer: Box<dyn Write>,
+    format: LogFormat,
+}
+
+impl EventLog {
+    /// Creates the log file, overwriting it if it exists. The format is CSV for .csv files, and JSON lines otherwise
+    /// Input: the path of the file
+    /// Output: the event log, or the error that prevented creating the file
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let format = match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("csv") => LogFormat::Csv,
+            _ => LogFormat::JsonLines,
+        };
+        Self::new(Box::new(BufWriter::new(File::create(path)?)), format)
+    }
+
+    fn new(mut writer: Box<dyn Write>, format: LogFormat) -> io::Result<Self> {
+        if format == LogFormat::Csv {
+            writeln!(writer, "generation,x,y,event")?;
+        }
+        Ok(EventLog { writer, format })
+    }
+
+    /// Logs every cell that changed state on a generation
+    /// Input: the generation number of the new board, and the births and deaths that led to it
+    pub fn log_events(
+        &mut self,
+        generation: usize,
+        events: impl IntoIterator<Item = CellEvent>,
+    ) -> io::Result<()> {
+        for event in events {
+            let (x, y, event) = match event {
+                CellEvent::Birth { x, y } => (x, y, "birth"),
+                CellEvent::Death { x, y } => (x, y, "death"),
+            };
+            match self.format {
+                LogFormat::Csv => writeln!(self.writer, "{generation},{x},{y},{event}")?,
+                LogFormat::JsonLines => writeln!(
+                    self.writer,
+                    r#"{{"generation":{generation},"x":{x},"y":{y},"event":"{event}"}}"#
+                )?,
+            }
+        }
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::Board;
+
+    /// Writer that keeps everything written to it, so tests can inspect it
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn blinker_step(format: LogFormat) -> String {
+        let buffer = SharedBuffer::default();
+        let mut log = EventLog::new(Box::new(buffer.clone()), format).unwrap();
+        let mut board = Board::new(3, 3);
+        board.toggle_cell_state((0, 1));
+        board.toggle_cell_state((1, 1));
+        board.toggle_cell_state((2, 1));
+        board.record_events(true);
+        board.update_board();
+
+        log.log_events(1, board.drain_events()).unwrap();
+        String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap()
+    }
+
+    #[test]
+    fn csv_log_has_header_and_one_line_per_event() {
+        assert_eq!(
+            "generation,x,y,event\n1,1,0,birth\n1,0,1,death\n1,2,1,death\n1,1,2,birth\n",
+            blinker_step(LogFormat::Csv)
+        );
+    }
+
+    #[test]
+    fn json_lines_log_has_one_object_per_event() {
+        let log = blinker_step(LogFormat::JsonLines);
+        assert_eq!(4, log.lines().count());
+        assert_eq!(
+            r#"{"generation":1,"x":1,"y":0,"event":"birth"}"#,
+            log.lines().next().unwrap()
+        );
+    }
+}
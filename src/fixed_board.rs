@@ -0,0 +1,273 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Index, IndexMut};
+
+use crate::board::count_neighbours;
+use crate::neighborhood::{Moore, Neighborhood};
+use crate::rules::{LifeLikeRule, NeighborCounts, Rule};
+use crate::{Board, Boundary, CellState, GolError, Point, StepStats};
+
+/// A board whose size is known when compiling, with its cells kept in arrays instead of on the heap. Stepping it
+/// doesn't allocate, which makes it faster than a [`Board`] for small boards, and usable where allocating isn't wanted.
+/// Cells follow the same rules as on a `Board`, always counting the eight cells around each cell as its neighbours
+///
+/// ```
+/// use conways_game_of_life::{Board, CellState, FixedBoard};
+///
+/// // A blinker goes from a row of three cells to a column
+/// let mut board = FixedBoard::<5, 5>::new();
+/// for x in 1..4 {
+///     board.toggle_cell_state((x, 2));
+/// }
+/// board.update_board();
+/// assert_eq!(CellState::Alive, board[(2, 1)]);
+/// assert_eq!(CellState::Dead, board[(1, 2)]);
+///
+/// // Both kinds of board run the same way, and can be turned into each other
+/// let mut heap_board = Board::from(&board);
+/// heap_board.update_board();
+/// board.update_board();
+/// assert_eq!(heap_board, Board::from(&board));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct FixedBoard<const W: usize, const H: usize> {
+    cells: [[CellState; W]; H],
+    /// Rule the cells follow on every generation
+    pub rule: LifeLikeRule,
+    /// What lies past the edges of the board
+    pub boundary: Boundary,
+}
+
+impl<const W: usize, const H: usize> FixedBoard<W, H> {
+    /// Creates a board with every cell dead, following the standard rules, B3/S23
+    pub fn new() -> Self {
+        FixedBoard {
+            cells: [[CellState::Dead; W]; H],
+            rule: LifeLikeRule::conway(),
+            boundary: Boundary::Bounded,
+        }
+    }
+
+    /// Swaps the state of a cell, to edit the board by hand
+    /// Input: the cell, as a point or a column and row
+    pub fn toggle_cell_state(&mut self, cell: impl Into<Point>) {
+        let cell = cell.into();
+        self[cell] = match self[cell] {
+            CellState::Alive => CellState::Dead,
+            CellState::Dead => CellState::Alive,
+        };
+    }
+
+    /// Updates the states of every cell in the board, following the rule of the board
+    /// Output: how many cells were born and how many died
+    pub fn update_board(&mut self) -> StepStats {
+        let rule = self.rule;
+        self.update_board_with(&rule)
+    }
+
+    /// Updates the states of every cell in the board, following any rule instead of the one of the board
+    /// Input: the rule
+    /// Output: how many cells were born and how many died
+    pub fn update_board_with<R: Rule + ?Sized>(&mut self, rule: &R) -> StepStats {
+        //The cells are copied on the stack, so nothing is allocated
+        let old_cells = self.cells;
+        let mut stats = StepStats::default();
+        for (y, row) in old_cells.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                let neighbours = self.count_neighbours(&old_cells, x, y);
+                let next = rule.next_state(cell, &neighbours);
+                match (cell, next) {
+                    (CellState::Dead, CellState::Alive) => stats.births += 1,
+                    (CellState::Alive, CellState::Dead) => stats.deaths += 1,
+                    _ => {}
+                }
+                self.cells[y][x] = next;
+            }
+        }
+        stats
+    }
+
+    /// Counts the alive and dead neighbours a cell had on the previous generation
+    /// Input: the cells of the previous generation, and the coordinates of the cell
+    /// Output: how many of its neighbours were alive, and how many dead
+    fn count_neighbours(
+        &self,
+        old_cells: &[[CellState; W]; H],
+        x: usize,
+        y: usize,
+    ) -> NeighborCounts {
+        //The rows of the arrays are laid out one after the other, like the cells of a board
+        count_neighbours(
+            old_cells.as_flattened(),
+            (W, H),
+            self.boundary,
+            (x, y),
+            Moore.offsets(),
+        )
+    }
+
+    /// Returns how many columns the board has
+    pub const fn width(&self) -> usize {
+        W
+    }
+
+    /// Returns how many rows the board has
+    pub const fn length(&self) -> usize {
+        H
+    }
+
+    /// Counts the alive cells in the board
+    pub fn population(&self) -> usize {
+        self.live_cells().count()
+    }
+
+    /// Goes through the alive cells of the board
+    /// Output: the coordinates of every alive cell, row by row
+    pub fn live_cells(&self) -> impl Iterator<Item = Point> + '_ {
+        self.cells.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, cell)| **cell == CellState::Alive)
+                .map(move |(x, _)| Point::new(x, y))
+        })
+    }
+}
+
+impl<const W: usize, const H: usize> Default for FixedBoard<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> Index<(usize, usize)> for FixedBoard<W, H> {
+    type Output = CellState;
+
+    fn index(&self, (x, y): (usize, usize)) -> &CellState {
+        &self.cells[y][x]
+    }
+}
+
+impl<const W: usize, const H: usize> IndexMut<(usize, usize)> for FixedBoard<W, H> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut CellState {
+        &mut self.cells[y][x]
+    }
+}
+
+impl<const W: usize, const H: usize> Index<Point> for FixedBoard<W, H> {
+    type Output = CellState;
+
+    fn index(&self, point: Point) -> &CellState {
+        &self.cells[point.y][point.x]
+    }
+}
+
+impl<const W: usize, const H: usize> IndexMut<Point> for FixedBoard<W, H> {
+    fn index_mut(&mut self, point: Point) -> &mut CellState {
+        &mut self.cells[point.y][point.x]
+    }
+}
+
+/// Like boards, fixed boards are equal when they have the same cells alive, whatever rule or edges they follow
+impl<const W: usize, const H: usize> PartialEq for FixedBoard<W, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells
+    }
+}
+
+impl<const W: usize, const H: usize> Eq for FixedBoard<W, H> {}
+
+impl<const W: usize, const H: usize> Hash for FixedBoard<W, H> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.cells.hash(state);
+    }
+}
+
+/// Fixed boards are displayed the same way as boards, with a # for each alive cell and a . for each dead one
+impl<const W: usize, const H: usize> fmt::Display for FixedBoard<W, H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.cells.iter().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                match cell {
+                    CellState::Alive => write!(f, "#")?,
+                    CellState::Dead => write!(f, ".")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const W: usize, const H: usize> From<&FixedBoard<W, H>> for Board {
+    fn from(fixed_board: &FixedBoard<W, H>) -> Self {
+        let mut board = Board::new(W, H);
+        board.rule = fixed_board.rule;
+        board.boundary = fixed_board.boundary;
+        for cell in fixed_board.live_cells() {
+            board[cell] = CellState::Alive;
+        }
+        board.old_board = board.board.clone();
+        board
+    }
+}
+
+/// Boards can only be turned into fixed boards of their same size, and counting the eight cells around each cell as
+/// their neighbours, as fixed boards always do
+impl<const W: usize, const H: usize> TryFrom<&Board> for FixedBoard<W, H> {
+    type Error = GolError;
+
+    fn try_from(board: &Board) -> Result<Self, GolError> {
+        if (board.width(), board.length()) != (W, H) {
+            return Err(GolError::InvalidDimensions {
+                width: board.width(),
+                length: board.length(),
+            });
+        }
+        if board.neighborhood.offsets() != Moore.offsets() {
+            return Err(GolError::UnsupportedNeighborhood);
+        }
+        let mut fixed_board = FixedBoard::new();
+        fixed_board.rule = board.rule;
+        fixed_board.boundary = board.boundary;
+        for cell in board.live_cells() {
+            fixed_board[cell] = CellState::Alive;
+        }
+        Ok(fixed_board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_boards_step_like_boards() {
+        let mut board: Board = "
+            .#......
+            ..#.....
+            ###.....
+            ........
+            .....##.
+            .....#..
+        "
+        .parse()
+        .unwrap();
+        for boundary in [Boundary::Bounded, Boundary::Toroidal] {
+            board.boundary = boundary;
+            let mut fixed_board = FixedBoard::<8, 6>::try_from(&board).unwrap();
+            assert_eq!(board.to_string(), fixed_board.to_string());
+            for generation in board.generations().take(30) {
+                fixed_board.update_board();
+                assert_eq!(generation, Board::from(&fixed_board));
+            }
+        }
+        assert!(FixedBoard::<6, 8>::try_from(&board).is_err());
+        board.neighborhood = std::sync::Arc::new(crate::neighborhood::Hexagonal);
+        assert!(matches!(
+            FixedBoard::<8, 6>::try_from(&board),
+            Err(GolError::UnsupportedNeighborhood)
+        ));
+    }
+}
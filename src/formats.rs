@@ -0,0 +1,402 @@
+//! Reading and writing patterns in the plaintext (.cells) and run length encoded (.rle) formats. Nothing here depends
+//! on boards, so tools that only handle pattern files can use these on their own.
+//!
+//! ```
+//! use conways_game_of_life::formats::{self, PatternMeta};
+//!
+//! let (glider, meta) = formats::read_rle("#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+//! assert_eq!(Some("Glider"), meta.name.as_deref());
+//! assert_eq!(Some("B3/S23"), meta.rule.as_deref());
+//! assert_eq!("!Name: Glider\n.O.\n..O\nOOO\n", formats::write_plaintext(&glider, &meta));
+//! assert_eq!("x = 3, y = 3\nbo$2bo$3o!\n", formats::write_rle(&glider, &PatternMeta::default()));
+//! ```
+
+use crate::board::MAX_CELLS;
+use crate::geometry::Point;
+use crate::{CellState, GolError};
+
+/// Longest lines RLE files are written with, as most programs reading them expect
+const RLE_LINE_LENGTH: usize = 70;
+
+/// A rectangle of cells, independent of any board. Patterns can be read from plaintext (.cells) and RLE (.rle) files
+///
+/// ```
+/// use conways_game_of_life::{CellState, Pattern};
+///
+/// let glider = Pattern::parse_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+/// assert_eq!((3, 3), (glider.width, glider.height));
+/// assert_eq!(CellState::Alive, glider.get((1, 0)));
+/// assert_eq!(glider, Pattern::from_library("glider").unwrap());
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Pattern {
+    pub width: usize,
+    pub height: usize,
+    pub(crate) cells: Vec<CellState>,
+}
+
+/// What pattern files tell about their pattern besides its cells. Every part of it is optional, as most files only
+/// have some of them
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct PatternMeta {
+    /// Name of the pattern, from the !Name: line of plaintext files or the #N line of RLE files
+    pub name: Option<String>,
+    /// Who found the pattern, from the !Author: line of plaintext files or the #O line of RLE files
+    pub author: Option<String>,
+    /// Any other comment lines, in order
+    pub comments: Vec<String>,
+    /// Rulestring the pattern is meant to run under, from the header line of RLE files
+    pub rule: Option<String>,
+}
+
+impl Pattern {
+    /// Creates a pattern from rows of cells, padding the shorter rows with dead cells
+    fn from_rows(rows: Vec<Vec<CellState>>) -> Result<Self, GolError> {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if width == 0 {
+            return Err(GolError::Parse("the pattern has no cells".to_string()));
+        }
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(pattern_size(width, height)?);
+        for mut row in rows {
+            row.resize(width, CellState::Dead);
+            cells.extend(row);
+        }
+        Ok(Pattern {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a pattern in plaintext format, where each line is a row, '.' is a dead cell and 'O' an alive one.
+    /// Lines starting with '!' are comments
+    pub fn parse_plaintext(text: &str) -> Result<Self, GolError> {
+        read_plaintext(text).map(|(pattern, _)| pattern)
+    }
+
+    /// Parses a pattern in run length encoded format, such as "bo$2bo$3o!" for a glider.
+    /// Lines starting with '#' are comments, and the header line with the size is optional
+    pub fn parse_rle(text: &str) -> Result<Self, GolError> {
+        read_rle(text).map(|(pattern, _)| pattern)
+    }
+
+    /// Writes a pattern in plaintext format, with a line for each row
+    pub fn to_plaintext(&self) -> String {
+        write_plaintext(self, &PatternMeta::default())
+    }
+
+    /// Writes a pattern in run length encoded format, with its header line
+    pub fn to_rle(&self) -> String {
+        write_rle(self, &PatternMeta::default())
+    }
+
+    /// Returns the state of a cell of the pattern, given its coordinates as a point or a column and row
+    pub fn get(&self, cell: impl Into<Point>) -> CellState {
+        let Point { x, y } = cell.into();
+        self.cells[y * self.width + x]
+    }
+}
+
+/// Parses a pattern in plaintext format, keeping what its comments tell about it. Comments are lines starting with '!',
+/// where "!Name:" and "!Author:" give the name and author of the pattern
+/// Input: the contents of a .cells file
+/// Output: the pattern and what's known about it, or what's wrong with the text
+pub fn read_plaintext(text: &str) -> Result<(Pattern, PatternMeta), GolError> {
+    let mut meta = PatternMeta::default();
+    let mut rows = Vec::new();
+    for line in text.lines() {
+        if let Some(comment) = line.strip_prefix('!') {
+            if let Some(name) = comment.strip_prefix("Name:") {
+                meta.name = Some(name.trim().to_string());
+            } else if let Some(author) = comment.strip_prefix("Author:") {
+                meta.author = Some(author.trim().to_string());
+            } else {
+                meta.comments.push(comment.trim().to_string());
+            }
+            continue;
+        }
+        let mut row = Vec::new();
+        for cell in line.trim_end().chars() {
+            match cell {
+                '.' => row.push(CellState::Dead),
+                'O' | 'o' | '*' => row.push(CellState::Alive),
+                _ => {
+                    return Err(GolError::Parse(format!(
+                        "unexpected character '{cell}' in pattern"
+                    )));
+                }
+            }
+        }
+        rows.push(row);
+    }
+    Ok((Pattern::from_rows(rows)?, meta))
+}
+
+/// Parses a pattern in run length encoded format, keeping what its comments and header tell about it. The size in the
+/// header, when there's one, pads the pattern with the dead cells left out at the end of its rows
+/// Input: the contents of a .rle file
+/// Output: the pattern and what's known about it, or what's wrong with the text
+pub fn read_rle(text: &str) -> Result<(Pattern, PatternMeta), GolError> {
+    let mut meta = PatternMeta::default();
+    let (mut width, mut height) = (0, 0);
+    //Only the runs of alive cells are kept while reading, as (row, column, length), so the cells are allocated once
+    //the size of the pattern is known to be within MAX_CELLS
+    let mut alive_runs = Vec::new();
+    let (mut column, mut row) = (0usize, 0usize);
+    let mut run_length = String::new();
+    'body: for line in text.lines().map(str::trim) {
+        if let Some(comment) = line.strip_prefix('#') {
+            match comment.split_at_checked(1) {
+                Some(("N", name)) => meta.name = Some(name.trim().to_string()),
+                Some(("O", author)) => meta.author = Some(author.trim().to_string()),
+                Some(("C" | "c", text)) => meta.comments.push(text.trim().to_string()),
+                _ => {}
+            }
+            continue;
+        }
+        if line.starts_with("x ") || line.starts_with("x=") {
+            (width, height) = read_rle_header(line, &mut meta)?;
+            continue;
+        }
+        for tag in line.chars() {
+            let count: usize = if tag.is_ascii_digit() {
+                run_length.push(tag);
+                continue;
+            } else if run_length.is_empty() {
+                1
+            } else {
+                run_length
+                    .parse()
+                    .map_err(|_| GolError::Parse(format!("run length {run_length} is too big")))?
+            };
+            run_length.clear();
+            match tag {
+                'b' | '.' => column = column.saturating_add(count),
+                '$' => {
+                    row = row.saturating_add(count);
+                    column = 0;
+                }
+                '!' => break 'body,
+                tag if tag.is_ascii_alphabetic() => {
+                    alive_runs.push((row, column, count));
+                    column = column.saturating_add(count);
+                }
+                tag if tag.is_whitespace() => {}
+                _ => {
+                    return Err(GolError::Parse(format!(
+                        "unexpected character '{tag}' in pattern"
+                    )));
+                }
+            }
+            width = width.max(column);
+            pattern_size(width, row.saturating_add(1))?;
+        }
+    }
+    let height = height.max(row + 1);
+    if width == 0 {
+        return Err(GolError::Parse("the pattern has no cells".to_string()));
+    }
+    let mut cells = vec![CellState::Dead; pattern_size(width, height)?];
+    for (row, column, length) in alive_runs {
+        cells[row * width + column..][..length].fill(CellState::Alive);
+    }
+    Ok((
+        Pattern {
+            width,
+            height,
+            cells,
+        },
+        meta,
+    ))
+}
+
+/// Checks that a pattern isn't too big to be allocated
+/// Input: the width and height of the pattern
+/// Output: how many cells it has, or an error if that's more than MAX_CELLS
+fn pattern_size(width: usize, height: usize) -> Result<usize, GolError> {
+    width
+        .checked_mul(height)
+        .filter(|&cells| cells <= MAX_CELLS)
+        .ok_or_else(|| GolError::Parse(format!("the pattern is too big, at {width} by {height}")))
+}
+
+/// Reads the header line of an RLE file, such as "x = 3, y = 3, rule = B3/S23"
+/// Input: the line, and where to keep the rule it gives
+/// Output: the width and height it gives, or what's wrong with them
+fn read_rle_header(line: &str, meta: &mut PatternMeta) -> Result<(usize, usize), GolError> {
+    let (mut width, mut height) = (0, 0);
+    for setting in line.split(',') {
+        let Some((key, value)) = setting.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        let size = || {
+            value
+                .parse()
+                .map_err(|_| GolError::Parse(format!("invalid size '{value}' in pattern header")))
+        };
+        match key.trim() {
+            "x" => width = size()?,
+            "y" => height = size()?,
+            "rule" => meta.rule = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    pattern_size(width, height)?;
+    Ok((width, height))
+}
+
+/// Writes a pattern in plaintext format, with its name, author and comments in comment lines before its cells
+/// Input: the pattern, and what's known about it
+/// Output: the contents of a .cells file
+pub fn write_plaintext(pattern: &Pattern, meta: &PatternMeta) -> String {
+    let mut text = String::with_capacity((pattern.width + 1) * pattern.height);
+    if let Some(name) = &meta.name {
+        text.push_str(&format!("!Name: {name}\n"));
+    }
+    if let Some(author) = &meta.author {
+        text.push_str(&format!("!Author: {author}\n"));
+    }
+    for comment in &meta.comments {
+        text.push_str(&format!("!{comment}\n"));
+    }
+    for row in pattern.cells.chunks(pattern.width) {
+        for cell in row {
+            text.push(match cell {
+                CellState::Alive => 'O',
+                CellState::Dead => '.',
+            });
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Writes a pattern in run length encoded format, with its name, author and comments in comment lines, and its size
+/// and rule in the header line. Dead cells at the end of rows are left out, as the header keeps the size
+/// Input: the pattern, and what's known about it
+/// Output: the contents of a .rle file, with no line longer than 70 characters
+pub fn write_rle(pattern: &Pattern, meta: &PatternMeta) -> String {
+    let mut text = String::new();
+    if let Some(name) = &meta.name {
+        text.push_str(&format!("#N {name}\n"));
+    }
+    if let Some(author) = &meta.author {
+        text.push_str(&format!("#O {author}\n"));
+    }
+    for comment in &meta.comments {
+        text.push_str(&format!("#C {comment}\n"));
+    }
+    text.push_str(&format!("x = {}, y = {}", pattern.width, pattern.height));
+    if let Some(rule) = &meta.rule {
+        text.push_str(&format!(", rule = {rule}"));
+    }
+    text.push('\n');
+
+    //Each run is a count and a tag, with rows ending in $ only once a later row has alive cells
+    let mut runs = Vec::new();
+    let mut row_ends = 0;
+    for row in pattern.cells.chunks(pattern.width) {
+        let length = row
+            .iter()
+            .rposition(|cell| *cell == CellState::Alive)
+            .map_or(0, |last| last + 1);
+        if length > 0 && row_ends > 0 {
+            runs.push((row_ends, '$'));
+            row_ends = 0;
+        }
+        for cell in &row[..length] {
+            let tag = match cell {
+                CellState::Alive => 'o',
+                CellState::Dead => 'b',
+            };
+            match runs.last_mut() {
+                Some((count, last_tag)) if *last_tag == tag => *count += 1,
+                _ => runs.push((1, tag)),
+            }
+        }
+        row_ends += 1;
+    }
+    runs.push((1, '!'));
+
+    let mut line_length = 0;
+    for (count, tag) in runs {
+        let run = if count > 1 {
+            format!("{count}{tag}")
+        } else {
+            tag.to_string()
+        };
+        if line_length + run.len() > RLE_LINE_LENGTH {
+            text.push('\n');
+            line_length = 0;
+        }
+        line_length += run.len();
+        text.push_str(&run);
+    }
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plaintext_and_rle_gliders_are_equal() {
+        let plaintext = Pattern::parse_plaintext("!Name: Glider\n.O.\n..O\nOOO\n").unwrap();
+        let rle = Pattern::parse_rle("#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+        assert_eq!(plaintext, rle);
+        assert_eq!((3, 3), (rle.width, rle.height));
+        assert_eq!(CellState::Alive, rle.get((2, 1)));
+    }
+
+    #[test]
+    fn rle_rows_are_padded_and_empty_rows_kept() {
+        let pattern = Pattern::parse_rle("o2$3o!").unwrap();
+        assert_eq!((3, 3), (pattern.width, pattern.height));
+        assert_eq!(CellState::Dead, pattern.get((1, 0)));
+        assert_eq!(CellState::Dead, pattern.get((0, 1)));
+    }
+
+    #[test]
+    fn plaintext_is_written_back_as_read() {
+        let text = ".O.\n..O\nOOO\n";
+        assert_eq!(text, Pattern::parse_plaintext(text).unwrap().to_plaintext());
+    }
+
+    #[test]
+    fn rle_is_written_back_as_read() {
+        let text = "#N Spaced out\n#O Someone\n#C Two lines\n#C of comments\nx = 5, y = 4, rule = B36/S23\n\
+                    2bo2$o$3o!\n";
+        let (pattern, meta) = read_rle(text).unwrap();
+        assert_eq!((5, 4), (pattern.width, pattern.height));
+        assert_eq!(vec!["Two lines", "of comments"], meta.comments);
+        assert_eq!(text, write_rle(&pattern, &meta));
+
+        //Long runs of cells are split over several lines
+        let (row, _) = read_plaintext(&"O.".repeat(50)).unwrap();
+        let rle = row.to_rle();
+        assert!(rle.lines().all(|line| line.len() <= RLE_LINE_LENGTH));
+        assert_eq!(row, Pattern::parse_rle(&rle).unwrap());
+
+        let (pattern, meta) = read_plaintext("!Name: Blinker\n!Period 2\nOOO").unwrap();
+        assert_eq!(Some("Blinker".to_string()), meta.name);
+        assert_eq!(
+            Pattern::parse_rle(&write_rle(&pattern, &meta)).unwrap(),
+            pattern
+        );
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        assert!(Pattern::parse_plaintext(".x.").is_err());
+        assert!(Pattern::parse_plaintext("").is_err());
+        assert!(Pattern::parse_rle("bo%!").is_err());
+        assert!(Pattern::parse_rle("x = three, y = 1\no!").is_err());
+        //Sizes that would take gigabytes are rejected before anything is allocated
+        assert!(Pattern::parse_rle("x = 4000000000, y = 4000000000\no!").is_err());
+        assert!(Pattern::parse_rle("99999999999o!").is_err());
+        assert!(Pattern::parse_rle("99999999999$o!").is_err());
+    }
+}
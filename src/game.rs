@@ -0,0 +1,969 @@
+use std::path::{Path, PathBuf};
+
+use macroquad::{
+    miniquad::{CursorIcon, date, window},
+    prelude::*,
+};
+
+use crate::achievements::{Achievements, show_achievements, unlocked_message};
+use crate::attachments::Attachments;
+use crate::automata::{AUTOMATA, run_automaton};
+use crate::benchmark::run_benchmark;
+use crate::board::Board;
+use crate::challenge::run_challenge;
+use crate::color_picker::ColorPicker;
+use crate::config::Config;
+use crate::event_log::EventLog;
+use crate::headless::run_headless;
+use crate::input::{TransportButton, show_cursor};
+use crate::keys::Action;
+use crate::lessons::LESSONS;
+use crate::locale::{Language, tr, tr_with};
+use crate::menu::{GameStart, StartingMenu};
+use crate::options::Options;
+use crate::overlay::{OverlayBackground, run_overlay, run_screensaver};
+use crate::palette::PALETTES;
+use crate::pattern_file::{PatternFile, pattern_board};
+use crate::patterns::Pattern;
+use crate::pause_menu::{PauseMenu, PauseMenuEntry};
+use crate::quit_prompt::{QuitChoice, QuitPrompt};
+use crate::rule_files::load_rule_files;
+use crate::rules::{LifeLikeRule, add_file_rules};
+use crate::sandbox::{Frame, Sandbox, Tools};
+use crate::sandpile::run_sandpile;
+use crate::scripting::ScriptHost;
+use crate::search::run_search;
+use crate::settings::{Settings, SettingsEvent, SettingsScreen};
+use crate::simulation::Simulation;
+use crate::smooth_life::run_smooth_life;
+use crate::sound::Sounds;
+use crate::text::{draw_text, measure_text};
+use crate::theme::{THEMES, Theme};
+use crate::time_attack::run_time_attack;
+use crate::toast::Toasts;
+use crate::versus::run_versus;
+
+/// Writes the settings to the config file, reporting any error without stopping the game
+/// Input: the settings, and the path of the config file, if it's known
+pub fn save_config(config: &Config, path: Option<&Path>) {
+    if let Some(path) = path
+        && let Err(error) = config.save(path)
+    {
+        eprintln!("Couldn't save the config file {}: {error}", path.display());
+    }
+}
+
+/// Saves a board to the save file, as it's loaded back from the pause menu
+/// Input: the simulation of the board, which no longer has unsaved edits once saved, and the path of the file
+/// Output: whether it was saved, or a message explaining why it couldn't be
+pub fn save_board(simulation: &mut Simulation, path: Option<&Path>) -> Result<(), String> {
+    let path = path.ok_or(tr("game.no_save_directory"))?;
+    Pattern::from_board(&simulation.board)
+        .save(path)
+        .map_err(|error| error.to_string())?;
+    simulation.has_unsaved_edits = false;
+    Ok(())
+}
+
+/// Stores the size and position of the window in the config, and writes it to the config file if they changed, so
+/// the window opens the same way on the next session
+/// Input: the config, and the path of the config file, if it's known
+pub fn remember_window(config: &mut Config, path: Option<&Path>) {
+    let size = (
+        screen_width().round() as u32,
+        screen_height().round() as u32,
+    );
+    let position = window::get_window_position();
+    if config.window_size != Some(size) || config.window_position != Some(position) {
+        config.window_size = Some(size);
+        config.window_position = Some(position);
+        save_config(config, path);
+    }
+}
+
+/// Stores the settings in the config, and writes it to the config file
+/// Input: the settings, the config, and the path of the config file, if it's known
+pub fn save_settings(settings: &Settings, config: &mut Config, path: Option<&Path>) {
+    settings.store(config);
+    save_config(config, path);
+}
+
+/// Settings of the window. On high density displays, such as Retina or 4K ones, everything is drawn at the full
+/// resolution of the display, while sizes and positions stay in logical pixels, so they look the same as on other displays.
+/// The window opens with the size it had when the game was last closed, if it's in the config file. In overlay mode
+/// with a transparent background, the window is made see-through, and screensavers open fullscreen
+pub fn window_conf() -> Conf {
+    let default = Conf::default();
+    let options = Options::parse(std::env::args().skip(1)).unwrap_or_default();
+    let is_transparent = options.overlay == Some(OverlayBackground::Transparent);
+    let (window_width, window_height) = Config::path()
+        .and_then(|path| Config::load(&path).ok())
+        .and_then(|config| config.window_size)
+        .map_or(
+            (default.window_width, default.window_height),
+            |(width, height)| (width as i32, height as i32),
+        );
+    Conf {
+        window_title: "Conway's Game of Life".to_string(),
+        window_width,
+        window_height,
+        high_dpi: true,
+        fullscreen: options.screensaver,
+        platform: miniquad::conf::Platform {
+            framebuffer_alpha: is_transparent,
+            ..default.platform
+        },
+        ..default
+    }
+}
+
+/// Runs the game without a window if asked to on the command line, showing a random board on the terminal or writing
+/// it to images instead, or searching for interesting soups
+/// Output: whether it ran, in which case the window mustn't be opened
+pub fn run_without_window() -> bool {
+    //Wrong arguments are reported once the window opens, along with the usage
+    let Ok(options) = Options::parse(std::env::args().skip(1)) else {
+        return false;
+    };
+    if let Some(directory) = &options.search {
+        if let Err(error) = run_search(directory, options.max_generations.unwrap_or(1000)) {
+            eprintln!("Couldn't save the soups found: {error}");
+            std::process::exit(1);
+        }
+        return true;
+    }
+    let Some(output) = &options.headless else {
+        return false;
+    };
+    if let Err(error) = run_headless(output, options.max_generations.unwrap_or(100)) {
+        eprintln!("Couldn't show the board: {error}");
+        std::process::exit(1);
+    }
+    true
+}
+
+/// Reads the settings of previous sessions, stopping the game if the config file can't be read, so it isn't
+/// overwritten
+/// Input: the path of the config file, if it's known
+/// Output: the settings in it, or the default ones if there's no config file
+fn load_config(path: Option<&Path>) -> Config {
+    match path {
+        Some(path) => match Config::load(path) {
+            Ok(config) => config,
+            Err(message) => {
+                eprintln!("Couldn't load the config file {message}");
+                std::process::exit(1);
+            }
+        },
+        None => Config::default(),
+    }
+}
+
+/// Finds the pattern boards watch for from the start, stopping the game if it can't be found
+/// Input: the name of a pattern of the library, or the path of a pattern file, as given on the command line
+/// Output: the name and the pattern, if one was given
+fn load_watch_target(target: Option<&String>) -> Option<(String, Pattern)> {
+    let target = target?;
+    match Pattern::from_library(target) {
+        Some(pattern) => Some((target.clone(), pattern)),
+        None => match Pattern::load(Path::new(target)) {
+            Ok(pattern) => Some((target.clone(), pattern)),
+            Err(message) => {
+                eprintln!("Couldn't load the pattern to watch for: {message}");
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
+/// Draws lines of text in the top right corner of the window, one under the other
+/// Input: the lines, how many lines are already drawn above them, and the colours
+fn draw_corner_lines(lines: &[String], first_row: usize, theme: &Theme) {
+    let (window_width, window_height) = (screen_width(), screen_height());
+    for (index, line) in lines.iter().enumerate() {
+        draw_text(
+            line,
+            window_width - measure_text(line, None, 24, 1.).width - window_width / 80.,
+            window_height / 20. + (index + first_row) as f32 * 26.,
+            24.,
+            theme.label,
+        );
+    }
+}
+
+/// Why the boards of the sandbox stop being played
+#[derive(Clone, Copy, PartialEq)]
+enum SandboxExit {
+    /// Going back to the starting menu, which ends the game
+    Menu,
+    /// Closing the window
+    Quit,
+}
+
+/// Whatever lasts from one game to the next: the settings, the menus, and what the boards are shared with
+struct Game {
+    options: Options,
+    config: Config,
+    config_path: Option<PathBuf>,
+    settings: Settings,
+    /// The game can still be played without sounds, such as when there's no audio device
+    sounds: Option<Sounds>,
+    /// Messages shown for a moment over the game, such as speed changes or the result of saving
+    toasts: Toasts,
+    /// Scripts of the scripts directory, only run when turned on
+    scripts: ScriptHost,
+    attachments: Attachments,
+    /// Milestones reached over every session, and where they're kept
+    achievements: Achievements,
+    achievements_path: Option<PathBuf>,
+    /// Image alive cells are drawn with instead of plain squares
+    sprite: Option<Texture2D>,
+    /// Pattern the first board of each game watches for, as given on the command line
+    watch_target: Option<(String, Pattern)>,
+    /// Log the first board of the next game is followed by
+    event_log: Option<EventLog>,
+    /// Where the board is saved to and loaded from
+    save_path: Option<PathBuf>,
+    menu: StartingMenu,
+    color_picker: ColorPicker,
+    settings_screen: SettingsScreen,
+    /// Menu opened with escape, where the board can be restarted, saved or loaded
+    pause_menu: PauseMenu,
+    /// Prompt asking whether to save the board when quitting with edits that weren't saved
+    quit_prompt: QuitPrompt,
+    /// Whether quit was picked on the pause menu, which is handled like closing the window
+    is_quit_picked: bool,
+    /// Cursor being shown, which changes with what clicking does
+    shown_cursor: CursorIcon,
+    tools: Tools,
+    /// Whether how long frames take is shown
+    show_performance: bool,
+    /// Whether the board in view is played as music
+    is_sonifying: bool,
+    /// Whether the window takes the whole screen
+    is_fullscreen: bool,
+}
+
+/// Runs the game in the window, from the starting menu until it's closed
+pub async fn run() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(2);
+        }
+    };
+
+    //The log file is created right away, so a wrong path is reported before playing
+    let event_log = match &options.event_log {
+        Some(path) => match EventLog::create(path) {
+            Ok(event_log) => Some(event_log),
+            Err(error) => {
+                eprintln!("Couldn't create the event log {}: {error}", path.display());
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    //Image alive cells are drawn with instead of plain squares
+    let sprite = match &options.sprite {
+        Some(path) => match load_texture(&path.to_string_lossy()).await {
+            Ok(sprite) => Some(sprite),
+            Err(error) => {
+                eprintln!("Couldn't load the cell sprite {}: {error}", path.display());
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    //Settings from previous sessions
+    let config_path = Config::path();
+    let config = load_config(config_path.as_deref());
+    crate::locale::set_language(config.language.unwrap_or(Language::English));
+    //The size of the window was set when creating it, but its position can only be set once it's open
+    if let Some((x, y)) = config.window_position {
+        window::set_window_position(x, y);
+    }
+
+    let watch_target = load_watch_target(options.watch_target.as_ref());
+
+    // I set initial window width and height appropriately for the menu, unless the window opened with the size it
+    // had on the last session
+    if config.window_size.is_none() {
+        request_new_screen_size(screen_width(), screen_width() * 3. / 5.);
+        next_frame();
+    }
+
+    //Settings that can be changed during the game, kept from the last session.
+    //The ones given on the command line take precedence
+    let mut settings = Settings::from_config(&config);
+    let sounds = match Sounds::load().await {
+        Ok(sounds) => Some(sounds),
+        Err(message) => {
+            eprintln!("Couldn't load the sounds, the game is silent: {message}");
+            None
+        }
+    };
+    if let Some(name) = &options.theme {
+        settings.theme_index = match Theme::index_of(name) {
+            Some(index) => index,
+            None => {
+                let names: Vec<_> = THEMES.iter().map(|theme| theme.name).collect();
+                eprintln!(
+                    "Unknown theme '{name}', the themes are: {}",
+                    names.join(", ")
+                );
+                std::process::exit(2);
+            }
+        };
+    }
+    if let Some(cell_shape) = options.cell_shape {
+        settings.cell_shape = cell_shape;
+    }
+    crate::text::set_text_scale(settings.ui_scale, settings.large_text);
+    //Overlay and screensaver modes skip the menus and the rest of the game
+    if let Some(background) = options.overlay {
+        let theme = config.apply(&THEMES[settings.theme_index]);
+        run_overlay(background, &theme, settings.speed_index).await;
+        return;
+    }
+    if options.screensaver {
+        let theme = config.apply(&THEMES[settings.theme_index]);
+        run_screensaver(&theme, settings.speed_index).await;
+        return;
+    }
+    //Scripts are read once, from the scripts directory next to the config file, and only run when turned on
+    let config_directory = Config::directory().unwrap_or_default();
+    let (scripts, script_errors) = ScriptHost::load(&config_directory.join("scripts"));
+    for error in script_errors {
+        eprintln!("Couldn't load a script: {error}");
+    }
+    //Rules of the rules directory can be picked after the presets
+    let (file_rules, rule_errors) = load_rule_files(&config_directory.join("rules"));
+    for error in rule_errors {
+        eprintln!("Couldn't load a rule: {error}");
+    }
+    add_file_rules(file_rules);
+    let mut toasts = Toasts::default();
+    let attachments = Attachments::open(&options, &config, &mut toasts);
+    let achievements_path = Config::directory().map(|directory| directory.join("achievements"));
+    let achievements = achievements_path
+        .as_deref()
+        .map(Achievements::load)
+        .unwrap_or_default();
+    //Closing the window only asks to quit, so boards with unsaved edits can be saved first
+    prevent_quit();
+
+    let mut game = Game {
+        //Generations after which the game stops on its own, 0 meaning it never does, can be given on the command line
+        menu: StartingMenu::new(options.max_generations.unwrap_or(0)),
+        options,
+        config,
+        config_path,
+        settings,
+        sounds,
+        toasts,
+        scripts,
+        attachments,
+        achievements,
+        achievements_path,
+        sprite,
+        watch_target,
+        event_log,
+        save_path: Config::directory().map(|directory| directory.join("saved board.cells")),
+        color_picker: ColorPicker::default(),
+        settings_screen: SettingsScreen::default(),
+        pause_menu: PauseMenu::default(),
+        quit_prompt: QuitPrompt::default(),
+        is_quit_picked: false,
+        shown_cursor: CursorIcon::Default,
+        tools: Tools::default(),
+        show_performance: false,
+        is_sonifying: false,
+        is_fullscreen: false,
+    };
+    game.run().await;
+}
+
+impl Game {
+    /// Plays one game after another, going back to the starting menu in between, until the window is closed
+    async fn run(&mut self) {
+        loop {
+            //Keys typed during the game are still queued, and mustn't end up in the fields of the menu
+            clear_input_queue();
+            show_cursor(&mut self.shown_cursor, CursorIcon::Default);
+            let Some(game_start) = self.run_menu().await else {
+                break;
+            };
+            if let Some(is_closing) = self.run_mode(game_start).await {
+                if is_closing {
+                    break;
+                }
+                continue;
+            }
+            let mut sandbox = self.start_sandbox(game_start);
+            let exit = loop {
+                if let Some(exit) = self.run_sandbox_frame(&mut sandbox).await {
+                    break exit;
+                }
+            };
+            if exit == SandboxExit::Quit {
+                break;
+            }
+            //The event log follows the boards of the next game, and the window goes back to the proportions of the menu
+            self.event_log = sandbox
+                .simulations
+                .iter_mut()
+                .find_map(|simulation| simulation.event_log.take());
+            request_new_screen_size(screen_width(), screen_width() * 3. / 5.);
+            next_frame().await;
+        }
+        remember_window(&mut self.config, self.config_path.as_deref());
+    }
+
+    /// Shows the starting menu until a game is started from it
+    /// Output: how the game starts, or None if the window was closed
+    async fn run_menu(&mut self) -> Option<GameStart> {
+        loop {
+            //Closing the window is handled by the game, which asks to save the board first when it was edited
+            if is_quit_requested() {
+                return None;
+            }
+            //The window can be resized at any time, so the layout follows its current size
+            let window = Rect::new(0., 0., screen_width(), screen_height());
+            let theme = &self.config.apply(&THEMES[self.settings.theme_index]);
+
+            //The colour picker takes the whole window while open
+            if self.color_picker.is_open {
+                self.update_color_picker(window);
+                next_frame().await;
+                continue;
+            }
+            #[cfg(feature = "discord")]
+            self.attachments.show_menu(get_time());
+            if let Some(game_start) = self.menu.update(
+                &mut self.settings,
+                &mut self.config,
+                self.config_path.as_deref(),
+                &mut self.color_picker,
+                theme,
+            ) {
+                return Some(game_start);
+            }
+            next_frame().await;
+        }
+    }
+
+    /// Runs the games of the starting menu that aren't played on the sandbox, until they go back to the menu
+    /// Input: how the game starts
+    /// Output: whether the window was closed, or None if the game is played on the sandbox
+    async fn run_mode(&mut self, game_start: GameStart) -> Option<bool> {
+        let (board_width, board_height) = self.menu.board_size();
+        let theme = self.config.apply(&THEMES[self.settings.theme_index]);
+        let is_closing = match game_start {
+            GameStart::Achievements => show_achievements(&self.achievements, &theme).await,
+            GameStart::TimeAttack => {
+                let best_times_path =
+                    Config::directory().map(|directory| directory.join("best_times"));
+                run_time_attack(&theme, best_times_path).await
+            }
+            //Probabilistic automata, sandpiles and SmoothLife run on grids of their own
+            GameStart::Automaton(index) => {
+                run_automaton(
+                    AUTOMATA[index],
+                    (board_width, board_height),
+                    self.settings.boundary,
+                    &theme,
+                    &self.settings.keys,
+                    self.settings.speed_index,
+                )
+                .await
+            }
+            GameStart::Sandpile => run_sandpile((board_width, board_height), &theme).await,
+            GameStart::SmoothLife => {
+                run_smooth_life(&theme, &self.settings.keys, self.settings.speed_index).await
+            }
+            //Versus games, challenges and benchmarks run on boards of their own
+            GameStart::Versus | GameStart::Challenge | GameStart::Benchmark => {
+                let mut board = Board::new(board_width, board_height);
+                board.rule = LifeLikeRule::preset(self.menu.rule_index);
+                board.boundary = self.settings.boundary;
+                match game_start {
+                    GameStart::Versus => {
+                        let palette = &PALETTES[self.settings.palette_index];
+                        run_versus(board, &theme, palette, self.settings.speed_index).await
+                    }
+                    GameStart::Challenge => {
+                        let high_scores_path =
+                            Config::directory().map(|directory| directory.join("high_scores"));
+                        run_challenge(board, &theme, self.settings.speed_index, high_scores_path)
+                            .await
+                    }
+                    _ => run_benchmark(board, &theme).await,
+                }
+            }
+            GameStart::Board | GameStart::Tutorial | GameStart::Lesson(_) => return None,
+        };
+        Some(is_closing)
+    }
+
+    /// Opens the first board of a game played on the sandbox, with the window taking the proportions of the board
+    /// Input: how the game starts
+    /// Output: the sandbox the game is played on
+    fn start_sandbox(&mut self, game_start: GameStart) -> Sandbox {
+        let (mut board_width, mut board_height) = self.menu.board_size();
+        let max_generations = self.menu.max_generations();
+        let rule_index = self.menu.rule_index;
+        let lesson = match game_start {
+            GameStart::Lesson(index) => Some(&LESSONS[index]),
+            _ => None,
+        };
+        if let Some(lesson) = lesson {
+            (board_width, board_height) = lesson.board_size;
+        }
+        //The pattern being edited is read again on every game, since it may have changed, and the board grows to fit it
+        let edited_pattern = match (&self.options.edit, lesson) {
+            (Some(path), None) => match Pattern::load(path) {
+                Ok(pattern) => Some(pattern),
+                Err(error) => {
+                    self.toasts.push(tr_with("game.load_failed", &[&error]));
+                    None
+                }
+            },
+            _ => None,
+        };
+        if let Some(pattern) = &edited_pattern {
+            board_width = board_width.max(pattern.width);
+            board_height = board_height.max(pattern.height);
+        }
+        //I calculate the proportions of the board, to resize the window accordingly
+        let board_proportions = board_width as f32 / board_height as f32;
+        //I adapt the screen size to the board proportions, according to whether the screen is longer than it's tall, or viceversa
+        //I need to multiply or divide by the proportions so cells are always square shaped
+        let (mut window_width, mut window_height) = (screen_width(), screen_height());
+        if screen_height() < screen_width() {
+            window_width = screen_height() * board_proportions;
+        } else {
+            window_height = screen_width() / board_proportions;
+        }
+        //Since the OS bar on top of the window is counted for the height, I need to add a bit to it
+        //window_height += 0.08 * screen_height();
+        request_new_screen_size(window_width, window_height);
+        next_frame();
+
+        let mut simulation = Simulation::new(board_width, board_height, rule_index);
+        simulation.board.boundary = self.settings.boundary;
+        //Lessons are about the standard rules, on a bounded board
+        if let Some(lesson) = lesson {
+            simulation = Simulation::new(board_width, board_height, 0);
+            simulation.reset(lesson.board());
+        }
+        simulation.speed_index = self.settings.speed_index;
+        simulation.enable_background_stepping();
+        simulation.max_generations = (max_generations > 0).then_some(max_generations);
+        simulation.watch_target = self.watch_target.clone();
+        //The edited file is followed even if it couldn't be read, so fixing it loads it
+        if let Some(path) = self.options.edit.as_ref().filter(|_| lesson.is_none()) {
+            if let Some(pattern) = &edited_pattern {
+                let board = pattern_board(pattern, &simulation.board);
+                simulation.reset(board);
+            }
+            simulation.pattern_file = Some(PatternFile::new(path.clone()));
+        }
+        //Only the first board is logged
+        simulation.event_log = self.event_log.take();
+        Sandbox::new(simulation, lesson, game_start == GameStart::Tutorial)
+    }
+
+    /// Plays a frame of the boards of the sandbox, or of the menu open over them
+    /// Input: the sandbox
+    /// Output: why the boards stop being played, or None if they carry on
+    async fn run_sandbox_frame(&mut self, sandbox: &mut Sandbox) -> Option<SandboxExit> {
+        let current_time = get_time();
+        let theme = &self.config.apply(&THEMES[self.settings.theme_index]);
+        let palette = &PALETTES[self.settings.palette_index];
+        let window = Rect::new(0., 0., screen_width(), screen_height());
+        let volume = self.settings.volume as f32 / 100.;
+        //Sounds keep playing under the menus, so changes to the volume are heard right away
+        if let Some(sounds) = &mut self.sounds {
+            sounds.update(volume, self.settings.play_ambient, current_time);
+        }
+
+        //Quitting with edits that weren't saved asks whether to save them first, showing the board they were made on
+        if is_quit_requested() || self.is_quit_picked {
+            self.is_quit_picked = false;
+            match sandbox
+                .simulations
+                .iter()
+                .position(|simulation| simulation.has_unsaved_edits)
+            {
+                Some(edited) => {
+                    sandbox.current_simulation = edited;
+                    self.quit_prompt.is_open = true;
+                }
+                None => return Some(SandboxExit::Quit),
+            }
+        }
+
+        //The colour picker, the settings screen and the menus take the whole window while open
+        if self.color_picker.is_open
+            || self.settings_screen.is_open
+            || self.pause_menu.is_open
+            || self.quit_prompt.is_open
+        {
+            show_cursor(&mut self.shown_cursor, CursorIcon::Default);
+            let exit = self.update_menus(sandbox, theme, window);
+            next_frame().await;
+            return exit;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            self.pause_menu.is_open = true;
+            sandbox.simulations[sandbox.current_simulation].is_paused = true;
+        }
+        let is_control_down =
+            is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+        sandbox.handle_tab_keys(
+            is_control_down,
+            self.attachments.is_attached(),
+            self.settings.speed_index,
+            &mut self.toasts,
+        );
+
+        self.update_boards(sandbox, current_time);
+
+        let frame = self.handle_input(sandbox, is_control_down);
+
+        let drawing_start = date::now();
+        sandbox.draw(
+            &mut self.tools,
+            &frame,
+            &self.settings,
+            self.sprite.as_ref(),
+            theme,
+            palette,
+        );
+        //The generation and population are always shown in the top right corner, with the open boards under them
+        let mut corner_lines = sandbox.corner_lines();
+        if self.settings.run_scripts {
+            corner_lines.extend(
+                self.scripts
+                    .metrics()
+                    .into_iter()
+                    .map(|(name, value)| format!("{name}: {value}")),
+            );
+        }
+        draw_corner_lines(&corner_lines, 0, theme);
+        let clicked_button = sandbox.update_transport(&frame, theme, &mut self.toasts);
+        let simulation = &mut sandbox.simulations[sandbox.current_simulation];
+        if clicked_button == Some(TransportButton::Menu) {
+            self.pause_menu.is_open = true;
+            simulation.is_paused = true;
+        }
+        if self.settings.keys.is_pressed(Action::Pause)
+            || clicked_button == Some(TransportButton::Pause)
+        {
+            simulation.is_paused = !simulation.is_paused;
+            simulation.is_extinct = false;
+            if let Some(sounds) = &self.sounds {
+                sounds.play_pause(simulation.is_paused, volume);
+            }
+        }
+        sandbox.draw_panels(&mut self.tools, &frame, &self.settings, theme);
+
+        //Shows how long frames take, and how much of that goes to drawing and to computing generations
+        let drawing_duration = date::now() - drawing_start;
+        if self.show_performance {
+            let simulation = &sandbox.simulations[sandbox.current_simulation];
+            let lines = [
+                tr_with("game.fps", &[&get_fps()]),
+                tr_with(
+                    "game.frame_time",
+                    &[&format!("{:.1}", get_frame_time() * 1000.)],
+                ),
+                tr_with(
+                    "game.drawing_time",
+                    &[&format!("{:.1}", drawing_duration * 1000.)],
+                ),
+                tr_with(
+                    "game.generation_time",
+                    &[&format!("{:.1}", simulation.last_step_duration * 1000.)],
+                ),
+            ];
+            draw_corner_lines(&lines, corner_lines.len(), theme);
+        }
+
+        self.play_changes(sandbox, volume, current_time);
+        //Toasts are stacked above the bottom line, which is kept for messages that stay
+        self.toasts.draw(
+            (window.w / 80., window.h - window.h / 40. - 34.),
+            theme,
+            !self.settings.reduced_motion,
+        );
+        next_frame().await;
+        None
+    }
+
+    /// Runs every board, sending them to whatever they're shared with, loading the files they follow again, and
+    /// running the scripts on the board in view
+    /// Input: the sandbox, and the current time
+    fn update_boards(&mut self, sandbox: &mut Sandbox, current_time: f64) {
+        sandbox.update(current_time);
+        self.attachments.update(
+            &mut sandbox.simulations,
+            sandbox.current_simulation,
+            &mut self.toasts,
+            current_time,
+        );
+        sandbox.reload_pattern_files(current_time, &mut self.toasts);
+        for achievement in self.achievements.observe(
+            &sandbox.simulations,
+            sandbox.current_simulation,
+            current_time,
+        ) {
+            self.toasts.push(unlocked_message(achievement));
+        }
+        if let Some(path) = &self.achievements_path
+            && let Err(error) = self.achievements.save_if_due(path, current_time)
+        {
+            eprintln!("Couldn't save the achievements: {error}");
+        }
+        let simulation = &mut sandbox.simulations[sandbox.current_simulation];
+        //Scripts run on each new generation of the board in view
+        if self.settings.run_scripts && !self.scripts.is_empty() && simulation.generation > 0 {
+            let (is_changed, errors) = self
+                .scripts
+                .run(&mut simulation.board, simulation.generation);
+            if is_changed {
+                simulation.mark_edited();
+            }
+            for error in errors {
+                self.toasts.push(tr_with("game.script_failed", &[&error]));
+            }
+        }
+    }
+
+    /// Plays the births and deaths of the board in view, and shows what happened on every board
+    /// Input: the sandbox, the volume, and the current time
+    fn play_changes(&mut self, sandbox: &mut Sandbox, volume: f32, current_time: f64) {
+        //Boards in other tabs keep running, so what happens on them is shown too. Only the board in view is heard
+        for (index, other) in sandbox.simulations.iter_mut().enumerate() {
+            let changes = std::mem::take(&mut other.recent_changes);
+            if index == sandbox.current_simulation
+                && !self.is_sonifying
+                && let Some(sounds) = &mut self.sounds
+            {
+                sounds.add_changes(changes);
+            }
+            for notice in other.notices.drain(..) {
+                if index == sandbox.current_simulation {
+                    self.toasts.push(notice);
+                } else {
+                    self.toasts
+                        .push(tr_with("game.tab_notice", &[&(index + 1), &notice]));
+                }
+            }
+        }
+        //While the board is played as music, its births and deaths aren't heard on their own
+        if self.is_sonifying
+            && let Some(sounds) = &mut self.sounds
+        {
+            let board = &sandbox.simulations[sandbox.current_simulation].board;
+            sounds.sonify(board, volume, current_time);
+        }
+    }
+
+    /// Handles the input of a frame of the sandbox: the boards take what's done on them, and the game the keys that
+    /// change the settings and the window
+    /// Input: the sandbox, and whether control is held down
+    /// Output: where the boards and controls are on this frame, and what the mouse is over
+    fn handle_input(&mut self, sandbox: &mut Sandbox, is_control_down: bool) -> Frame {
+        let frame = sandbox.handle_input(
+            &mut self.tools,
+            &self.settings,
+            is_control_down,
+            &mut self.toasts,
+        );
+        show_cursor(&mut self.shown_cursor, frame.cursor);
+
+        let keys = self.settings.keys;
+        if keys.is_pressed(Action::Sonify) {
+            self.is_sonifying = !self.is_sonifying;
+            self.toasts.replace(
+                "sonify",
+                tr(if self.is_sonifying {
+                    "game.sonify_on"
+                } else {
+                    "game.sonify_off"
+                }),
+            );
+        }
+        let mut is_changed = false;
+        if keys.is_pressed(Action::Ambient) {
+            self.settings.play_ambient = !self.settings.play_ambient;
+            is_changed = true;
+        }
+        if keys.is_pressed(Action::Grid) {
+            self.settings.show_grid = !self.settings.show_grid;
+            is_changed = true;
+        }
+        if keys.is_pressed(Action::Rulers) {
+            self.settings.show_rulers = !self.settings.show_rulers;
+            is_changed = true;
+        }
+        if keys.is_pressed(Action::CellShape) {
+            self.settings.cell_shape = self.settings.cell_shape.next();
+            is_changed = true;
+        }
+        if is_changed {
+            save_settings(
+                &self.settings,
+                &mut self.config,
+                self.config_path.as_deref(),
+            );
+        }
+        //The layout follows the size of the window on every frame, so it adapts on its own
+        let is_alt_down = is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt);
+        if keys.is_pressed(Action::Fullscreen) || (is_alt_down && is_key_pressed(KeyCode::Enter)) {
+            self.is_fullscreen = !self.is_fullscreen;
+            set_fullscreen(self.is_fullscreen);
+        }
+        if keys.is_pressed(Action::Performance) {
+            self.show_performance = !self.show_performance;
+        }
+        frame
+    }
+
+    /// Shows the menu open over the boards, which takes the whole window
+    /// Input: the sandbox, the colours, and the window
+    /// Output: why the boards stop being played, or None if they carry on
+    fn update_menus(
+        &mut self,
+        sandbox: &mut Sandbox,
+        theme: &Theme,
+        window: Rect,
+    ) -> Option<SandboxExit> {
+        let simulation = &mut sandbox.simulations[sandbox.current_simulation];
+        if self.quit_prompt.is_open {
+            let is_quitting = match self.quit_prompt.update(theme, window) {
+                Some(QuitChoice::Save) => match save_board(simulation, self.save_path.as_deref()) {
+                    Ok(()) => true,
+                    Err(error) => {
+                        self.toasts.push(tr_with("game.save_failed", &[&error]));
+                        false
+                    }
+                },
+                Some(QuitChoice::Discard) => true,
+                Some(QuitChoice::Cancel) | None => false,
+            };
+            return is_quitting.then_some(SandboxExit::Quit);
+        }
+        if self.color_picker.is_open {
+            self.update_color_picker(window);
+            return None;
+        }
+        if self.settings_screen.is_open {
+            match self
+                .settings_screen
+                .update(&mut self.settings, theme, window)
+            {
+                Some(SettingsEvent::Changed) => {
+                    simulation.speed_index = self.settings.speed_index;
+                    simulation.set_boundary(self.settings.boundary);
+                    crate::text::set_text_scale(self.settings.ui_scale, self.settings.large_text);
+                    save_settings(
+                        &self.settings,
+                        &mut self.config,
+                        self.config_path.as_deref(),
+                    );
+                }
+                Some(SettingsEvent::PickColours) => self.color_picker.is_open = true,
+                None => {}
+            }
+            return None;
+        }
+        self.update_pause_menu(sandbox, theme, window)
+    }
+
+    /// Shows the colour picker, writing the colours to the config file as they're picked and once it's closed
+    /// Input: the window
+    fn update_color_picker(&mut self, window: Rect) {
+        if self
+            .color_picker
+            .update(&mut self.config, &THEMES[self.settings.theme_index], window)
+            || !self.color_picker.is_open
+        {
+            save_config(&self.config, self.config_path.as_deref());
+        }
+    }
+
+    /// Shows the pause menu, where the board in view can be restarted, replaced, saved or loaded
+    /// Input: the sandbox, the colours, and the window
+    /// Output: why the boards stop being played, or None if they carry on
+    fn update_pause_menu(
+        &mut self,
+        sandbox: &mut Sandbox,
+        theme: &Theme,
+        window: Rect,
+    ) -> Option<SandboxExit> {
+        let simulation = &mut sandbox.simulations[sandbox.current_simulation];
+        //A new or loaded board keeps the settings of the one it replaces
+        let (board_width, board_height) = simulation.board_size();
+        let mut empty_board = Board::new(board_width, board_height);
+        empty_board.rule = simulation.board.rule;
+        empty_board.boundary = simulation.board.boundary;
+        match self.pause_menu.update(theme, window) {
+            Some(PauseMenuEntry::Resume) => simulation.is_paused = false,
+            Some(PauseMenuEntry::Restart) => simulation.restart(),
+            Some(PauseMenuEntry::NewBoard) => {
+                simulation.reset(empty_board);
+                simulation.pattern_file = None;
+            }
+            Some(PauseMenuEntry::Settings) => {
+                //The settings screen shows the ones of the board being played
+                self.settings.speed_index = simulation.speed_index;
+                self.settings.boundary = simulation.board.boundary;
+                self.settings_screen.is_open = true;
+            }
+            Some(PauseMenuEntry::Save) => {
+                self.toasts
+                    .push(match save_board(simulation, self.save_path.as_deref()) {
+                        Ok(()) => tr("game.board_saved").to_string(),
+                        Err(error) => tr_with("game.save_failed", &[&error]),
+                    });
+                //Every board following the file it was just written to, in any tab, has nothing new to load from it
+                for pattern_file in sandbox
+                    .simulations
+                    .iter_mut()
+                    .filter_map(|simulation| simulation.pattern_file.as_mut())
+                    .filter(|pattern_file| {
+                        Some(pattern_file.path.as_path()) == self.save_path.as_deref()
+                    })
+                {
+                    pattern_file.mark_seen();
+                }
+            }
+            Some(PauseMenuEntry::Load) => {
+                let result = match &self.save_path {
+                    Some(path) => Pattern::load(path).map_err(|error| error.to_string()),
+                    None => Err(tr("game.no_load_directory").to_string()),
+                };
+                match result {
+                    //The pattern goes where loading the file again puts it, so the board doesn't move once the
+                    // file changes
+                    Ok(pattern) => {
+                        simulation.reset(pattern_board(&pattern, &simulation.board));
+                        simulation.pattern_file = self.save_path.clone().map(PatternFile::new);
+                        self.toasts.push(tr("game.board_loaded"));
+                    }
+                    Err(error) => self.toasts.push(tr_with("game.load_failed", &[&error])),
+                }
+            }
+            Some(PauseMenuEntry::Setup) => return Some(SandboxExit::Menu),
+            Some(PauseMenuEntry::Quit) => self.is_quit_picked = true,
+            None => {}
+        }
+        None
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt;
+
+/// Coordinates of a cell of a board: its column and row, counting from 0 at the top left corner
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub const fn new(x: usize, y: usize) -> Self {
+        Point { x, y }
+    }
+
+    /// Moves the point by some number of columns and rows, which can take it past the top or left edge of a board
+    /// Input: how many columns right and rows down it moves, which are negative to move left or up
+    /// Output: the coordinates it ends up at, which can be negative
+    pub fn offset(self, x_offset: isize, y_offset: isize) -> (isize, isize) {
+        (self.x as isize + x_offset, self.y as isize + y_offset)
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((x, y): (usize, usize)) -> Self {
+        Point { x, y }
+    }
+}
+
+impl From<Point> for (usize, usize) {
+    fn from(point: Point) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// A rectangle of cells, given by the cell at its top left corner and its size in cells
+///
+/// ```
+/// use conways_game_of_life::{Point, Rect};
+///
+/// let rect = Rect::from_corners(Point::new(3, 1), Point::new(1, 2));
+/// assert_eq!(Rect::new(1, 1, 3, 2), rect);
+/// assert!(rect.contains(Point::new(3, 2)));
+/// assert!(!rect.contains(Point::new(4, 2)));
+/// assert_eq!(6, rect.points().count());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub const fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Creates the smallest rectangle holding two cells, such as the ones a selection was dragged between
+    /// Input: the two cells, in any order
+    /// Output: the rectangle, with both cells inside it
+    pub fn from_corners(first: Point, second: Point) -> Self {
+        let (left, top) = (first.x.min(second.x), first.y.min(second.y));
+        Rect {
+            x: left,
+            y: top,
+            width: first.x.max(second.x) - left + 1,
+            height: first.y.max(second.y) - top + 1,
+        }
+    }
+
+    /// Returns the cell at the top left corner of the rectangle
+    pub fn top_left(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    /// Checks whether a cell is inside the rectangle
+    pub fn contains(&self, point: Point) -> bool {
+        (self.x..self.x + self.width).contains(&point.x)
+            && (self.y..self.y + self.height).contains(&point.y)
+    }
+
+    /// Goes through every cell of the rectangle
+    /// Output: the coordinates of each cell, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> + use<> {
+        let (x_range, y_range) = (self.x..self.x + self.width, self.y..self.y + self.height);
+        y_range.flat_map(move |y| x_range.clone().map(move |x| Point::new(x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rects_hold_both_corners_row_by_row() {
+        let rect = Rect::from_corners(Point::new(2, 5), Point::new(2, 4));
+        assert_eq!(Rect::new(2, 4, 1, 2), rect);
+        assert_eq!(Point::new(2, 4), rect.top_left());
+        assert_eq!(
+            vec![Point::new(2, 4), Point::new(2, 5)],
+            rect.points().collect::<Vec<_>>()
+        );
+        assert!(!Rect::new(0, 0, 0, 0).contains(Point::new(0, 0)));
+        assert_eq!((1, -1), Point::new(2, 0).offset(-1, -1));
+        assert_eq!(Point::new(7, 3), (7, 3).into());
+    }
+}
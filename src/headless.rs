@@ -0,0 +1,88 @@
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use crate::overlay::random_board;
+use crate::renderer::{PngRenderer, Renderer, TextRenderer};
+use crate::{GolError, SplitMix64};
+
+/// Width and height of the boards run in the terminal, in cells, small enough to fit in most terminals
+const TERMINAL_BOARD_SIZE: (usize, usize) = (60, 30);
+
+/// Width and height of the boards written to images, in cells
+const IMAGE_BOARD_SIZE: (usize, usize) = (160, 120);
+
+/// Time each generation is shown for in the terminal
+const TERMINAL_FRAME_TIME: Duration = Duration::from_millis(100);
+
+/// Where the generations of a headless run are shown
+#[derive(Clone, PartialEq, Debug)]
+pub enum HeadlessOutput {
+    /// Printed on the terminal, each one over the last
+    Terminal,
+    /// Written as PNG images to a directory
+    Images(PathBuf),
+}
+
+impl FromStr for HeadlessOutput {
+    type Err = String;
+
+    /// Parses an output, either "tui" for the terminal or the directory to write images to
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if text.is_empty() {
+            Err("--headless needs tui or a directory".to_string())
+        } else if text.eq_ignore_ascii_case("tui") {
+            Ok(HeadlessOutput::Terminal)
+        } else {
+            Ok(HeadlessOutput::Images(PathBuf::from(text)))
+        }
+    }
+}
+
+/// Runs a random board without opening a window, showing each generation on the terminal or writing it to an image
+/// Input: where to show the generations, and how many generations to run for
+/// Output: whether every generation was shown, or what kept one from being shown
+pub fn run_headless(output: &HeadlessOutput, generations: usize) -> Result<(), GolError> {
+    let mut source = SplitMix64::new(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |time| time.as_nanos() as u64),
+    );
+    let (mut renderer, (width, length), frame_time): (Box<dyn Renderer>, _, _) = match output {
+        HeadlessOutput::Terminal => {
+            let mut renderer = TextRenderer::new(io::stdout().lock());
+            renderer.is_animated = true;
+            (Box::new(renderer), TERMINAL_BOARD_SIZE, TERMINAL_FRAME_TIME)
+        }
+        HeadlessOutput::Images(directory) => (
+            Box::new(PngRenderer::new(directory.clone())),
+            IMAGE_BOARD_SIZE,
+            Duration::ZERO,
+        ),
+    };
+    let mut board = random_board(width, length, &mut source);
+    renderer.render(&board, 0)?;
+    for generation in 1..=generations {
+        board.update_board();
+        thread::sleep(frame_time);
+        renderer.render(&board, generation)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headless_output_is_the_terminal_or_a_directory() {
+        assert_eq!(Ok(HeadlessOutput::Terminal), "TUI".parse());
+        assert_eq!(
+            Ok(HeadlessOutput::Images(PathBuf::from("frames"))),
+            "frames".parse()
+        );
+        assert!("".parse::<HeadlessOutput>().is_err());
+    }
+}
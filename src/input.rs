@@ -0,0 +1,125 @@
+use macroquad::miniquad::{CursorIcon, window::set_mouse_cursor};
+use macroquad::prelude::*;
+
+use crate::view::View;
+use crate::{Board, Point};
+
+/// Finds the cell of a board under a point of the screen. Points outside the area the board is drawn in, such as the
+/// margins around it, aren't over any cell, even on toroidal boards
+/// Input: the board, its view, the area it's drawn in, the point, and the size of the cells with the whole board in view
+/// Output: the position of the cell on screen, in cells from the top left corner of the board, which can be past its
+/// edges on toroidal boards, along with its coordinates on the board, if the point is over one
+pub fn cell_under(
+    board: &Board,
+    view: &View,
+    area: Rect,
+    (x, y): (f32, f32),
+    fit_cell_size: f32,
+) -> Option<((isize, isize), Point)> {
+    if !area.contains(vec2(x, y)) {
+        return None;
+    }
+    let cell = view.cell_at((x - area.x, y - area.y), fit_cell_size);
+    board
+        .wrap_coordinates(cell.0, cell.1)
+        .map(|coordinates| (cell, coordinates))
+}
+
+/// Picks the mouse cursor that shows what clicking does at the moment
+/// Input: whether the view is being dragged, whether the mouse is over a button or the minimap, whether it's over a cell,
+/// whether the game is paused, and whether clicking on a cell traces its influence instead of toggling it
+pub fn tool_cursor(
+    is_panning: bool,
+    is_over_control: bool,
+    is_over_cell: bool,
+    is_paused: bool,
+    is_tracing_influence: bool,
+) -> CursorIcon {
+    if is_panning {
+        CursorIcon::Move
+    } else if is_over_control {
+        CursorIcon::Pointer
+    } else if is_over_cell && is_paused && is_tracing_influence {
+        CursorIcon::Help
+    } else if is_over_cell && is_paused {
+        CursorIcon::Crosshair
+    } else {
+        CursorIcon::Default
+    }
+}
+
+/// Changes the mouse cursor, unless it's already the one shown
+/// Input: the cursor being shown, and the one to show
+pub fn show_cursor(shown: &mut CursorIcon, cursor: CursorIcon) {
+    if *shown != cursor {
+        set_mouse_cursor(cursor);
+        *shown = cursor;
+    }
+}
+
+/// Buttons at the bottom of the window that drive the game with the mouse alone, in order
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TransportButton {
+    Slower,
+    StepBack,
+    Pause,
+    Step,
+    Faster,
+    Menu,
+}
+
+pub const TRANSPORT_BUTTONS: [TransportButton; 6] = [
+    TransportButton::Slower,
+    TransportButton::StepBack,
+    TransportButton::Pause,
+    TransportButton::Step,
+    TransportButton::Faster,
+    TransportButton::Menu,
+];
+
+/// Finds where the transport buttons go, in the middle of the bottom of the window
+/// Input: the width and height of the window
+pub fn transport_rect(window_width: f32, window_height: f32) -> Rect {
+    Rect::new(window_width / 2. - 210., window_height - 42., 420., 32.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Boundary;
+
+    #[test]
+    fn clicks_around_the_board_are_not_over_any_cell() {
+        let mut board = Board::new(4, 4);
+        board.boundary = Boundary::Toroidal;
+        let area = Rect::new(100., 0., 40., 40.);
+        let view = View::default();
+        assert_eq!(
+            Some(((1, 2), Point::new(1, 2))),
+            cell_under(&board, &view, area, (115., 25.), 10.)
+        );
+        assert_eq!(None, cell_under(&board, &view, area, (95., 25.), 10.));
+        assert_eq!(None, cell_under(&board, &view, area, (115., 45.), 10.));
+    }
+
+    #[test]
+    fn cursor_follows_what_clicking_does() {
+        assert_eq!(CursorIcon::Move, tool_cursor(true, true, true, true, false));
+        assert_eq!(
+            CursorIcon::Pointer,
+            tool_cursor(false, true, false, true, false)
+        );
+        assert_eq!(
+            CursorIcon::Crosshair,
+            tool_cursor(false, false, true, true, false)
+        );
+        assert_eq!(
+            CursorIcon::Help,
+            tool_cursor(false, false, true, true, true)
+        );
+        assert_eq!(
+            CursorIcon::Default,
+            tool_cursor(false, false, true, false, false)
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use std::str::FromStr;
+
+use macroquad::prelude::*;
+
+/// Actions done with a key during the game, which can each be bound to any key
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Action {
+    Pause,
+    Slower,
+    Faster,
+    StepBack,
+    PanUp,
+    PanLeft,
+    PanDown,
+    PanRight,
+    Compare,
+    NextRule,
+    PreviousRule,
+    Diff,
+    Grid,
+    Rulers,
+    CellShape,
+    Watch,
+    Influence,
+    Census,
+    Tutorial,
+    Performance,
+    Fullscreen,
+    Ambient,
+    Sonify,
+    Split,
+    Magnifier,
+    Preview,
+    Focus,
+}
+
+pub const ACTIONS: [Action; 27] = [
+    Action::Pause,
+    Action::Slower,
+    Action::Faster,
+    Action::StepBack,
+    Action::PanUp,
+    Action::PanLeft,
+    Action::PanDown,
+    Action::PanRight,
+    Action::Compare,
+    Action::NextRule,
+    Action::PreviousRule,
+    Action::Diff,
+    Action::Grid,
+    Action::Rulers,
+    Action::CellShape,
+    Action::Watch,
+    Action::Influence,
+    Action::Census,
+    Action::Tutorial,
+    Action::Performance,
+    Action::Fullscreen,
+    Action::Ambient,
+    Action::Sonify,
+    Action::Split,
+    Action::Magnifier,
+    Action::Preview,
+    Action::Focus,
+];
+
+/// Keys that actions can be bound to. Escape and the modifiers are left out, as they're used by themselves
+const BINDABLE_KEYS: [KeyCode; 95] = [
+    KeyCode::Space,
+    KeyCode::Apostrophe,
+    KeyCode::Comma,
+    KeyCode::Minus,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::Semicolon,
+    KeyCode::Equal,
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::LeftBracket,
+    KeyCode::Backslash,
+    KeyCode::RightBracket,
+    KeyCode::GraveAccent,
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::Backspace,
+    KeyCode::Insert,
+    KeyCode::Delete,
+    KeyCode::Right,
+    KeyCode::Left,
+    KeyCode::Down,
+    KeyCode::Up,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::Pause,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::Kp0,
+    KeyCode::Kp1,
+    KeyCode::Kp2,
+    KeyCode::Kp3,
+    KeyCode::Kp4,
+    KeyCode::Kp5,
+    KeyCode::Kp6,
+    KeyCode::Kp7,
+    KeyCode::Kp8,
+    KeyCode::Kp9,
+    KeyCode::KpDecimal,
+    KeyCode::KpDivide,
+    KeyCode::KpMultiply,
+    KeyCode::KpSubtract,
+    KeyCode::KpAdd,
+    KeyCode::KpEnter,
+    KeyCode::KpEqual,
+    KeyCode::ScrollLock,
+    KeyCode::PrintScreen,
+    KeyCode::CapsLock,
+    KeyCode::NumLock,
+];
+
+impl Action {
+    /// Returns the name of the action in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Pause => "pause",
+            Action::Slower => "slower",
+            Action::Faster => "faster",
+            Action::StepBack => "step_back",
+            Action::PanUp => "pan_up",
+            Action::PanLeft => "pan_left",
+            Action::PanDown => "pan_down",
+            Action::PanRight => "pan_right",
+            Action::Compare => "compare",
+            Action::NextRule => "next_rule",
+            Action::PreviousRule => "previous_rule",
+            Action::Diff => "diff",
+            Action::Grid => "grid",
+            Action::Rulers => "rulers",
+            Action::CellShape => "cell_shape",
+            Action::Watch => "watch",
+            Action::Influence => "influence",
+            Action::Census => "census",
+            Action::Tutorial => "tutorial",
+            Action::Performance => "performance",
+            Action::Fullscreen => "fullscreen",
+            Action::Ambient => "ambient",
+            Action::Sonify => "sonify",
+            Action::Split => "split",
+            Action::Magnifier => "magnifier",
+            Action::Preview => "preview",
+            Action::Focus => "focus",
+        }
+    }
+
+    /// Returns the key of the text describing what the action does
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Pause => "controls.pause",
+            Action::Slower => "controls.slower",
+            Action::Faster => "controls.faster",
+            Action::StepBack => "controls.step_back",
+            Action::PanUp => "controls.pan_up",
+            Action::PanLeft => "controls.pan_left",
+            Action::PanDown => "controls.pan_down",
+            Action::PanRight => "controls.pan_right",
+            Action::Compare => "controls.compare",
+            Action::NextRule => "controls.next_rule",
+            Action::PreviousRule => "controls.previous_rule",
+            Action::Diff => "controls.diff",
+            Action::Grid => "controls.grid",
+            Action::Rulers => "controls.rulers",
+            Action::CellShape => "controls.shape",
+            Action::Watch => "controls.watch",
+            Action::Influence => "controls.influence",
+            Action::Census => "controls.census",
+            Action::Tutorial => "controls.tutorial",
+            Action::Performance => "controls.performance",
+            Action::Fullscreen => "controls.fullscreen",
+            Action::Ambient => "controls.ambient",
+            Action::Sonify => "controls.sonify",
+            Action::Split => "controls.split",
+            Action::Magnifier => "controls.magnifier",
+            Action::Preview => "controls.preview",
+            Action::Focus => "controls.focus",
+        }
+    }
+
+    /// Returns the key the action is bound to unless it's changed
+    fn default_key(self) -> KeyCode {
+        match self {
+            Action::Pause => KeyCode::Space,
+            Action::Slower => KeyCode::Left,
+            Action::Faster => KeyCode::Right,
+            Action::StepBack => KeyCode::Backspace,
+            Action::PanUp => KeyCode::W,
+            Action::PanLeft => KeyCode::A,
+            Action::PanDown => KeyCode::S,
+            Action::PanRight => KeyCode::D,
+            Action::Compare => KeyCode::C,
+            Action::NextRule => KeyCode::Down,
+            Action::PreviousRule => KeyCode::Up,
+            Action::Diff => KeyCode::X,
+            Action::Grid => KeyCode::G,
+            Action::Rulers => KeyCode::R,
+            Action::CellShape => KeyCode::V,
+            Action::Watch => KeyCode::P,
+            Action::Influence => KeyCode::I,
+            Action::Census => KeyCode::O,
+            Action::Tutorial => KeyCode::H,
+            Action::Performance => KeyCode::F3,
+            Action::Fullscreen => KeyCode::F11,
+            Action::Ambient => KeyCode::M,
+            Action::Sonify => KeyCode::N,
+            Action::Split => KeyCode::Z,
+            Action::Magnifier => KeyCode::L,
+            Action::Preview => KeyCode::E,
+            Action::Focus => KeyCode::F,
+        }
+    }
+
+    fn index(self) -> usize {
+        ACTIONS.iter().position(|action| *action == self).unwrap()
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    /// Parses the name of an action, as it's written in the config file
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        ACTIONS
+            .into_iter()
+            .find(|action| action.name() == name)
+            .ok_or(format!("unknown action '{name}'"))
+    }
+}
+
+/// Returns the name of a key, as it's shown and written in the config file
+pub fn key_name(key: KeyCode) -> String {
+    format!("{key:?}")
+}
+
+/// Parses the name of a key, ignoring case
+/// Input: the name, such as "Space" or "F3"
+/// Output: the key, or a message explaining why it isn't one
+pub fn parse_key(name: &str) -> Result<KeyCode, String> {
+    BINDABLE_KEYS
+        .into_iter()
+        .find(|key| key_name(*key).eq_ignore_ascii_case(name))
+        .ok_or(format!("unknown key '{name}'"))
+}
+
+/// Key each action is bound to
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct KeyBindings {
+    /// Key of each action, in the order of ACTIONS
+    keys: [KeyCode; ACTIONS.len()],
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            keys: ACTIONS.map(Action::default_key),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Returns the key an action is bound to
+    pub fn key(&self, action: Action) -> KeyCode {
+        self.keys[action.index()]
+    }
+
+    /// Binds an action to a key. If another action was bound to that key, it takes the previous key of this one, so
+    /// every key still does a single thing
+    /// Input: the action, and the key
+    pub fn bind(&mut self, action: Action, key: KeyCode) {
+        let previous = self.key(action);
+        for bound in &mut self.keys {
+            if *bound == key {
+                *bound = previous;
+            }
+        }
+        self.keys[action.index()] = key;
+    }
+
+    /// Returns whether the key of an action was pressed on this frame
+    pub fn is_pressed(&self, action: Action) -> bool {
+        is_key_pressed(self.key(action))
+    }
+
+    /// Returns whether the key of an action is being held down
+    pub fn is_down(&self, action: Action) -> bool {
+        is_key_down(self.key(action))
+    }
+
+    /// Returns the actions bound to other keys than their default ones, with their keys
+    pub fn changed(&self) -> impl Iterator<Item = (Action, KeyCode)> + '_ {
+        ACTIONS
+            .into_iter()
+            .map(|action| (action, self.key(action)))
+            .filter(|(action, key)| *key != action.default_key())
+    }
+}
+
+/// Returns whether a key can be bound to an action
+pub fn is_bindable(key: KeyCode) -> bool {
+    BINDABLE_KEYS.contains(&key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binding_a_taken_key_swaps_the_keys() {
+        let mut keys = KeyBindings::default();
+        keys.bind(Action::Pause, KeyCode::P);
+        assert_eq!(KeyCode::P, keys.key(Action::Pause));
+        assert_eq!(KeyCode::Space, keys.key(Action::Watch));
+        assert_eq!(
+            vec![(Action::Pause, KeyCode::P), (Action::Watch, KeyCode::Space)],
+            keys.changed().collect::<Vec<_>>()
+        );
+
+        keys.bind(Action::Pause, KeyCode::Enter);
+        assert_eq!(KeyCode::Space, keys.key(Action::Watch));
+        assert_eq!(KeyCode::Enter, keys.key(Action::Pause));
+    }
+
+    #[test]
+    fn keys_are_parsed_by_name() {
+        for key in BINDABLE_KEYS {
+            assert_eq!(Ok(key), parse_key(&key_name(key)));
+        }
+        assert_eq!(Ok(KeyCode::F3), parse_key("f3"));
+        assert!(parse_key("Escape").is_err());
+        assert_eq!(Ok(Action::StepBack), "step_back".parse());
+    }
+}
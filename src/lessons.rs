@@ -0,0 +1,117 @@
+use crate::locale::tr;
+use crate::patterns::Pattern;
+use crate::theme::Theme;
+use crate::tutorial::draw_banner;
+use crate::{Board, Point};
+
+/// Famous pattern that runs with notes explaining what to watch for
+pub struct Lesson {
+    /// Key of the name of the lesson
+    pub name: &'static str,
+    /// The pattern, in RLE format
+    rle: &'static str,
+    /// Width and height of the board the pattern runs on, big enough for what the notes talk about
+    pub board_size: (usize, usize),
+    /// Coordinates of the cell the top left corner of the pattern goes on
+    position: Point,
+    /// Keys of the notes shown as the pattern runs, each one from a generation on, in order
+    notes: &'static [(usize, &'static str)],
+}
+
+pub static LESSONS: [Lesson; 3] = [
+    Lesson {
+        name: "lesson.r_pentomino",
+        rle: "b2o$2o$bo!",
+        board_size: (160, 120),
+        position: Point::new(79, 59),
+        notes: &[
+            (0, "lesson.r_pentomino.0"),
+            (10, "lesson.r_pentomino.1"),
+            (69, "lesson.r_pentomino.2"),
+            (200, "lesson.r_pentomino.3"),
+            (600, "lesson.r_pentomino.4"),
+            (1103, "lesson.r_pentomino.5"),
+        ],
+    },
+    Lesson {
+        name: "lesson.gun",
+        rle: "24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+        board_size: (80, 60),
+        position: Point::new(2, 2),
+        notes: &[
+            (0, "lesson.gun.0"),
+            (10, "lesson.gun.1"),
+            (30, "lesson.gun.2"),
+            (120, "lesson.gun.3"),
+            (240, "lesson.gun.4"),
+        ],
+    },
+    Lesson {
+        name: "lesson.puffer",
+        rle: "6bo$4bob2o$4bobo$4bo$2bo$obo!",
+        board_size: (300, 300),
+        position: Point::new(60, 60),
+        notes: &[
+            (0, "lesson.puffer.0"),
+            (50, "lesson.puffer.1"),
+            (300, "lesson.puffer.2"),
+            (600, "lesson.puffer.3"),
+        ],
+    },
+];
+
+impl Lesson {
+    /// Creates the board the lesson starts with, with the pattern on it
+    pub fn board(&self) -> Board {
+        let mut board = Board::new(self.board_size.0, self.board_size.1);
+        board.paste(&self.pattern(), self.position);
+        board
+    }
+
+    /// Returns the pattern of the lesson, on its own
+    pub fn pattern(&self) -> Pattern {
+        Pattern::parse_rle(self.rle).expect("lesson patterns are valid")
+    }
+
+    /// Returns the key of the note shown at a generation, which is the last one that started before it
+    pub fn note_at(&self, generation: usize) -> &'static str {
+        self.notes
+            .iter()
+            .take_while(|(start, _)| *start <= generation)
+            .last()
+            .map_or("", |(_, note)| note)
+    }
+
+    /// Draws the note of a generation near the top of the window, along with the name of the lesson
+    /// Input: the generation, and the theme to draw the note with
+    pub fn draw(&self, generation: usize, theme: &Theme) {
+        draw_banner(
+            &format!("{}: {}", tr(self.name), tr(self.note_at(generation))),
+            theme,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lesson_patterns_fit_their_boards() {
+        for lesson in &LESSONS {
+            let pattern = Pattern::parse_rle(lesson.rle).unwrap();
+            assert!(lesson.position.x + pattern.width <= lesson.board_size.0);
+            assert!(lesson.position.y + pattern.height <= lesson.board_size.1);
+            assert!(lesson.board().population() > 0);
+            assert!(lesson.notes.is_sorted_by_key(|(start, _)| *start));
+        }
+    }
+
+    #[test]
+    fn notes_follow_the_generation() {
+        let gun = &LESSONS[1];
+        assert_eq!(gun.notes[0].1, gun.note_at(0));
+        assert_eq!(gun.notes[2].1, gun.note_at(45));
+        assert_eq!(gun.notes[4].1, gun.note_at(10000));
+    }
+}
@@ -0,0 +1,96 @@
+//! Conway's Game of Life, and other life-like cellular automata, played with Macroquad.
+//!
+//! Besides the game itself, which is started with [`run`], the simulation can be used on its own: a [`Board`] of
+//! cells follows a [`LifeLikeRule`], or any other [`Rule`], and [`Pattern`]s of cells can be placed on it, found on it, or read from and written to
+//! pattern files. Whatever can fail, like reading a rulestring or a pattern file, returns a [`GolError`] instead of
+//! panicking.
+//!
+//! ```
+//! use conways_game_of_life::{Board, LifeLikeRule, Pattern, Point};
+//!
+//! let mut board = Board::new(20, 20);
+//! board.rule = "B3/S23".parse::<LifeLikeRule>().unwrap();
+//! board.paste(&Pattern::from_library("Glider").unwrap(), (5, 5));
+//! // After four generations, the glider has moved a cell down and to the right
+//! let board = board.generations().nth(3).unwrap();
+//! assert_eq!(5, board.population());
+//! assert_eq!(Some(Point::new(6, 6)), board.find_pattern(&Pattern::from_library("Glider").unwrap()));
+//! ```
+
+mod achievements;
+mod attachments;
+mod automata;
+mod benchmark;
+pub mod board;
+mod button;
+mod census;
+mod challenge;
+mod chat;
+mod color_picker;
+mod config;
+#[cfg(feature = "http-api")]
+mod control;
+mod demo;
+mod error;
+mod event_log;
+pub mod fixed_board;
+pub mod formats;
+mod game;
+pub mod geometry;
+mod headless;
+mod input;
+mod keys;
+mod lessons;
+mod locale;
+mod magnifier;
+mod menu;
+mod music_output;
+pub mod neighborhood;
+mod network;
+mod options;
+mod overlay;
+mod palette;
+mod pattern_file;
+pub mod patterns;
+mod pause_menu;
+#[cfg(feature = "discord")]
+mod presence;
+mod quit_prompt;
+pub mod random;
+mod render;
+pub mod renderer;
+mod rule_files;
+mod rulers;
+pub mod rules;
+mod sandbox;
+mod sandpile;
+mod scripting;
+mod search;
+#[cfg(feature = "serde")]
+mod serialization;
+mod settings;
+mod simulation;
+mod smooth_life;
+mod sound;
+mod streaming;
+mod text;
+mod text_field;
+mod theme;
+mod time_attack;
+mod toast;
+mod touch;
+mod tutorial;
+mod versus;
+mod view;
+mod worker;
+
+pub use board::{BlitMode, Board, Boundary, CellEvent, CellState, StepStats};
+pub use error::GolError;
+pub use fixed_board::FixedBoard;
+pub use formats::{Pattern, PatternMeta};
+pub use game::{run, run_without_window, window_conf};
+pub use geometry::{Point, Rect};
+pub use neighborhood::{CustomNeighborhood, Hexagonal, Moore, Neighborhood, VonNeumann};
+pub use random::{RandomSource, SplitMix64};
+pub use renderer::{PngRenderer, Renderer, ScreenRenderer, TextRenderer};
+pub use rules::{LifeLikeRule, NeighborCounts, Rule};
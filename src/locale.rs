@@ -0,0 +1,1177 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Languages the game can be shown in
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Language {
+    English,
+    Spanish,
+}
+
+const LANGUAGES: [Language; 2] = [Language::English, Language::Spanish];
+
+/// Index of the language the game is shown in, out of LANGUAGES. The whole game is shown in the same language, so
+/// it's kept here instead of being passed to everything that shows text
+static CURRENT_LANGUAGE: AtomicUsize = AtomicUsize::new(0);
+
+impl Language {
+    /// Returns the text of every key in the language
+    fn texts(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => &ENGLISH,
+            Language::Spanish => &SPANISH,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    /// Writes the code of the language, as it's parsed
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let code = match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+        };
+        write!(f, "{code}")
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    /// Parses the code of a language, ignoring case
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        match code.to_lowercase().as_str() {
+            "en" => Ok(Language::English),
+            "es" => Ok(Language::Spanish),
+            _ => Err(format!("unknown language '{code}', expected en or es")),
+        }
+    }
+}
+
+/// Changes the language the game is shown in
+pub fn set_language(language: Language) {
+    let index = LANGUAGES
+        .iter()
+        .position(|known| *known == language)
+        .unwrap();
+    CURRENT_LANGUAGE.store(index, Ordering::Relaxed);
+}
+
+/// Returns the text shown for a key, in the current language
+/// Input: the key, such as "menu.title"
+/// Output: the text, in English if the language doesn't have it, or the key itself if no language does
+pub fn tr(key: &str) -> &str {
+    let language = LANGUAGES[CURRENT_LANGUAGE.load(Ordering::Relaxed)];
+    lookup(language, key)
+        .or_else(|| lookup(Language::English, key))
+        .unwrap_or(key)
+}
+
+/// Returns the text shown for a key, in the current language, with each "{}" in it replaced by a value, in order
+/// Input: the key, and the values
+pub fn tr_with(key: &str, values: &[&dyn fmt::Display]) -> String {
+    fill(tr(key), values)
+}
+
+/// Replaces each "{}" in a text by a value, in order. Any "{}" left without a value is removed
+fn fill(text: &str, values: &[&dyn fmt::Display]) -> String {
+    let mut parts = text.split("{}");
+    let mut filled = parts.next().unwrap_or_default().to_string();
+    let mut values = values.iter();
+    for part in parts {
+        if let Some(value) = values.next() {
+            filled.push_str(&value.to_string());
+        }
+        filled.push_str(part);
+    }
+    filled
+}
+
+/// Finds the text of a key in a language, if it has one
+fn lookup(language: Language, key: &str) -> Option<&'static str> {
+    language
+        .texts()
+        .iter()
+        .find(|(text_key, _)| *text_key == key)
+        .map(|(_, text)| *text)
+}
+
+/// Text of every key in English, which every other language falls back to
+const ENGLISH: [(&str, &str); 264] = [
+    ("menu.title", "GAME OF LIFE"),
+    ("menu.rules", "Rules:"),
+    (
+        "menu.underpopulation",
+        "- Any alive cell with less than two neighbours dies by underpopulation",
+    ),
+    (
+        "menu.overpopulation",
+        "- Any alive cell with more than three neighbours dies by overpopulation",
+    ),
+    (
+        "menu.reproduction",
+        "- Any dead cell with three neighbours becomes alive by reproduction",
+    ),
+    (
+        "menu.pause_hint",
+        "Press {} to pause. While paused, click on a cell to change its state",
+    ),
+    (
+        "menu.speed_hint",
+        "While playing, press {} or {} to decrease or increase cell state update speed",
+    ),
+    ("menu.start", "Start (enter)"),
+    ("menu.tutorial", "Tutorial (enter)"),
+    ("menu.board_size", "Input board size: "),
+    ("menu.by", " by "),
+    ("menu.presets", "Presets:"),
+    ("menu.preset.small", "Small 30x20"),
+    ("menu.preset.medium", "Medium 80x60"),
+    ("menu.preset.large", "Large 200x150"),
+    ("menu.preset.fit", "Fit to screen"),
+    (
+        "menu.fields_hint",
+        "(use tab to move between numbers, type them with the number keys, and edit them with the arrows and backspace)",
+    ),
+    ("menu.width", "width"),
+    ("menu.height", "height"),
+    (
+        "menu.size_error",
+        "The {} has to be between {} and {} cells",
+    ),
+    ("menu.fix_to_start", "{}, fix it to start"),
+    ("menu.rule", "Rule: {} ({})   (use up and down to change)"),
+    (
+        "menu.edges_bounded",
+        "Edges: bounded, cells past them are dead   (press B to change)",
+    ),
+    (
+        "menu.edges_wrap",
+        "Edges: wrap around to the opposite side   (press B to change)",
+    ),
+    (
+        "menu.theme",
+        "Theme: {}   (press T to change, or O to pick colours)",
+    ),
+    ("menu.stop_after", "Stop after: "),
+    ("menu.generations_to_stop", " generations (0 to never stop)"),
+    ("menu.mode", "Mode (page up/down):"),
+    ("menu.mode.sandbox", "Sandbox"),
+    ("menu.mode.puzzle", "Puzzle"),
+    ("menu.mode.challenge", "Challenge"),
+    ("menu.mode.lessons", "Lessons"),
+    ("menu.mode.benchmark", "Benchmark"),
+    ("menu.benchmark", "Run benchmark (enter)"),
+    ("game.extinct", "The population died out"),
+    ("game.generations_survived", "Generations survived: {}"),
+    ("game.peak_population", "Peak population: {}"),
+    ("game.total_births", "Total births: {}"),
+    ("game.press_to_continue", "Press {} to continue"),
+    (
+        "game.nothing_to_focus",
+        "There are no alive cells to center on",
+    ),
+    ("game.board_saved", "Board saved"),
+    ("game.board_loaded", "Board loaded"),
+    ("game.no_save_directory", "there's no directory to save to"),
+    (
+        "game.no_load_directory",
+        "there's no directory to load from",
+    ),
+    ("game.save_failed", "Couldn't save the board: {}"),
+    ("game.load_failed", "Couldn't load the board: {}"),
+    ("game.watching", "Watching for: {}"),
+    ("game.not_watching", "Not watching for any pattern"),
+    ("game.sonify_on", "Playing the board as music"),
+    ("game.sonify_off", "Stopped playing the board as music"),
+    ("game.found", "Found a {} at ({}, {})"),
+    (
+        "game.influence",
+        "{} cells from {} generations back influenced this one",
+    ),
+    (
+        "game.influence_capped",
+        "{} cells from {} generations back, as far as the history goes, influenced this one",
+    ),
+    (
+        "game.tab_attached",
+        "The first board is shared, streamed, or driven from chat or over HTTP, so its tab can't be closed",
+    ),
+    ("game.trace_hint", "Click on a cell to trace its influence"),
+    ("game.status", "Generation {}, population {}"),
+    ("game.tab", "Board {}/{}"),
+    ("game.tab_notice", "Board {}: {}"),
+    ("game.script_failed", "Script stopped: {}"),
+    ("game.play", "Play"),
+    ("game.pause", "Pause"),
+    ("game.menu", "Menu"),
+    ("game.speed", "Speed: {}"),
+    ("game.stopped_after", "Stopped after {} generations"),
+    ("game.paused", "Paused"),
+    (
+        "game.slowed_down",
+        "Slowed down: each generation takes {} ms",
+    ),
+    ("game.fps", "{} FPS"),
+    ("game.frame_time", "Frame: {} ms"),
+    ("game.drawing_time", "Drawing: {} ms"),
+    ("game.generation_time", "Generation: {} ms"),
+    ("object.title", "Objects"),
+    ("object.block", "Block"),
+    ("object.beehive", "Beehive"),
+    ("object.loaf", "Loaf"),
+    ("object.boat", "Boat"),
+    ("object.tub", "Tub"),
+    ("object.blinker", "Blinker"),
+    ("object.glider", "Glider"),
+    ("object.other", "Other"),
+    ("pause.resume", "Resume"),
+    ("pause.restart", "Restart"),
+    ("pause.new_board", "New board"),
+    ("pause.setup", "Back to setup"),
+    ("pause.settings", "Settings"),
+    ("pause.save", "Save"),
+    ("pause.load", "Load"),
+    ("pause.quit", "Quit"),
+    ("pause.unsaved_changes", "Save the board before quitting?"),
+    ("pause.save_and_quit", "Save and quit"),
+    ("pause.quit_without_saving", "Quit without saving"),
+    ("pause.cancel", "Cancel"),
+    ("settings.title", "Settings"),
+    ("settings.speed", "Speed: < {} >"),
+    ("settings.theme", "Theme: < {} >"),
+    ("settings.colours", "Pick colours..."),
+    ("settings.palette", "Highlight colours: < {} >"),
+    ("palette.default", "default"),
+    ("palette.deuteranopia", "deuteranopia"),
+    ("palette.protanopia", "protanopia"),
+    ("palette.tritanopia", "tritanopia"),
+    ("settings.grid", "Grid lines: < {} >"),
+    ("settings.shown", "shown"),
+    ("settings.hidden", "hidden"),
+    ("settings.rulers", "Rulers: < {} >"),
+    ("settings.cell_shape", "Cell shape: < {} >"),
+    ("settings.shape.square", "square"),
+    ("settings.shape.rounded", "rounded"),
+    ("settings.shape.circle", "circle"),
+    ("settings.edges", "Edges: < {} >"),
+    ("settings.bounded", "bounded"),
+    ("settings.wrap", "wrap around"),
+    ("settings.volume", "Volume: < {}% >"),
+    ("settings.ambient", "Ambient music: < {} >"),
+    ("settings.on", "on"),
+    ("settings.off", "off"),
+    ("settings.accessibility", "Accessibility mode: < {} >"),
+    ("settings.ui_scale", "UI scale: < {}% >"),
+    ("settings.large_text", "Large text: < {} >"),
+    ("settings.cell_outlines", "Cell outlines: < {} >"),
+    ("settings.reduced_motion", "Reduced motion: < {} >"),
+    ("settings.scripts", "Scripts: < {} >"),
+    ("settings.back", "Back"),
+    ("settings.controls", "Controls"),
+    ("settings.press_key", "Press a key..."),
+    ("controls.keys.wheel", "Mouse wheel"),
+    ("controls.keys.middle_drag", "Middle drag"),
+    ("controls.pause", "Pause or resume"),
+    ("controls.slower", "Slow down"),
+    ("controls.faster", "Speed up"),
+    ("controls.zoom", "Zoom"),
+    ("controls.pan", "Move around the board"),
+    ("controls.step_back", "Go back a generation"),
+    ("controls.pan_up", "Move up"),
+    ("controls.pan_left", "Move left"),
+    ("controls.pan_down", "Move down"),
+    ("controls.pan_right", "Move right"),
+    ("controls.compare", "Compare rules side by side"),
+    ("controls.next_rule", "Next rule to compare with"),
+    ("controls.previous_rule", "Previous rule to compare with"),
+    ("controls.diff", "Show differences with a snapshot"),
+    ("controls.grid", "Show or hide grid lines"),
+    ("controls.rulers", "Show or hide the rulers"),
+    ("controls.shape", "Change the shape of cells"),
+    ("controls.watch", "Watch for a pattern"),
+    ("controls.influence", "Trace the influence of a cell"),
+    ("controls.census", "Count the objects on the board"),
+    ("controls.tutorial", "Hide or show the tutorial"),
+    ("controls.tabs", "Open, close and switch boards"),
+    ("controls.performance", "Show frame times"),
+    ("controls.fullscreen", "Switch to fullscreen"),
+    ("controls.ambient", "Play or stop the ambient music"),
+    ("controls.sonify", "Play the board as music"),
+    ("controls.split", "Split the view in two"),
+    ("controls.focus", "Center on the pattern"),
+    (
+        "controls.preview",
+        "Preview the next generation while paused",
+    ),
+    ("controls.magnifier", "Magnify the cells under the cursor"),
+    ("picker.title", "Pick colours"),
+    ("picker.alive", "Alive cells"),
+    ("picker.dead", "Dead cells"),
+    ("picker.background", "Background"),
+    (
+        "picker.hint",
+        "Up and down: choose what to colour   Click: pick a colour   R: back to the theme's   O: done",
+    ),
+    (
+        "tutorial.play",
+        "Welcome! The game starts paused. Press {} to play it",
+    ),
+    ("tutorial.pause", "Press {} again to pause it"),
+    (
+        "tutorial.toggle_cell",
+        "While paused, click on a cell to bring it to life. Click again to kill it",
+    ),
+    (
+        "tutorial.build_blinker",
+        "Build a blinker: three alive cells in a row, with nothing else around them",
+    ),
+    (
+        "tutorial.run_blinker",
+        "Press {} and watch it blink, then pause again",
+    ),
+    (
+        "tutorial.build_glider",
+        "Build a glider:  .O.  ..O  OOO  (three rows, O being alive cells)",
+    ),
+    (
+        "tutorial.run_glider",
+        "Press {} and watch it glide across the board",
+    ),
+    (
+        "tutorial.change_speed",
+        "While it plays, press {} or {} to change the speed",
+    ),
+    (
+        "tutorial.done",
+        "You've finished the tutorial! Press escape for the menu, or {} to hide this",
+    ),
+    ("lesson.r_pentomino", "R-pentomino"),
+    (
+        "lesson.r_pentomino.0",
+        "The R-pentomino: only five cells, yet one of the most chaotic small patterns",
+    ),
+    (
+        "lesson.r_pentomino.1",
+        "Watch it grow in every direction, building objects and tearing them apart",
+    ),
+    (
+        "lesson.r_pentomino.2",
+        "Around generation 69, the first glider escapes: look for it moving away",
+    ),
+    (
+        "lesson.r_pentomino.3",
+        "Blocks, beehives and blinkers keep appearing, colliding and vanishing",
+    ),
+    (
+        "lesson.r_pentomino.4",
+        "It takes over a thousand generations to settle. Can you guess where?",
+    ),
+    (
+        "lesson.r_pentomino.5",
+        "On an endless board it settles here, at generation 1103, with six gliders out",
+    ),
+    ("lesson.gun", "Gosper glider gun"),
+    (
+        "lesson.gun.0",
+        "The Gosper glider gun, found in 1970: the first pattern known to grow forever",
+    ),
+    (
+        "lesson.gun.1",
+        "Two shuttles bounce back and forth, held in place by the blocks on each side",
+    ),
+    (
+        "lesson.gun.2",
+        "Every 30 generations, the shuttles meet in the middle and fire a glider",
+    ),
+    (
+        "lesson.gun.3",
+        "Gliders keep coming, so the population grows without limit",
+    ),
+    (
+        "lesson.gun.4",
+        "It answered Conway's question of whether any pattern could grow forever",
+    ),
+    ("lesson.puffer", "Switch engine puffer"),
+    (
+        "lesson.puffer.0",
+        "Ten cells that never stop growing, one of the smallest such patterns",
+    ),
+    (
+        "lesson.puffer.1",
+        "It turns into a switch engine: a puffer, which moves while leaving debris behind",
+    ),
+    (
+        "lesson.puffer.2",
+        "Follow the engine towards the bottom right: a trail of blocks grows behind it",
+    ),
+    (
+        "lesson.puffer.3",
+        "The trail never ends, so the population grows for as long as there's room",
+    ),
+    ("network.hosting", "Sharing the board on port {}"),
+    ("network.joined", "{} joined the board"),
+    ("network.left", "{} left the board"),
+    (
+        "network.host_lost",
+        "Lost the connection to {}, the board now runs on its own",
+    ),
+    ("streaming.started", "Streaming the board on port {}"),
+    ("streaming.connected", "{} is watching the board"),
+    ("streaming.disconnected", "{} stopped watching the board"),
+    ("control.started", "Answering HTTP requests on port {}"),
+    ("menu.versus", "Versus (V)"),
+    (
+        "versus.placing",
+        "Player {}: click to place a cell ({} left)",
+    ),
+    (
+        "versus.evolving",
+        "Generation {} of {}: player 1 has {} cells, player 2 has {}",
+    ),
+    (
+        "versus.won",
+        "Player {} wins, {} cells to {}! Enter to play again",
+    ),
+    (
+        "versus.tie",
+        "It's a tie, {} cells to {}! Enter to play again",
+    ),
+    (
+        "versus.computer_placing",
+        "The computer is placing a cell ({} left)",
+    ),
+    (
+        "versus.hint",
+        "C: play against the computer or another player, escape: back to the menu",
+    ),
+    ("chat.joined", "Reading the chat of {}"),
+    ("chat.placed", "{}: {}"),
+    ("chat.lost", "Lost the connection to the chat: {}"),
+    ("menu.challenge", "Challenge (enter)"),
+    (
+        "challenge.seeding",
+        "Place up to {} more cells, then press enter: keep more than {} alive for as long as you can",
+    ),
+    (
+        "challenge.running",
+        "Generation {}, {} cells alive (enter skips to the end)",
+    ),
+    (
+        "challenge.over",
+        "Your cells lasted {} generations. Enter to try again",
+    ),
+    (
+        "challenge.high_score",
+        "Your cells lasted {} generations, high score number {}! Enter to try again",
+    ),
+    ("challenge.best", "Best on this board: {}"),
+    (
+        "challenge.daily_seeding",
+        "Daily challenge of {}: change up to {} more cells, then press enter: have as many alive as you can after {} generations",
+    ),
+    (
+        "challenge.daily_over",
+        "{} cells alive at the end. Enter to try again",
+    ),
+    (
+        "challenge.daily_high_score",
+        "{} cells alive at the end, high score number {}! Enter to try again",
+    ),
+    ("menu.achievements", "Achievements (A)"),
+    ("achievement.title", "Achievements: {} of {} unlocked"),
+    ("achievement.locked", "{} (locked)"),
+    ("achievement.progress", " ({} of {} so far)"),
+    ("achievement.unlocked", "Achievement unlocked: {}"),
+    ("achievement.hint", "Escape: back to the menu"),
+    ("achievement.first_glider", "Glider builder"),
+    (
+        "achievement.first_glider.description",
+        "Have a glider on the board",
+    ),
+    ("achievement.slow_oscillator", "Slow beat"),
+    (
+        "achievement.slow_oscillator.description",
+        "Have a board that repeats itself every 5 generations or more",
+    ),
+    ("achievement.crowd", "Crowd"),
+    (
+        "achievement.crowd.description",
+        "Have 1000 cells alive at once",
+    ),
+    ("achievement.ten_thousand_generations", "Marathon"),
+    (
+        "achievement.ten_thousand_generations.description",
+        "Simulate 10,000 generations",
+    ),
+    ("achievement.million_generations", "Eternity"),
+    (
+        "achievement.million_generations.description",
+        "Simulate a million generations",
+    ),
+    ("menu.time_attack", "Time attack (enter)"),
+    ("time_attack.target", "Build: {}"),
+    (
+        "time_attack.waiting",
+        "Build the {} as fast as you can: the clock starts on your first click",
+    ),
+    ("time_attack.building", "Building the {}: {} s"),
+    ("time_attack.built", "{} built in {} s"),
+    ("time_attack.record", "{} built in {} s, your best time!"),
+    ("time_attack.best", "Best time: {} s"),
+    (
+        "time_attack.hint",
+        "Click to place or remove cells, left and right: another pattern, enter: start over, escape: back to the menu",
+    ),
+    (
+        "benchmark.running",
+        "Measuring: {} generations, {} a second, {} million cells a second",
+    ),
+    (
+        "benchmark.result",
+        "{} generations in 10 seconds: {} a second, {} million cells a second",
+    ),
+    (
+        "benchmark.hint",
+        "Enter: measure again once done, escape: back to the menu",
+    ),
+    ("automata.forest_fire", "Forest fire"),
+    ("automata.epidemic", "Epidemic"),
+    ("automata.growth", "Tree growth: {}"),
+    ("automata.lightning", "Lightning: {}"),
+    ("automata.infection", "Infection: {}"),
+    ("automata.recovery", "Recovery: {}"),
+    ("automata.immunity_loss", "Immunity loss: {}"),
+    (
+        "automata.forest_fire.counts",
+        "Generation {}: {} trees, {} burning",
+    ),
+    (
+        "automata.epidemic.counts",
+        "Generation {}: {} susceptible, {} infected, {} recovered",
+    ),
+    (
+        "automata.hint",
+        "Click on a cell to set it on fire or infect it, the game keys pause and change the speed, enter: start over, escape: back to the menu",
+    ),
+    ("menu.sandpile", "Sandpile"),
+    ("sandpile.toppling", "{} grains, {} topplings so far"),
+    ("sandpile.stable", "{} grains, settled after {} topplings"),
+    (
+        "sandpile.hint",
+        "Click or hold to drop sand, C: drop a big pile on the centre, enter: empty the grid, escape: back to the menu",
+    ),
+    ("menu.smooth_life", "SmoothLife"),
+    (
+        "smooth_life.generation",
+        "Generation {}, {} cells of life in all",
+    ),
+    (
+        "smooth_life.hint",
+        "Click to bring a circle to life, the game keys pause and change the speed, enter: start over, escape: back to the menu",
+    ),
+    ("presence.menu", "Picking a board"),
+    ("presence.rule", "Rule: {}"),
+    ("presence.board", "Generation {}, {} cells alive"),
+    ("game.pattern_reloaded", "Loaded {} again"),
+    (
+        "challenge.hint",
+        "Click to place or remove cells, D: switch to or from the daily challenge, escape: back to the menu",
+    ),
+];
+
+/// Text of every key in Spanish
+const SPANISH: [(&str, &str); 264] = [
+    ("menu.title", "JUEGO DE LA VIDA"),
+    ("menu.rules", "Reglas:"),
+    (
+        "menu.underpopulation",
+        "- Toda célula viva con menos de dos vecinas muere por subpoblación",
+    ),
+    (
+        "menu.overpopulation",
+        "- Toda célula viva con más de tres vecinas muere por sobrepoblación",
+    ),
+    (
+        "menu.reproduction",
+        "- Toda célula muerta con tres vecinas nace por reproducción",
+    ),
+    (
+        "menu.pause_hint",
+        "Pulsa {} para pausar. En pausa, haz clic en una célula para cambiar su estado",
+    ),
+    (
+        "menu.speed_hint",
+        "Durante la partida, pulsa {} o {} para bajar o subir la velocidad de actualización",
+    ),
+    ("menu.start", "Empezar (intro)"),
+    ("menu.tutorial", "Tutorial (intro)"),
+    ("menu.board_size", "Tamaño del tablero: "),
+    ("menu.by", " por "),
+    ("menu.presets", "Tamaños:"),
+    ("menu.preset.small", "Pequeño 30x20"),
+    ("menu.preset.medium", "Mediano 80x60"),
+    ("menu.preset.large", "Grande 200x150"),
+    ("menu.preset.fit", "Ajustar a la pantalla"),
+    (
+        "menu.fields_hint",
+        "(usa tab para cambiar de número, escríbelos con las teclas numéricas y edítalos con las flechas y retroceso)",
+    ),
+    ("menu.width", "ancho"),
+    ("menu.height", "alto"),
+    (
+        "menu.size_error",
+        "El {} tiene que estar entre {} y {} células",
+    ),
+    ("menu.fix_to_start", "{}, corrígelo para empezar"),
+    (
+        "menu.rule",
+        "Regla: {} ({})   (usa arriba y abajo para cambiarla)",
+    ),
+    (
+        "menu.edges_bounded",
+        "Bordes: cerrados, las células fuera están muertas   (pulsa B para cambiarlos)",
+    ),
+    (
+        "menu.edges_wrap",
+        "Bordes: continúan por el lado opuesto   (pulsa B para cambiarlos)",
+    ),
+    (
+        "menu.theme",
+        "Tema: {}   (pulsa T para cambiarlo, u O para elegir colores)",
+    ),
+    ("menu.stop_after", "Parar tras: "),
+    (
+        "menu.generations_to_stop",
+        " generaciones (0 para no parar)",
+    ),
+    ("menu.mode", "Modo (re pág/av pág):"),
+    ("menu.mode.sandbox", "Libre"),
+    ("menu.mode.puzzle", "Puzle"),
+    ("menu.mode.challenge", "Desafío"),
+    ("menu.mode.lessons", "Lecciones"),
+    ("menu.mode.benchmark", "Rendimiento"),
+    ("menu.benchmark", "Medir (intro)"),
+    ("game.extinct", "La población se ha extinguido"),
+    ("game.generations_survived", "Generaciones sobrevividas: {}"),
+    ("game.peak_population", "Población máxima: {}"),
+    ("game.total_births", "Nacimientos totales: {}"),
+    ("game.press_to_continue", "Pulsa {} para continuar"),
+    (
+        "game.nothing_to_focus",
+        "No hay células vivas en las que centrar",
+    ),
+    ("game.board_saved", "Tablero guardado"),
+    ("game.board_loaded", "Tablero cargado"),
+    (
+        "game.no_save_directory",
+        "no hay ningún directorio donde guardarlo",
+    ),
+    (
+        "game.no_load_directory",
+        "no hay ningún directorio desde donde cargarlo",
+    ),
+    ("game.save_failed", "No se pudo guardar el tablero: {}"),
+    ("game.load_failed", "No se pudo cargar el tablero: {}"),
+    ("game.watching", "Buscando: {}"),
+    ("game.not_watching", "Sin buscar ningún patrón"),
+    ("game.sonify_on", "Tocando el tablero como música"),
+    ("game.sonify_off", "Se dejó de tocar el tablero como música"),
+    ("game.found", "Encontrado: {} en ({}, {})"),
+    (
+        "game.influence",
+        "{} células de hace {} generaciones influyeron en esta",
+    ),
+    (
+        "game.influence_capped",
+        "{} células de hace {} generaciones, hasta donde llega el historial, influyeron en esta",
+    ),
+    (
+        "game.tab_attached",
+        "El primer tablero está compartido, transmitido o controlado desde el chat o por HTTP, así que su pestaña no se puede cerrar",
+    ),
+    (
+        "game.trace_hint",
+        "Haz clic en una célula para rastrear su influencia",
+    ),
+    ("game.status", "Generación {}, población {}"),
+    ("game.tab", "Tablero {}/{}"),
+    ("game.tab_notice", "Tablero {}: {}"),
+    ("game.script_failed", "Script detenido: {}"),
+    ("game.play", "Jugar"),
+    ("game.pause", "Pausar"),
+    ("game.menu", "Menú"),
+    ("game.speed", "Velocidad: {}"),
+    ("game.stopped_after", "Parado tras {} generaciones"),
+    ("game.paused", "En pausa"),
+    (
+        "game.slowed_down",
+        "Ralentizado: cada generación tarda {} ms",
+    ),
+    ("game.fps", "{} FPS"),
+    ("game.frame_time", "Fotograma: {} ms"),
+    ("game.drawing_time", "Dibujo: {} ms"),
+    ("game.generation_time", "Generación: {} ms"),
+    ("object.title", "Objetos"),
+    ("object.block", "Bloque"),
+    ("object.beehive", "Colmena"),
+    ("object.loaf", "Hogaza"),
+    ("object.boat", "Barco"),
+    ("object.tub", "Bañera"),
+    ("object.blinker", "Parpadeador"),
+    ("object.glider", "Planeador"),
+    ("object.other", "Otros"),
+    ("pause.resume", "Continuar"),
+    ("pause.restart", "Reiniciar"),
+    ("pause.new_board", "Tablero nuevo"),
+    ("pause.setup", "Volver al menú inicial"),
+    ("pause.settings", "Ajustes"),
+    ("pause.save", "Guardar"),
+    ("pause.load", "Cargar"),
+    ("pause.quit", "Salir"),
+    (
+        "pause.unsaved_changes",
+        "¿Guardar el tablero antes de salir?",
+    ),
+    ("pause.save_and_quit", "Guardar y salir"),
+    ("pause.quit_without_saving", "Salir sin guardar"),
+    ("pause.cancel", "Cancelar"),
+    ("settings.title", "Ajustes"),
+    ("settings.speed", "Velocidad: < {} >"),
+    ("settings.theme", "Tema: < {} >"),
+    ("settings.colours", "Elegir colores..."),
+    ("settings.palette", "Colores de resaltado: < {} >"),
+    ("palette.default", "predeterminados"),
+    ("palette.deuteranopia", "deuteranopía"),
+    ("palette.protanopia", "protanopía"),
+    ("palette.tritanopia", "tritanopía"),
+    ("settings.grid", "Líneas de la cuadrícula: < {} >"),
+    ("settings.shown", "visibles"),
+    ("settings.hidden", "ocultas"),
+    ("settings.rulers", "Reglas: < {} >"),
+    ("settings.cell_shape", "Forma de las células: < {} >"),
+    ("settings.shape.square", "cuadrado"),
+    ("settings.shape.rounded", "redondeado"),
+    ("settings.shape.circle", "círculo"),
+    ("settings.edges", "Bordes: < {} >"),
+    ("settings.bounded", "cerrados"),
+    ("settings.wrap", "continuos"),
+    ("settings.volume", "Volumen: < {}% >"),
+    ("settings.ambient", "Música ambiental: < {} >"),
+    ("settings.on", "sí"),
+    ("settings.off", "no"),
+    ("settings.accessibility", "Modo de accesibilidad: < {} >"),
+    ("settings.ui_scale", "Escala de la interfaz: < {}% >"),
+    ("settings.large_text", "Texto grande: < {} >"),
+    ("settings.cell_outlines", "Contorno de las células: < {} >"),
+    ("settings.reduced_motion", "Menos animaciones: < {} >"),
+    ("settings.scripts", "Scripts: < {} >"),
+    ("settings.back", "Volver"),
+    ("settings.controls", "Controles"),
+    ("settings.press_key", "Pulsa una tecla..."),
+    ("controls.keys.wheel", "Rueda del ratón"),
+    (
+        "controls.keys.middle_drag",
+        "Arrastrar con el botón central",
+    ),
+    ("controls.pause", "Pausar o continuar"),
+    ("controls.slower", "Ir más despacio"),
+    ("controls.faster", "Ir más deprisa"),
+    ("controls.zoom", "Zoom"),
+    ("controls.pan", "Moverse por el tablero"),
+    ("controls.step_back", "Volver una generación atrás"),
+    ("controls.pan_up", "Moverse hacia arriba"),
+    ("controls.pan_left", "Moverse a la izquierda"),
+    ("controls.pan_down", "Moverse hacia abajo"),
+    ("controls.pan_right", "Moverse a la derecha"),
+    ("controls.compare", "Comparar reglas lado a lado"),
+    ("controls.next_rule", "Siguiente regla a comparar"),
+    ("controls.previous_rule", "Regla anterior a comparar"),
+    ("controls.diff", "Ver diferencias con una instantánea"),
+    ("controls.grid", "Mostrar u ocultar la cuadrícula"),
+    ("controls.rulers", "Mostrar u ocultar las reglas"),
+    ("controls.shape", "Cambiar la forma de las células"),
+    ("controls.watch", "Buscar un patrón"),
+    ("controls.influence", "Rastrear la influencia de una célula"),
+    ("controls.census", "Contar los objetos del tablero"),
+    ("controls.tutorial", "Ocultar o mostrar el tutorial"),
+    ("controls.tabs", "Abrir, cerrar y cambiar de tablero"),
+    ("controls.performance", "Mostrar tiempos de fotograma"),
+    ("controls.fullscreen", "Cambiar a pantalla completa"),
+    ("controls.ambient", "Poner o quitar la música ambiental"),
+    ("controls.sonify", "Tocar el tablero como música"),
+    ("controls.split", "Dividir la vista en dos"),
+    ("controls.focus", "Centrar en el patrón"),
+    ("controls.preview", "Ver la siguiente generación en pausa"),
+    ("controls.magnifier", "Ampliar las células bajo el cursor"),
+    ("picker.title", "Elegir colores"),
+    ("picker.alive", "Células vivas"),
+    ("picker.dead", "Células muertas"),
+    ("picker.background", "Fondo"),
+    (
+        "picker.hint",
+        "Arriba y abajo: elegir qué colorear   Clic: elegir un color   R: volver al del tema   O: listo",
+    ),
+    (
+        "tutorial.play",
+        "¡Bienvenido! La partida empieza en pausa. Pulsa {} para jugar",
+    ),
+    ("tutorial.pause", "Pulsa {} otra vez para pausarla"),
+    (
+        "tutorial.toggle_cell",
+        "En pausa, haz clic en una célula para darle vida. Otro clic la mata",
+    ),
+    (
+        "tutorial.build_blinker",
+        "Construye un parpadeador: tres células vivas en fila, sin nada alrededor",
+    ),
+    (
+        "tutorial.run_blinker",
+        "Pulsa {} y mira cómo parpadea, luego vuelve a pausar",
+    ),
+    (
+        "tutorial.build_glider",
+        "Construye un planeador:  .O.  ..O  OOO  (tres filas, O son células vivas)",
+    ),
+    (
+        "tutorial.run_glider",
+        "Pulsa {} y mira cómo cruza el tablero",
+    ),
+    (
+        "tutorial.change_speed",
+        "Mientras se mueve, pulsa {} o {} para cambiar la velocidad",
+    ),
+    (
+        "tutorial.done",
+        "¡Has terminado el tutorial! Pulsa escape para el menú, o {} para ocultar esto",
+    ),
+    ("lesson.r_pentomino", "R-pentominó"),
+    (
+        "lesson.r_pentomino.0",
+        "El R-pentominó: solo cinco células, y aun así uno de los patrones pequeños más caóticos",
+    ),
+    (
+        "lesson.r_pentomino.1",
+        "Mira cómo crece en todas direcciones, formando objetos y destruyéndolos",
+    ),
+    (
+        "lesson.r_pentomino.2",
+        "Hacia la generación 69 escapa el primer planeador: búscalo alejándose",
+    ),
+    (
+        "lesson.r_pentomino.3",
+        "Bloques, colmenas y parpadeadores aparecen, chocan y desaparecen sin parar",
+    ),
+    (
+        "lesson.r_pentomino.4",
+        "Tarda más de mil generaciones en estabilizarse. ¿Adivinas dónde?",
+    ),
+    (
+        "lesson.r_pentomino.5",
+        "En un tablero infinito se estabiliza aquí, en la generación 1103, con seis planeadores fuera",
+    ),
+    ("lesson.gun", "Cañón de planeadores de Gosper"),
+    (
+        "lesson.gun.0",
+        "El cañón de Gosper, de 1970: el primer patrón conocido que crece para siempre",
+    ),
+    (
+        "lesson.gun.1",
+        "Dos lanzaderas van y vienen, sujetas por los bloques de cada lado",
+    ),
+    (
+        "lesson.gun.2",
+        "Cada 30 generaciones, las lanzaderas se encuentran en el centro y disparan un planeador",
+    ),
+    (
+        "lesson.gun.3",
+        "Los planeadores no dejan de salir, así que la población crece sin límite",
+    ),
+    (
+        "lesson.gun.4",
+        "Respondió a la pregunta de Conway de si algún patrón podía crecer para siempre",
+    ),
+    ("lesson.puffer", "Switch engine, un tren"),
+    (
+        "lesson.puffer.0",
+        "Diez células que nunca dejan de crecer, uno de los patrones más pequeños que lo hacen",
+    ),
+    (
+        "lesson.puffer.1",
+        "Se convierte en un switch engine: un tren, que avanza dejando restos detrás",
+    ),
+    (
+        "lesson.puffer.2",
+        "Sigue al motor hacia abajo a la derecha: detrás crece un rastro de bloques",
+    ),
+    (
+        "lesson.puffer.3",
+        "El rastro nunca acaba, así que la población crece mientras haya sitio",
+    ),
+    ("network.hosting", "Compartiendo el tablero en el puerto {}"),
+    ("network.joined", "{} se ha unido al tablero"),
+    ("network.left", "{} ha dejado el tablero"),
+    (
+        "network.host_lost",
+        "Se perdió la conexión con {}, el tablero sigue por su cuenta",
+    ),
+    (
+        "streaming.started",
+        "Transmitiendo el tablero en el puerto {}",
+    ),
+    ("streaming.connected", "{} está mirando el tablero"),
+    ("streaming.disconnected", "{} ha dejado de mirar el tablero"),
+    (
+        "control.started",
+        "Respondiendo a peticiones HTTP en el puerto {}",
+    ),
+    ("menu.versus", "Versus (V)"),
+    (
+        "versus.placing",
+        "Jugador {}: haz clic para colocar una célula (quedan {})",
+    ),
+    (
+        "versus.evolving",
+        "Generación {} de {}: el jugador 1 tiene {} células, el jugador 2 tiene {}",
+    ),
+    (
+        "versus.won",
+        "¡Gana el jugador {}, {} células a {}! Intro para jugar otra vez",
+    ),
+    (
+        "versus.tie",
+        "¡Empate, {} células a {}! Intro para jugar otra vez",
+    ),
+    (
+        "versus.computer_placing",
+        "El ordenador está colocando una célula (quedan {})",
+    ),
+    (
+        "versus.hint",
+        "C: jugar contra el ordenador u otro jugador, escape: volver al menú",
+    ),
+    ("chat.joined", "Leyendo el chat de {}"),
+    ("chat.placed", "{}: {}"),
+    ("chat.lost", "Se perdió la conexión con el chat: {}"),
+    ("menu.challenge", "Desafío (intro)"),
+    (
+        "challenge.seeding",
+        "Coloca hasta {} células más y pulsa intro: mantén más de {} vivas todo lo que puedas",
+    ),
+    (
+        "challenge.running",
+        "Generación {}, {} células vivas (intro salta al final)",
+    ),
+    (
+        "challenge.over",
+        "Tus células duraron {} generaciones. Intro para volver a intentarlo",
+    ),
+    (
+        "challenge.high_score",
+        "¡Tus células duraron {} generaciones, récord número {}! Intro para volver a intentarlo",
+    ),
+    ("challenge.best", "Mejores en este tablero: {}"),
+    (
+        "challenge.daily_seeding",
+        "Desafío diario del {}: cambia hasta {} células más y pulsa intro: ten tantas vivas como puedas tras {} generaciones",
+    ),
+    (
+        "challenge.daily_over",
+        "{} células vivas al final. Intro para volver a intentarlo",
+    ),
+    (
+        "challenge.daily_high_score",
+        "¡{} células vivas al final, récord número {}! Intro para volver a intentarlo",
+    ),
+    ("menu.achievements", "Logros (A)"),
+    ("achievement.title", "Logros: {} de {} conseguidos"),
+    ("achievement.locked", "{} (bloqueado)"),
+    ("achievement.progress", " ({} de {} por ahora)"),
+    ("achievement.unlocked", "Logro conseguido: {}"),
+    ("achievement.hint", "Escape: volver al menú"),
+    ("achievement.first_glider", "Constructor de planeadores"),
+    (
+        "achievement.first_glider.description",
+        "Ten un planeador en el tablero",
+    ),
+    ("achievement.slow_oscillator", "Ritmo lento"),
+    (
+        "achievement.slow_oscillator.description",
+        "Ten un tablero que se repita cada 5 generaciones o más",
+    ),
+    ("achievement.crowd", "Multitud"),
+    (
+        "achievement.crowd.description",
+        "Ten 1000 células vivas a la vez",
+    ),
+    ("achievement.ten_thousand_generations", "Maratón"),
+    (
+        "achievement.ten_thousand_generations.description",
+        "Simula 10.000 generaciones",
+    ),
+    ("achievement.million_generations", "Eternidad"),
+    (
+        "achievement.million_generations.description",
+        "Simula un millón de generaciones",
+    ),
+    ("menu.time_attack", "Contrarreloj (intro)"),
+    ("time_attack.target", "Construye: {}"),
+    (
+        "time_attack.waiting",
+        "Construye el {} lo más rápido que puedas: el reloj empieza con tu primer clic",
+    ),
+    ("time_attack.building", "Construyendo el {}: {} s"),
+    ("time_attack.built", "{} construido en {} s"),
+    (
+        "time_attack.record",
+        "¡{} construido en {} s, tu mejor tiempo!",
+    ),
+    ("time_attack.best", "Mejor tiempo: {} s"),
+    (
+        "time_attack.hint",
+        "Haz clic para poner o quitar células, izquierda y derecha: otro patrón, intro: empezar de nuevo, escape: volver al menú",
+    ),
+    (
+        "benchmark.running",
+        "Midiendo: {} generaciones, {} por segundo, {} millones de células por segundo",
+    ),
+    (
+        "benchmark.result",
+        "{} generaciones en 10 segundos: {} por segundo, {} millones de células por segundo",
+    ),
+    (
+        "benchmark.hint",
+        "Intro: medir de nuevo al acabar, escape: volver al menú",
+    ),
+    ("automata.forest_fire", "Incendio forestal"),
+    ("automata.epidemic", "Epidemia"),
+    ("automata.growth", "Crecimiento de árboles: {}"),
+    ("automata.lightning", "Rayos: {}"),
+    ("automata.infection", "Contagio: {}"),
+    ("automata.recovery", "Recuperación: {}"),
+    ("automata.immunity_loss", "Pérdida de inmunidad: {}"),
+    (
+        "automata.forest_fire.counts",
+        "Generación {}: {} árboles, {} ardiendo",
+    ),
+    (
+        "automata.epidemic.counts",
+        "Generación {}: {} susceptibles, {} infectadas, {} recuperadas",
+    ),
+    (
+        "automata.hint",
+        "Haz clic en una célula para prenderle fuego o infectarla, las teclas del juego pausan y cambian la velocidad, intro: empezar de nuevo, escape: volver al menú",
+    ),
+    ("menu.sandpile", "Pila de arena"),
+    ("sandpile.toppling", "{} granos, {} derrumbes por ahora"),
+    ("sandpile.stable", "{} granos, asentados tras {} derrumbes"),
+    (
+        "sandpile.hint",
+        "Haz clic o mantén pulsado para echar arena, C: echar un gran montón en el centro, intro: vaciar la cuadrícula, escape: volver al menú",
+    ),
+    ("menu.smooth_life", "SmoothLife"),
+    (
+        "smooth_life.generation",
+        "Generación {}, {} células de vida en total",
+    ),
+    (
+        "smooth_life.hint",
+        "Haz clic para dar vida a un círculo, las teclas del juego pausan y cambian la velocidad, intro: empezar de nuevo, escape: volver al menú",
+    ),
+    ("presence.menu", "Eligiendo un tablero"),
+    ("presence.rule", "Regla: {}"),
+    ("presence.board", "Generación {}, {} células vivas"),
+    ("game.pattern_reloaded", "Se volvió a cargar {}"),
+    (
+        "challenge.hint",
+        "Haz clic para poner o quitar células, D: cambiar al desafío diario o volver, escape: volver al menú",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_language_has_the_english_keys_with_as_many_values() {
+        for language in LANGUAGES {
+            let texts = language.texts();
+            assert_eq!(ENGLISH.len(), texts.len(), "{language}");
+            for ((key, english), (text_key, text)) in ENGLISH.iter().zip(texts) {
+                assert_eq!(key, text_key, "{language}");
+                assert_eq!(
+                    english.matches("{}").count(),
+                    text.matches("{}").count(),
+                    "{key}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn keys_used_in_the_code_have_english_text() {
+        let sources = [
+            include_str!("achievements.rs"),
+            include_str!("automata.rs"),
+            include_str!("benchmark.rs"),
+            include_str!("census.rs"),
+            include_str!("color_picker.rs"),
+            include_str!("game.rs"),
+            include_str!("input.rs"),
+            include_str!("keys.rs"),
+            include_str!("lessons.rs"),
+            include_str!("menu.rs"),
+            include_str!("pause_menu.rs"),
+            include_str!("presence.rs"),
+            include_str!("quit_prompt.rs"),
+            include_str!("render.rs"),
+            include_str!("sandpile.rs"),
+            include_str!("settings.rs"),
+            include_str!("simulation.rs"),
+            include_str!("smooth_life.rs"),
+            include_str!("time_attack.rs"),
+            include_str!("tutorial.rs"),
+        ];
+        let prefixes = [
+            "menu.",
+            "game.",
+            "object.",
+            "pause.",
+            "settings.",
+            "controls.",
+            "picker.",
+            "tutorial.",
+            "lesson.",
+            "achievement.",
+            "time_attack.",
+            "benchmark.",
+            "automata.",
+            "sandpile.",
+            "smooth_life.",
+            "presence.",
+        ];
+        for source in sources {
+            for literal in source.split('"').skip(1).step_by(2) {
+                let is_key = prefixes.iter().any(|prefix| literal.starts_with(prefix))
+                    && literal.chars().all(|c| {
+                        c.is_ascii_lowercase() || c.is_ascii_digit() || c == '.' || c == '_'
+                    });
+                if is_key {
+                    assert!(lookup(Language::English, literal).is_some(), "{literal}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn values_fill_the_text_in_order() {
+        assert_eq!("Board 2/3", fill("Board {}/{}", &[&2, &3]));
+        assert_eq!(
+            "Found a  at (1, )",
+            fill("Found a {} at ({}, {})", &[&"", &1])
+        );
+        assert_eq!(Ok(Language::Spanish), "ES".parse());
+        assert_eq!(Some("Tablero {}/{}"), lookup(Language::Spanish, "game.tab"));
+    }
+}
@@ -0,0 +1,91 @@
+use macroquad::prelude::*;
+
+use crate::theme::Theme;
+use crate::{Board, CellState};
+
+/// How many cells the magnifier shows across, with the one under the cursor in the middle
+const MAGNIFIED_CELLS: isize = 15;
+
+/// Width and height of the magnifier, in pixels
+const MAGNIFIER_SIZE: f32 = 180.;
+
+/// Distance between the cursor and the magnifier, so it doesn't cover the cells being pointed at
+const CURSOR_GAP: f32 = 20.;
+
+/// Finds where the magnifier goes, below and to the right of the cursor, or on the other side of it when that would
+/// go past the edges of the screen
+/// Input: the position of the cursor, and the size of the screen
+/// Output: the screen rectangle of the magnifier
+fn magnifier_rect((x, y): (f32, f32), (screen_width, screen_height): (f32, f32)) -> Rect {
+    let x = if x + CURSOR_GAP + MAGNIFIER_SIZE > screen_width {
+        x - CURSOR_GAP - MAGNIFIER_SIZE
+    } else {
+        x + CURSOR_GAP
+    };
+    let y = if y + CURSOR_GAP + MAGNIFIER_SIZE > screen_height {
+        y - CURSOR_GAP - MAGNIFIER_SIZE
+    } else {
+        y + CURSOR_GAP
+    };
+    Rect::new(x, y, MAGNIFIER_SIZE, MAGNIFIER_SIZE)
+}
+
+/// Draws a window next to the cursor with the cells around the one under it zoomed in, which helps on boards zoomed
+/// out to about a pixel per cell. Past the edges of bounded boards, the background is shown
+/// Input: the board, the position of the cell under the cursor, in cells from the top left corner of the board, which
+/// can be past its edges on toroidal boards, the position of the cursor, and the theme to draw with
+pub fn draw_magnifier(board: &Board, (x, y): (isize, isize), cursor: (f32, f32), theme: &Theme) {
+    let rect = magnifier_rect(cursor, (screen_width(), screen_height()));
+    let cell_size = MAGNIFIER_SIZE / MAGNIFIED_CELLS as f32;
+    let radius = MAGNIFIED_CELLS / 2;
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme.background);
+    for offset_x in -radius..=radius {
+        for offset_y in -radius..=radius {
+            let color = match board.wrap_coordinates(x + offset_x, y + offset_y) {
+                Some(coordinates) if board[coordinates] == CellState::Alive => theme.alive,
+                Some(_) => theme.dead,
+                None => continue,
+            };
+            draw_rectangle(
+                rect.x + (offset_x + radius) as f32 * cell_size,
+                rect.y + (offset_y + radius) as f32 * cell_size,
+                cell_size,
+                cell_size,
+                color,
+            );
+        }
+    }
+    //The cell under the cursor is outlined, like it is on the board
+    draw_rectangle_lines(
+        rect.x + radius as f32 * cell_size,
+        rect.y + radius as f32 * cell_size,
+        cell_size,
+        cell_size,
+        2.,
+        theme.highlight,
+    );
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2., theme.label);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn magnifier_stays_on_screen_next_to_the_cursor() {
+        let screen = (800., 600.);
+        assert_eq!(
+            Rect::new(120., 120., MAGNIFIER_SIZE, MAGNIFIER_SIZE),
+            magnifier_rect((100., 100.), screen)
+        );
+        assert_eq!(
+            Rect::new(
+                780. - CURSOR_GAP - MAGNIFIER_SIZE,
+                590. - CURSOR_GAP - MAGNIFIER_SIZE,
+                MAGNIFIER_SIZE,
+                MAGNIFIER_SIZE
+            ),
+            magnifier_rect((780., 590.), screen)
+        );
+    }
+}
@@ -0,0 +1,631 @@
+use std::path::Path;
+
+use macroquad::prelude::*;
+
+use crate::Boundary;
+use crate::automata::AUTOMATA;
+use crate::button::{button, row_rect};
+use crate::color_picker::ColorPicker;
+use crate::config::Config;
+use crate::demo::Demo;
+use crate::game::save_settings;
+use crate::keys::{Action, key_name};
+use crate::lessons::LESSONS;
+use crate::locale::{tr, tr_with};
+use crate::rules::{LifeLikeRule, rule_count, rule_name};
+use crate::settings::Settings;
+use crate::text::draw_text;
+use crate::text_field::{NumberField, typed_characters};
+use crate::theme::{THEMES, Theme};
+
+/// Smallest and largest width or height a board can have. Bigger boards take too much memory and time to run
+const MIN_BOARD_SIDE: usize = 3;
+const MAX_BOARD_SIDE: usize = 1000;
+
+/// Checks that a board of the given size can be played on
+/// Input: the width and height of the board, in cells
+/// Output: a message explaining what's wrong with the size, if anything
+fn validate_board_size(width: usize, height: usize) -> Result<(), String> {
+    for (name, side) in [("menu.width", width), ("menu.height", height)] {
+        if !(MIN_BOARD_SIDE..=MAX_BOARD_SIDE).contains(&side) {
+            return Err(tr_with(
+                "menu.size_error",
+                &[&tr(name), &MIN_BOARD_SIDE, &MAX_BOARD_SIDE],
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Board size that can be picked on the starting menu instead of typing it
+struct SizePreset {
+    label: &'static str,
+    /// Key that picks the size
+    key: KeyCode,
+    /// Width and height of the board, if it doesn't depend on the size of the window
+    size: Option<(usize, usize)>,
+}
+
+const SIZE_PRESETS: [SizePreset; 4] = [
+    SizePreset {
+        label: "menu.preset.small",
+        key: KeyCode::S,
+        size: Some((30, 20)),
+    },
+    SizePreset {
+        label: "menu.preset.medium",
+        key: KeyCode::M,
+        size: Some((80, 60)),
+    },
+    SizePreset {
+        label: "menu.preset.large",
+        key: KeyCode::L,
+        size: Some((200, 150)),
+    },
+    SizePreset {
+        label: "menu.preset.fit",
+        key: KeyCode::F,
+        size: None,
+    },
+];
+
+/// Size cells are drawn at on boards that fit the screen, big enough to click on them comfortably
+const FIT_CELL_SIZE: f32 = 10.;
+
+/// Finds the size of the board that fills the window with cells of a comfortable size
+/// Input: the size of the window
+/// Output: the width and height of the board, in cells, within the bounds boards can have
+fn fit_to_screen_size((window_width, window_height): (f32, f32)) -> (usize, usize) {
+    let side =
+        |length: f32| ((length / FIT_CELL_SIZE) as usize).clamp(MIN_BOARD_SIDE, MAX_BOARD_SIDE);
+    (side(window_width), side(window_height))
+}
+
+/// How the game is started from the starting menu
+#[derive(Clone, Copy, PartialEq)]
+pub enum GameStart {
+    /// With an empty board of the size picked
+    Board,
+    /// With an empty board and the tutorial
+    Tutorial,
+    /// With the pattern of a lesson, given its index out of LESSONS
+    Lesson(usize),
+    /// With a game for two players, on an empty board of the size picked
+    Versus,
+    /// With a survival challenge, on an empty board of the size picked
+    Challenge,
+    /// With the achievements screen, which needs no board
+    Achievements,
+    /// With a time attack, on boards fitting the patterns to build
+    TimeAttack,
+    /// With a benchmark, on a random board of the size picked
+    Benchmark,
+    /// With a probabilistic automaton on a grid of the size picked, given its index out of AUTOMATA
+    Automaton(usize),
+    /// With an Abelian sandpile on an empty grid of the size picked
+    Sandpile,
+    /// With SmoothLife, on a grid of its own
+    SmoothLife,
+}
+
+/// Kinds of games picked on the starting menu, each one started by the buttons at the bottom of it
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Mode {
+    /// Boards to play freely with
+    Sandbox,
+    /// Patterns to build against the clock
+    Puzzle,
+    /// Survival and daily challenges, and games for two players
+    Challenge,
+    /// The tutorial and the lessons about famous patterns
+    Lessons,
+    /// How fast boards of the size picked step on this computer
+    Benchmark,
+}
+
+/// Every mode, in the order they're shown in
+const MODES: [Mode; 5] = [
+    Mode::Sandbox,
+    Mode::Puzzle,
+    Mode::Challenge,
+    Mode::Lessons,
+    Mode::Benchmark,
+];
+
+impl Mode {
+    /// Returns the key of the name of the mode
+    fn name(self) -> &'static str {
+        match self {
+            Mode::Sandbox => "menu.mode.sandbox",
+            Mode::Puzzle => "menu.mode.puzzle",
+            Mode::Challenge => "menu.mode.challenge",
+            Mode::Lessons => "menu.mode.lessons",
+            Mode::Benchmark => "menu.mode.benchmark",
+        }
+    }
+}
+
+/// Numbers that can be input on the starting menu
+#[derive(Clone, Copy, PartialEq)]
+enum MenuField {
+    Width,
+    Height,
+    MaxGenerations,
+}
+
+/// Starting menu, where the mode, the size of the board, the rule and when the game stops are picked before starting
+/// it, along with a few settings
+pub struct StartingMenu {
+    /// Fields the numbers of the menu are typed into
+    width_field: NumberField,
+    height_field: NumberField,
+    max_generations_field: NumberField,
+    /// Used to know which number is selected
+    selected_field: MenuField,
+    /// Index of the rule the board will follow, out of the presets
+    pub rule_index: usize,
+    /// Kind of game the buttons at the bottom start
+    mode: Mode,
+    /// Patterns running behind the menu while it's idle
+    demo: Demo,
+}
+
+impl StartingMenu {
+    /// Creates the menu, with a small board and the default rule picked
+    /// Input: the generations after which the game stops on its own, 0 meaning it never does
+    /// Output: the menu
+    pub fn new(max_generations: usize) -> Self {
+        StartingMenu {
+            width_field: NumberField::new(10, 4),
+            height_field: NumberField::new(10, 4),
+            max_generations_field: NumberField::new(max_generations, 9),
+            selected_field: MenuField::Width,
+            rule_index: 0,
+            mode: Mode::Sandbox,
+            demo: Demo::new(0.),
+        }
+    }
+
+    /// Returns the width and height of the board typed in, which may not be valid yet
+    pub fn board_size(&self) -> (usize, usize) {
+        (self.width_field.value(), self.height_field.value())
+    }
+
+    /// Returns the generations after which the game stops on its own, 0 meaning it never does
+    pub fn max_generations(&self) -> usize {
+        self.max_generations_field.value()
+    }
+
+    /// Handles the input of the menu and draws it over the whole window
+    /// Input: the settings, which the menu can change, the config they're stored in, the path of the config file, if
+    /// it's known, the colour picker, which the menu can open, and the theme to draw with
+    /// Output: how the game starts, if it was started on this frame
+    pub fn update(
+        &mut self,
+        settings: &mut Settings,
+        config: &mut Config,
+        config_path: Option<&Path>,
+        color_picker: &mut ColorPicker,
+        theme: &Theme,
+    ) -> Option<GameStart> {
+        let window_width = screen_width();
+        let window_height = screen_height();
+        clear_background(theme.background);
+        self.demo
+            .update(LifeLikeRule::preset(self.rule_index), theme, get_time());
+
+        let text_lines = 14.2;
+
+        draw_text(
+            tr("menu.title"),
+            window_width / 3.5,
+            window_height / text_lines,
+            60.,
+            theme.text,
+        );
+        draw_text(
+            tr("menu.rules"),
+            window_width / 40.,
+            2. * window_height / text_lines,
+            30.,
+            theme.text,
+        );
+        draw_text(
+            tr("menu.underpopulation"),
+            window_width / 40.,
+            3. * window_height / text_lines,
+            24.,
+            theme.text,
+        );
+        draw_text(
+            tr("menu.overpopulation"),
+            window_width / 40.,
+            4. * window_height / text_lines,
+            24.,
+            theme.text,
+        );
+        draw_text(
+            tr("menu.reproduction"),
+            window_width / 40.,
+            5. * window_height / text_lines,
+            24.,
+            theme.text,
+        );
+        draw_text(
+            &tr_with(
+                "menu.pause_hint",
+                &[&key_name(settings.keys.key(Action::Pause))],
+            ),
+            window_width / 27.,
+            6. * window_height / text_lines,
+            24.,
+            theme.text,
+        );
+        draw_text(
+            &tr_with(
+                "menu.speed_hint",
+                &[
+                    &key_name(settings.keys.key(Action::Slower)),
+                    &key_name(settings.keys.key(Action::Faster)),
+                ],
+            ),
+            window_width / 27.,
+            7. * window_height / text_lines,
+            20.,
+            theme.text,
+        );
+        let is_achievements_requested = button(
+            tr("menu.achievements"),
+            Rect::new(
+                window_width * 0.76,
+                1.6 * window_height / text_lines,
+                window_width * 0.22,
+                0.7 * window_height / text_lines,
+            ),
+            20.,
+            false,
+            theme,
+        ) || is_key_pressed(KeyCode::A);
+
+        //Pick a preset size, with its key or by clicking on it
+        let presets_area = Rect::new(
+            window_width * 0.18,
+            8.25 * window_height / text_lines,
+            window_width * 0.8,
+            0.55 * window_height / text_lines,
+        );
+        for (index, preset) in SIZE_PRESETS.iter().enumerate() {
+            let is_clicked = button(
+                &format!("{} ({:?})", tr(preset.label), preset.key),
+                row_rect(presets_area, SIZE_PRESETS.len(), index),
+                18.,
+                preset.size == Some((self.width_field.value(), self.height_field.value())),
+                theme,
+            );
+            if is_key_pressed(preset.key) || is_clicked {
+                let (width, height) = preset
+                    .size
+                    .unwrap_or(fit_to_screen_size((window_width, window_height)));
+                self.width_field = NumberField::new(width, 4);
+                self.height_field = NumberField::new(height, 4);
+            }
+        }
+
+        //Move around between the numbers to modify
+        if is_key_pressed(KeyCode::Tab) {
+            let backwards = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+            self.selected_field = match (self.selected_field, backwards) {
+                (MenuField::Width, false) | (MenuField::MaxGenerations, true) => MenuField::Height,
+                (MenuField::Height, false) | (MenuField::Width, true) => MenuField::MaxGenerations,
+                (MenuField::MaxGenerations, false) | (MenuField::Height, true) => MenuField::Width,
+            };
+        }
+        let typed = typed_characters();
+        match self.selected_field {
+            MenuField::Width => self.width_field.handle_input(&typed),
+            MenuField::Height => self.height_field.handle_input(&typed),
+            MenuField::MaxGenerations => self.max_generations_field.handle_input(&typed),
+        }
+        let (board_width, board_height) = self.board_size();
+        let size_error = validate_board_size(board_width, board_height).err();
+
+        draw_text(
+            tr("menu.board_size"),
+            window_width / 30.,
+            8. * window_height / text_lines,
+            24.,
+            theme.text,
+        );
+        self.width_field.draw(
+            (2. * window_width / 6., 8. * window_height / text_lines),
+            24.,
+            self.selected_field == MenuField::Width,
+            theme,
+        );
+        draw_text(
+            tr("menu.by"),
+            3. * window_width / 6.,
+            8. * window_height / text_lines,
+            24.,
+            theme.text,
+        );
+        self.height_field.draw(
+            (4. * window_width / 6., 8. * window_height / text_lines),
+            24.,
+            self.selected_field == MenuField::Height,
+            theme,
+        );
+        draw_text(
+            tr("menu.presets"),
+            window_width / 30.,
+            8.65 * window_height / text_lines,
+            20.,
+            theme.text,
+        );
+
+        //The game can't start until the size is valid, so instead of the hint, what's wrong with it is shown
+        match &size_error {
+            Some(message) => draw_text(
+                &tr_with("menu.fix_to_start", &[&message]),
+                window_width / 30.,
+                9.3 * window_height / text_lines,
+                20.,
+                RED,
+            ),
+            None => draw_text(
+                tr("menu.fields_hint"),
+                window_width / 200.,
+                9.3 * window_height / text_lines,
+                18.,
+                theme.text,
+            ),
+        };
+
+        //Cycle through the rule presets
+        if is_key_pressed(KeyCode::Down) {
+            self.rule_index = (self.rule_index + 1) % rule_count();
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.rule_index = (self.rule_index + rule_count() - 1) % rule_count();
+        }
+        draw_text(
+            &tr_with(
+                "menu.rule",
+                &[
+                    &rule_name(self.rule_index),
+                    &LifeLikeRule::preset(self.rule_index),
+                ],
+            ),
+            window_width / 30.,
+            9.9 * window_height / text_lines,
+            24.,
+            theme.text,
+        );
+        //Swap between bounded and toroidal edges
+        if is_key_pressed(KeyCode::B) {
+            settings.boundary = match settings.boundary {
+                Boundary::Bounded => Boundary::Toroidal,
+                Boundary::Toroidal => Boundary::Bounded,
+            };
+            save_settings(settings, config, config_path);
+        }
+        draw_text(
+            match settings.boundary {
+                Boundary::Bounded => tr("menu.edges_bounded"),
+                Boundary::Toroidal => tr("menu.edges_wrap"),
+            },
+            window_width / 30.,
+            11.1 * window_height / text_lines,
+            24.,
+            theme.text,
+        );
+        //Cycle through the colour themes, or pick colours of my own
+        if is_key_pressed(KeyCode::T) {
+            settings.theme_index = (settings.theme_index + 1) % THEMES.len();
+            save_settings(settings, config, config_path);
+        }
+        if is_key_pressed(KeyCode::O) {
+            color_picker.is_open = true;
+        }
+        draw_text(
+            &tr_with("menu.theme", &[&theme.name]),
+            window_width / 30.,
+            11.7 * window_height / text_lines,
+            24.,
+            theme.text,
+        );
+        draw_text(
+            tr("menu.stop_after"),
+            window_width / 30.,
+            10.5 * window_height / text_lines,
+            24.,
+            theme.text,
+        );
+        self.max_generations_field.draw(
+            (2. * window_width / 6., 10.5 * window_height / text_lines),
+            24.,
+            self.selected_field == MenuField::MaxGenerations,
+            theme,
+        );
+        draw_text(
+            tr("menu.generations_to_stop"),
+            3. * window_width / 6.,
+            10.5 * window_height / text_lines,
+            24.,
+            theme.text,
+        );
+
+        //Pick the mode, with page up and down or by clicking on it, which decides what the buttons below start. The
+        //arrows are left to the cursor of the fields
+        if is_key_pressed(KeyCode::PageDown) {
+            self.mode = MODES[(self.mode as usize + 1) % MODES.len()];
+        }
+        if is_key_pressed(KeyCode::PageUp) {
+            self.mode = MODES[(self.mode as usize + MODES.len() - 1) % MODES.len()];
+        }
+        draw_text(
+            tr("menu.mode"),
+            window_width / 30.,
+            12.35 * window_height / text_lines,
+            20.,
+            theme.text,
+        );
+        let modes_area = Rect::new(
+            window_width * 0.18,
+            11.95 * window_height / text_lines,
+            window_width * 0.8,
+            0.55 * window_height / text_lines,
+        );
+        for (index, mode) in MODES.iter().enumerate() {
+            if button(
+                tr(mode.name()),
+                row_rect(modes_area, MODES.len(), index),
+                18.,
+                *mode == self.mode,
+                theme,
+            ) {
+                self.mode = *mode;
+            }
+        }
+
+        let start_area = Rect::new(
+            window_width / 3.5,
+            12.85 * window_height / text_lines,
+            window_width * 0.3,
+            0.9 * window_height / text_lines,
+        );
+        let is_enter_pressed = is_key_pressed(KeyCode::Enter);
+        //Each mode starts from the buttons of the bottom row, the first one of them also with enter
+        let game_start = match self.mode {
+            Mode::Sandbox => {
+                //Next to the board come the other grids to play freely with, which follow rules of their own
+                let sandbox_area = Rect::new(
+                    window_width / 40.,
+                    start_area.y,
+                    window_width * 0.95,
+                    start_area.h,
+                );
+                let buttons = AUTOMATA.len() + 3;
+                let is_start_requested = button(
+                    tr("menu.start"),
+                    row_rect(sandbox_area, buttons, 0),
+                    30.,
+                    false,
+                    theme,
+                ) || is_enter_pressed;
+                let picked_automaton = (0..AUTOMATA.len()).find(|index| {
+                    button(
+                        tr(AUTOMATA[*index].name()),
+                        row_rect(sandbox_area, buttons, index + 1),
+                        20.,
+                        false,
+                        theme,
+                    )
+                });
+                let is_sandpile_requested = button(
+                    tr("menu.sandpile"),
+                    row_rect(sandbox_area, buttons, AUTOMATA.len() + 1),
+                    20.,
+                    false,
+                    theme,
+                );
+                let is_smooth_life_requested = button(
+                    tr("menu.smooth_life"),
+                    row_rect(sandbox_area, buttons, AUTOMATA.len() + 2),
+                    20.,
+                    false,
+                    theme,
+                );
+                match picked_automaton {
+                    Some(index) => Some(GameStart::Automaton(index)),
+                    None if is_sandpile_requested => Some(GameStart::Sandpile),
+                    None if is_smooth_life_requested => Some(GameStart::SmoothLife),
+                    None => is_start_requested.then_some(GameStart::Board),
+                }
+            }
+            Mode::Puzzle => (button(tr("menu.time_attack"), start_area, 30., false, theme)
+                || is_enter_pressed)
+                .then_some(GameStart::TimeAttack),
+            Mode::Challenge => {
+                let is_challenge_requested =
+                    button(tr("menu.challenge"), start_area, 30., false, theme) || is_enter_pressed;
+                let versus_area = Rect::new(
+                    start_area.right() + window_width * 0.02,
+                    start_area.y,
+                    window_width * 0.2,
+                    start_area.h,
+                );
+                let is_versus_requested = button(tr("menu.versus"), versus_area, 24., false, theme)
+                    || is_key_pressed(KeyCode::V);
+                if is_versus_requested {
+                    Some(GameStart::Versus)
+                } else {
+                    is_challenge_requested.then_some(GameStart::Challenge)
+                }
+            }
+            Mode::Lessons => {
+                //Lessons come with a board of their own, so they can be started whatever the size picked
+                let lessons_area = Rect::new(
+                    window_width / 40.,
+                    start_area.y,
+                    window_width * 0.95,
+                    start_area.h,
+                );
+                let is_tutorial_requested = button(
+                    tr("menu.tutorial"),
+                    row_rect(lessons_area, LESSONS.len() + 1, 0),
+                    24.,
+                    false,
+                    theme,
+                ) || is_enter_pressed;
+                let picked_lesson = (0..LESSONS.len()).find(|index| {
+                    button(
+                        tr(LESSONS[*index].name),
+                        row_rect(lessons_area, LESSONS.len() + 1, index + 1),
+                        20.,
+                        false,
+                        theme,
+                    )
+                });
+                match picked_lesson {
+                    Some(index) => return Some(GameStart::Lesson(index)),
+                    None => is_tutorial_requested.then_some(GameStart::Tutorial),
+                }
+            }
+            Mode::Benchmark => (button(tr("menu.benchmark"), start_area, 30., false, theme)
+                || is_enter_pressed)
+                .then_some(GameStart::Benchmark),
+        };
+
+        if is_achievements_requested {
+            return Some(GameStart::Achievements);
+        }
+        //Time attacks and SmoothLife make boards of their own too, but the other modes play on a board of the size picked
+        match game_start {
+            Some(GameStart::TimeAttack | GameStart::SmoothLife) => game_start,
+            Some(_) if size_error.is_none() => game_start,
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn board_sizes_out_of_bounds_are_rejected() {
+        assert!(validate_board_size(MIN_BOARD_SIDE, MAX_BOARD_SIDE).is_ok());
+        assert!(validate_board_size(0, 10).is_err());
+        assert!(validate_board_size(10, MAX_BOARD_SIDE + 1).is_err());
+    }
+
+    #[test]
+    fn boards_fit_to_screen_stay_within_bounds() {
+        assert_eq!((80, 60), fit_to_screen_size((800., 600.)));
+        assert_eq!(
+            (MAX_BOARD_SIDE, MIN_BOARD_SIDE),
+            fit_to_screen_size((20000., 10.))
+        );
+    }
+}
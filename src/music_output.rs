@@ -0,0 +1,398 @@
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, ErrorKind, Write};
+use std::net::UdpSocket;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::{Board, CellState};
+
+/// Shortest time between two sets of notes or messages, in seconds. Generations in between are sent together, so
+/// fast boards don't flood synthesizers with notes
+const OUTPUT_INTERVAL: f64 = 0.125;
+
+/// Octaves of the scale the bands of the board are spread over
+const OCTAVES: usize = 3;
+
+/// Which way the board is split into bands, each one played with a note of the scale
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Axis {
+    /// Bands of columns, from the lowest note on the left to the highest on the right
+    #[default]
+    Columns,
+    /// Bands of rows, from the lowest note at the bottom to the highest at the top
+    Rows,
+}
+
+impl fmt::Display for Axis {
+    /// Writes the name of the axis, as it's parsed
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Axis::Columns => "columns",
+            Axis::Rows => "rows",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Axis {
+    type Err = String;
+
+    /// Parses the name of an axis, ignoring case
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "columns" => Ok(Axis::Columns),
+            "rows" => Ok(Axis::Rows),
+            _ => Err(format!(
+                "unknown music axis '{name}', expected columns or rows"
+            )),
+        }
+    }
+}
+
+/// Scales the bands of the board are played with
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Scale {
+    /// Five notes per octave, which sound fine together however many bands play at once
+    #[default]
+    Pentatonic,
+    Major,
+    Minor,
+    Blues,
+    Chromatic,
+}
+
+impl Scale {
+    /// Returns the notes of the scale, as semitones above the first note of each octave
+    fn semitones(&self) -> &'static [u8] {
+        match self {
+            Scale::Pentatonic => &[0, 2, 4, 7, 9],
+            Scale::Major => &[0, 2, 4, 5, 7, 9, 11],
+            Scale::Minor => &[0, 2, 3, 5, 7, 8, 10],
+            Scale::Blues => &[0, 3, 5, 6, 7, 10],
+            Scale::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        }
+    }
+}
+
+impl fmt::Display for Scale {
+    /// Writes the name of the scale, as it's parsed
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Scale::Pentatonic => "pentatonic",
+            Scale::Major => "major",
+            Scale::Minor => "minor",
+            Scale::Blues => "blues",
+            Scale::Chromatic => "chromatic",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Scale {
+    type Err = String;
+
+    /// Parses the name of a scale, ignoring case
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "pentatonic" => Ok(Scale::Pentatonic),
+            "major" => Ok(Scale::Major),
+            "minor" => Ok(Scale::Minor),
+            "blues" => Ok(Scale::Blues),
+            "chromatic" => Ok(Scale::Chromatic),
+            _ => Err(format!(
+                "unknown scale '{name}', expected pentatonic, major, minor, blues or chromatic"
+            )),
+        }
+    }
+}
+
+/// How births and deaths are turned into notes, set in the config file
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MusicMapping {
+    pub axis: Axis,
+    pub scale: Scale,
+    /// MIDI note of the lowest band, 60 being middle C
+    pub root: u8,
+    /// MIDI channels births and deaths are played on, from 1 to 16
+    pub birth_channel: u8,
+    pub death_channel: u8,
+}
+
+impl Default for MusicMapping {
+    fn default() -> Self {
+        MusicMapping {
+            axis: Axis::Columns,
+            scale: Scale::Pentatonic,
+            root: 48,
+            birth_channel: 1,
+            death_channel: 2,
+        }
+    }
+}
+
+impl MusicMapping {
+    /// Returns how many bands the board is split into, one for each note of the scale over OCTAVES octaves
+    fn band_count(&self) -> usize {
+        OCTAVES * self.scale.semitones().len()
+    }
+
+    /// Returns the MIDI note a band is played with, kept under 128
+    /// Input: the index of the band, counting up from the lowest one
+    fn note(&self, band: usize) -> u8 {
+        let semitones = self.scale.semitones();
+        let note = self.root as usize
+            + 12 * (band / semitones.len())
+            + semitones[band % semitones.len()] as usize;
+        note.min(127) as u8
+    }
+
+    /// Returns the band a cell falls in
+    fn band(&self, (x, y): (usize, usize), board: &Board) -> usize {
+        match self.axis {
+            Axis::Columns => x * self.band_count() / board.width(),
+            Axis::Rows => (board.length() - 1 - y) * self.band_count() / board.length(),
+        }
+    }
+}
+
+/// Births and deaths of each band of a board since they were last sent, from the lowest band
+#[derive(PartialEq, Debug)]
+struct BandChanges {
+    births: Vec<usize>,
+    deaths: Vec<usize>,
+}
+
+/// Returns how hard a note is played for a number of births or deaths, as a MIDI velocity. Each tenfold of cells is a
+/// bit louder, so big boards don't play everything at full velocity
+fn velocity(count: usize) -> u8 {
+    (40. + (count as f32).log10() * 30.).min(127.) as u8
+}
+
+/// Writes an OSC message whose arguments are all 32 bit integers
+/// Input: the address pattern of the message, like /life/birth, and its arguments
+fn osc_message(address: &str, arguments: &[i32]) -> Vec<u8> {
+    //Strings end with at least one null byte, and every part is padded to a multiple of 4 bytes
+    let padded = |text: &str| {
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.resize((bytes.len() / 4 + 1) * 4, 0);
+        bytes
+    };
+    let mut message = padded(address);
+    message.extend(padded(&format!(",{}", "i".repeat(arguments.len()))));
+    for argument in arguments {
+        message.extend(argument.to_be_bytes());
+    }
+    message
+}
+
+/// Sends the births and deaths of a board as MIDI notes, written to a MIDI device or any other file, and as OSC
+/// messages over UDP, so the board can drive synthesizers and generative music. Each band of columns or rows is a
+/// note of the scale of the mapping, played harder the more cells changed in it, and lasting until the next notes
+pub struct MusicOutput {
+    mapping: MusicMapping,
+    midi: Option<Box<dyn Write>>,
+    osc: Option<UdpSocket>,
+    /// Cells of the board when the last notes were sent
+    cells: Vec<CellState>,
+    last_sent: f64,
+    /// Channel and note of every MIDI note still playing, to stop them before the next ones
+    playing: Vec<(u8, u8)>,
+}
+
+impl MusicOutput {
+    /// Opens the outputs music is sent to
+    /// Input: how births and deaths are turned into notes, the MIDI device or file notes are written to, if they are,
+    /// and the address of the OSC server messages are sent to, like 127.0.0.1:57120, if they are
+    /// Output: the music output, or the error that prevented opening one of them
+    pub fn open(mapping: MusicMapping, midi: Option<&Path>, osc: Option<&str>) -> io::Result<Self> {
+        let midi = match midi {
+            Some(path) => Some(Box::new(
+                OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .truncate(true)
+                    .open(path)?,
+            ) as Box<dyn Write>),
+            None => None,
+        };
+        let osc = match osc {
+            Some(address) => {
+                let socket = UdpSocket::bind("0.0.0.0:0")?;
+                socket.connect(address)?;
+                socket.set_nonblocking(true)?;
+                Some(socket)
+            }
+            None => None,
+        };
+        Ok(MusicOutput {
+            mapping,
+            midi,
+            osc,
+            cells: Vec::new(),
+            last_sent: 0.,
+            playing: Vec::new(),
+        })
+    }
+
+    /// Counts the births and deaths in each band of a board since the last call, and keeps its cells for the next one
+    /// Output: the changes, or nothing if the board changed size since the last call
+    fn changes_by_band(&mut self, board: &Board) -> Option<BandChanges> {
+        if self.cells.len() != board.board.len() {
+            self.cells.clone_from(&board.board);
+            return None;
+        }
+        let band_count = self.mapping.band_count();
+        let mut changes = BandChanges {
+            births: vec![0; band_count],
+            deaths: vec![0; band_count],
+        };
+        for (index, (previous, cell)) in self.cells.iter().zip(&board.board).enumerate() {
+            let counts = match (previous, cell) {
+                (CellState::Dead, CellState::Alive) => &mut changes.births,
+                (CellState::Alive, CellState::Dead) => &mut changes.deaths,
+                _ => continue,
+            };
+            counts[self
+                .mapping
+                .band((index % board.width(), index / board.width()), board)] += 1;
+        }
+        self.cells.clone_from(&board.board);
+        Some(changes)
+    }
+
+    /// Sends the births and deaths of a board since the last notes, if it's time to: notes on the MIDI channels of
+    /// births and deaths, and /life/birth and /life/death messages with the band, note and count, followed by a
+    /// /life/generation message with the generation and population
+    /// Input: the board, its generation, and the current time
+    /// Output: an error if the MIDI device or file couldn't be written to
+    pub fn send(&mut self, board: &Board, generation: usize, time: f64) -> io::Result<()> {
+        if time - self.last_sent < OUTPUT_INTERVAL {
+            return Ok(());
+        }
+        self.last_sent = time;
+        let Some(changes) = self.changes_by_band(board) else {
+            return Ok(());
+        };
+        let mut midi_bytes = self.stop_notes();
+        let mut messages = Vec::new();
+        for (counts, channel, address) in [
+            (&changes.births, self.mapping.birth_channel, "/life/birth"),
+            (&changes.deaths, self.mapping.death_channel, "/life/death"),
+        ] {
+            for (band, count) in counts.iter().enumerate().filter(|(_, count)| **count > 0) {
+                let note = self.mapping.note(band);
+                //Channels are numbered from 1 in the config file, but from 0 in the messages
+                midi_bytes.extend([0x90 | (channel - 1), note, velocity(*count)]);
+                self.playing.push((channel, note));
+                messages.push(osc_message(
+                    address,
+                    &[band as i32, note as i32, *count as i32],
+                ));
+            }
+        }
+        messages.push(osc_message(
+            "/life/generation",
+            &[generation as i32, board.population() as i32],
+        ));
+        if let Some(osc) = &self.osc {
+            for message in messages {
+                match osc.send(&message) {
+                    //Nothing may be listening yet, which UDP reports on a later message, and messages the socket
+                    //has no room for are dropped like any other lost datagram
+                    Err(error)
+                        if error.kind() != ErrorKind::ConnectionRefused
+                            && error.kind() != ErrorKind::WouldBlock =>
+                    {
+                        return Err(error);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.write_midi(&midi_bytes)
+    }
+
+    /// Returns the MIDI messages stopping every note still playing, and forgets them
+    fn stop_notes(&mut self) -> Vec<u8> {
+        self.playing
+            .drain(..)
+            .flat_map(|(channel, note)| [0x80 | (channel - 1), note, 0])
+            .collect()
+    }
+
+    /// Writes MIDI messages to the MIDI device or file, if there's one
+    fn write_midi(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match &mut self.midi {
+            Some(midi) if !bytes.is_empty() => {
+                midi.write_all(bytes)?;
+                midi.flush()
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Drop for MusicOutput {
+    /// Stops the notes still playing, so synthesizers don't hold them forever once the game is closed
+    fn drop(&mut self) {
+        let bytes = self.stop_notes();
+        let _ = self.write_midi(&bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn births_and_deaths_are_sent_as_notes_by_band() {
+        let mapping = MusicMapping::default();
+        assert_eq!(48, mapping.note(0));
+        assert_eq!(60, mapping.note(5));
+        assert_eq!(57, mapping.note(4));
+        let high = MusicMapping {
+            root: 120,
+            ..mapping
+        };
+        assert_eq!(127, high.note(14));
+        assert_eq!(Ok(Scale::Blues), "Blues".parse());
+        assert!("dorian".parse::<Scale>().is_err());
+
+        let path = std::env::temp_dir().join(format!("music_{}.mid", std::process::id()));
+        let mut output = MusicOutput::open(mapping, Some(&path), None).unwrap();
+        let mut board = Board::new(30, 10);
+        board.toggle_cell_state((0, 0));
+        assert_eq!(None, output.changes_by_band(&board));
+        board.toggle_cell_state((0, 0));
+        board.toggle_cell_state((1, 5));
+        board.toggle_cell_state((29, 9));
+        let changes = output.changes_by_band(&board).unwrap();
+        assert_eq!(1, changes.births[0]);
+        assert_eq!(1, changes.births[14]);
+        assert_eq!(1, changes.deaths[0]);
+
+        //Rows are played from the bottom up
+        let rows = MusicMapping {
+            axis: Axis::Rows,
+            ..mapping
+        };
+        assert_eq!(13, rows.band((0, 0), &board));
+        assert_eq!(0, rows.band((0, 9), &board));
+
+        board.toggle_cell_state((10, 3));
+        output.send(&board, 1, 1.).unwrap();
+        //Notes last until the next ones, and are all stopped once the output is closed
+        drop(output);
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(vec![0x90, 60, 40, 0x80, 60, 0], bytes);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(40, velocity(1));
+        assert_eq!(127, velocity(1_000_000));
+        assert_eq!(
+            vec![b'/', b'a', 0, 0, b',', b'i', 0, 0, 0, 0, 0, 7],
+            osc_message("/a", &[7])
+        );
+    }
+}
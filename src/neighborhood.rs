@@ -0,0 +1,128 @@
+use std::fmt::Debug;
+
+/// Which cells around a cell count as its neighbours. Boards count the alive cells at each offset on every generation,
+/// so new shapes of neighbourhood only need their offsets
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use conways_game_of_life::{Board, CellState, VonNeumann};
+///
+/// let board: Board = "
+///     #.#
+///     ...
+///     .#.
+/// "
+/// .parse()
+/// .unwrap();
+/// // The cell in the middle has three alive neighbours, and is born
+/// assert_eq!(CellState::Alive, board.generations().next().unwrap()[(1, 1)]);
+///
+/// // Diagonal cells aren't neighbours under Von Neumann neighbourhoods, so it only has one
+/// let mut board = board;
+/// board.neighborhood = Arc::new(VonNeumann);
+/// assert_eq!(CellState::Dead, board.generations().next().unwrap()[(1, 1)]);
+/// ```
+pub trait Neighborhood: Debug + Send + Sync {
+    /// Returns the offsets of the neighbours from the cell, in columns and rows. The cell itself isn't one of them
+    fn offsets(&self) -> &[(isize, isize)];
+}
+
+/// The eight cells around a cell, touching it on a side or a corner, as in the Game of Life
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Moore;
+
+/// The four cells touching a cell on a side
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct VonNeumann;
+
+/// The six cells around a cell on a hexagonal grid, drawn skewed onto the square one: each row is half a cell to the
+/// right of the one above it, so the cells to the top right and the bottom left aren't neighbours
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Hexagonal;
+
+/// Any set of cells around a cell, given by their offsets
+#[derive(Clone, PartialEq, Debug)]
+pub struct CustomNeighborhood(pub Vec<(isize, isize)>);
+
+impl Neighborhood for Moore {
+    fn offsets(&self) -> &[(isize, isize)] {
+        &[
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ]
+    }
+}
+
+impl Neighborhood for VonNeumann {
+    fn offsets(&self) -> &[(isize, isize)] {
+        &[(0, -1), (-1, 0), (1, 0), (0, 1)]
+    }
+}
+
+impl Neighborhood for Hexagonal {
+    fn offsets(&self) -> &[(isize, isize)] {
+        &[(-1, -1), (0, -1), (-1, 0), (1, 0), (0, 1), (1, 1)]
+    }
+}
+
+impl Neighborhood for CustomNeighborhood {
+    fn offsets(&self) -> &[(isize, isize)] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{Board, Boundary, CellState};
+
+    #[test]
+    fn hexagonal_neighbourhoods_skip_two_corners() {
+        //The dead cell in the middle has three alive neighbours on the usual grid, but the top right one isn't a
+        //neighbour on a hexagonal one
+        let mut board: Board = "
+            ..#
+            #..
+            .#.
+        "
+        .parse()
+        .unwrap();
+        assert_eq!(
+            CellState::Alive,
+            board.generations().next().unwrap()[(1, 1)]
+        );
+        board.neighborhood = Arc::new(Hexagonal);
+        assert_eq!(CellState::Dead, board.generations().next().unwrap()[(1, 1)]);
+    }
+
+    #[test]
+    fn custom_neighbourhoods_reach_further_and_wrap_around() {
+        //Every other cell of a 5 by 5 square, 12 of them, which is more neighbours than rules have counts for
+        let offsets: Vec<_> = (-2..=2)
+            .flat_map(|y| (-2..=2).map(move |x| (x, y)))
+            .filter(|(x, y)| (x + y) % 2 != 0)
+            .collect();
+        let mut board = Board::new(7, 7);
+        board.neighborhood = Arc::new(CustomNeighborhood(offsets));
+        board.boundary = Boundary::Toroidal;
+        board.toggle_cell_state((0, 1));
+        board.toggle_cell_state((0, 6));
+        board.toggle_cell_state((5, 1));
+        //The three cells are neighbours of the corner, two of them across the edges
+        board.update_board();
+        assert_eq!(CellState::Alive, board[(0, 0)]);
+
+        board.board.fill(CellState::Alive);
+        board.update_board();
+        assert_eq!(0, board.population());
+    }
+}
@@ -0,0 +1,754 @@
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::board::MAX_CELLS;
+use crate::locale::tr_with;
+use crate::simulation::Simulation;
+use crate::streaming::Spectator;
+use crate::{Board, Boundary, CellState, Point};
+
+/// Longest a client waits for the host to answer when joining
+pub(crate) const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest share of the cells that can change for the changes to be sent one by one. Past it, the whole board is sent
+const MAX_CHANGED_SHARE: usize = 8;
+
+/// Longest line a host can send, in bytes. Whole boards take a byte for each cell and one for each row, and changes
+/// are only sent one by one for up to an eighth of the cells, which takes no more, so twice the most cells a board can
+/// have is enough for any board being edited, loaded or picked in the menu, with room for the generation, rule and
+/// edges sent before them
+const MAX_HOST_LINE_LENGTH: usize = 2 * MAX_CELLS + 256;
+
+/// Longest line a client can send, in bytes. Clients only ask for a cell to be set or the board to be paused
+const MAX_CLIENT_LINE_LENGTH: usize = 64;
+
+/// What the host and the clients of a shared board tell each other, one message per line. The host sends the board
+/// and how it changes, and clients ask the host to change it
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum Message {
+    /// The whole board, sent when a client joins and when too much changed to send the changes
+    Board {
+        generation: usize,
+        is_paused: bool,
+        board: Board,
+    },
+    /// Cells that changed since the last message, with the generation the board is on now
+    Changes {
+        generation: usize,
+        is_paused: bool,
+        cells: Vec<(Point, CellState)>,
+    },
+    /// A client asking for a cell to be set
+    Set { cell: Point, state: CellState },
+    /// A client asking for the board to be paused or resumed
+    Pause(bool),
+}
+
+impl Message {
+    /// Writes the message as a line of text, such as "set 3 4 1" or "pause 0"
+    pub(crate) fn to_line(&self) -> String {
+        let flag = |value: bool| if value { "1" } else { "0" };
+        let state = |state: CellState| flag(state == CellState::Alive);
+        match self {
+            Message::Board {
+                generation,
+                is_paused,
+                board,
+            } => {
+                let boundary = match board.boundary {
+                    Boundary::Bounded => "bounded",
+                    Boundary::Toroidal => "toroidal",
+                };
+                let rows = board.to_string().replace('\n', "/");
+                format!(
+                    "board {generation} {} {} {boundary} {rows}\n",
+                    flag(*is_paused),
+                    board.rule
+                )
+            }
+            Message::Changes {
+                generation,
+                is_paused,
+                cells,
+            } => {
+                let mut line = format!("changes {generation} {}", flag(*is_paused));
+                for (cell, cell_state) in cells {
+                    line.push_str(&format!(" {},{},{}", cell.x, cell.y, state(*cell_state)));
+                }
+                line.push('\n');
+                line
+            }
+            Message::Set { cell, state: value } => {
+                format!("set {} {} {}\n", cell.x, cell.y, state(*value))
+            }
+            Message::Pause(is_paused) => format!("pause {}\n", flag(*is_paused)),
+        }
+    }
+
+    /// Reads a message from a line of text, as written by to_line
+    /// Output: the message, or what's wrong with the line
+    pub(crate) fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let mut next = || {
+            words
+                .next()
+                .ok_or_else(|| format!("incomplete message '{line}'"))
+        };
+        let number = |word: &str| {
+            word.parse::<usize>()
+                .map_err(|_| format!("invalid number '{word}' in '{line}'"))
+        };
+        let flag = |word: &str| match word {
+            "0" => Ok(false),
+            "1" => Ok(true),
+            _ => Err(format!("expected 0 or 1, found '{word}' in '{line}'")),
+        };
+        let state = |word: &str| {
+            flag(word).map(|is_alive| {
+                if is_alive {
+                    CellState::Alive
+                } else {
+                    CellState::Dead
+                }
+            })
+        };
+        match next()? {
+            "board" => {
+                let generation = number(next()?)?;
+                let is_paused = flag(next()?)?;
+                let rule = next()?.parse().map_err(|error| format!("{error}"))?;
+                let boundary = match next()? {
+                    "bounded" => Boundary::Bounded,
+                    "toroidal" => Boundary::Toroidal,
+                    word => return Err(format!("unknown edges '{word}' in '{line}'")),
+                };
+                let mut board: Board = next()?
+                    .replace('/', "\n")
+                    .parse()
+                    .map_err(|error| format!("{error}"))?;
+                board.rule = rule;
+                board.boundary = boundary;
+                Ok(Message::Board {
+                    generation,
+                    is_paused,
+                    board,
+                })
+            }
+            "changes" => {
+                let generation = number(next()?)?;
+                let is_paused = flag(next()?)?;
+                let cells = words
+                    .map(|change| {
+                        let parts: Vec<_> = change.split(',').collect();
+                        let [x, y, cell_state] = parts[..] else {
+                            return Err(format!("invalid change '{change}' in '{line}'"));
+                        };
+                        Ok((Point::new(number(x)?, number(y)?), state(cell_state)?))
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(Message::Changes {
+                    generation,
+                    is_paused,
+                    cells,
+                })
+            }
+            "set" => {
+                let cell = Point::new(number(next()?)?, number(next()?)?);
+                Ok(Message::Set {
+                    cell,
+                    state: state(next()?)?,
+                })
+            }
+            "pause" => Ok(Message::Pause(flag(next()?)?)),
+            word => Err(format!("unknown message '{word}'")),
+        }
+    }
+}
+
+/// A connection to the other side of a shared board, which never waits for it: what's sent is queued until it can be
+/// written, and what's received is kept until whole lines arrive
+struct Connection {
+    stream: TcpStream,
+    incoming: Vec<u8>,
+    outgoing: Vec<u8>,
+    /// Longest line the other side can send, past which it's dropped instead of kept in memory
+    max_line_length: usize,
+}
+
+impl Connection {
+    fn new(stream: TcpStream, max_line_length: usize) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        Ok(Connection {
+            stream,
+            incoming: Vec::new(),
+            outgoing: Vec::new(),
+            max_line_length,
+        })
+    }
+
+    /// Queues a message to be sent the next time the connection is flushed
+    fn send(&mut self, message: &Message) {
+        self.outgoing.extend(message.to_line().as_bytes());
+    }
+
+    /// Writes as much of what was queued as the connection takes without waiting
+    /// Output: an error if the connection was lost
+    fn flush(&mut self) -> io::Result<()> {
+        while !self.outgoing.is_empty() {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(written) => {
+                    self.outgoing.drain(..written);
+                }
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads every message that arrived whole since the last call
+    /// Output: the messages, or an error if the connection was lost or the other side sent something unreadable or
+    /// a line longer than max_line_length
+    fn receive(&mut self) -> io::Result<Vec<Message>> {
+        let mut buffer = [0; 4096];
+        //Only the start of a line is ever left from the last call, so only what's read needs looking at to know how
+        // long the last line has grown, and reading a whole board takes time in proportion to its size
+        let mut partial_line = self.incoming.len();
+        //Whatever is left once the lines that arrived are read waits for the next call, so memory stays bounded
+        while self.incoming.len() <= self.max_line_length {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(read) => {
+                    let read = &buffer[..read];
+                    self.incoming.extend(read);
+                    partial_line = match read.iter().rev().position(|byte| *byte == b'\n') {
+                        Some(position) => position,
+                        None => partial_line + read.len(),
+                    };
+                    if partial_line > self.max_line_length {
+                        return Err(io::Error::new(
+                            ErrorKind::InvalidData,
+                            "the line sent is too long",
+                        ));
+                    }
+                }
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        let mut messages = Vec::new();
+        while let Some(end) = self.incoming.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.incoming.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            messages.push(Message::parse(&line).map_err(io::Error::other)?);
+        }
+        Ok(messages)
+    }
+}
+
+/// What the host last sent of the board, to send only what changed since
+pub(crate) struct SentState {
+    board: Board,
+    generation: usize,
+    is_paused: bool,
+}
+
+/// A board shared over the network, so several people can build patterns on it together. The host runs the board,
+/// and every change to it, from the host or from clients, goes through the host, which sends it on to every client
+/// Streamed boards are watched as sessions too, with nothing sent back
+pub struct Session {
+    role: Role,
+}
+
+/// Which side of a shared board the game is on
+enum Role {
+    /// Shares its board with the clients that join it
+    Host {
+        listener: TcpListener,
+        clients: Vec<(String, Connection)>,
+        sent: Option<SentState>,
+    },
+    /// Shows the board of a host, sending it the edits made to it
+    Client {
+        address: String,
+        connection: Option<Connection>,
+        /// Board as the host last sent it, along with its generation and whether it's paused
+        shared: Option<SentState>,
+    },
+    /// Shows a board streamed by another game, which can only be watched
+    Spectator {
+        address: String,
+        spectator: Option<Box<Spectator>>,
+        /// Board as it was last streamed, along with its generation and whether it's paused
+        shared: Option<SentState>,
+    },
+}
+
+impl Session {
+    /// Starts sharing a board, waiting for clients on a port
+    /// Input: the address of the network interface to listen on, like 127.0.0.1 to only let this computer join, and
+    /// the port
+    /// Output: the session, or why the port couldn't be listened on
+    pub fn host(address: IpAddr, port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind((address, port))?;
+        listener.set_nonblocking(true)?;
+        Ok(Session {
+            role: Role::Host {
+                listener,
+                clients: Vec::new(),
+                sent: None,
+            },
+        })
+    }
+
+    /// Joins the board shared by a host
+    /// Input: the address of the host, such as "192.168.1.20:7878"
+    /// Output: the session, or why the host couldn't be reached
+    pub fn join(address: &str) -> io::Result<Self> {
+        let socket_address = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "the address has no host"))?;
+        let stream = TcpStream::connect_timeout(&socket_address, CONNECT_TIMEOUT)?;
+        Ok(Session {
+            role: Role::Client {
+                address: address.to_string(),
+                connection: Some(Connection::new(stream, MAX_HOST_LINE_LENGTH)?),
+                shared: None,
+            },
+        })
+    }
+
+    /// Watches a board streamed by another game, without being able to change it
+    /// Input: the address of the game streaming it, such as "192.168.1.20:7879"
+    /// Output: the session, or why the board couldn't be reached
+    pub fn spectate(address: &str) -> io::Result<Self> {
+        Ok(Session {
+            role: Role::Spectator {
+                address: address.to_string(),
+                spectator: Some(Box::new(Spectator::connect(address)?)),
+                shared: None,
+            },
+        })
+    }
+
+    /// Exchanges what changed with the other side. Hosts take in the edits of clients and send every change of the
+    /// board to them, clients send the edits made since the last call and take the board as the host has it, and
+    /// spectators take the board as it's streamed
+    /// Input: the simulation running the shared board
+    /// Output: messages about people joining and leaving, to show
+    pub fn sync(&mut self, simulation: &mut Simulation) -> Vec<String> {
+        match &mut self.role {
+            Role::Host {
+                listener,
+                clients,
+                sent,
+            } => sync_host(listener, clients, sent, simulation),
+            Role::Client {
+                address,
+                connection,
+                shared,
+            } => {
+                let Some(open_connection) = connection else {
+                    return Vec::new();
+                };
+                match sync_client(open_connection, shared, simulation) {
+                    Ok(()) => Vec::new(),
+                    Err(_) => {
+                        //The board keeps running on its own once the host is gone
+                        *connection = None;
+                        simulation.is_remote = false;
+                        vec![tr_with("network.host_lost", &[address])]
+                    }
+                }
+            }
+            Role::Spectator {
+                address,
+                spectator,
+                shared,
+            } => {
+                let Some(open_spectator) = spectator else {
+                    return Vec::new();
+                };
+                simulation.is_remote = true;
+                match open_spectator.receive() {
+                    Ok(messages) => {
+                        for message in messages {
+                            take_update(shared, message);
+                        }
+                        //Edits made while watching are undone, as the board is shown as it was streamed
+                        show_shared(shared, simulation);
+                        Vec::new()
+                    }
+                    Err(_) => {
+                        *spectator = None;
+                        simulation.is_remote = false;
+                        vec![tr_with("network.host_lost", &[address])]
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Writes the whole board of a simulation as a message
+pub(crate) fn full_board(simulation: &Simulation) -> Message {
+    Message::Board {
+        generation: simulation.generation,
+        is_paused: simulation.is_paused,
+        board: simulation.board.clone(),
+    }
+}
+
+/// Finds how the board changed since it was last sent, and takes it as sent
+/// Input: what was last sent, if anything, and the simulation running the board
+/// Output: the message to send, with the whole board if too much changed, or nothing if nothing did
+pub(crate) fn next_update(
+    sent: &mut Option<SentState>,
+    simulation: &Simulation,
+) -> Option<Message> {
+    let board = &simulation.board;
+    let update = match sent {
+        Some(sent)
+            if sent.board.width() == board.width()
+                && sent.board.length() == board.length()
+                && sent.board.rule == board.rule
+                && sent.board.boundary == board.boundary =>
+        {
+            let cells: Vec<_> = board
+                .differences(&sent.board)
+                .map(|cell| (cell, board[cell]))
+                .collect();
+            if cells.len() > board.width() * board.length() / MAX_CHANGED_SHARE {
+                full_board(simulation)
+            } else if !cells.is_empty()
+                || sent.generation != simulation.generation
+                || sent.is_paused != simulation.is_paused
+            {
+                Message::Changes {
+                    generation: simulation.generation,
+                    is_paused: simulation.is_paused,
+                    cells,
+                }
+            } else {
+                return None;
+            }
+        }
+        _ => full_board(simulation),
+    };
+    match sent {
+        Some(sent) => sent.board.clone_from(&simulation.board),
+        None => {
+            *sent = Some(SentState {
+                board: simulation.board.clone(),
+                generation: 0,
+                is_paused: true,
+            })
+        }
+    }
+    let sent = sent.as_mut().unwrap();
+    sent.generation = simulation.generation;
+    sent.is_paused = simulation.is_paused;
+    Some(update)
+}
+
+/// Takes in new clients and their edits, and sends them how the board changed
+/// Input: the listener new clients arrive on, the clients, what was last sent to them, and the simulation
+/// Output: messages about clients joining and leaving
+fn sync_host(
+    listener: &TcpListener,
+    clients: &mut Vec<(String, Connection)>,
+    sent: &mut Option<SentState>,
+    simulation: &mut Simulation,
+) -> Vec<String> {
+    let mut notices = Vec::new();
+    while let Ok((stream, address)) = listener.accept() {
+        if let Ok(mut connection) = Connection::new(stream, MAX_CLIENT_LINE_LENGTH) {
+            connection.send(&full_board(simulation));
+            notices.push(tr_with("network.joined", &[&address]));
+            clients.push((address.to_string(), connection));
+        }
+    }
+
+    clients.retain_mut(|(address, connection)| match connection.receive() {
+        Ok(messages) => {
+            for message in messages {
+                match message {
+                    Message::Set { cell, state } => {
+                        if cell.x < simulation.board.width()
+                            && cell.y < simulation.board.length()
+                            && simulation.board[cell] != state
+                        {
+                            simulation.toggle_cell_state(cell);
+                        }
+                    }
+                    Message::Pause(is_paused) => simulation.is_paused = is_paused,
+                    //Only the host sends boards and changes
+                    Message::Board { .. } | Message::Changes { .. } => {}
+                }
+            }
+            true
+        }
+        Err(_) => {
+            notices.push(tr_with("network.left", &[address]));
+            false
+        }
+    });
+
+    if let Some(update) = next_update(sent, simulation) {
+        for (_, connection) in clients.iter_mut() {
+            connection.send(&update);
+        }
+    }
+    clients.retain_mut(|(address, connection)| match connection.flush() {
+        Ok(()) => true,
+        Err(_) => {
+            notices.push(tr_with("network.left", &[address]));
+            false
+        }
+    });
+    notices
+}
+
+/// Sends the host the edits made on the board since the last call, and takes the board as the host has it. Until the
+/// host sends the board back with the edits, the board is shown as the host had it
+/// Input: the connection to the host, the board as the host last sent it, and the simulation
+/// Output: an error if the connection to the host was lost
+fn sync_client(
+    connection: &mut Connection,
+    shared: &mut Option<SentState>,
+    simulation: &mut Simulation,
+) -> io::Result<()> {
+    simulation.is_remote = true;
+    //Cells stepped on their own, like with the step key, aren't edits, and go back to how the host has them
+    if let Some(shared) = shared
+        && shared.generation == simulation.generation
+        && shared.board.width() == simulation.board.width()
+        && shared.board.length() == simulation.board.length()
+    {
+        for cell in simulation.board.differences(&shared.board) {
+            let state = simulation.board[cell];
+            connection.send(&Message::Set { cell, state });
+        }
+        if shared.is_paused != simulation.is_paused {
+            connection.send(&Message::Pause(simulation.is_paused));
+        }
+    }
+    connection.flush()?;
+
+    for message in connection.receive()? {
+        take_update(shared, message);
+    }
+    show_shared(shared, simulation);
+    Ok(())
+}
+
+/// Takes in a message from the host of a board, keeping the board as the host has it
+/// Input: the board as the host last sent it, if it did, and the message
+pub(crate) fn take_update(shared: &mut Option<SentState>, message: Message) {
+    match message {
+        Message::Board {
+            generation,
+            is_paused,
+            board,
+        } => {
+            *shared = Some(SentState {
+                board,
+                generation,
+                is_paused,
+            })
+        }
+        Message::Changes {
+            generation,
+            is_paused,
+            cells,
+        } => {
+            if let Some(shared) = shared {
+                for (cell, state) in cells {
+                    if cell.x < shared.board.width() && cell.y < shared.board.length() {
+                        shared.board[cell] = state;
+                    }
+                }
+                shared.generation = generation;
+                shared.is_paused = is_paused;
+            }
+        }
+        //Only clients ask for changes
+        Message::Set { .. } | Message::Pause(_) => {}
+    }
+}
+
+/// Shows the board as the host last sent it, replacing the board of the simulation
+/// Input: the board as the host last sent it, if it did, and the simulation
+pub(crate) fn show_shared(shared: &Option<SentState>, simulation: &mut Simulation) {
+    if let Some(shared) = shared {
+        if simulation.board != shared.board
+            || simulation.board.rule != shared.board.rule
+            || simulation.board.boundary != shared.board.boundary
+        {
+            simulation.board.clone_from(&shared.board);
+            simulation.influence = None;
+        }
+        simulation.generation = shared.generation;
+        simulation.is_paused = shared.is_paused;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keeps syncing a host and a client until something happens, giving up after a while
+    fn sync_until(
+        (host, hosted): (&mut Session, &mut Simulation),
+        (client, joined): (&mut Session, &mut Simulation),
+        condition: impl Fn(&Simulation, &Simulation) -> bool,
+    ) -> bool {
+        for _ in 0..200 {
+            host.sync(hosted);
+            client.sync(joined);
+            if condition(hosted, joined) {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        false
+    }
+
+    #[test]
+    fn clients_edit_the_board_of_the_host() {
+        let mut host = Session::host(IpAddr::from([127, 0, 0, 1]), 0).unwrap();
+        let Role::Host { listener, .. } = &host.role else {
+            unreachable!()
+        };
+        let port = listener.local_addr().unwrap().port();
+        let mut client = Session::join(&format!("127.0.0.1:{port}")).unwrap();
+        let mut hosted = Simulation::new(8, 6, 0);
+        hosted.board = ".#......\n..#.....\n###.....\n........\n........\n........"
+            .parse()
+            .unwrap();
+        let mut joined = Simulation::new(3, 3, 0);
+
+        //Clients take the board of the host when joining
+        assert!(sync_until(
+            (&mut host, &mut hosted),
+            (&mut client, &mut joined),
+            |hosted, joined| hosted.board == joined.board
+        ));
+        assert!(joined.is_remote);
+
+        //Edits of clients go through the host, and generations of the host reach the clients
+        joined.toggle_cell_state((7, 5));
+        hosted.step();
+        assert!(sync_until(
+            (&mut host, &mut hosted),
+            (&mut client, &mut joined),
+            |hosted, joined| hosted.board[(7, 5)] == CellState::Alive
+                && hosted.board == joined.board
+                && joined.generation == 1
+        ));
+        joined.is_paused = false;
+        assert!(sync_until(
+            (&mut host, &mut hosted),
+            (&mut client, &mut joined),
+            |hosted, joined| !hosted.is_paused && !joined.is_paused
+        ));
+
+        //Clients keep the board once the host is gone
+        drop(host);
+        let notices: Vec<_> = (0..200)
+            .flat_map(|_| {
+                std::thread::sleep(Duration::from_millis(1));
+                client.sync(&mut joined)
+            })
+            .take(1)
+            .collect();
+        assert_eq!(1, notices.len());
+        assert!(!joined.is_remote);
+    }
+
+    #[test]
+    fn boards_bigger_than_the_menu_makes_are_shared() {
+        let mut host = Session::host(IpAddr::from([127, 0, 0, 1]), 0).unwrap();
+        let Role::Host { listener, .. } = &host.role else {
+            unreachable!()
+        };
+        let port = listener.local_addr().unwrap().port();
+        let mut client = Session::join(&format!("127.0.0.1:{port}")).unwrap();
+        let mut hosted = Simulation::new(2100, 2100, 0);
+        hosted.toggle_cell_state((2099, 2099));
+        let mut joined = Simulation::new(3, 3, 0);
+        assert!(sync_until(
+            (&mut host, &mut hosted),
+            (&mut client, &mut joined),
+            |hosted, joined| hosted.board == joined.board
+        ));
+    }
+
+    #[test]
+    fn clients_sending_lines_too_long_are_dropped() {
+        let mut host = Session::host(IpAddr::from([127, 0, 0, 1]), 0).unwrap();
+        let Role::Host { listener, .. } = &host.role else {
+            unreachable!()
+        };
+        let port = listener.local_addr().unwrap().port();
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        let mut hosted = Simulation::new(3, 3, 0);
+        stream
+            .write_all(&[b'1'; MAX_CLIENT_LINE_LENGTH + 1])
+            .unwrap();
+        let left = (0..200).any(|_| {
+            std::thread::sleep(Duration::from_millis(5));
+            let notices = host.sync(&mut hosted);
+            let Role::Host { clients, .. } = &host.role else {
+                unreachable!()
+            };
+            !notices.is_empty() && clients.is_empty()
+        });
+        assert!(left);
+    }
+
+    #[test]
+    fn messages_are_read_back_as_written() {
+        let mut board: Board = ".#.\n..#\n###".parse().unwrap();
+        board.boundary = Boundary::Toroidal;
+        let messages = [
+            Message::Board {
+                generation: 12,
+                is_paused: false,
+                board,
+            },
+            Message::Changes {
+                generation: 13,
+                is_paused: true,
+                cells: vec![
+                    (Point::new(0, 1), CellState::Alive),
+                    (Point::new(2, 0), CellState::Dead),
+                ],
+            },
+            Message::Set {
+                cell: Point::new(4, 5),
+                state: CellState::Alive,
+            },
+            Message::Pause(true),
+        ];
+        for message in messages {
+            let line = message.to_line();
+            assert_eq!(Ok(&message), Message::parse(&line).as_ref());
+            if let Message::Board { board, .. } = &message {
+                let Ok(Message::Board { board: read, .. }) = Message::parse(&line) else {
+                    unreachable!()
+                };
+                assert_eq!(board.boundary, read.boundary);
+            }
+        }
+        assert!(Message::parse("set 1 2").is_err());
+        assert!(Message::parse("changes 1 0 1,2").is_err());
+        assert!(Message::parse("jump 3").is_err());
+    }
+}
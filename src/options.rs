@@ -0,0 +1,352 @@
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+
+use crate::headless::HeadlessOutput;
+use crate::overlay::OverlayBackground;
+use crate::render::CellShape;
+
+const USAGE: &str = "Usage: conways-game-of-life [OPTIONS]
+
+Options:
+  --event-log <FILE>         Log every birth and death to FILE, as CSV if it ends in .csv and JSON lines otherwise
+  --max-generations <COUNT>  Stop the game automatically after COUNT generations
+  --edit <FILE>              Start the board with the pattern of FILE, a .cells or .rle file, and load it again whenever
+                             it changes, to edit it live in a text editor
+  --watch <PATTERN>          Pause the game when PATTERN appears, either a built-in pattern or a .cells/.rle file
+  --cell-shape <SHAPE>       Draw alive cells as squares, rounded squares or circles: square, rounded or circle
+  --sprite <FILE>            Draw alive cells with the image in FILE, a PNG, scaled to the size of the cells
+  --theme <NAME>             Draw the game with the NAME colour theme: Classic, Dark, Solarized dark, Solarized light or
+                             High contrast
+  --overlay <BACKGROUND>     Only show a random board that keeps running, with no menus or text, over BACKGROUND:
+                             transparent, or a colour like #00ff00 to key out in streaming software
+  --screensaver, /s          Run as a screensaver: fullscreen, with no menus or text, showing random boards following
+                             random rules, until any key is pressed or the mouse is moved
+  --headless <OUTPUT>        Run a random board without a window, printing it on the terminal with tui, or writing each
+                             generation as a PNG to the OUTPUT directory, for --max-generations generations (100 by
+                             default)
+  --search <DIRECTORY>       Evolve random soups without a window, looking for ones that last long, grow big and send out
+                             gliders, each run for up to --max-generations generations (1000 by default), and write the
+                             best ones as RLE files to DIRECTORY
+  --host <PORT>              Share the board with other players on the network, who join it on PORT
+  --join <ADDRESS>           Play on the board shared by another player, at an ADDRESS like 192.168.1.20:7878
+  --listen <ADDRESS>         Listen for --host, --stream and --http on the network interface at ADDRESS, like 0.0.0.0
+                             for every one, instead of only on this computer (127.0.0.1)
+  --stream <PORT>            Stream the board over WebSocket on PORT, to watch it live with web/spectator.html or
+                             --spectate
+  --spectate <ADDRESS>       Watch the board streamed by another game, at an ADDRESS like 192.168.1.20:7879, without
+                             changing it
+  --chat <CHANNEL>           Let viewers of a Twitch CHANNEL place cells and patterns by typing '!cell 10 12' or
+                             '!spawn glider 10 12' in chat
+  --chat-server <ADDRESS>    Read the chat from another IRC server, at an ADDRESS like irc.libera.chat:6667
+  --midi <DEVICE>            Play the births and deaths of the board as MIDI notes, written to DEVICE, such as a raw
+                             MIDI port like /dev/snd/midiC1D0 or a file, with the channels and scale of the config file
+  --osc <ADDRESS>            Send the births and deaths of the board as OSC messages over UDP to a server at an
+                             ADDRESS like 127.0.0.1:57120
+  --http <PORT>              Let other programs read and drive the board over HTTP on PORT, if built with the http-api
+                             feature
+  --discord <ID>             Show the rule, generation and population of the board on Discord, as the Discord application
+                             ID, if built with the discord feature
+  -h, --help                 Print this message";
+
+/// Options given on the command line
+#[derive(Default, Debug, PartialEq)]
+pub struct Options {
+    /// File to log every birth and death to
+    pub event_log: Option<PathBuf>,
+    /// Generations after which the game stops on its own
+    pub max_generations: Option<usize>,
+    /// Pattern file boards start with, and follow as it's edited
+    pub edit: Option<PathBuf>,
+    /// Name of a built-in pattern, or path of a pattern file, that pauses the game when it appears
+    pub watch_target: Option<String>,
+    /// Shape alive cells are drawn with
+    pub cell_shape: Option<CellShape>,
+    /// Image file alive cells are drawn with
+    pub sprite: Option<PathBuf>,
+    /// Name of the colour theme to start with
+    pub theme: Option<String>,
+    /// Background of the overlay mode, if the game runs in it
+    pub overlay: Option<OverlayBackground>,
+    /// Whether the game runs as a screensaver
+    pub screensaver: bool,
+    /// Where the board is shown when running without a window, if it does
+    pub headless: Option<HeadlessOutput>,
+    /// Directory the soups found by a search are written to, if the game runs one
+    pub search: Option<PathBuf>,
+    /// Port the board is shared on, if it's shared
+    pub host_port: Option<u16>,
+    /// Address of the player whose board is joined, if one is
+    pub join_address: Option<String>,
+    /// Address of the network interface servers listen on, if it isn't this computer's own
+    pub listen_address: Option<IpAddr>,
+    /// Port the board is streamed on, if it's streamed
+    pub stream_port: Option<u16>,
+    /// Address of the game whose streamed board is watched, if one is
+    pub spectate_address: Option<String>,
+    /// Channel whose chat places cells on the board, if one does
+    pub chat_channel: Option<String>,
+    /// Address of the IRC server the chat is read from, if it isn't Twitch's
+    pub chat_server: Option<String>,
+    /// MIDI device or file the births and deaths of the board are played to, if they are
+    pub midi: Option<PathBuf>,
+    /// Address of the OSC server the births and deaths of the board are sent to, if they are
+    pub osc_address: Option<String>,
+    /// Port the board is driven over HTTP on, if it is
+    #[cfg(feature = "http-api")]
+    pub http_port: Option<u16>,
+    /// ID of the Discord application the activity is shown as, if it's shown
+    #[cfg(feature = "discord")]
+    pub discord_application_id: Option<String>,
+}
+
+impl Options {
+    /// Parses the command line arguments, without the program name
+    /// Output: the options, or a message explaining what's wrong with the arguments
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--event-log" => {
+                    let path = args.next().ok_or("--event-log needs a file")?;
+                    options.event_log = Some(PathBuf::from(path));
+                }
+                "--max-generations" => {
+                    let count = args.next().ok_or("--max-generations needs a count")?;
+                    let count = count
+                        .parse()
+                        .map_err(|_| format!("invalid generation count '{count}'"))?;
+                    options.max_generations = Some(count);
+                }
+                "--edit" => {
+                    let path = args.next().ok_or("--edit needs a file")?;
+                    options.edit = Some(PathBuf::from(path));
+                }
+                "--watch" => {
+                    let pattern = args.next().ok_or("--watch needs a pattern")?;
+                    options.watch_target = Some(pattern);
+                }
+                "--cell-shape" => {
+                    let shape = args.next().ok_or("--cell-shape needs a shape")?;
+                    options.cell_shape = Some(shape.parse()?);
+                }
+                "--sprite" => {
+                    let path = args.next().ok_or("--sprite needs a file")?;
+                    options.sprite = Some(PathBuf::from(path));
+                }
+                "--theme" => {
+                    let name = args.next().ok_or("--theme needs a name")?;
+                    options.theme = Some(name);
+                }
+                "--overlay" => {
+                    let background = args.next().ok_or("--overlay needs a background")?;
+                    options.overlay = Some(background.parse()?);
+                }
+                //Windows starts screensavers with /s
+                "--screensaver" | "/s" | "/S" => options.screensaver = true,
+                "--headless" => {
+                    let output = args.next().unwrap_or_default();
+                    options.headless = Some(output.parse()?);
+                }
+                "--search" => {
+                    let directory = args.next().ok_or("--search needs a directory")?;
+                    options.search = Some(PathBuf::from(directory));
+                }
+                "--host" => {
+                    let port = args.next().ok_or("--host needs a port")?;
+                    let port = port.parse().map_err(|_| format!("invalid port '{port}'"))?;
+                    options.host_port = Some(port);
+                }
+                "--join" => {
+                    let address = args.next().ok_or("--join needs an address")?;
+                    options.join_address = Some(address);
+                }
+                "--listen" => {
+                    let address = args.next().ok_or("--listen needs an address")?;
+                    let address = address
+                        .parse()
+                        .map_err(|_| format!("invalid address '{address}'"))?;
+                    options.listen_address = Some(address);
+                }
+                "--stream" => {
+                    let port = args.next().ok_or("--stream needs a port")?;
+                    let port = port.parse().map_err(|_| format!("invalid port '{port}'"))?;
+                    options.stream_port = Some(port);
+                }
+                "--spectate" => {
+                    let address = args.next().ok_or("--spectate needs an address")?;
+                    options.spectate_address = Some(address);
+                }
+                "--chat" => {
+                    let channel = args.next().ok_or("--chat needs a channel")?;
+                    options.chat_channel = Some(channel);
+                }
+                "--chat-server" => {
+                    let address = args.next().ok_or("--chat-server needs an address")?;
+                    options.chat_server = Some(address);
+                }
+                "--midi" => {
+                    let device = args.next().ok_or("--midi needs a device")?;
+                    options.midi = Some(PathBuf::from(device));
+                }
+                "--osc" => {
+                    let address = args.next().ok_or("--osc needs an address")?;
+                    options.osc_address = Some(address);
+                }
+                #[cfg(feature = "http-api")]
+                "--http" => {
+                    let port = args.next().ok_or("--http needs a port")?;
+                    let port = port.parse().map_err(|_| format!("invalid port '{port}'"))?;
+                    options.http_port = Some(port);
+                }
+                #[cfg(feature = "discord")]
+                "--discord" => {
+                    let application_id = args.next().ok_or("--discord needs an application ID")?;
+                    options.discord_application_id = Some(application_id);
+                }
+                "-h" | "--help" => return Err(USAGE.to_string()),
+                _ => return Err(format!("unknown argument '{arg}'\n\n{USAGE}")),
+            }
+        }
+        if options.screensaver && options.overlay.is_some() {
+            return Err("--screensaver and --overlay can't be used together".to_string());
+        }
+        if options.host_port.is_some() && options.join_address.is_some() {
+            return Err("--host and --join can't be used together".to_string());
+        }
+        if options.chat_server.is_some() && options.chat_channel.is_none() {
+            return Err("--chat-server needs --chat".to_string());
+        }
+        if options.spectate_address.is_some()
+            && (options.host_port.is_some() || options.join_address.is_some())
+        {
+            return Err("--spectate can't be used with --host or --join".to_string());
+        }
+        Ok(options)
+    }
+
+    /// Returns the address of the network interface servers listen on, this computer's own unless --listen says
+    /// otherwise, so nothing is reachable from the network without asking for it
+    pub fn listen_address(&self) -> IpAddr {
+        self.listen_address
+            .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_give_default_options() {
+        assert_eq!(Ok(Options::default()), parse(&[]));
+    }
+
+    #[test]
+    fn event_log_takes_a_file() {
+        let options = parse(&["--event-log", "events.csv"]).unwrap();
+        assert_eq!(Some(PathBuf::from("events.csv")), options.event_log);
+        assert!(parse(&["--event-log"]).is_err());
+    }
+
+    #[test]
+    fn max_generations_takes_a_count() {
+        let options = parse(&["--max-generations", "500"]).unwrap();
+        assert_eq!(Some(500), options.max_generations);
+        assert!(parse(&["--max-generations", "many"]).is_err());
+    }
+
+    #[test]
+    fn edit_takes_a_pattern_file() {
+        let options = parse(&["--edit", "glider.rle"]).unwrap();
+        assert_eq!(Some(PathBuf::from("glider.rle")), options.edit);
+        assert!(parse(&["--edit"]).is_err());
+    }
+
+    #[test]
+    fn theme_takes_a_name() {
+        let options = parse(&["--theme", "Solarized dark"]).unwrap();
+        assert_eq!(Some("Solarized dark".to_string()), options.theme);
+        assert!(parse(&["--theme"]).is_err());
+    }
+
+    #[test]
+    fn overlay_takes_a_background() {
+        let options = parse(&["--overlay", "transparent"]).unwrap();
+        assert_eq!(Some(OverlayBackground::Transparent), options.overlay);
+        assert!(parse(&["--overlay"]).is_err());
+        assert!(parse(&["--overlay", "see-through"]).is_err());
+    }
+
+    #[test]
+    fn screensavers_take_the_windows_flag_too() {
+        assert!(parse(&["--screensaver"]).unwrap().screensaver);
+        assert!(parse(&["/S"]).unwrap().screensaver);
+        assert!(parse(&["--screensaver", "--overlay", "transparent"]).is_err());
+    }
+
+    #[test]
+    fn headless_takes_an_output() {
+        let options = parse(&["--headless", "tui"]).unwrap();
+        assert_eq!(Some(HeadlessOutput::Terminal), options.headless);
+        assert!(parse(&["--headless"]).is_err());
+    }
+
+    #[test]
+    fn search_takes_a_directory() {
+        let options = parse(&["--search", "finds"]).unwrap();
+        assert_eq!(Some(PathBuf::from("finds")), options.search);
+        assert!(parse(&["--search"]).is_err());
+    }
+
+    #[test]
+    fn boards_are_either_hosted_or_joined() {
+        let options = parse(&["--host", "7878"]).unwrap();
+        assert_eq!(Some(7878), options.host_port);
+        let options = parse(&["--join", "192.168.1.20:7878"]).unwrap();
+        assert_eq!(Some("192.168.1.20:7878".to_string()), options.join_address);
+        assert!(parse(&["--host", "70000"]).is_err());
+        assert!(parse(&["--host", "7878", "--join", "localhost:7878"]).is_err());
+        let options = parse(&["--host", "7878", "--stream", "7879"]).unwrap();
+        assert_eq!(Some(7879), options.stream_port);
+        let options = parse(&["--spectate", "localhost:7879"]).unwrap();
+        assert_eq!(Some("localhost:7879".to_string()), options.spectate_address);
+        assert!(parse(&["--join", "localhost:7878", "--spectate", "localhost:7879"]).is_err());
+    }
+
+    #[test]
+    fn servers_only_listen_on_this_computer_unless_asked() {
+        assert_eq!(
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+            Options::default().listen_address()
+        );
+        let options = parse(&["--host", "7878", "--listen", "0.0.0.0"]).unwrap();
+        assert_eq!(IpAddr::V4(Ipv4Addr::UNSPECIFIED), options.listen_address());
+        assert!(parse(&["--listen", "everywhere"]).is_err());
+    }
+
+    #[test]
+    fn chat_servers_need_a_channel() {
+        let options = parse(&["--chat", "somechannel"]).unwrap();
+        assert_eq!(Some("somechannel".to_string()), options.chat_channel);
+        assert_eq!(None, options.chat_server);
+        assert!(parse(&["--chat-server", "irc.libera.chat:6667"]).is_err());
+    }
+
+    #[test]
+    fn music_goes_to_a_midi_device_and_an_osc_server() {
+        let options = parse(&["--midi", "/dev/snd/midiC1D0", "--osc", "127.0.0.1:57120"]).unwrap();
+        assert_eq!(Some(PathBuf::from("/dev/snd/midiC1D0")), options.midi);
+        assert_eq!(Some("127.0.0.1:57120".to_string()), options.osc_address);
+        assert!(parse(&["--midi"]).is_err());
+        assert!(parse(&["--osc"]).is_err());
+    }
+
+    #[test]
+    fn unknown_arguments_are_rejected() {
+        assert!(parse(&["--fast"]).is_err());
+    }
+}
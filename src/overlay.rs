@@ -0,0 +1,208 @@
+use std::str::FromStr;
+
+use macroquad::miniquad::date;
+use macroquad::prelude::*;
+
+use crate::config::parse_hex_color;
+use crate::renderer::{Renderer, ScreenRenderer};
+use crate::rules::{LifeLikeRule, rule_count};
+use crate::simulation::SPEEDS;
+use crate::theme::Theme;
+use crate::{Board, Boundary, RandomSource, SplitMix64};
+
+/// Size of the cells in overlay mode, in pixels. The board is as big as fits in the window
+const OVERLAY_CELL_SIZE: f32 = 8.;
+
+/// Share of the cells that start alive on each new board
+const SOUP_DENSITY: f64 = 0.3;
+
+/// Generations after which a board is replaced with a new one, even if it's still changing, as the gliders left on
+/// toroidal boards can keep them going forever
+const SOUP_LIFETIME: usize = 3000;
+
+/// Pixels the mouse can move in screensaver mode without closing the game, so bumping the desk doesn't close it
+const MOUSE_SLACK: f32 = 10.;
+
+/// What's drawn behind the cells in overlay mode
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum OverlayBackground {
+    /// Nothing, so whatever is behind the window shows through
+    Transparent,
+    /// A plain colour, to be keyed out by streaming software
+    ChromaKey(Color),
+}
+
+impl FromStr for OverlayBackground {
+    type Err = String;
+
+    /// Parses a background, either "transparent" or a colour written as "#rrggbb"
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if text.eq_ignore_ascii_case("transparent") {
+            Ok(OverlayBackground::Transparent)
+        } else {
+            parse_hex_color(text)
+                .map(OverlayBackground::ChromaKey)
+                .map_err(|_| {
+                    format!("invalid overlay background '{text}', expected transparent or a colour like #00ff00")
+                })
+        }
+    }
+}
+
+/// Creates a board filled with random cells, with its edges wrapping around so it keeps going for longer
+/// Input: the width and height of the board, in cells, and where the random numbers come from
+/// Output: the board
+pub fn random_board(width: usize, length: usize, source: &mut impl RandomSource) -> Board {
+    let mut board = Board::random_with(width, length, SOUP_DENSITY, source);
+    board.boundary = Boundary::Toroidal;
+    board
+}
+
+/// A random board that keeps running, replaced with a new one when it dies out, settles or gets old
+struct Soups {
+    board: Board,
+    generation: usize,
+    source: SplitMix64,
+    /// Whether each new board follows a rule picked at random, instead of the standard rules
+    is_cycling_rules: bool,
+}
+
+impl Soups {
+    /// Starts with a random board
+    /// Input: the width and height of the board, in cells, and whether each board follows a rule picked at random
+    fn new((width, length): (usize, usize), is_cycling_rules: bool) -> Self {
+        let mut soups = Soups {
+            board: Board::new(1, 1),
+            generation: 0,
+            source: SplitMix64::new(date::now().to_bits()),
+            is_cycling_rules,
+        };
+        soups.replace((width, length));
+        soups
+    }
+
+    /// Replaces the board with a new random one
+    /// Input: the width and height of the new board, in cells
+    fn replace(&mut self, (width, length): (usize, usize)) {
+        self.board = random_board(width, length, &mut self.source);
+        if self.is_cycling_rules {
+            let index = (self.source.next_u64() % rule_count() as u64) as usize;
+            self.board.rule = LifeLikeRule::preset(index);
+        }
+        self.generation = 0;
+    }
+
+    /// Steps the board, replacing it once it died out, settled or got old
+    fn step(&mut self) {
+        //The board keeps the generation before the current one, which matches the next one once only still lifes and
+        //blinkers are left
+        let previous = self.board.old_board.clone();
+        self.board.update_board();
+        self.generation += 1;
+        if self.board.board == previous
+            || self.board.population() == 0
+            || self.generation >= SOUP_LIFETIME
+        {
+            self.replace((self.board.width(), self.board.length()));
+        }
+    }
+}
+
+/// Returns the size of the board that fills the window, with cells of OVERLAY_CELL_SIZE pixels
+fn window_board_size() -> (usize, usize) {
+    (
+        ((screen_width() / OVERLAY_CELL_SIZE) as usize).max(1),
+        ((screen_height() / OVERLAY_CELL_SIZE) as usize).max(1),
+    )
+}
+
+/// Runs the game as a decoration to be shown on top of other windows or streaming scenes: a random board fills the
+/// window and keeps running, with no menus, buttons or text, and is replaced with a new one when it dies out, settles
+/// or gets old. Only the alive cells are drawn, over a transparent or plain background. Escape closes the game
+/// Input: the background to draw, the theme alive cells are drawn with, and the index of the speed, out of SPEEDS
+pub async fn run_overlay(background: OverlayBackground, theme: &Theme, speed_index: usize) {
+    let mut renderer = ScreenRenderer {
+        alive: theme.alive,
+        background: match background {
+            OverlayBackground::Transparent => Color::new(0., 0., 0., 0.),
+            OverlayBackground::ChromaKey(color) => color,
+        },
+    };
+    let mut soups = Soups::new(window_board_size(), false);
+    let mut last_update = get_time();
+    loop {
+        if is_key_pressed(KeyCode::Escape) {
+            return;
+        }
+        //The board follows the size of the window, starting over when it changes
+        if window_board_size() != (soups.board.width(), soups.board.length()) {
+            soups.replace(window_board_size());
+        }
+        if get_time() - last_update >= SPEEDS[speed_index].1 {
+            last_update = get_time();
+            soups.step();
+        }
+
+        //Drawing on the window can't fail
+        let _ = renderer.render(&soups.board, soups.generation);
+        next_frame().await;
+    }
+}
+
+/// Runs the game as a screensaver: fullscreen, with no menus, buttons or text, showing random boards that each follow
+/// a rule picked at random, one after the other. Any key, click, touch or move of the mouse closes the game
+/// Input: the theme to draw with, and the index of the speed, out of SPEEDS
+pub async fn run_screensaver(theme: &Theme, speed_index: usize) {
+    set_fullscreen(true);
+    show_mouse(false);
+    let mut renderer = ScreenRenderer {
+        alive: theme.alive,
+        background: theme.dead,
+    };
+    let mut soups = Soups::new(window_board_size(), true);
+    let mut last_update = get_time();
+    //Where the mouse was on the first frame, as it can be reported a little off while the window goes fullscreen
+    let mut first_mouse_position = None;
+    loop {
+        let mouse = Vec2::from(mouse_position());
+        let first_mouse = *first_mouse_position.get_or_insert(mouse);
+        let is_input = get_last_key_pressed().is_some()
+            || [MouseButton::Left, MouseButton::Right, MouseButton::Middle]
+                .into_iter()
+                .any(is_mouse_button_pressed)
+            || mouse_wheel() != (0., 0.)
+            || !touches().is_empty()
+            || mouse.distance(first_mouse) > MOUSE_SLACK;
+        if is_input || is_quit_requested() {
+            return;
+        }
+        if window_board_size() != (soups.board.width(), soups.board.length()) {
+            soups.replace(window_board_size());
+        }
+        if get_time() - last_update >= SPEEDS[speed_index].1 {
+            last_update = get_time();
+            soups.step();
+        }
+
+        let _ = renderer.render(&soups.board, soups.generation);
+        next_frame().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_backgrounds_are_transparent_or_a_colour() {
+        assert_eq!(
+            Ok(OverlayBackground::Transparent),
+            "Transparent".parse::<OverlayBackground>()
+        );
+        assert_eq!(
+            Ok(OverlayBackground::ChromaKey(Color::from_hex(0x00ff00))),
+            "#00ff00".parse::<OverlayBackground>()
+        );
+        assert!("green".parse::<OverlayBackground>().is_err());
+    }
+}
@@ -0,0 +1,98 @@
+use macroquad::color::{Color, ORANGE};
+
+/// Colours of the highlights drawn over the board, which tell things apart by colour alone
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Palette {
+    /// Name of the palette in the config file
+    pub name: &'static str,
+    /// Colour of the cells only alive on the board, when comparing it to a snapshot
+    pub board_only: Color,
+    /// Colour of the cells only alive on the snapshot
+    pub snapshot_only: Color,
+    /// Colour of the cells that influenced a traced cell
+    pub influence: Color,
+    /// Colour of the cells that are born on the next generation, when previewing it
+    pub born: Color,
+    /// Colour of the cells that die on the next generation
+    pub dying: Color,
+    /// Colour of the outlines marking something on the board, like a found pattern or the part in view on the minimap
+    pub marker: Color,
+}
+
+/// Built-in palettes, the first one being the default. The others keep their colours apart for players with each kind
+/// of colour blindness, using colours from the Okabe-Ito and IBM colourblind-safe sets
+pub const PALETTES: [Palette; 4] = [
+    Palette {
+        name: "default",
+        board_only: Color::new(0.9, 0.16, 0.22, 0.8),
+        snapshot_only: Color::new(0., 0.47, 0.95, 0.5),
+        influence: Color::new(0.44, 0.12, 0.75, 0.6),
+        born: Color::new(0.18, 0.8, 0.34, 0.6),
+        dying: Color::new(0.9, 0.16, 0.22, 0.6),
+        marker: ORANGE,
+    },
+    //Red and green look alike, so the colours go from orange to blue instead
+    Palette {
+        name: "deuteranopia",
+        board_only: Color::new(0.9, 0.62, 0., 0.8),
+        snapshot_only: Color::new(0., 0.45, 0.7, 0.6),
+        influence: Color::new(0.8, 0.47, 0.65, 0.6),
+        born: Color::new(0., 0.45, 0.7, 0.6),
+        dying: Color::new(0.9, 0.62, 0., 0.6),
+        marker: Color::new(0.34, 0.71, 0.91, 1.),
+    },
+    //Red looks dark too, so the colours are bright ones
+    Palette {
+        name: "protanopia",
+        board_only: Color::new(1., 0.69, 0., 0.8),
+        snapshot_only: Color::new(0.39, 0.56, 1., 0.6),
+        influence: Color::new(0.47, 0.37, 0.94, 0.6),
+        born: Color::new(0.39, 0.56, 1., 0.6),
+        dying: Color::new(1., 0.69, 0., 0.6),
+        marker: Color::new(1., 0.38, 0., 1.),
+    },
+    //Blue and yellow look alike, so the colours go from red to blue and teal
+    Palette {
+        name: "tritanopia",
+        board_only: Color::new(0.86, 0.2, 0.13, 0.8),
+        snapshot_only: Color::new(0., 0.35, 0.71, 0.6),
+        influence: Color::new(0., 0.62, 0.45, 0.6),
+        born: Color::new(0., 0.62, 0.45, 0.6),
+        dying: Color::new(0.86, 0.2, 0.13, 0.6),
+        marker: Color::new(0.84, 0.11, 0.38, 1.),
+    },
+];
+
+impl Palette {
+    /// Finds one of the built-in palettes
+    /// Input: the name of the palette, ignoring case
+    /// Output: the index of the palette in PALETTES, if there's one with that name
+    pub fn index_of(name: &str) -> Option<usize> {
+        PALETTES
+            .iter()
+            .position(|palette| palette.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the key of the name shown for the palette
+    pub fn label(&self) -> String {
+        format!("palette.{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palettes_are_found_ignoring_case_and_tell_highlights_apart() {
+        assert_eq!(Some(1), Palette::index_of("Deuteranopia"));
+        assert_eq!(None, Palette::index_of("monochromacy"));
+        for palette in PALETTES {
+            let colors = [palette.board_only, palette.snapshot_only, palette.influence];
+            for (index, color) in colors.iter().enumerate() {
+                assert!(!colors[index + 1..].contains(color));
+            }
+            assert_ne!(palette.born, palette.dying);
+        }
+    }
+}
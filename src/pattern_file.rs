@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::{Board, GolError, Pattern};
+
+/// Seconds between two checks of the file, so the disk isn't read on every frame
+const CHECK_INTERVAL: f64 = 0.5;
+
+/// A .cells or .rle file loaded onto a board, checked now and then so the board follows it while it's edited in a
+/// text editor
+pub struct PatternFile {
+    pub path: PathBuf,
+    /// When the file was last modified and how long it was, when it was last loaded. Unknown if it couldn't be read
+    version: Option<(SystemTime, u64)>,
+    last_check: f64,
+}
+
+impl PatternFile {
+    /// Starts following a file, as it is now
+    /// Input: the path of the file
+    pub fn new(path: PathBuf) -> Self {
+        let mut pattern_file = PatternFile {
+            path,
+            version: None,
+            last_check: f64::NEG_INFINITY,
+        };
+        pattern_file.mark_seen();
+        pattern_file
+    }
+
+    /// Returns when the file was last modified and how long it is, if it can be read
+    fn current_version(&self) -> Option<(SystemTime, u64)> {
+        let metadata = fs::metadata(&self.path).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+
+    /// Takes the file as it is now as already loaded, so changes made by the game itself, like saving the board to
+    /// it, aren't loaded back
+    pub fn mark_seen(&mut self) {
+        self.version = self.current_version();
+    }
+
+    /// Checks whether the file changed since it was last loaded, if it's time to
+    /// Input: the current time
+    /// Output: the pattern in the file if it changed, or what kept it from being read
+    pub fn poll(&mut self, time: f64) -> Option<Result<Pattern, GolError>> {
+        if time - self.last_check < CHECK_INTERVAL {
+            return None;
+        }
+        self.last_check = time;
+        let version = self.current_version();
+        //A file that's gone, as some editors do while saving, is waited for until it's back
+        if version.is_none() || version == self.version {
+            return None;
+        }
+        self.version = version;
+        Some(Pattern::load(&self.path))
+    }
+}
+
+/// Places a pattern in the centre of an empty board with the same size, rule, edges and neighbourhood as another one
+/// Input: the pattern, and the board it replaces
+/// Output: the new board
+pub fn pattern_board(pattern: &Pattern, board: &Board) -> Board {
+    let mut new_board = Board::new(board.width(), board.length());
+    new_board.rule = board.rule;
+    new_board.boundary = board.boundary;
+    new_board.neighborhood = Arc::clone(&board.neighborhood);
+    let corner = (
+        board.width().saturating_sub(pattern.width) / 2,
+        board.length().saturating_sub(pattern.height) / 2,
+    );
+    new_board.paste(pattern, corner);
+    new_board
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::neighborhood::{Hexagonal, Neighborhood};
+
+    #[test]
+    fn pattern_files_are_loaded_again_once_they_change() {
+        let path = std::env::temp_dir().join(format!("watched_{}.cells", std::process::id()));
+        fs::write(&path, "O").unwrap();
+        let mut pattern_file = PatternFile::new(path.clone());
+        assert!(pattern_file.poll(0.).is_none());
+
+        fs::write(&path, "OOO").unwrap();
+        //Files are only checked once in a while
+        assert!(pattern_file.poll(CHECK_INTERVAL / 2.).is_none());
+        let pattern = pattern_file.poll(CHECK_INTERVAL).unwrap().unwrap();
+        assert_eq!(3, pattern.width);
+        assert!(pattern_file.poll(2. * CHECK_INTERVAL).is_none());
+
+        //Writing the file from the game doesn't load it back
+        fs::write(&path, "O\nO").unwrap();
+        pattern_file.mark_seen();
+        assert!(pattern_file.poll(3. * CHECK_INTERVAL).is_none());
+
+        //Patterns are placed in the centre, or in the corner when they're too big, and keep the neighbourhood
+        let mut hexagonal = Board::new(7, 5);
+        hexagonal.neighborhood = Arc::new(Hexagonal);
+        let board = pattern_board(&pattern, &hexagonal);
+        assert_eq!(3, board.population());
+        assert_eq!(crate::CellState::Alive, board[(2, 2)]);
+        assert_eq!(Hexagonal.offsets(), board.neighborhood.offsets());
+        assert_eq!(2, pattern_board(&pattern, &Board::new(2, 2)).population());
+        fs::remove_file(path).unwrap();
+    }
+}
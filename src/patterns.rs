@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::Path;
+
+pub use crate::formats::Pattern;
+use crate::{Board, GolError};
+
+/// Built-in patterns, each one with its name and its cells in plaintext format
+pub const LIBRARY: [(&str, &str); 6] = [
+    ("Glider", ".O.\n..O\nOOO"),
+    ("Blinker", "OOO"),
+    ("Block", "OO\nOO"),
+    ("Beehive", ".OO.\nO..O\n.OO."),
+    ("Toad", ".OOO\nOOO."),
+    ("Lightweight spaceship", ".O..O\nO....\nO...O\nOOOO."),
+];
+
+impl Pattern {
+    /// Creates a pattern with every cell of a board, dead or alive
+    pub fn from_board(board: &Board) -> Self {
+        Pattern {
+            width: board.width,
+            height: board.length,
+            cells: board.board.clone(),
+        }
+    }
+
+    /// Returns one of the built-in patterns
+    /// Input: the name of the pattern, ignoring case
+    /// Output: the pattern, if there's one with that name
+    pub fn from_library(name: &str) -> Option<Self> {
+        LIBRARY
+            .iter()
+            .find(|(pattern_name, _)| pattern_name.eq_ignore_ascii_case(name))
+            .map(|(_, cells)| Self::parse_plaintext(cells).expect("library patterns are valid"))
+    }
+
+    /// Reads a pattern from a file, in RLE format if its extension is .rle, and in plaintext otherwise
+    /// Input: the path of the file
+    /// Output: the pattern, or what kept it from being read
+    pub fn load(path: &Path) -> Result<Self, GolError> {
+        let contents = fs::read_to_string(path).map_err(|source| GolError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("rle") => Self::parse_rle(&contents),
+            _ => Self::parse_plaintext(&contents),
+        }
+    }
+
+    /// Writes a pattern to a file in plaintext format, creating its directory if needed
+    /// Input: the path of the file
+    /// Output: whether it was written, or what kept it from being written
+    pub fn save(&self, path: &Path) -> Result<(), GolError> {
+        let io_error = |source| GolError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(io_error)?;
+        }
+        fs::write(path, self.to_plaintext()).map_err(io_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_library_patterns_parse() {
+        for (name, _) in LIBRARY {
+            assert!(Pattern::from_library(name).is_some());
+        }
+    }
+}
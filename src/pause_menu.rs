@@ -0,0 +1,125 @@
+use macroquad::prelude::*;
+
+use crate::locale::tr;
+use crate::text::draw_text;
+use crate::theme::Theme;
+
+/// Entries of the pause menu
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PauseMenuEntry {
+    Resume,
+    Restart,
+    NewBoard,
+    Setup,
+    Settings,
+    Save,
+    Load,
+    Quit,
+}
+
+/// Every entry of the pause menu, in order, with the text shown for it
+const ENTRIES: [(PauseMenuEntry, &str); 8] = [
+    (PauseMenuEntry::Resume, "pause.resume"),
+    (PauseMenuEntry::Restart, "pause.restart"),
+    (PauseMenuEntry::NewBoard, "pause.new_board"),
+    (PauseMenuEntry::Setup, "pause.setup"),
+    (PauseMenuEntry::Settings, "pause.settings"),
+    (PauseMenuEntry::Save, "pause.save"),
+    (PauseMenuEntry::Load, "pause.load"),
+    (PauseMenuEntry::Quit, "pause.quit"),
+];
+
+/// Finds the screen rectangle of an entry of the menu, with the entries stacked in the middle of an area
+/// Input: the area the menu is drawn in, and the index of the entry
+pub fn entry_rect(area: Rect, index: usize) -> Rect {
+    let height = area.h / (ENTRIES.len() + 3) as f32;
+    Rect::new(
+        area.x + area.w / 3.,
+        area.y + (index + 2) as f32 * height,
+        area.w / 3.,
+        height * 0.8,
+    )
+}
+
+/// Menu opened with escape during the game
+#[derive(Default)]
+pub struct PauseMenu {
+    pub is_open: bool,
+    /// Index of the highlighted entry
+    selected: usize,
+}
+
+impl PauseMenu {
+    /// Handles the input of the menu and draws it over an area of the screen. Entries are picked with the arrow keys
+    /// and enter, or by clicking on them, and escape resumes the game
+    /// Input: the theme to draw the menu with, and the area to draw in
+    /// Output: the entry picked on this frame, if any
+    pub fn update(&mut self, theme: &Theme, area: Rect) -> Option<PauseMenuEntry> {
+        if is_key_pressed(KeyCode::Down) {
+            self.selected = (self.selected + 1) % ENTRIES.len();
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.selected = (self.selected + ENTRIES.len() - 1) % ENTRIES.len();
+        }
+        let (mouse_x, mouse_y) = mouse_position();
+        let hovered = (0..ENTRIES.len())
+            .find(|index| entry_rect(area, *index).contains(vec2(mouse_x, mouse_y)));
+        if let Some(hovered) = hovered {
+            self.selected = hovered;
+        }
+
+        let picked = if is_key_pressed(KeyCode::Escape) {
+            Some(PauseMenuEntry::Resume)
+        } else if is_key_pressed(KeyCode::Enter)
+            || (hovered.is_some() && is_mouse_button_pressed(MouseButton::Left))
+        {
+            Some(ENTRIES[self.selected].0)
+        } else {
+            None
+        };
+        //Settings open on top of the menu, so it's still there when they're closed
+        if picked.is_some_and(|entry| entry != PauseMenuEntry::Settings) {
+            self.is_open = false;
+            self.selected = 0;
+        }
+
+        clear_background(theme.background);
+        let title_rect = entry_rect(area, 0);
+        draw_text(
+            tr("game.paused"),
+            title_rect.x,
+            title_rect.y - title_rect.h / 2.,
+            48.,
+            theme.text,
+        );
+        for (index, (_, name)) in ENTRIES.iter().enumerate() {
+            let rect = entry_rect(area, index);
+            if index == self.selected {
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme.highlight);
+            }
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2., theme.text);
+            draw_text(
+                tr(name),
+                rect.x + rect.w / 10.,
+                rect.y + rect.h * 0.7,
+                30.,
+                theme.text,
+            );
+        }
+        picked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_stacked_without_overlapping() {
+        let area = Rect::new(0., 0., 300., 1000.);
+        for index in 1..ENTRIES.len() {
+            assert!(entry_rect(area, index - 1).bottom() < entry_rect(area, index).y);
+        }
+        assert!(entry_rect(area, ENTRIES.len() - 1).bottom() < area.bottom());
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use discord_rich_presence::activity::{Activity as DiscordActivity, Timestamps};
+use discord_rich_presence::{DiscordIpc, DiscordIpcClient};
+
+use crate::locale::{tr, tr_with};
+
+/// Shortest time between two updates of the activity, in seconds. Discord only takes one every 15 seconds
+const UPDATE_INTERVAL: f64 = 15.;
+
+/// What the game is doing, as shown on Discord: the details go on the first line, and the state on the second
+#[derive(Clone, PartialEq, Debug)]
+pub struct Activity {
+    details: String,
+    state: String,
+}
+
+impl Activity {
+    /// Returns the activity of picking a board on the starting menu
+    pub fn menu() -> Self {
+        Activity {
+            details: tr("presence.menu").to_string(),
+            state: String::new(),
+        }
+    }
+
+    /// Returns the activity of running a board
+    /// Input: the name of the rule the board follows, its generation and its population
+    pub fn board(rule_name: &str, generation: usize, population: usize) -> Self {
+        Activity {
+            details: tr_with("presence.rule", &[&rule_name]),
+            state: tr_with("presence.board", &[&generation, &population]),
+        }
+    }
+}
+
+/// Publishes what the game is doing to Discord Rich Presence, so friends see the rule, generation and population of
+/// the board on the profile of the player. Discord is talked to on a thread of its own, connecting again whenever an
+/// update fails, so the game keeps running the same whether Discord is open or not
+pub struct DiscordPresence {
+    sender: Sender<Activity>,
+    /// Activity last published, and when
+    last_activity: Option<Activity>,
+    last_update: f64,
+}
+
+impl DiscordPresence {
+    /// Starts publishing to Discord, which doesn't have to be open yet
+    /// Input: the ID of the Discord application the activity is published as, which gives it its name and images
+    pub fn start(application_id: String) -> Self {
+        let (sender, receiver) = mpsc::channel::<Activity>();
+        let start = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs() as i64);
+        thread::spawn(move || {
+            let mut client = DiscordIpcClient::new(&application_id);
+            let mut is_connected = false;
+            //The thread ends once the game drops the presence, and takes the activity off Discord
+            for activity in receiver {
+                if !is_connected {
+                    is_connected = client.connect().is_ok();
+                }
+                let mut payload = DiscordActivity::new()
+                    .details(&activity.details)
+                    .timestamps(Timestamps::new().start(start));
+                if !activity.state.is_empty() {
+                    payload = payload.state(&activity.state);
+                }
+                if is_connected && client.set_activity(payload).is_err() {
+                    let _ = client.close();
+                    is_connected = false;
+                }
+            }
+            if is_connected {
+                let _ = client.clear_activity();
+                let _ = client.close();
+            }
+        });
+        DiscordPresence {
+            sender,
+            last_activity: None,
+            last_update: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Picks what to publish, if enough time went by since the last update and the activity changed
+    /// Input: the current time, and what makes the current activity, only called when it's time to update
+    /// Output: the activity to publish, if there's one
+    fn due(&mut self, time: f64, activity: impl FnOnce() -> Activity) -> Option<Activity> {
+        if time - self.last_update < UPDATE_INTERVAL {
+            return None;
+        }
+        let activity = activity();
+        if self.last_activity.as_ref() == Some(&activity) {
+            return None;
+        }
+        self.last_update = time;
+        self.last_activity = Some(activity.clone());
+        Some(activity)
+    }
+
+    /// Publishes the current activity, if it's time to
+    /// Input: the current time, and what makes the current activity, only called when it's time to update
+    pub fn show(&mut self, time: f64, activity: impl FnOnce() -> Activity) {
+        if let Some(activity) = self.due(time, activity) {
+            //The thread only stops once the presence is dropped
+            let _ = self.sender.send(activity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_activity_is_published_when_it_changes_at_most_every_interval() {
+        let (sender, _receiver) = mpsc::channel();
+        let mut presence = DiscordPresence {
+            sender,
+            last_activity: None,
+            last_update: f64::NEG_INFINITY,
+        };
+        let board = Activity::board("Conway", 10, 5);
+        assert_eq!(Some(board.clone()), presence.due(0., || board.clone()));
+        assert_eq!(None, presence.due(1., Activity::menu));
+        //Nothing is sent when nothing changed, so the next change is sent right away
+        assert_eq!(None, presence.due(UPDATE_INTERVAL, || board.clone()));
+        assert_eq!(
+            Some(Activity::menu()),
+            presence.due(UPDATE_INTERVAL + 1., Activity::menu)
+        );
+    }
+}
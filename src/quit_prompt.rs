@@ -0,0 +1,94 @@
+use macroquad::prelude::*;
+
+use crate::locale::tr;
+use crate::pause_menu::entry_rect;
+use crate::text::{draw_text, measure_text};
+use crate::theme::Theme;
+
+/// What can be done when quitting with unsaved changes
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum QuitChoice {
+    /// Saves the board, and quits
+    Save,
+    /// Quits without saving, losing the changes
+    Discard,
+    /// Goes back to the game
+    Cancel,
+}
+
+/// Every choice of the prompt, in order, with the text shown for it
+const CHOICES: [(QuitChoice, &str); 3] = [
+    (QuitChoice::Save, "pause.save_and_quit"),
+    (QuitChoice::Discard, "pause.quit_without_saving"),
+    (QuitChoice::Cancel, "pause.cancel"),
+];
+
+/// Prompt shown when quitting while the board has changes that weren't saved, asking whether to save them first
+#[derive(Default)]
+pub struct QuitPrompt {
+    pub is_open: bool,
+    /// Index of the highlighted choice
+    selected: usize,
+}
+
+impl QuitPrompt {
+    /// Handles the input of the prompt and draws it over an area of the screen. Choices are picked with the arrow keys
+    /// and enter, or by clicking on them, and escape goes back to the game
+    /// Input: the theme to draw the prompt with, and the area to draw in
+    /// Output: the choice picked on this frame, if any
+    pub fn update(&mut self, theme: &Theme, area: Rect) -> Option<QuitChoice> {
+        if is_key_pressed(KeyCode::Down) {
+            self.selected = (self.selected + 1) % CHOICES.len();
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.selected = (self.selected + CHOICES.len() - 1) % CHOICES.len();
+        }
+        let (mouse_x, mouse_y) = mouse_position();
+        let hovered = (0..CHOICES.len())
+            .find(|index| entry_rect(area, *index).contains(vec2(mouse_x, mouse_y)));
+        if let Some(hovered) = hovered {
+            self.selected = hovered;
+        }
+
+        let picked = if is_key_pressed(KeyCode::Escape) {
+            Some(QuitChoice::Cancel)
+        } else if is_key_pressed(KeyCode::Enter)
+            || (hovered.is_some() && is_mouse_button_pressed(MouseButton::Left))
+        {
+            Some(CHOICES[self.selected].0)
+        } else {
+            None
+        };
+        if picked.is_some() {
+            self.is_open = false;
+            self.selected = 0;
+        }
+
+        clear_background(theme.background);
+        //The question is longer than the choices, so it's centered over them
+        let title = tr("pause.unsaved_changes");
+        let title_rect = entry_rect(area, 0);
+        draw_text(
+            title,
+            area.x + (area.w - measure_text(title, None, 36, 1.).width) / 2.,
+            title_rect.y - title_rect.h / 2.,
+            36.,
+            theme.text,
+        );
+        for (index, (_, name)) in CHOICES.iter().enumerate() {
+            let rect = entry_rect(area, index);
+            if index == self.selected {
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme.highlight);
+            }
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2., theme.text);
+            draw_text(
+                tr(name),
+                rect.x + rect.w / 10.,
+                rect.y + rect.h * 0.7,
+                30.,
+                theme.text,
+            );
+        }
+        picked
+    }
+}
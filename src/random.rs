@@ -0,0 +1,71 @@
+/// Where the random numbers boards are filled with come from, so the same seed always gives the same board, and tests
+/// can give a sequence of their own. Closures returning numbers are sources too
+///
+/// ```
+/// use conways_game_of_life::{Board, CellState};
+///
+/// // Numbers alternating between the lowest and the highest make every other cell alive
+/// let mut numbers = [0, u64::MAX].into_iter().cycle();
+/// let board = Board::random_with(4, 1, 0.5, &mut || numbers.next().unwrap());
+/// assert_eq!("#.#.", board.to_string());
+/// ```
+pub trait RandomSource {
+    /// Returns the next random number, with every bit as likely to be set as not
+    fn next_u64(&mut self) -> u64;
+
+    /// Returns the next random number between 0, included, and 1, excluded
+    fn next_f64(&mut self) -> f64 {
+        //The 53 highest bits are as many as fit in the mantissa of an f64
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl<F: FnMut() -> u64> RandomSource for F {
+    fn next_u64(&mut self) -> u64 {
+        self()
+    }
+}
+
+/// A small and fast generator of random numbers, SplitMix64, which gives the same numbers for the same seed on every
+/// platform. It's not meant for anything needing secrecy
+#[derive(Clone, Debug)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub const fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+}
+
+impl RandomSource for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut bits = self.state;
+        bits = (bits ^ (bits >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        bits = (bits ^ (bits >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        bits ^ (bits >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Board;
+
+    #[test]
+    fn seeds_give_the_same_boards() {
+        let mut generator = SplitMix64::new(0);
+        //First numbers of SplitMix64 seeded with 0, as given by its reference implementation
+        assert_eq!(0xE220_A839_7B1D_CDAF, generator.next_u64());
+        assert_eq!(0x6E78_9E6A_A1B9_65F4, generator.next_u64());
+
+        let board = Board::random(40, 30, 0.3, 7);
+        assert_eq!(board, Board::random(40, 30, 0.3, 7));
+        assert_ne!(board, Board::random(40, 30, 0.3, 8));
+        assert!((300..420).contains(&board.population()));
+        assert_eq!(0, Board::random(10, 10, 0.0, 7).population());
+        assert_eq!(100, Board::random(10, 10, 1.0, 7).population());
+    }
+}
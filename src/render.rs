@@ -0,0 +1,851 @@
+use std::fmt;
+use std::str::FromStr;
+
+use macroquad::prelude::*;
+
+use crate::census::Census;
+use crate::keys::key_name;
+use crate::locale::{tr, tr_with};
+use crate::palette::Palette;
+use crate::simulation::{Influence, Simulation};
+use crate::text::{draw_text, measure_text};
+use crate::theme::Theme;
+use crate::view::View;
+use crate::{Board, Boundary, CellState, Point};
+
+/// Shape alive cells are drawn with
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CellShape {
+    /// Squares filling the whole cell, so neighbouring cells touch
+    Square,
+    /// Squares with rounded corners, with a small gap between them
+    Rounded,
+    /// Circles, with a small gap between them
+    Circle,
+}
+
+impl CellShape {
+    /// Returns the shape that comes after this one, going back to the first after the last
+    pub fn next(self) -> Self {
+        match self {
+            CellShape::Square => CellShape::Rounded,
+            CellShape::Rounded => CellShape::Circle,
+            CellShape::Circle => CellShape::Square,
+        }
+    }
+}
+
+impl fmt::Display for CellShape {
+    /// Writes the name of the shape, as it's parsed
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CellShape::Square => "square",
+            CellShape::Rounded => "rounded",
+            CellShape::Circle => "circle",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for CellShape {
+    type Err = String;
+
+    /// Parses the name of a shape, ignoring case
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "square" => Ok(CellShape::Square),
+            "rounded" => Ok(CellShape::Rounded),
+            "circle" => Ok(CellShape::Circle),
+            _ => Err(format!(
+                "unknown cell shape '{name}', expected square, rounded or circle"
+            )),
+        }
+    }
+}
+
+/// Draws the cells of a board that are inside an area of the screen. Cells on the edges of the area are cut to fit it,
+/// so a zoomed in board doesn't spill out of it. Toroidal boards are repeated over the whole area, as their edges wrap around
+/// Cells that aren't squares can't be cut, so the ones on the edges of the area are left dead
+/// Input: the board, the screen position of its top left corner, the size of each cell, the area to draw in, the
+/// style to draw cells with, and the cells as they were last drawn, to only draw the ones that changed since then
+pub fn draw_board(
+    board: &Board,
+    (x_origin, y_origin): (f32, f32),
+    cell_size: f32,
+    area: Rect,
+    style: &CellStyle,
+    previous_cells: Option<&[CellState]>,
+) {
+    let CellStyle {
+        theme,
+        sprite,
+        shape,
+    } = *style;
+    let first_x = ((area.x - x_origin) / cell_size).floor() as isize;
+    let last_x = ((area.right() - x_origin) / cell_size).ceil() as isize;
+    let first_y = ((area.y - y_origin) / cell_size).floor() as isize;
+    let last_y = ((area.bottom() - y_origin) / cell_size).ceil() as isize;
+    for x in first_x..last_x {
+        for y in first_y..last_y {
+            let Some(Point {
+                x: board_x,
+                y: board_y,
+            }) = board.wrap_coordinates(x, y)
+            else {
+                continue;
+            };
+            if let Some(previous_cells) = previous_cells
+                && previous_cells[board_y * board.width + board_x] == board[(board_x, board_y)]
+            {
+                continue;
+            }
+            let cell = Rect::new(
+                x_origin + (x as f32) * cell_size,
+                y_origin + (y as f32) * cell_size,
+                cell_size,
+                cell_size,
+            );
+            let Some(visible) = cell.intersect(area) else {
+                continue;
+            };
+            match (board[(board_x, board_y)], sprite) {
+                (CellState::Alive, Some(sprite)) => {
+                    //The sprite goes over a dead cell, so transparent parts of it look like the board
+                    draw_rectangle(visible.x, visible.y, visible.w, visible.h, theme.dead);
+                    draw_texture_ex(
+                        sprite,
+                        visible.x,
+                        visible.y,
+                        WHITE,
+                        DrawTextureParams {
+                            dest_size: Some(vec2(visible.w, visible.h)),
+                            source: Some(visible_source(cell, visible, sprite.size())),
+                            ..Default::default()
+                        },
+                    );
+                }
+                (CellState::Alive, None) if shape == CellShape::Square => {
+                    draw_rectangle(visible.x, visible.y, visible.w, visible.h, theme.alive);
+                }
+                (CellState::Alive, None) => {
+                    draw_rectangle(visible.x, visible.y, visible.w, visible.h, theme.dead);
+                    if visible == cell {
+                        draw_cell_shape(cell, shape, theme.alive);
+                    }
+                }
+                (CellState::Dead, _) => {
+                    draw_rectangle(visible.x, visible.y, visible.w, visible.h, theme.dead);
+                }
+            }
+        }
+    }
+}
+
+/// How the cells of a board are drawn, when they're drawn one by one
+#[derive(Clone, Copy)]
+pub struct CellStyle<'a> {
+    pub theme: &'a Theme,
+    /// Sprite alive cells are drawn with, if there is one
+    pub sprite: Option<&'a Texture2D>,
+    /// Shape alive cells are drawn with when there's no sprite
+    pub shape: CellShape,
+}
+
+/// Drawing of the part of a board that's inside an area of the screen, kept between frames. Boards drawn cell by cell
+/// are slow to draw, and most frames show the same cells, so only the cells that changed are drawn again
+#[derive(Default)]
+pub struct BoardCanvas {
+    target: Option<RenderTarget>,
+    /// Everything besides the cells that the drawing depends on. The whole board is drawn again when any of it changes
+    layout: Option<CanvasLayout>,
+    /// Cells as they were last drawn
+    cells: Vec<CellState>,
+}
+
+/// Everything besides the cells that the drawing of a board depends on
+#[derive(Clone, Copy, PartialEq)]
+struct CanvasLayout {
+    area: Rect,
+    origin: (f32, f32),
+    cell_size: f32,
+    shape: CellShape,
+    /// Colours of alive cells, dead cells, and the background
+    colors: [Color; 3],
+}
+
+impl BoardCanvas {
+    /// Draws the cells of a board that changed since the last frame onto the canvas, and the canvas onto the screen
+    /// Input: the board, the screen position of its top left corner, the size of each cell, the area to draw in, and
+    /// the style to draw cells with
+    pub fn draw(
+        &mut self,
+        board: &Board,
+        (x_origin, y_origin): (f32, f32),
+        cell_size: f32,
+        area: Rect,
+        style: &CellStyle,
+    ) {
+        let theme = style.theme;
+        let layout = Some(CanvasLayout {
+            area,
+            origin: (x_origin, y_origin),
+            cell_size,
+            shape: style.shape,
+            colors: [theme.alive, theme.dead, theme.background],
+        });
+        //The canvas has a pixel for each pixel of the display, which can be more than one per logical pixel
+        let dpi_scale = screen_dpi_scale();
+        let (width, height) = (
+            (area.w * dpi_scale).ceil() as u32,
+            (area.h * dpi_scale).ceil() as u32,
+        );
+        let target = match self.target.take() {
+            Some(target)
+                if target.texture.width() as u32 == width
+                    && target.texture.height() as u32 == height =>
+            {
+                target
+            }
+            _ => {
+                self.layout = None;
+                let target = render_target(width.max(1), height.max(1));
+                target.texture.set_filter(FilterMode::Nearest);
+                target
+            }
+        };
+        let is_redrawn = self.layout != layout || self.cells.len() != board.board.len();
+
+        //The canvas has its own coordinates, with the top left corner of the area at 0, 0
+        let mut camera = Camera2D::from_display_rect(Rect::new(0., 0., area.w, area.h));
+        camera.render_target = Some(target.clone());
+        set_camera(&camera);
+        if is_redrawn {
+            clear_background(theme.background);
+        }
+        draw_board(
+            board,
+            (x_origin - area.x, y_origin - area.y),
+            cell_size,
+            Rect::new(0., 0., area.w, area.h),
+            style,
+            (!is_redrawn).then_some(&self.cells),
+        );
+        set_default_camera();
+
+        draw_texture_ex(
+            &target.texture,
+            area.x,
+            area.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(area.w, area.h)),
+                //Render targets are drawn upside down
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+        self.cells.clone_from(&board.board);
+        self.layout = layout;
+        self.target = Some(target);
+    }
+}
+
+/// Share of the size of a cell left empty around rounded and circle cells
+const CELL_GAP_SHARE: f32 = 0.1;
+
+/// Draws an alive cell with a shape other than a square, leaving a small gap around it
+/// Input: the screen rectangle of the cell, its shape, and its colour
+fn draw_cell_shape(cell: Rect, shape: CellShape, color: Color) {
+    let gap = cell.w * CELL_GAP_SHARE;
+    let inner = Rect::new(
+        cell.x + gap,
+        cell.y + gap,
+        cell.w - 2. * gap,
+        cell.h - 2. * gap,
+    );
+    match shape {
+        CellShape::Square => draw_rectangle(cell.x, cell.y, cell.w, cell.h, color),
+        CellShape::Circle => draw_circle(inner.center().x, inner.center().y, inner.w / 2., color),
+        //A rounded square is made of a cross of two rectangles, with a circle on each corner
+        CellShape::Rounded => {
+            let radius = inner.w / 4.;
+            draw_rectangle(
+                inner.x + radius,
+                inner.y,
+                inner.w - 2. * radius,
+                inner.h,
+                color,
+            );
+            draw_rectangle(
+                inner.x,
+                inner.y + radius,
+                inner.w,
+                inner.h - 2. * radius,
+                color,
+            );
+            for (x, y) in [
+                (inner.x + radius, inner.y + radius),
+                (inner.right() - radius, inner.y + radius),
+                (inner.x + radius, inner.bottom() - radius),
+                (inner.right() - radius, inner.bottom() - radius),
+            ] {
+                draw_circle(x, y, radius, color);
+            }
+        }
+    }
+}
+
+/// Finds the part of a texture to draw on a rectangle that's only partly visible, so the texture is cut instead of squashed
+/// Input: the screen rectangle the whole texture goes on, the part of it that's visible, and the size of the texture
+/// Output: the rectangle of the texture to draw, in pixels of the texture
+fn visible_source(rect: Rect, visible: Rect, texture_size: Vec2) -> Rect {
+    let scale_x = texture_size.x / rect.w;
+    let scale_y = texture_size.y / rect.h;
+    Rect::new(
+        (visible.x - rect.x) * scale_x,
+        (visible.y - rect.y) * scale_y,
+        visible.w * scale_x,
+        visible.h * scale_y,
+    )
+}
+
+/// Texture of a board with one pixel per cell, which is only redrawn when the board or its colours change, instead of
+/// drawing every cell on every frame
+#[derive(Default)]
+pub struct BoardTexture {
+    /// Cells and colours the texture was last drawn with
+    cells: Vec<CellState>,
+    colors: Option<(Color, Color)>,
+    image: Option<Image>,
+    texture: Option<Texture2D>,
+}
+
+impl BoardTexture {
+    /// Redraws the texture if the board changed since the last time
+    /// Input: the board, and the theme to draw it with
+    /// Output: the texture of the board
+    pub fn update(&mut self, board: &Board, theme: &Theme) -> &Texture2D {
+        let colors = Some((theme.alive, theme.dead));
+        let is_same_size = self.cells.len() == board.board.len()
+            && self
+                .image
+                .as_ref()
+                .is_some_and(|image| image.width() == board.width);
+        if !is_same_size || self.colors != colors || self.cells != board.board {
+            self.redraw(board, theme, is_same_size);
+        }
+        self.texture.as_ref().unwrap()
+    }
+
+    /// Draws every cell of a board on the texture
+    /// Input: the board, the theme to draw it with, and whether the board has the same size as the texture
+    fn redraw(&mut self, board: &Board, theme: &Theme, is_same_size: bool) {
+        let mut image = match self.image.take() {
+            Some(image) if is_same_size => image,
+            _ => Image::gen_image_color(board.width as u16, board.length as u16, theme.dead),
+        };
+        for y in 0..board.length {
+            for x in 0..board.width {
+                let color = match board[(x, y)] {
+                    CellState::Alive => theme.alive,
+                    CellState::Dead => theme.dead,
+                };
+                image.set_pixel(x as u32, y as u32, color);
+            }
+        }
+        //The texture is reused as long as the board keeps the same size
+        match &self.texture {
+            Some(texture) if is_same_size => texture.update(&image),
+            _ => {
+                let texture = Texture2D::from_image(&image);
+                texture.set_filter(FilterMode::Nearest);
+                self.texture = Some(texture);
+            }
+        }
+        self.cells.clone_from(&board.board);
+        self.colors = Some((theme.alive, theme.dead));
+        self.image = Some(image);
+    }
+}
+
+/// Draws the part of a board that's inside an area of the screen from its texture, as a single quad for each time the
+/// board is repeated. Toroidal boards are repeated over the whole area, as their edges wrap around
+/// Input: the board, its texture, the screen position of its top left corner, the size of each cell, and the area to draw in
+pub fn draw_board_texture(
+    board: &Board,
+    texture: &Texture2D,
+    (x_origin, y_origin): (f32, f32),
+    cell_size: f32,
+    area: Rect,
+) {
+    let board_width = board.width as f32 * cell_size;
+    let board_height = board.length as f32 * cell_size;
+    let (tiles_x, tiles_y) = match board.boundary {
+        Boundary::Bounded => (0..=0, 0..=0),
+        Boundary::Toroidal => (
+            ((area.x - x_origin) / board_width).floor() as isize
+                ..=((area.right() - x_origin) / board_width).floor() as isize,
+            ((area.y - y_origin) / board_height).floor() as isize
+                ..=((area.bottom() - y_origin) / board_height).floor() as isize,
+        ),
+    };
+    for tile_x in tiles_x {
+        for tile_y in tiles_y.clone() {
+            let tile = Rect::new(
+                x_origin + tile_x as f32 * board_width,
+                y_origin + tile_y as f32 * board_height,
+                board_width,
+                board_height,
+            );
+            let Some(visible) = tile.intersect(area) else {
+                continue;
+            };
+            draw_texture_ex(
+                texture,
+                visible.x,
+                visible.y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(visible.w, visible.h)),
+                    source: Some(visible_source(tile, visible, texture.size())),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+/// Smallest cell size grid lines are drawn at, as below it they would cover the cells
+const MIN_GRID_CELL_SIZE: f32 = 5.;
+
+/// Draws lines between the cells of a board that are inside an area of the screen
+/// Input: the board, the screen position of its top left corner, the size of each cell, the area to draw in, and the
+/// theme to draw it with
+pub fn draw_grid(
+    board: &Board,
+    (x_origin, y_origin): (f32, f32),
+    cell_size: f32,
+    area: Rect,
+    theme: &Theme,
+) {
+    if cell_size < MIN_GRID_CELL_SIZE {
+        return;
+    }
+    //Toroidal boards repeat over the whole area, while bounded ones only have lines up to their edges
+    let lines_area = match board.boundary {
+        Boundary::Toroidal => area,
+        Boundary::Bounded => {
+            let board_rect = Rect::new(
+                x_origin,
+                y_origin,
+                board.width as f32 * cell_size,
+                board.length as f32 * cell_size,
+            );
+            match board_rect.intersect(area) {
+                Some(lines_area) => lines_area,
+                None => return,
+            }
+        }
+    };
+    let color = theme.grid;
+
+    let first_x = ((lines_area.x - x_origin) / cell_size).ceil() as isize;
+    let last_x = ((lines_area.right() - x_origin) / cell_size).floor() as isize;
+    for x in first_x..=last_x {
+        let x_screen_pos = x_origin + x as f32 * cell_size;
+        draw_line(
+            x_screen_pos,
+            lines_area.y,
+            x_screen_pos,
+            lines_area.bottom(),
+            1.,
+            color,
+        );
+    }
+    let first_y = ((lines_area.y - y_origin) / cell_size).ceil() as isize;
+    let last_y = ((lines_area.bottom() - y_origin) / cell_size).floor() as isize;
+    for y in first_y..=last_y {
+        let y_screen_pos = y_origin + y as f32 * cell_size;
+        draw_line(
+            lines_area.x,
+            y_screen_pos,
+            lines_area.right(),
+            y_screen_pos,
+            1.,
+            color,
+        );
+    }
+}
+
+/// Draws an outline around each alive cell of a board that's inside an area of the screen, so neighbouring cells are
+/// told apart and stand out from the dead ones
+/// Input: the board, the screen position of its top left corner, the size of each cell, the area to draw in, and the
+/// theme to draw it with
+pub fn draw_cell_outlines(
+    board: &Board,
+    (x_origin, y_origin): (f32, f32),
+    cell_size: f32,
+    area: Rect,
+    theme: &Theme,
+) {
+    if cell_size < MIN_GRID_CELL_SIZE {
+        return;
+    }
+    let thickness = (cell_size / 8.).max(1.);
+    let first_x = ((area.x - x_origin) / cell_size).floor() as isize;
+    let last_x = ((area.right() - x_origin) / cell_size).ceil() as isize;
+    let first_y = ((area.y - y_origin) / cell_size).floor() as isize;
+    let last_y = ((area.bottom() - y_origin) / cell_size).ceil() as isize;
+    for x in first_x..last_x {
+        for y in first_y..last_y {
+            let Some(Point {
+                x: board_x,
+                y: board_y,
+            }) = board.wrap_coordinates(x, y)
+            else {
+                continue;
+            };
+            if board[(board_x, board_y)] == CellState::Dead {
+                continue;
+            }
+            let cell = Rect::new(
+                x_origin + (x as f32) * cell_size,
+                y_origin + (y as f32) * cell_size,
+                cell_size,
+                cell_size,
+            );
+            //Cells cut by the edges of the area aren't outlined, as the outline would go past them
+            if cell.intersect(area) == Some(cell) {
+                draw_rectangle_lines(cell.x, cell.y, cell.w, cell.h, thickness, theme.highlight);
+            }
+        }
+    }
+}
+
+/// Largest share of the width or height of the area a board is drawn in that its minimap can take
+const MINIMAP_SHARE: f32 = 0.2;
+
+/// Finds where the minimap of a board goes, in the bottom right corner of the area the board is drawn in
+/// Input: the board, and the area it's drawn in
+/// Output: the screen rectangle of the minimap, keeping the proportions of the board
+pub fn minimap_rect(board: &Board, area: Rect) -> Rect {
+    let scale = (area.w * MINIMAP_SHARE / board.width as f32)
+        .min(area.h * MINIMAP_SHARE / board.length as f32);
+    let width = board.width as f32 * scale;
+    let height = board.length as f32 * scale;
+    let margin = area.w.min(area.h) / 50.;
+    Rect::new(
+        area.right() - width - margin,
+        area.bottom() - height - margin,
+        width,
+        height,
+    )
+}
+
+/// Draws a small map of the whole board, with a rectangle around the part that's in view
+/// Input: the board, its texture, its view, the area it's drawn in, the size of the cells with the whole board in
+/// view, the theme to draw it with, and the palette to mark the part in view with
+pub fn draw_minimap(
+    board: &Board,
+    texture: &Texture2D,
+    view: &View,
+    area: Rect,
+    fit_cell_size: f32,
+    theme: &Theme,
+    palette: &Palette,
+) {
+    let minimap = minimap_rect(board, area);
+    draw_texture_ex(
+        texture,
+        minimap.x,
+        minimap.y,
+        WHITE,
+        DrawTextureParams {
+            dest_size: Some(vec2(minimap.w, minimap.h)),
+            ..Default::default()
+        },
+    );
+    draw_rectangle_lines(minimap.x, minimap.y, minimap.w, minimap.h, 2., theme.label);
+
+    let scale = minimap.w / board.width as f32;
+    let (x, y, width, height) = view.visible_cells((area.w, area.h), fit_cell_size);
+    let viewport = Rect::new(
+        minimap.x + x * scale,
+        minimap.y + y * scale,
+        width * scale,
+        height * scale,
+    );
+    //A toroidal board can be panned past its edges, so the rectangle is cut to the minimap
+    if let Some(viewport) = viewport.intersect(minimap) {
+        draw_rectangle_lines(
+            viewport.x,
+            viewport.y,
+            viewport.w,
+            viewport.h,
+            2.,
+            palette.marker,
+        );
+    }
+}
+
+/// Next generation of a board, which is only computed again when the board changes, so it can be previewed on every
+/// frame without stepping the board every time
+#[derive(Default)]
+pub struct NextGeneration {
+    /// Board the next generation was computed from
+    board: Option<Board>,
+    next: Option<Board>,
+}
+
+impl NextGeneration {
+    /// Computes the next generation of a board, unless it's the same board as the last time
+    /// Input: the board
+    /// Output: the board as it will be on the next generation
+    pub fn update(&mut self, board: &Board) -> &Board {
+        let is_same_board = self.board.as_ref().is_some_and(|previous| {
+            previous.board == board.board
+                && previous.rule == board.rule
+                && previous.boundary == board.boundary
+        });
+        if !is_same_board || self.next.is_none() {
+            let mut next = board.clone();
+            next.update_board();
+            self.board = Some(board.clone());
+            self.next = Some(next);
+        }
+        self.next.as_ref().unwrap()
+    }
+}
+
+/// Highlights the cells that will be born and the ones that will die on the next generation of a board
+/// Input: the board, its next generation, the screen position of their top left corner, the size of each cell, and
+/// the palette to draw with
+pub fn draw_next_generation(
+    board: &Board,
+    next: &Board,
+    (x_origin, y_origin): (f32, f32),
+    cell_size: f32,
+    palette: &Palette,
+) {
+    for Point { x, y } in board.differences(next) {
+        let color = match next[(x, y)] {
+            CellState::Alive => palette.born,
+            CellState::Dead => palette.dying,
+        };
+        draw_rectangle(
+            x_origin + (x as f32) * cell_size,
+            y_origin + (y as f32) * cell_size,
+            cell_size,
+            cell_size,
+            color,
+        );
+    }
+}
+
+/// Highlights the cells that differ between a board and the one it's being compared against.
+/// Cells only alive on the board are drawn in red, and cells only alive on the other one in blue, unless the palette
+/// says otherwise
+/// Input: both boards, the screen position of their top left corner, the size of each cell, and the palette
+pub fn draw_differences(
+    board: &Board,
+    other: &Board,
+    (x_origin, y_origin): (f32, f32),
+    cell_size: f32,
+    palette: &Palette,
+) {
+    for Point { x, y } in board.differences(other) {
+        let color = match board[(x, y)] {
+            CellState::Alive => palette.board_only,
+            CellState::Dead => palette.snapshot_only,
+        };
+        draw_rectangle(
+            x_origin + (x as f32) * cell_size,
+            y_origin + (y as f32) * cell_size,
+            cell_size,
+            cell_size,
+            color,
+        );
+    }
+}
+
+/// Outlines the cell under the cursor, and shows its coordinates in the bottom right corner of the window
+/// Input: the position of the cell on screen, in cells from the top left corner of the board, which can be past its
+/// edges on toroidal boards, its coordinates on the board, the screen position of the top left corner of the board,
+/// the size of each cell, how far up from the bottom of the window the coordinates go, and the theme to draw with
+pub fn draw_hovered_cell(
+    (x, y): (isize, isize),
+    board_cell: Point,
+    (x_origin, y_origin): (f32, f32),
+    cell_size: f32,
+    bottom_margin: f32,
+    theme: &Theme,
+) {
+    draw_rectangle_lines(
+        x_origin + x as f32 * cell_size,
+        y_origin + y as f32 * cell_size,
+        cell_size,
+        cell_size,
+        2.,
+        theme.highlight,
+    );
+    let text = board_cell.to_string();
+    draw_text(
+        &text,
+        screen_width() - measure_text(&text, None, 24, 1.).width - screen_width() / 80.,
+        screen_height() - bottom_margin,
+        24.,
+        theme.label,
+    );
+}
+
+/// Highlights the cells that influenced a traced cell, over the current board, and outlines the traced cell
+/// Input: the traced influence, the screen position of the top left corner of the board, the size of each cell, and the
+/// palette to draw with
+pub fn draw_influence(
+    influence: &Influence,
+    (x_origin, y_origin): (f32, f32),
+    cell_size: f32,
+    palette: &Palette,
+) {
+    for ancestor in &influence.ancestors {
+        draw_rectangle(
+            x_origin + ancestor.x as f32 * cell_size,
+            y_origin + ancestor.y as f32 * cell_size,
+            cell_size,
+            cell_size,
+            palette.influence,
+        );
+    }
+    draw_rectangle_lines(
+        x_origin + influence.cell.x as f32 * cell_size,
+        y_origin + influence.cell.y as f32 * cell_size,
+        cell_size,
+        cell_size,
+        3.,
+        palette.marker,
+    );
+}
+
+/// Draws a panel summarizing the run of a board that died out
+/// Input: the simulation that went extinct, the key that continues the game, and the window size
+pub fn draw_extinction_summary(
+    simulation: &Simulation,
+    continue_key: KeyCode,
+    window_width: f32,
+    window_height: f32,
+) {
+    let lines = [
+        tr("game.extinct").to_string(),
+        tr_with("game.generations_survived", &[&simulation.generation]),
+        tr_with("game.peak_population", &[&simulation.stats.peak_population]),
+        tr_with("game.total_births", &[&simulation.stats.total_births]),
+        tr_with("game.press_to_continue", &[&key_name(continue_key)]),
+    ];
+    let panel_width = window_width * 0.6;
+    let panel_height = window_height * 0.4;
+    let x = (window_width - panel_width) / 2.;
+    let y = (window_height - panel_height) / 2.;
+    draw_rectangle(
+        x,
+        y,
+        panel_width,
+        panel_height,
+        Color::new(0., 0., 0., 0.75),
+    );
+    for (index, line) in lines.iter().enumerate() {
+        draw_text(
+            line,
+            x + panel_width / 20.,
+            y + (index + 1) as f32 * panel_height / (lines.len() + 1) as f32,
+            28.,
+            WHITE,
+        );
+    }
+}
+
+/// Draws a panel with the count of each object on the board, on the left side of the window
+/// Input: the census of the board, and the theme to draw the panel with
+pub fn draw_census_panel(census: &Census, theme: &Theme) {
+    let counts: Vec<_> = census.counts().collect();
+    let line_height = 24.;
+    let panel = Rect::new(
+        screen_width() / 80.,
+        screen_height() / 4.,
+        200.,
+        (counts.len() + 2) as f32 * line_height,
+    );
+    draw_rectangle(
+        panel.x,
+        panel.y,
+        panel.w,
+        panel.h,
+        Color {
+            a: 0.85,
+            ..theme.background
+        },
+    );
+    draw_rectangle_lines(panel.x, panel.y, panel.w, panel.h, 1., theme.label);
+    draw_text(
+        tr("object.title"),
+        panel.x + 10.,
+        panel.y + line_height,
+        24.,
+        theme.text,
+    );
+    for (index, (name, count)) in counts.iter().enumerate() {
+        let y = panel.y + (index + 2) as f32 * line_height;
+        draw_text(tr(name), panel.x + 10., y, 20., theme.label);
+        let count = count.to_string();
+        draw_text(
+            &count,
+            panel.right() - measure_text(&count, None, 20, 1.).width - 10.,
+            y,
+            20.,
+            theme.label,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_generation_follows_edits_without_stepping_the_board() {
+        let mut board = Board::new(3, 3);
+        board.toggle_cell_state((1, 0));
+        board.toggle_cell_state((1, 1));
+        board.toggle_cell_state((1, 2));
+        let mut next_generation = NextGeneration::default();
+        let next = next_generation.update(&board);
+        assert_eq!(
+            vec![(1, 0), (0, 1), (2, 1), (1, 2)],
+            board
+                .differences(next)
+                .map(Into::into)
+                .collect::<Vec<(usize, usize)>>()
+        );
+        assert_eq!(CellState::Alive, board[(1, 0)]);
+
+        board.toggle_cell_state((1, 0));
+        let next = next_generation.update(&board);
+        assert_eq!(0, next.population());
+    }
+
+    #[test]
+    fn cell_shapes_are_parsed_ignoring_case() {
+        assert_eq!(Ok(CellShape::Circle), "Circle".parse());
+        assert_eq!(Ok(CellShape::Rounded), "rounded".parse());
+        assert!("hexagon".parse::<CellShape>().is_err());
+    }
+
+    #[test]
+    fn textures_are_cut_to_the_visible_part() {
+        let cell = Rect::new(10., 10., 20., 20.);
+        let visible = Rect::new(20., 10., 10., 5.);
+        assert_eq!(
+            Rect::new(32., 0., 32., 16.),
+            visible_source(cell, visible, vec2(64., 64.))
+        );
+    }
+}
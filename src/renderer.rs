@@ -0,0 +1,159 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use macroquad::prelude::*;
+
+use crate::{Board, CellState, GolError, Point};
+
+/// Something generations of a board can be shown on, like the window, a terminal or image files, so the same loop
+/// can run a board whatever it's shown on
+pub trait Renderer {
+    /// Shows a generation of a board
+    /// Input: the board, and how many generations it has been running for
+    /// Output: whether it was shown, or what kept it from being shown
+    fn render(&mut self, board: &Board, generation: usize) -> Result<(), GolError>;
+}
+
+/// Draws boards on the window with Macroquad, as big as they fit, with only their alive cells over a background
+pub struct ScreenRenderer {
+    pub alive: Color,
+    pub background: Color,
+}
+
+impl Renderer for ScreenRenderer {
+    fn render(&mut self, board: &Board, _generation: usize) -> Result<(), GolError> {
+        let cell_size =
+            (screen_width() / board.width() as f32).min(screen_height() / board.length() as f32);
+        clear_background(self.background);
+        for Point { x, y } in board.live_cells() {
+            draw_rectangle(
+                x as f32 * cell_size,
+                y as f32 * cell_size,
+                cell_size,
+                cell_size,
+                self.alive,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Prints boards as text, with a # for each alive cell and a . for each dead one. When writing to a terminal, each
+/// generation can be drawn over the last one, so the board is animated in place
+pub struct TextRenderer<W: Write> {
+    output: W,
+    /// Whether the terminal is cleared before each generation
+    pub is_animated: bool,
+}
+
+impl<W: Write> TextRenderer<W> {
+    /// Creates a renderer that prints each generation after the last one
+    /// Input: where to print the boards, such as the standard output
+    pub fn new(output: W) -> Self {
+        TextRenderer {
+            output,
+            is_animated: false,
+        }
+    }
+}
+
+impl<W: Write> Renderer for TextRenderer<W> {
+    fn render(&mut self, board: &Board, generation: usize) -> Result<(), GolError> {
+        if self.is_animated {
+            //Moves the cursor to the top left corner and clears the terminal
+            write!(self.output, "\x1b[H\x1b[2J").map_err(GolError::Output)?;
+        }
+        writeln!(self.output, "Generation {generation}\n{board}\n").map_err(GolError::Output)?;
+        self.output.flush().map_err(GolError::Output)
+    }
+}
+
+/// Writes each generation of a board to a PNG image of its own, in a directory
+pub struct PngRenderer {
+    directory: PathBuf,
+    /// Width and height of each cell, in pixels
+    pub cell_size: u32,
+    pub alive: Color,
+    pub dead: Color,
+}
+
+impl PngRenderer {
+    /// Creates a renderer that writes black cells over white
+    /// Input: the directory the images go in, which is created if needed
+    pub fn new(directory: PathBuf) -> Self {
+        PngRenderer {
+            directory,
+            cell_size: 8,
+            alive: BLACK,
+            dead: WHITE,
+        }
+    }
+
+    /// Finds the path of the image of a generation, numbered so they sort in order
+    /// Input: the generation
+    /// Output: the path of its image
+    pub fn path(&self, generation: usize) -> PathBuf {
+        self.directory
+            .join(format!("generation_{generation:06}.png"))
+    }
+}
+
+impl Renderer for PngRenderer {
+    fn render(&mut self, board: &Board, generation: usize) -> Result<(), GolError> {
+        let path = self.path(generation);
+        let io_error = |source| GolError::Io {
+            path: self.directory.clone(),
+            source,
+        };
+        std::fs::create_dir_all(&self.directory).map_err(io_error)?;
+        let cell_size = self.cell_size.max(1);
+        let (width, height) = (
+            board.width() as u32 * cell_size,
+            board.length() as u32 * cell_size,
+        );
+        let (alive, dead): ([u8; 4], [u8; 4]) = (self.alive.into(), self.dead.into());
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let cell = board[((x / cell_size) as usize, (y / cell_size) as usize)];
+                pixels.extend(match cell {
+                    CellState::Alive => alive,
+                    CellState::Dead => dead,
+                });
+            }
+        }
+        image::save_buffer(&path, &pixels, width, height, image::ColorType::Rgba8).map_err(
+            |error| GolError::Io {
+                source: std::io::Error::other(error.to_string()),
+                path,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_renderer_prints_each_generation() {
+        let board: Board = ".#.\n...".parse().unwrap();
+        let mut output = Vec::new();
+        let mut renderer = TextRenderer::new(&mut output);
+        renderer.render(&board, 3).unwrap();
+        assert_eq!(
+            "Generation 3\n.#.\n...\n\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn png_renderer_numbers_images_in_order() {
+        let renderer = PngRenderer::new(PathBuf::from("frames"));
+        assert_eq!(
+            PathBuf::from("frames/generation_000042.png"),
+            renderer.path(42)
+        );
+        assert!(renderer.path(9) < renderer.path(10));
+    }
+}
@@ -0,0 +1,139 @@
+use std::fs;
+use std::path::Path;
+
+use rhai::{Engine, Scope};
+
+use crate::{GolError, LifeLikeRule};
+
+/// Most alive neighbours a cell can have, with the eight cells around it counted
+const MAX_NEIGHBOURS: i64 = 8;
+
+/// Reads a rule file, which gives a rule a name and states when cells are born and survive with conditions on their
+/// neighbours, so rules can be added without recompiling the game. Each line holds a setting, and lines starting
+/// with # are comments:
+///
+/// ```text
+/// name = Parity
+/// birth = alive % 2 == 1
+/// survival = alive % 2 == 1 && dead > 2
+/// ```
+///
+/// Conditions are Rhai expressions, where `alive` and `dead` are how many neighbours of the cell are alive and dead.
+/// Each one is checked for every count of alive neighbours when the file is read, which turns it into a life-like rule
+/// Input: the contents of the file
+/// Output: the name and the rule, or what's wrong with the file
+pub fn parse_rule_file(text: &str) -> Result<(String, LifeLikeRule), GolError> {
+    let (mut name, mut birth, mut survival) = (None, None, None);
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(GolError::InvalidRule(format!(
+                "expected a setting like 'birth = alive == 3', found '{line}'"
+            )));
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "name" => name = Some(value),
+            "birth" => birth = Some(value),
+            "survival" => survival = Some(value),
+            key => {
+                return Err(GolError::InvalidRule(format!(
+                    "unknown setting '{key}', expected name, birth or survival"
+                )));
+            }
+        }
+    }
+    let missing = |setting: &str| GolError::InvalidRule(format!("the rule has no {setting}"));
+    let name = name.ok_or_else(|| missing("name"))?;
+    let birth = counts_meeting(&birth.ok_or_else(|| missing("birth condition"))?)?;
+    let survival = counts_meeting(&survival.ok_or_else(|| missing("survival condition"))?)?;
+    let rule = format!("B{birth}/S{survival}").parse()?;
+    Ok((name, rule))
+}
+
+/// Finds the counts of alive neighbours that meet a condition
+/// Input: the condition, as a Rhai expression of `alive` and `dead`
+/// Output: the counts, written one digit each as in rulestrings, or why the condition couldn't be checked
+fn counts_meeting(condition: &str) -> Result<String, GolError> {
+    let engine = Engine::new();
+    let expression = engine.compile_expression(condition).map_err(|error| {
+        GolError::InvalidRule(format!("invalid condition '{condition}': {error}"))
+    })?;
+    let mut counts = String::new();
+    for alive in 0..=MAX_NEIGHBOURS {
+        let mut scope = Scope::new();
+        scope.push("alive", alive);
+        scope.push("dead", MAX_NEIGHBOURS - alive);
+        let is_met = engine
+            .eval_ast_with_scope::<bool>(&mut scope, &expression)
+            .map_err(|error| {
+                GolError::InvalidRule(format!("couldn't check '{condition}': {error}"))
+            })?;
+        if is_met {
+            counts.push_str(&alive.to_string());
+        }
+    }
+    Ok(counts)
+}
+
+/// Reads every .rule file of a directory, in order of their names
+/// Input: the directory, which doesn't have to exist
+/// Output: the name and rule of each file, and a message for each file that couldn't be read
+pub fn load_rule_files(directory: &Path) -> (Vec<(String, LifeLikeRule)>, Vec<String>) {
+    let mut paths: Vec<_> = match fs::read_dir(directory) {
+        Ok(entries) => entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "rule")
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    paths.sort();
+    let mut errors = Vec::new();
+    let rules = paths
+        .into_iter()
+        .filter_map(|path| {
+            let text = fs::read_to_string(&path).map_err(|source| GolError::Io {
+                path: path.clone(),
+                source,
+            });
+            match text.and_then(|text| parse_rule_file(&text)) {
+                Ok(rule) => Some(rule),
+                Err(error) => {
+                    errors.push(format!("{}: {error}", path.display()));
+                    None
+                }
+            }
+        })
+        .collect();
+    (rules, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conditions_become_life_like_rules() {
+        let (name, rule) = parse_rule_file(
+            "
+            # HighLife, with conditions
+            name = HighLife
+            birth = alive == 3 || alive == 6
+            survival = alive >= 2 && dead >= 5
+            ",
+        )
+        .unwrap();
+        assert_eq!("HighLife", name);
+        assert_eq!(LifeLikeRule::preset(1), rule);
+
+        assert!(parse_rule_file("name = Nothing\nbirth = alive == 3").is_err());
+        assert!(parse_rule_file("name = Typo\nbirth = alive ==\nsurvival = true").is_err());
+        assert!(parse_rule_file("name = Number\nbirth = alive\nsurvival = true").is_err());
+        assert!(parse_rule_file("colour = red").is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use macroquad::prelude::*;
+
+use crate::text::{draw_text, measure_text};
+use crate::theme::Theme;
+use crate::{Board, Boundary};
+
+/// Steps between ticks the rulers can use, in cells. The smallest one that leaves enough room between labels is used
+const TICK_STEPS: [usize; 12] = [1, 2, 5, 10, 20, 50, 100, 200, 500, 1000, 2000, 5000];
+
+/// Least space between ticks, in pixels, so their labels don't run into each other
+const MIN_TICK_SPACING: f32 = 40.;
+
+/// Thickness of the rulers, in pixels
+const RULER_SIZE: f32 = 18.;
+
+/// Finds how many cells apart the ticks of the rulers go at a zoom level
+/// Input: the size of the cells on screen
+/// Output: the step between ticks, in cells
+fn tick_step(cell_size: f32) -> usize {
+    TICK_STEPS
+        .into_iter()
+        .find(|step| *step as f32 * cell_size >= MIN_TICK_SPACING)
+        .unwrap_or(TICK_STEPS[TICK_STEPS.len() - 1])
+}
+
+/// Finds the ticks of a ruler along one side of the board
+/// Input: the screen position of the top or left edge of the board, the size of each cell, the part of the screen
+/// the ruler covers, from start to end, how many cells long the board is along it, and whether its edges wrap around
+/// Output: the screen position of each tick, with the index of the row or column it marks
+fn ticks(
+    origin: f32,
+    cell_size: f32,
+    (start, end): (f32, f32),
+    length: usize,
+    wraps_around: bool,
+) -> Vec<(f32, usize)> {
+    let step = tick_step(cell_size) as isize;
+    let first = ((start - origin) / cell_size).ceil() as isize;
+    let last = ((end - origin) / cell_size).floor() as isize;
+    (first..=last)
+        .filter_map(|cell| {
+            //Toroidal boards repeat, so their rulers count from 0 again on each copy
+            let index = if wraps_around {
+                cell.rem_euclid(length as isize)
+            } else {
+                cell
+            };
+            ((0..length as isize).contains(&index) && index % step == 0)
+                .then_some((origin + cell as f32 * cell_size, index as usize))
+        })
+        .collect()
+}
+
+/// Draws rulers along the top and left edges of the area a board is drawn in, with the index of every few columns
+/// and rows, so positions given in pattern files are easy to find. Ticks are further apart the smaller cells are
+/// Input: the board, the screen position of its top left corner, the size of each cell, the area it's drawn in, and
+/// the theme to draw with
+pub fn draw_rulers(
+    board: &Board,
+    (x_origin, y_origin): (f32, f32),
+    cell_size: f32,
+    area: Rect,
+    theme: &Theme,
+) {
+    let wraps_around = board.boundary == Boundary::Toroidal;
+    let band = Color {
+        a: 0.85,
+        ..theme.background
+    };
+    draw_rectangle(area.x, area.y, area.w, RULER_SIZE, band);
+    draw_rectangle(
+        area.x,
+        area.y + RULER_SIZE,
+        RULER_SIZE,
+        area.h - RULER_SIZE,
+        band,
+    );
+
+    let columns = ticks(
+        x_origin,
+        cell_size,
+        (area.x + RULER_SIZE, area.right()),
+        board.width,
+        wraps_around,
+    );
+    for (x, index) in columns {
+        draw_line(
+            x,
+            area.y + RULER_SIZE * 0.6,
+            x,
+            area.y + RULER_SIZE,
+            1.,
+            theme.label,
+        );
+        draw_text(
+            &index.to_string(),
+            x + 2.,
+            area.y + RULER_SIZE * 0.6,
+            14.,
+            theme.label,
+        );
+    }
+    let rows = ticks(
+        y_origin,
+        cell_size,
+        (area.y + RULER_SIZE, area.bottom()),
+        board.length,
+        wraps_around,
+    );
+    for (y, index) in rows {
+        draw_line(
+            area.x + RULER_SIZE * 0.6,
+            y,
+            area.x + RULER_SIZE,
+            y,
+            1.,
+            theme.label,
+        );
+        //Row labels would be hard to read sideways, so they're drawn under their tick, shrunk to fit the ruler
+        let label = index.to_string();
+        let font_size =
+            (14. * RULER_SIZE / measure_text(&label, None, 14, 1.).width.max(RULER_SIZE)).min(14.);
+        draw_text(&label, area.x + 1., y + font_size, font_size, theme.label);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_get_further_apart_when_zooming_out() {
+        assert_eq!(1, tick_step(50.));
+        assert_eq!(5, tick_step(10.));
+        assert_eq!(50, tick_step(1.));
+    }
+
+    #[test]
+    fn ticks_count_from_the_edge_of_the_board() {
+        assert_eq!(
+            vec![(100., 0), (150., 5), (200., 10)],
+            ticks(100., 10., (90., 205.), 12, false)
+        );
+        assert_eq!(
+            vec![(80., 10), (100., 0), (150., 5), (200., 10), (220., 0)],
+            ticks(100., 10., (55., 225.), 12, true)
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::{CellState, GolError};
+
+/// Named rules that can be picked from the menus. Each one contains the name to display, and its rulestring
+pub const PRESETS: [(&str, &str); 7] = [
+    ("Conway's Life", "B3/S23"),
+    ("HighLife", "B36/S23"),
+    ("Seeds", "B2/S"),
+    ("Day & Night", "B3678/S34678"),
+    ("Life without Death", "B3/S012345678"),
+    ("2x2", "B36/S125"),
+    ("Maze", "B3/S12345"),
+];
+
+/// Rules read from rule files when the game starts, with their names, listed in the menus after the presets
+static FILE_RULES: OnceLock<Vec<(String, LifeLikeRule)>> = OnceLock::new();
+
+/// Adds rules to the ones that can be picked from the menus, after the presets. Rules can only be added once, before
+/// any is picked, so the index of each rule stays the same
+/// Input: the rules, with their names
+pub(crate) fn add_file_rules(rules: Vec<(String, LifeLikeRule)>) {
+    let _ = FILE_RULES.set(rules);
+}
+
+/// Returns the rules read from rule files, with their names
+fn file_rules() -> &'static [(String, LifeLikeRule)] {
+    FILE_RULES.get().map_or(&[], Vec::as_slice)
+}
+
+/// Counts the rules that can be picked from the menus: the presets, and the rules read from rule files
+pub(crate) fn rule_count() -> usize {
+    PRESETS.len() + file_rules().len()
+}
+
+/// Returns the name of a rule that can be picked from the menus
+/// Input: the index of the rule, counting the presets first
+pub(crate) fn rule_name(index: usize) -> &'static str {
+    match PRESETS.get(index) {
+        Some((name, _)) => name,
+        None => &file_rules()[index - PRESETS.len()].0,
+    }
+}
+
+/// How many neighbours of a cell were alive and dead on the previous generation
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct NeighborCounts {
+    pub alive: usize,
+    pub dead: usize,
+}
+
+/// How cells change from one generation to the next. Boards follow a life-like rule on their own, and can be stepped
+/// with any other rule through Board::update_board_with
+///
+/// ```
+/// use conways_game_of_life::{Board, CellState, NeighborCounts, Rule};
+///
+/// // Cells are alive when most of their neighbours are, and keep their state on ties
+/// struct Majority;
+///
+/// impl Rule for Majority {
+///     fn next_state(&self, current: CellState, neighbours: &NeighborCounts) -> CellState {
+///         match (current, neighbours.alive > neighbours.dead) {
+///             (_, true) => CellState::Alive,
+///             (CellState::Alive, false) if neighbours.alive == neighbours.dead => CellState::Alive,
+///             _ => CellState::Dead,
+///         }
+///     }
+/// }
+///
+/// let mut board: Board = "
+///     .###
+///     .#..
+///     .##.
+/// "
+/// .parse()
+/// .unwrap();
+/// board.update_board_with(&Majority);
+/// assert_eq!(CellState::Alive, board[(2, 1)]);
+/// assert_eq!(CellState::Dead, board[(3, 2)]);
+/// ```
+pub trait Rule {
+    /// Calculates the next state of a cell
+    /// Input: the current state of the cell, and how many of its neighbours are alive and dead
+    /// Output: the state of the cell on the next generation
+    fn next_state(&self, current: CellState, neighbours: &NeighborCounts) -> CellState;
+}
+
+/// A life-like rule, stating how many alive neighbours make a dead cell be born, or an alive cell survive. Rules are
+/// written as rulestrings, with the counts that give birth after a B and the ones that let a cell survive after an S
+///
+/// ```
+/// use conways_game_of_life::{CellState, LifeLikeRule, NeighborCounts, Rule};
+///
+/// let highlife: LifeLikeRule = "B36/S23".parse().unwrap();
+/// let six_alive = NeighborCounts { alive: 6, dead: 2 };
+/// assert_eq!(CellState::Alive, highlife.next_state(CellState::Dead, &six_alive));
+/// assert_eq!(CellState::Dead, LifeLikeRule::conway().next_state(CellState::Dead, &six_alive));
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LifeLikeRule {
+    birth: [bool; 9],
+    survival: [bool; 9],
+}
+
+impl LifeLikeRule {
+    /// Returns the rule of the standard Game of Life, B3/S23
+    pub fn conway() -> Self {
+        let mut rule = LifeLikeRule {
+            birth: [false; 9],
+            survival: [false; 9],
+        };
+        rule.birth[3] = true;
+        rule.survival[2] = true;
+        rule.survival[3] = true;
+        rule
+    }
+
+    /// Returns the rule of one of the presets, or of the rules read from rule files, which come after them
+    /// Input: the index of the rule, below rule_count
+    /// Output: the parsed rule
+    pub(crate) fn preset(index: usize) -> Self {
+        match PRESETS.get(index) {
+            Some((_, rulestring)) => rulestring.parse().expect("preset rulestrings are valid"),
+            None => file_rules()[index - PRESETS.len()].1,
+        }
+    }
+}
+
+impl Rule for LifeLikeRule {
+    fn next_state(&self, current: CellState, neighbours: &NeighborCounts) -> CellState {
+        let counts = match current {
+            CellState::Alive => &self.survival,
+            CellState::Dead => &self.birth,
+        };
+        //Neighbourhoods bigger than the usual one can have more than 8 alive neighbours, which never match any count
+        if counts.get(neighbours.alive) == Some(&true) {
+            CellState::Alive
+        } else {
+            CellState::Dead
+        }
+    }
+}
+
+impl Default for LifeLikeRule {
+    fn default() -> Self {
+        LifeLikeRule::conway()
+    }
+}
+
+impl FromStr for LifeLikeRule {
+    type Err = GolError;
+
+    /// Parses a rulestring in B/S notation, such as "B3/S23"
+    fn from_str(rulestring: &str) -> Result<Self, Self::Err> {
+        let mut rule = LifeLikeRule {
+            birth: [false; 9],
+            survival: [false; 9],
+        };
+        let (birth, survival) = rulestring.trim().split_once('/').ok_or_else(|| {
+            GolError::InvalidRule(format!("rulestring '{rulestring}' is missing a '/'"))
+        })?;
+
+        for (part, prefix, counts) in [
+            (birth, 'B', &mut rule.birth),
+            (survival, 'S', &mut rule.survival),
+        ] {
+            let digits = part
+                .strip_prefix(prefix)
+                .or_else(|| part.strip_prefix(prefix.to_ascii_lowercase()))
+                .ok_or_else(|| {
+                    GolError::InvalidRule(format!(
+                        "expected '{prefix}' in rulestring '{rulestring}'"
+                    ))
+                })?;
+            for digit in digits.chars() {
+                match digit.to_digit(10) {
+                    Some(count) if count <= 8 => counts[count as usize] = true,
+                    _ => {
+                        return Err(GolError::InvalidRule(format!(
+                            "invalid neighbour count '{digit}' in rulestring '{rulestring}'"
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(rule)
+    }
+}
+
+impl fmt::Display for LifeLikeRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "B")?;
+        for (count, _) in self.birth.iter().enumerate().filter(|(_, born)| **born) {
+            write!(f, "{count}")?;
+        }
+        write!(f, "/S")?;
+        for (count, _) in self.survival.iter().enumerate().filter(|(_, kept)| **kept) {
+            write!(f, "{count}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_presets_parse() {
+        for (_, rulestring) in PRESETS {
+            assert!(rulestring.parse::<LifeLikeRule>().is_ok());
+        }
+    }
+
+    #[test]
+    fn conway_rulestring_parses_to_conway_rule() {
+        assert_eq!(LifeLikeRule::conway(), "B3/S23".parse().unwrap());
+    }
+
+    #[test]
+    fn rule_displays_as_its_rulestring() {
+        assert_eq!("B36/S23", LifeLikeRule::preset(1).to_string());
+        assert_eq!("B2/S", LifeLikeRule::preset(2).to_string());
+    }
+
+    #[test]
+    fn invalid_rulestrings_are_rejected() {
+        assert!("B3S23".parse::<LifeLikeRule>().is_err());
+        assert!("B9/S23".parse::<LifeLikeRule>().is_err());
+        assert!("3/23".parse::<LifeLikeRule>().is_err());
+    }
+}
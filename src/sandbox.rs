@@ -0,0 +1,1053 @@
+use macroquad::{color::*, miniquad::CursorIcon, prelude::*};
+
+use crate::board::{Boundary, CellState};
+use crate::button::{button, row_rect};
+use crate::census::Census;
+use crate::geometry::Point;
+use crate::input::{TRANSPORT_BUTTONS, TransportButton, cell_under, tool_cursor, transport_rect};
+use crate::keys::Action;
+use crate::lessons::Lesson;
+use crate::locale::{tr, tr_with};
+use crate::magnifier::draw_magnifier;
+use crate::palette::Palette;
+use crate::pattern_file::pattern_board;
+use crate::patterns::{LIBRARY, Pattern};
+use crate::render::{
+    BoardCanvas, BoardTexture, CellShape, CellStyle, NextGeneration, draw_board_texture,
+    draw_cell_outlines, draw_census_panel, draw_differences, draw_extinction_summary, draw_grid,
+    draw_hovered_cell, draw_influence, draw_minimap, draw_next_generation, minimap_rect,
+};
+use crate::rulers::draw_rulers;
+use crate::rules::{rule_count, rule_name};
+use crate::settings::Settings;
+use crate::simulation::{SPEEDS, Simulation};
+use crate::text::draw_text;
+use crate::theme::Theme;
+use crate::toast::Toasts;
+use crate::touch::{Gesture, Gestures};
+use crate::tutorial::Tutorial;
+use crate::view::{PAN_STEP, View, ZOOM_STEP, fit_board};
+
+/// Boards of a game started from the starting menu, each one in its own tab, which are played until going back to the
+/// menu
+pub struct Sandbox {
+    pub simulations: Vec<Simulation>,
+    /// Index of the board being shown
+    pub current_simulation: usize,
+    /// Lesson the game was started for, whose notes are shown over the first board
+    lesson: Option<&'static Lesson>,
+    /// Guided tour of the game, when started from the starting menu
+    tutorial: Option<Tutorial>,
+}
+
+/// Tools and panels of the sandbox, and how boards are drawn, which stay as they were left from one game to the next
+#[derive(Default)]
+pub struct Tools {
+    /// Textures of the boards being shown, redrawn when they change
+    board_texture: BoardTexture,
+    comparison_texture: BoardTexture,
+    /// Drawings of the boards being shown, used instead of their textures when cells aren't plain squares
+    board_canvas: BoardCanvas,
+    comparison_canvas: BoardCanvas,
+    split_canvas: BoardCanvas,
+    /// Counts of the objects on the board being shown, and whether the panel with them is open
+    census: Census,
+    show_census: bool,
+    /// Whether a zoomed in window of the cells under the cursor follows it
+    is_magnifying: bool,
+    /// Whether the cells that change on the next generation are highlighted while paused
+    is_previewing: bool,
+    next_generation: NextGeneration,
+    /// Used to know how much the mouse moved when panning by dragging
+    last_mouse_position: (f32, f32),
+    /// Fingers on a touchscreen, and the state cells are painted with while a finger is dragged over them
+    gestures: Gestures,
+    paint_state: Option<CellState>,
+    /// Whether clicking on a cell traces its influence instead of toggling it
+    is_tracing_influence: bool,
+}
+
+/// Where the boards and controls of the sandbox are on a frame, and what the mouse is over
+pub struct Frame {
+    window_width: f32,
+    window_height: f32,
+    /// Size of cells with the board fit in its area, before zooming
+    fit_cell_size: f32,
+    board_area: Rect,
+    /// Area of the comparison board or second view, when there's one
+    side_area: Option<Rect>,
+    /// Size of cells and position of the board in view, as they were when the frame started
+    cell_size: f32,
+    board_origin: (f32, f32),
+    minimap: Rect,
+    is_minimap_shown: bool,
+    /// Area of the buttons at the bottom of the window
+    transport: Rect,
+    mouse_position: (f32, f32),
+    /// Area and view of the side the mouse is over
+    mouse_area: Rect,
+    mouse_view: View,
+    hovered_cell: Option<((isize, isize), Point)>,
+    /// Whether the mouse is over the minimap or the buttons, rather than the boards
+    is_mouse_over_control: bool,
+    /// Cursor showing what clicking does
+    pub cursor: CursorIcon,
+}
+
+impl Sandbox {
+    /// Starts a game on a board
+    /// Input: the first board, the lesson the game is for, if any, and whether the tutorial is shown
+    pub fn new(
+        simulation: Simulation,
+        lesson: Option<&'static Lesson>,
+        show_tutorial: bool,
+    ) -> Self {
+        Sandbox {
+            tutorial: show_tutorial.then(|| Tutorial::new(&simulation)),
+            simulations: vec![simulation],
+            current_simulation: 0,
+            lesson,
+        }
+    }
+
+    /// Switches tabs, opens one with an empty board of the same size and rule, or closes the one in view
+    /// Input: whether control is held down, whether anything follows the first board, which then can't be closed, the
+    /// speed new boards run at, and the toasts telling why a tab wasn't closed
+    pub fn handle_tab_keys(
+        &mut self,
+        is_control_down: bool,
+        is_attached: bool,
+        speed_index: usize,
+        toasts: &mut Toasts,
+    ) {
+        if !is_control_down {
+            return;
+        }
+        let count = self.simulations.len();
+        if is_key_pressed(KeyCode::Tab) {
+            if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) {
+                self.current_simulation = (self.current_simulation + count - 1) % count;
+            } else {
+                self.current_simulation = (self.current_simulation + 1) % count;
+            }
+        }
+        if is_key_pressed(KeyCode::T) {
+            let current = &self.simulations[self.current_simulation];
+            let (board_width, board_height) = current.board_size();
+            let mut simulation = Simulation::new(board_width, board_height, current.rule_index);
+            simulation.board.boundary = current.board.boundary;
+            simulation.speed_index = speed_index;
+            simulation.max_generations = current.max_generations;
+            simulation.enable_background_stepping();
+            self.simulations.push(simulation);
+            self.current_simulation = self.simulations.len() - 1;
+        }
+        if is_key_pressed(KeyCode::W) && self.simulations.len() > 1 {
+            if self.current_simulation == 0 && is_attached {
+                toasts.push(tr("game.tab_attached"));
+            } else {
+                self.simulations.remove(self.current_simulation);
+                self.current_simulation = self.current_simulation.min(self.simulations.len() - 1);
+            }
+        }
+    }
+
+    /// Runs every board, even the ones not being shown
+    /// Input: the current time
+    pub fn update(&mut self, current_time: f64) {
+        for simulation in &mut self.simulations {
+            simulation.update(current_time);
+        }
+    }
+
+    /// Loads boards again from the files they were loaded from when they're edited, carrying on running if they were
+    /// Input: the current time, and the toasts telling what was loaded
+    pub fn reload_pattern_files(&mut self, current_time: f64, toasts: &mut Toasts) {
+        for simulation in &mut self.simulations {
+            let Some(pattern_file) = &mut simulation.pattern_file else {
+                continue;
+            };
+            let path = pattern_file.path.clone();
+            match pattern_file.poll(current_time) {
+                Some(Ok(pattern)) => {
+                    let is_paused = simulation.is_paused;
+                    simulation.reset(pattern_board(&pattern, &simulation.board));
+                    simulation.is_paused = is_paused;
+                    toasts.push(tr_with("game.pattern_reloaded", &[&path.display()]));
+                }
+                Some(Err(error)) => toasts.push(tr_with("game.load_failed", &[&error])),
+                None => {}
+            }
+        }
+    }
+
+    /// Moves the view with the mouse, the keys and touches, edits the board in view while paused, and turns its tools
+    /// on and off
+    /// Input: the tools, the settings with the keys actions are bound to, whether control is held down, which keeps
+    /// the keys that pan for shortcuts, and the toasts telling what changed
+    /// Output: where the boards and controls are on this frame, and what the mouse is over
+    pub fn handle_input(
+        &mut self,
+        tools: &mut Tools,
+        settings: &Settings,
+        is_control_down: bool,
+        toasts: &mut Toasts,
+    ) -> Frame {
+        //Fingers on a touchscreen move the view and edit cells alike, so their gestures are only read once
+        let touch_gestures = tools.gestures.update(&touches());
+        let mut frame = self.move_view(tools, settings, is_control_down, &touch_gestures);
+        self.edit_cells(tools, &frame, touch_gestures);
+        self.handle_tool_keys(tools, settings, &frame, toasts);
+        let simulation = &self.simulations[self.current_simulation];
+        frame.cursor = tool_cursor(
+            is_mouse_button_down(MouseButton::Middle),
+            frame.is_mouse_over_control,
+            frame.hovered_cell.is_some(),
+            simulation.is_paused,
+            tools.is_tracing_influence,
+        );
+        frame
+    }
+
+    /// Fits the boards in the window, and moves the view with the mouse wheel, dragging, the keys that pan, touches
+    /// and clicks on the minimap, easing the camera towards it
+    /// Input: the tools, the settings with the keys actions are bound to, whether control is held down, and the
+    /// gestures of the fingers on a touchscreen
+    /// Output: where the boards and controls are on this frame, and what the mouse is over
+    fn move_view(
+        &mut self,
+        tools: &mut Tools,
+        settings: &Settings,
+        is_control_down: bool,
+        touch_gestures: &[Gesture],
+    ) -> Frame {
+        let simulation = &mut self.simulations[self.current_simulation];
+        let board_size = simulation.board_size();
+        let (board_width, board_height) = board_size;
+
+        //The window can be resized at any time, so the board is fit again on every frame, keeping cells square.
+        //When comparing or splitting the view, each side takes half of the window, so cells need to shrink to fit
+        let window_width = screen_width();
+        let window_height = screen_height();
+        let (fit_cell_size, board_area, side_area) =
+            if simulation.comparison_board.is_some() || simulation.split_view.is_some() {
+                let (board_area, half_cell_size) = fit_board(
+                    board_size,
+                    Rect::new(0., 0., window_width / 2., window_height),
+                );
+                let (side_area, _) = fit_board(
+                    board_size,
+                    Rect::new(window_width / 2., 0., window_width / 2., window_height),
+                );
+                (half_cell_size, board_area, Some(side_area))
+            } else {
+                let (board_area, fit_cell_size) =
+                    fit_board(board_size, Rect::new(0., 0., window_width, window_height));
+                (fit_cell_size, board_area, None)
+            };
+        let cell_size = simulation.camera.cell_size(fit_cell_size);
+        let board_origin = (
+            board_area.x + simulation.camera.offset.0,
+            board_area.y + simulation.camera.offset.1,
+        );
+
+        //Both boards share the view when comparing, so the mouse is taken relative to the one it's over. A split view
+        //has a view of its own on each side, and the mouse moves the one it's over
+        let (mouse_position_x, mouse_position_y) = mouse_position();
+        let is_mouse_on_side = mouse_position_x >= window_width / 2.;
+        let mouse_area = match side_area {
+            Some(side_area) if is_mouse_on_side => side_area,
+            _ => board_area,
+        };
+        let mut mouse_view = match simulation.split_view {
+            Some(split_view) if is_mouse_on_side => split_view,
+            _ => simulation.view,
+        };
+        let mouse_in_area = (
+            mouse_position_x - mouse_area.x,
+            mouse_position_y - mouse_area.y,
+        );
+
+        //Zoom in and out with the mouse wheel, keeping the cell under the cursor in place
+        let (_, mouse_wheel_y) = mouse_wheel();
+        if mouse_wheel_y != 0. {
+            mouse_view.zoom_at(ZOOM_STEP.powf(mouse_wheel_y.signum()), mouse_in_area);
+        }
+        //On touchscreens, two fingers pan and pinch to zoom, while a single one taps or paints cells further down
+        for gesture in touch_gestures {
+            match *gesture {
+                Gesture::Pan(delta) => mouse_view.pan((delta.x, delta.y)),
+                Gesture::Pinch { center, factor } => {
+                    mouse_view.zoom_at(factor, (center.x - mouse_area.x, center.y - mouse_area.y))
+                }
+                Gesture::Tap(_) | Gesture::Paint(_) => {}
+            }
+        }
+        //Dragging moves the board along with the mouse or fingers, so it isn't eased like the rest of the moves
+        let is_dragging = is_mouse_button_down(MouseButton::Middle)
+            || touch_gestures
+                .iter()
+                .any(|gesture| matches!(gesture, Gesture::Pan(_) | Gesture::Pinch { .. }));
+        //Pan by dragging with the middle mouse button, or with WASD
+        if is_mouse_button_down(MouseButton::Middle) {
+            mouse_view.pan((
+                mouse_position_x - tools.last_mouse_position.0,
+                mouse_position_y - tools.last_mouse_position.1,
+            ));
+        }
+        tools.last_mouse_position = (mouse_position_x, mouse_position_y);
+        if !is_control_down {
+            let pan_actions = [
+                (Action::PanUp, (0., PAN_STEP)),
+                (Action::PanLeft, (PAN_STEP, 0.)),
+                (Action::PanDown, (0., -PAN_STEP)),
+                (Action::PanRight, (-PAN_STEP, 0.)),
+            ];
+            for (action, delta) in pan_actions {
+                if settings.keys.is_down(action) {
+                    mouse_view.pan(delta);
+                }
+            }
+        }
+        //When zoomed in, clicking on the minimap jumps to that part of the board
+        let minimap = minimap_rect(&simulation.board, board_area);
+        let is_minimap_shown = simulation.camera.zoom > 1.;
+        let is_mouse_over_minimap =
+            is_minimap_shown && minimap.contains(vec2(mouse_position_x, mouse_position_y));
+        let transport = transport_rect(window_width, window_height);
+        let is_mouse_over_buttons = transport.contains(vec2(mouse_position_x, mouse_position_y));
+        if is_mouse_over_minimap && is_mouse_button_pressed(MouseButton::Left) {
+            let scale = minimap.w / board_width as f32;
+            mouse_view.center_on(
+                (
+                    (mouse_position_x - minimap.x) / scale,
+                    (mouse_position_y - minimap.y) / scale,
+                ),
+                (board_area.w, board_area.h),
+                fit_cell_size,
+            );
+        }
+
+        let fit_board_size = (
+            board_width as f32 * fit_cell_size,
+            board_height as f32 * fit_cell_size,
+        );
+        let wraps_around = simulation.board.boundary == Boundary::Toroidal;
+        mouse_view.constrain(fit_board_size, wraps_around);
+        match &mut simulation.split_view {
+            Some(split_view) if is_mouse_on_side => *split_view = mouse_view,
+            _ => simulation.view = mouse_view,
+        }
+        //The board is drawn with a camera that eases towards the view, unless animations are left out
+        if is_dragging || settings.reduced_motion {
+            simulation.camera = simulation.view;
+        } else {
+            simulation.camera.ease_towards(
+                simulation.view,
+                get_frame_time(),
+                fit_board_size,
+                wraps_around,
+            );
+            simulation.camera.constrain(fit_board_size, wraps_around);
+        }
+        //Cells are picked on the view as it's drawn, even while easing
+        let mouse_view = match simulation.split_view {
+            Some(split_view) if is_mouse_on_side => split_view,
+            _ => simulation.camera,
+        };
+
+        //The cell under the cursor is highlighted, to help placing patterns at specific offsets
+        let hovered_cell = if is_mouse_over_minimap || is_mouse_over_buttons {
+            None
+        } else {
+            cell_under(
+                &simulation.board,
+                &mouse_view,
+                mouse_area,
+                (mouse_position_x, mouse_position_y),
+                fit_cell_size,
+            )
+        };
+
+        Frame {
+            window_width,
+            window_height,
+            fit_cell_size,
+            board_area,
+            side_area,
+            cell_size,
+            board_origin,
+            minimap,
+            is_minimap_shown,
+            transport,
+            mouse_position: (mouse_position_x, mouse_position_y),
+            mouse_area,
+            mouse_view,
+            hovered_cell,
+            is_mouse_over_control: is_mouse_over_minimap || is_mouse_over_buttons,
+            cursor: CursorIcon::Default,
+        }
+    }
+
+    /// Toggles the cells clicked or tapped on the board in view while it's paused, or traces their influence, and
+    /// paints the ones a finger is dragged over
+    /// Input: the tools, where the boards are, and the gestures of the fingers on a touchscreen
+    fn edit_cells(&mut self, tools: &mut Tools, frame: &Frame, touch_gestures: Vec<Gesture>) {
+        let simulation = &mut self.simulations[self.current_simulation];
+        let (minimap, transport, board_area, side_area) = (
+            frame.minimap,
+            frame.transport,
+            frame.board_area,
+            frame.side_area,
+        );
+        let (window_width, fit_cell_size, is_minimap_shown) = (
+            frame.window_width,
+            frame.fit_cell_size,
+            frame.is_minimap_shown,
+        );
+
+        //If the game is paused, I can swap the states of the cells by clicking on them, or trace their influence.
+        //Clicks on the comparison board toggle the same cell on both boards, and clicks around the boards are ignored
+        //Touches press the left mouse button too, but they're only taken once the finger is lifted, as taps
+        if simulation.is_paused
+            && is_mouse_button_pressed(MouseButton::Left)
+            && touches().is_empty()
+            && let Some((_, cell)) = frame.hovered_cell
+        {
+            if tools.is_tracing_influence {
+                simulation.trace_influence(cell);
+            } else {
+                simulation.toggle_cell_state(cell);
+            }
+        }
+        //A tap does what a click does, and dragging a finger paints the cells it goes over with the state the
+        //first one changes to
+        if !tools.gestures.is_painting() {
+            tools.paint_state = None;
+        }
+        for gesture in touch_gestures {
+            let (Gesture::Tap(position) | Gesture::Paint(position)) = gesture else {
+                continue;
+            };
+            let is_over_control =
+                (is_minimap_shown && minimap.contains(position)) || transport.contains(position);
+            let is_on_side = position.x >= window_width / 2.;
+            let area = match side_area {
+                Some(side_area) if is_on_side => side_area,
+                _ => board_area,
+            };
+            let view = match simulation.split_view {
+                Some(split_view) if is_on_side => split_view,
+                _ => simulation.camera,
+            };
+            if !simulation.is_paused || is_over_control {
+                continue;
+            }
+            let Some((_, cell)) = cell_under(
+                &simulation.board,
+                &view,
+                area,
+                (position.x, position.y),
+                fit_cell_size,
+            ) else {
+                continue;
+            };
+            match gesture {
+                Gesture::Tap(_) if tools.is_tracing_influence => simulation.trace_influence(cell),
+                Gesture::Paint(_) if tools.is_tracing_influence => {}
+                Gesture::Paint(_) => {
+                    let state = simulation.board[cell];
+                    let painted_state = *tools.paint_state.get_or_insert(match state {
+                        CellState::Alive => CellState::Dead,
+                        CellState::Dead => CellState::Alive,
+                    });
+                    if state != painted_state {
+                        simulation.toggle_cell_state(cell);
+                    }
+                }
+                _ => simulation.toggle_cell_state(cell),
+            }
+        }
+    }
+
+    /// Turns the tools of the board in view on and off, and changes its speed while it runs
+    /// Input: the tools, where the boards are, the settings with the keys actions are bound to, and the toasts telling
+    /// what changed
+    fn handle_tool_keys(
+        &mut self,
+        tools: &mut Tools,
+        settings: &Settings,
+        frame: &Frame,
+        toasts: &mut Toasts,
+    ) {
+        let simulation = &mut self.simulations[self.current_simulation];
+        let (window_width, window_height) = (frame.window_width, frame.window_height);
+        let (board_area, fit_cell_size) = (frame.board_area, frame.fit_cell_size);
+        let board_size = simulation.board_size();
+        let fit_board_size = (
+            board_size.0 as f32 * fit_cell_size,
+            board_size.1 as f32 * fit_cell_size,
+        );
+        let wraps_around = simulation.board.boundary == Boundary::Toroidal;
+
+        //Swap between toggling cells and tracing their influence when clicking
+        if settings.keys.is_pressed(Action::Influence) {
+            tools.is_tracing_influence = !tools.is_tracing_influence;
+            simulation.influence = None;
+        }
+        //While paused, previous generations can be gone back to
+        if simulation.is_paused && settings.keys.is_pressed(Action::StepBack) {
+            simulation.step_back();
+        }
+
+        if settings.keys.is_pressed(Action::Compare) {
+            simulation.toggle_comparison();
+        }
+        //The second view starts zoomed in on the middle of what the first one shows
+        if settings.keys.is_pressed(Action::Split) {
+            let (split_area, split_cell_size) = fit_board(
+                board_size,
+                Rect::new(window_width / 2., 0., window_width / 2., window_height),
+            );
+            let (x, y, width, height) = simulation
+                .view
+                .visible_cells((board_area.w, board_area.h), fit_cell_size);
+            simulation.toggle_split_view(
+                (x + width / 2., y + height / 2.),
+                (split_area.w, split_area.h),
+                split_cell_size,
+            );
+        }
+        //The view zooms in on the alive cells, with the camera easing there
+        if settings.keys.is_pressed(Action::Focus) {
+            match simulation.board.live_bounds() {
+                Some(bounds) => {
+                    simulation.view.frame(
+                        (
+                            bounds.x as f32,
+                            bounds.y as f32,
+                            bounds.width as f32,
+                            bounds.height as f32,
+                        ),
+                        (board_area.w, board_area.h),
+                        fit_cell_size,
+                    );
+                    simulation.view.constrain(fit_board_size, wraps_around);
+                }
+                None => toasts.push(tr("game.nothing_to_focus")),
+            }
+        }
+        if settings.keys.is_pressed(Action::Diff) {
+            simulation.toggle_diff_view();
+        }
+        if settings.keys.is_pressed(Action::Preview) {
+            tools.is_previewing = !tools.is_previewing;
+        }
+        if settings.keys.is_pressed(Action::Magnifier) {
+            tools.is_magnifying = !tools.is_magnifying;
+        }
+        if settings.keys.is_pressed(Action::Census) {
+            tools.show_census = !tools.show_census;
+        }
+        //Cycle through the built-in patterns to watch for, and back to watching for none
+        if settings.keys.is_pressed(Action::Watch) {
+            let next_index = match &simulation.watch_target {
+                Some((name, _)) => LIBRARY
+                    .iter()
+                    .position(|(pattern_name, _)| pattern_name == name)
+                    .map_or(Some(0), |index| {
+                        Some(index + 1).filter(|index| *index < LIBRARY.len())
+                    }),
+                None => Some(0),
+            };
+            simulation.watch_target = next_index.map(|index| {
+                let name = LIBRARY[index].0;
+                (name.to_string(), Pattern::from_library(name).unwrap())
+            });
+            simulation.found_target = None;
+            toasts.replace(
+                "watch",
+                match &simulation.watch_target {
+                    Some((name, _)) => tr_with("game.watching", &[&name]),
+                    None => tr("game.not_watching").to_string(),
+                },
+            );
+        }
+        //Cycle through the rule presets for the comparison board
+        if simulation.comparison_board.is_some() {
+            if settings.keys.is_pressed(Action::NextRule) {
+                simulation
+                    .set_comparison_rule((simulation.comparison_rule_index + 1) % rule_count());
+            }
+            if settings.keys.is_pressed(Action::PreviousRule) {
+                simulation.set_comparison_rule(
+                    (simulation.comparison_rule_index + rule_count() - 1) % rule_count(),
+                );
+            }
+        }
+        //The speed only changes while the board runs
+        if !simulation.is_paused {
+            if settings.keys.is_pressed(Action::Faster) {
+                if simulation.speed_index < SPEEDS.len() - 1 {
+                    simulation.speed_index += 1;
+                }
+                toasts.replace(
+                    "speed",
+                    tr_with("game.speed", &[&SPEEDS[simulation.speed_index].0]),
+                );
+            }
+
+            if settings.keys.is_pressed(Action::Slower) {
+                simulation.speed_index = simulation.speed_index.saturating_sub(1);
+                toasts.replace(
+                    "speed",
+                    tr_with("game.speed", &[&SPEEDS[simulation.speed_index].0]),
+                );
+            }
+        }
+    }
+
+    /// Draws the board in view, with the comparison board or second view next to it, and everything drawn over them
+    /// Input: the tools, where the boards are, the settings of how they're drawn, the image alive cells are drawn
+    /// with, if any, and the colours they're drawn with
+    pub fn draw(
+        &self,
+        tools: &mut Tools,
+        frame: &Frame,
+        settings: &Settings,
+        sprite: Option<&Texture2D>,
+        theme: &Theme,
+        palette: &Palette,
+    ) {
+        let simulation = &self.simulations[self.current_simulation];
+        let (board_origin, cell_size, board_area) =
+            (frame.board_origin, frame.cell_size, frame.board_area);
+        let (window_width, window_height) = (frame.window_width, frame.window_height);
+        //I draw each cell. Plain square cells are drawn from a texture of the board, which is much faster on big boards,
+        //and other cells are drawn onto a canvas, only the ones that changed
+        clear_background(theme.background);
+        let cell_style = CellStyle {
+            theme,
+            sprite,
+            shape: settings.cell_shape,
+        };
+        let is_texture_drawn = sprite.is_none() && settings.cell_shape == CellShape::Square;
+        let texture = tools.board_texture.update(&simulation.board, theme);
+        if is_texture_drawn {
+            draw_board_texture(
+                &simulation.board,
+                texture,
+                board_origin,
+                cell_size,
+                board_area,
+            );
+        } else {
+            tools.board_canvas.draw(
+                &simulation.board,
+                board_origin,
+                cell_size,
+                board_area,
+                &cell_style,
+            );
+        }
+        if settings.show_grid {
+            draw_grid(
+                &simulation.board,
+                board_origin,
+                cell_size,
+                board_area,
+                theme,
+            );
+        }
+        if settings.cell_outlines {
+            draw_cell_outlines(
+                &simulation.board,
+                board_origin,
+                cell_size,
+                board_area,
+                theme,
+            );
+        }
+        //While paused, the cells about to change can be previewed, without stepping the board
+        if tools.is_previewing && simulation.is_paused {
+            draw_next_generation(
+                &simulation.board,
+                tools.next_generation.update(&simulation.board),
+                board_origin,
+                cell_size,
+                palette,
+            );
+        }
+        if let Some(reference_board) = &simulation.reference_board {
+            draw_differences(
+                &simulation.board,
+                reference_board,
+                board_origin,
+                cell_size,
+                palette,
+            );
+        }
+        if let (Some((_, pattern)), Some(corner)) =
+            (&simulation.watch_target, simulation.found_target)
+        {
+            draw_rectangle_lines(
+                board_origin.0 + (corner.x as f32 - 1.) * cell_size,
+                board_origin.1 + (corner.y as f32 - 1.) * cell_size,
+                (pattern.width + 2) as f32 * cell_size,
+                (pattern.height + 2) as f32 * cell_size,
+                4.,
+                palette.marker,
+            );
+        }
+        if let Some(influence) = &simulation.influence {
+            draw_influence(influence, board_origin, cell_size, palette);
+        }
+        if settings.show_rulers {
+            draw_rulers(
+                &simulation.board,
+                board_origin,
+                cell_size,
+                board_area,
+                theme,
+            );
+        }
+        self.draw_side(tools, frame, settings, &cell_style, is_texture_drawn);
+        if let Some((cell, coordinates)) = frame.hovered_cell {
+            //The coordinates go above the minimap when it's shown, so they don't cover it
+            let bottom_margin = if frame.is_minimap_shown {
+                window_height - frame.minimap.y + 8.
+            } else {
+                window_height / 40.
+            };
+            draw_hovered_cell(
+                cell,
+                coordinates,
+                (
+                    frame.mouse_area.x + frame.mouse_view.offset.0,
+                    frame.mouse_area.y + frame.mouse_view.offset.1,
+                ),
+                frame.mouse_view.cell_size(frame.fit_cell_size),
+                bottom_margin,
+                theme,
+            );
+        }
+        if frame.is_minimap_shown {
+            draw_minimap(
+                &simulation.board,
+                tools.board_texture.update(&simulation.board, theme),
+                &simulation.camera,
+                board_area,
+                frame.fit_cell_size,
+                theme,
+                palette,
+            );
+        }
+        if tools.is_magnifying
+            && let Some((cell, _)) = frame.hovered_cell
+        {
+            draw_magnifier(&simulation.board, cell, frame.mouse_position, theme);
+        }
+        if tools.is_tracing_influence {
+            let trace_text = match &simulation.influence {
+                Some(influence) => tr_with(
+                    if influence.reaches_start {
+                        "game.influence"
+                    } else {
+                        "game.influence_capped"
+                    },
+                    &[&influence.ancestors.len(), &influence.generations_back],
+                ),
+                None => tr("game.trace_hint").to_string(),
+            };
+            draw_text(
+                &trace_text,
+                window_width / 80.,
+                window_height - window_height / 40.,
+                30.,
+                theme.label,
+            );
+        }
+    }
+
+    /// Draws the comparison board, with the rules of both boards under them, or the second view of the board in view,
+    /// on the right half of the window
+    /// Input: the tools, where the boards are, the settings of how they're drawn, how cells are drawn, and whether
+    /// boards are drawn from their textures
+    fn draw_side(
+        &self,
+        tools: &mut Tools,
+        frame: &Frame,
+        settings: &Settings,
+        cell_style: &CellStyle,
+        is_texture_drawn: bool,
+    ) {
+        let simulation = &self.simulations[self.current_simulation];
+        let theme = cell_style.theme;
+        let cell_size = frame.cell_size;
+        let (window_width, window_height) = (frame.window_width, frame.window_height);
+        let comparison_area = frame
+            .side_area
+            .filter(|_| simulation.comparison_board.is_some());
+        let split_area = frame.side_area.filter(|_| simulation.split_view.is_some());
+        if let (Some(comparison_board), Some(comparison_area)) =
+            (&simulation.comparison_board, comparison_area)
+        {
+            let comparison_origin = (
+                comparison_area.x + simulation.camera.offset.0,
+                comparison_area.y + simulation.camera.offset.1,
+            );
+            if is_texture_drawn {
+                draw_board_texture(
+                    comparison_board,
+                    tools.comparison_texture.update(comparison_board, theme),
+                    comparison_origin,
+                    cell_size,
+                    comparison_area,
+                );
+            } else {
+                tools.comparison_canvas.draw(
+                    comparison_board,
+                    comparison_origin,
+                    cell_size,
+                    comparison_area,
+                    cell_style,
+                );
+            }
+            draw_text(
+                rule_name(simulation.rule_index),
+                window_width / 80.,
+                window_height - window_height / 40.,
+                30.,
+                theme.label,
+            );
+            draw_text(
+                rule_name(simulation.comparison_rule_index),
+                comparison_area.x + window_width / 80.,
+                window_height - window_height / 40.,
+                30.,
+                theme.label,
+            );
+        }
+        //The second view shows the same board, with the same cell style, grid, outlines and rulers
+        if let (Some(split_view), Some(split_area)) = (simulation.split_view, split_area) {
+            let split_origin = (
+                split_area.x + split_view.offset.0,
+                split_area.y + split_view.offset.1,
+            );
+            let split_cell_size = split_view.cell_size(frame.fit_cell_size);
+            if is_texture_drawn {
+                draw_board_texture(
+                    &simulation.board,
+                    tools.board_texture.update(&simulation.board, theme),
+                    split_origin,
+                    split_cell_size,
+                    split_area,
+                );
+            } else {
+                tools.split_canvas.draw(
+                    &simulation.board,
+                    split_origin,
+                    split_cell_size,
+                    split_area,
+                    cell_style,
+                );
+            }
+            if settings.show_grid {
+                draw_grid(
+                    &simulation.board,
+                    split_origin,
+                    split_cell_size,
+                    split_area,
+                    theme,
+                );
+            }
+            if settings.cell_outlines {
+                draw_cell_outlines(
+                    &simulation.board,
+                    split_origin,
+                    split_cell_size,
+                    split_area,
+                    theme,
+                );
+            }
+            if settings.show_rulers {
+                draw_rulers(
+                    &simulation.board,
+                    split_origin,
+                    split_cell_size,
+                    split_area,
+                    theme,
+                );
+            }
+            draw_rectangle_lines(
+                split_area.x,
+                split_area.y,
+                split_area.w,
+                split_area.h,
+                2.,
+                theme.label,
+            );
+        }
+    }
+
+    /// Lines always shown in the top right corner: the generation and population, and the open boards when there's
+    /// more than one
+    pub fn corner_lines(&self) -> Vec<String> {
+        let simulation = &self.simulations[self.current_simulation];
+        let mut corner_lines = vec![tr_with(
+            "game.status",
+            &[&simulation.generation, &simulation.board.population()],
+        )];
+        if self.simulations.len() > 1 {
+            corner_lines.push(tr_with(
+                "game.tab",
+                &[&(self.current_simulation + 1), &self.simulations.len()],
+            ));
+        }
+        corner_lines
+    }
+
+    /// Draws the buttons at the bottom of the window, which drive the whole game with the mouse alone. Stepping
+    /// forward or back pauses the game first, as generations are gone through one at a time
+    /// Input: where the buttons are, the colours they're drawn with, and the toasts telling the speed
+    /// Output: the button clicked, if any, pausing and opening the menu being left to the game
+    pub fn update_transport(
+        &mut self,
+        frame: &Frame,
+        theme: &Theme,
+        toasts: &mut Toasts,
+    ) -> Option<TransportButton> {
+        let simulation = &mut self.simulations[self.current_simulation];
+        let mut clicked_button = None;
+        for (index, transport_button) in TRANSPORT_BUTTONS.into_iter().enumerate() {
+            let label = match transport_button {
+                TransportButton::Slower => "-",
+                TransportButton::StepBack => "|<",
+                TransportButton::Pause if simulation.is_paused => tr("game.play"),
+                TransportButton::Pause => tr("game.pause"),
+                TransportButton::Step => ">|",
+                TransportButton::Faster => "+",
+                TransportButton::Menu => tr("game.menu"),
+            };
+            let rect = row_rect(frame.transport, TRANSPORT_BUTTONS.len(), index);
+            if button(label, rect, 20., false, theme) {
+                clicked_button = Some(transport_button);
+            }
+        }
+        match clicked_button {
+            Some(TransportButton::Slower) => {
+                simulation.speed_index = simulation.speed_index.saturating_sub(1);
+                toasts.replace(
+                    "speed",
+                    tr_with("game.speed", &[&SPEEDS[simulation.speed_index].0]),
+                );
+            }
+            Some(TransportButton::Faster) => {
+                simulation.speed_index = (simulation.speed_index + 1).min(SPEEDS.len() - 1);
+                toasts.replace(
+                    "speed",
+                    tr_with("game.speed", &[&SPEEDS[simulation.speed_index].0]),
+                );
+            }
+            Some(TransportButton::StepBack) => {
+                simulation.is_paused = true;
+                simulation.step_back();
+            }
+            Some(TransportButton::Step) => {
+                simulation.is_paused = true;
+                simulation.step();
+            }
+            Some(TransportButton::Pause | TransportButton::Menu) | None => {}
+        }
+        clicked_button
+    }
+
+    /// Draws the state of the board in view, and the lesson, tutorial and census over it, moving the tutorial on
+    /// once its step is done
+    /// Input: the tools, where the boards are, the settings with the keys actions are bound to, and the colours
+    pub fn draw_panels(
+        &mut self,
+        tools: &mut Tools,
+        frame: &Frame,
+        settings: &Settings,
+        theme: &Theme,
+    ) {
+        let simulation = &self.simulations[self.current_simulation];
+        let (window_width, window_height) = (frame.window_width, frame.window_height);
+        if simulation.is_extinct {
+            draw_extinction_summary(
+                simulation,
+                settings.keys.key(Action::Pause),
+                window_width,
+                window_height,
+            );
+        } else if simulation.is_paused && simulation.max_generations == Some(simulation.generation)
+        {
+            draw_text(
+                &tr_with("game.stopped_after", &[&simulation.generation]),
+                window_width / 80.,
+                window_height - window_height / 40.,
+                30.,
+                theme.label,
+            );
+        }
+
+        if simulation.is_paused {
+            draw_text(
+                tr("game.paused"),
+                window_width / 80.,
+                window_height / 15.,
+                42.,
+                theme.faded_text,
+            );
+        } else if simulation.is_throttled() {
+            draw_text(
+                &tr_with(
+                    "game.slowed_down",
+                    &[&format!("{:.0}", simulation.last_step_duration * 1000.)],
+                ),
+                window_width / 80.,
+                window_height / 15. + 30.,
+                24.,
+                RED,
+            );
+        }
+
+        //The lesson is about the first board, so its notes aren't shown on other tabs
+        if let Some(lesson) = self.lesson
+            && self.current_simulation == 0
+        {
+            lesson.draw(simulation.generation, theme);
+        }
+        if let Some(tutorial) = &mut self.tutorial {
+            tutorial.update(simulation);
+            if settings.keys.is_pressed(Action::Tutorial) {
+                tutorial.is_hidden = !tutorial.is_hidden;
+            }
+            tutorial.draw(&settings.keys, theme);
+        }
+
+        //The census is only taken while it's shown, as it goes through the whole board
+        if tools.show_census {
+            tools.census.update(&simulation.board);
+            draw_census_panel(&tools.census, theme);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_boards_are_only_counted_with_more_than_one() {
+        let mut sandbox = Sandbox::new(Simulation::new(10, 10, 0), None, false);
+        assert_eq!(1, sandbox.corner_lines().len());
+
+        sandbox.simulations.push(Simulation::new(10, 10, 0));
+        sandbox.current_simulation = 1;
+        let lines = sandbox.corner_lines();
+        assert_eq!(2, lines.len());
+        assert!(lines[1].contains("2/2"), "{}", lines[1]);
+    }
+}
@@ -0,0 +1,235 @@
+use macroquad::prelude::*;
+
+use crate::locale::{tr, tr_with};
+use crate::theme::Theme;
+use crate::tutorial::draw_banner;
+
+/// Grains a cell holds when it topples, giving one to each of its four neighbours
+const TOPPLING_GRAINS: u32 = 4;
+
+/// Grains dropped on the centre cell with C, enough to grow the fractal of the sandpile
+const CENTRE_PILE: u32 = 1 << 14;
+
+/// Most cells toppled on each frame, so big piles settle over a while and the window keeps responding
+const TOPPLINGS_PER_FRAME: usize = 20_000;
+
+/// Colours of the cells holding 0 to 3 grains. Cells about to topple are drawn in white
+const SAND_COLORS: [Color; 4] = [
+    Color::new(0.05, 0.05, 0.2, 1.),
+    Color::new(0.15, 0.45, 0.85, 1.),
+    Color::new(0.95, 0.8, 0.2, 1.),
+    Color::new(0.85, 0.2, 0.2, 1.),
+];
+
+/// Space left above the grid for the banner with the grains, in pixels
+const BANNER_MARGIN: f32 = 100.;
+
+/// A grid of cells holding grains of sand, where cells with TOPPLING_GRAINS or more topple onto their neighbours.
+/// Grains toppled past the edges are lost, so every pile settles in the end, and how it settles doesn't depend on the
+/// order cells topple in
+pub struct Sandpile {
+    width: usize,
+    length: usize,
+    /// Grains each cell holds, row by row
+    grains: Vec<u32>,
+    /// Cells holding TOPPLING_GRAINS or more grains, each one once
+    unstable: Vec<usize>,
+    /// Times cells toppled since the grid was emptied
+    pub topplings: u64,
+}
+
+impl Sandpile {
+    /// Returns an empty grid
+    pub fn new(width: usize, length: usize) -> Self {
+        Sandpile {
+            width,
+            length,
+            grains: vec![0; width * length],
+            unstable: Vec::new(),
+            topplings: 0,
+        }
+    }
+
+    /// Adds grains to a cell, which topple on the next calls to settle
+    pub fn drop_grains(&mut self, x: usize, y: usize, grains: u32) {
+        self.add(y * self.width + x, grains);
+    }
+
+    /// Adds grains to a cell, keeping track of it if it gets to toppling
+    fn add(&mut self, index: usize, grains: u32) {
+        let before = self.grains[index];
+        self.grains[index] += grains;
+        if before < TOPPLING_GRAINS && self.grains[index] >= TOPPLING_GRAINS {
+            self.unstable.push(index);
+        }
+    }
+
+    /// Counts the grains on the grid
+    pub fn total_grains(&self) -> u64 {
+        self.grains.iter().map(|grains| *grains as u64).sum()
+    }
+
+    /// Whether no cell has to topple
+    pub fn is_stable(&self) -> bool {
+        self.unstable.is_empty()
+    }
+
+    /// Topples cells until the grid is stable or enough cells were toppled. A cell topples every TOPPLING_GRAINS
+    /// grains it holds at once
+    /// Input: the most cells to topple
+    pub fn settle(&mut self, max_topplings: usize) {
+        for _ in 0..max_topplings {
+            let Some(index) = self.unstable.pop() else {
+                return;
+            };
+            let given = self.grains[index] / TOPPLING_GRAINS;
+            self.grains[index] %= TOPPLING_GRAINS;
+            self.topplings += 1;
+            let (x, y) = (index % self.width, index / self.width);
+            if x > 0 {
+                self.add(index - 1, given);
+            }
+            if x + 1 < self.width {
+                self.add(index + 1, given);
+            }
+            if y > 0 {
+                self.add(index - self.width, given);
+            }
+            if y + 1 < self.length {
+                self.add(index + self.width, given);
+            }
+        }
+    }
+
+    /// Draws every cell on an image with one pixel per cell, with the colour of its grains
+    /// Input: the image, as big as the grid
+    fn paint(&self, image: &mut Image) {
+        for (index, grains) in self.grains.iter().enumerate() {
+            let color = SAND_COLORS.get(*grains as usize).copied().unwrap_or(WHITE);
+            image.set_pixel(
+                (index % self.width) as u32,
+                (index / self.width) as u32,
+                color,
+            );
+        }
+    }
+}
+
+/// Runs a sandpile on the window until Escape goes back to the starting menu. Clicking, or holding the button down,
+/// drops grains on a cell, C drops a big pile on the centre, and Enter empties the grid
+/// Input: the width and length of the grid, and the theme to draw with
+/// Output: whether closing the window was asked for
+pub async fn run_sandpile((width, length): (usize, usize), theme: &Theme) -> bool {
+    let mut sandpile = Sandpile::new(width, length);
+    let mut image = Image::gen_image_color(width as u16, length as u16, SAND_COLORS[0]);
+    let texture = Texture2D::from_image(&image);
+    texture.set_filter(FilterMode::Nearest);
+    loop {
+        if is_quit_requested() {
+            return true;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            return false;
+        }
+        if is_key_pressed(KeyCode::Enter) {
+            sandpile = Sandpile::new(width, length);
+        }
+        if is_key_pressed(KeyCode::C) {
+            sandpile.drop_grains(width / 2, length / 2, CENTRE_PILE);
+        }
+
+        //The grid is as big as fits under the banner, centred
+        let cell_size = (screen_width() / width as f32)
+            .min((screen_height() - BANNER_MARGIN - 30.) / length as f32);
+        let corner = (
+            (screen_width() - cell_size * width as f32) / 2.,
+            BANNER_MARGIN
+                + (screen_height() - BANNER_MARGIN - 30. - cell_size * length as f32) / 2.,
+        );
+        if is_mouse_button_down(MouseButton::Left) {
+            let (mouse_x, mouse_y) = mouse_position();
+            let (x, y) = (
+                ((mouse_x - corner.0) / cell_size).floor(),
+                ((mouse_y - corner.1) / cell_size).floor(),
+            );
+            if x >= 0. && y >= 0. && (x as usize) < width && (y as usize) < length {
+                sandpile.drop_grains(x as usize, y as usize, 1);
+            }
+        }
+        sandpile.settle(TOPPLINGS_PER_FRAME);
+
+        clear_background(theme.background);
+        sandpile.paint(&mut image);
+        texture.update(&image);
+        draw_texture_ex(
+            &texture,
+            corner.0,
+            corner.1,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(cell_size * width as f32, cell_size * length as f32)),
+                ..Default::default()
+            },
+        );
+        let key = if sandpile.is_stable() {
+            "sandpile.stable"
+        } else {
+            "sandpile.toppling"
+        };
+        draw_banner(
+            &tr_with(key, &[&sandpile.total_grains(), &sandpile.topplings]),
+            theme,
+        );
+        draw_text(
+            tr("sandpile.hint"),
+            20.,
+            screen_height() - 10.,
+            18.,
+            theme.text,
+        );
+        next_frame().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn piles_topple_the_same_whatever_the_order() {
+        let mut sandpile = Sandpile::new(3, 3);
+        sandpile.drop_grains(1, 1, 3);
+        sandpile.settle(10);
+        assert!(sandpile.is_stable());
+        assert_eq!(0, sandpile.topplings);
+        sandpile.drop_grains(1, 1, 1);
+        assert!(!sandpile.is_stable());
+        sandpile.settle(10);
+        assert_eq!(vec![0, 1, 0, 1, 0, 1, 0, 1, 0], sandpile.grains);
+        assert_eq!(4, sandpile.total_grains());
+
+        //Grains toppled past the edges are lost
+        sandpile.drop_grains(0, 0, 4);
+        sandpile.settle(10);
+        assert_eq!(6, sandpile.total_grains());
+
+        //Dropping the grains one by one or all at once settles the same way
+        let mut one_by_one = Sandpile::new(9, 9);
+        for _ in 0..200 {
+            one_by_one.drop_grains(4, 4, 1);
+            one_by_one.settle(usize::MAX);
+        }
+        let mut at_once = Sandpile::new(9, 9);
+        at_once.drop_grains(4, 4, 200);
+        at_once.settle(1);
+        assert!(!at_once.is_stable());
+        at_once.settle(usize::MAX);
+        assert_eq!(one_by_one.grains, at_once.grains);
+        assert!(
+            at_once
+                .grains
+                .iter()
+                .all(|grains| *grains < TOPPLING_GRAINS)
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use rhai::{AST, Dynamic, Engine, EvalAltResult, Scope};
+
+use crate::patterns::Pattern;
+use crate::{Board, CellState};
+
+/// Most operations a script can take on a generation, so a script stuck in a loop stops instead of freezing the game
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// What scripts can reach while they run: the board, and what they've done to it
+struct ScriptContext {
+    /// Board being scripted, swapped in only while the scripts run
+    board: Board,
+    /// Whether a script changed any cell
+    is_changed: bool,
+    /// Values scripts keep track of, by name, in the order they were first given
+    metrics: Vec<(String, String)>,
+}
+
+/// A script, kept with its variables, so they carry on from one generation to the next
+struct Script {
+    name: String,
+    ast: AST,
+    scope: Scope<'static>,
+}
+
+/// Runs Rhai scripts on a board after each generation, so users can spawn patterns or collect metrics of their own.
+/// Each script is run whole every generation, with `generation`, `width`, `height` and `population` set, and can call:
+/// - `is_alive(x, y)`, to check a cell
+/// - `set_alive(x, y, alive)`, to change a cell
+/// - `place(name, x, y)`, to bring the alive cells of a pattern of the library to life, with its top left corner on a
+///   cell
+/// - `metric(name, value)`, to show a value in the corner of the window
+///
+/// Coordinates wrap around on toroidal boards, and cells past the edges of bounded boards are dead and can't be changed
+pub struct ScriptHost {
+    engine: Engine,
+    scripts: Vec<Script>,
+    context: Rc<RefCell<ScriptContext>>,
+    /// Generation the scripts were last run on
+    last_generation: Option<usize>,
+}
+
+impl ScriptHost {
+    /// Compiles every .rhai file of a directory, in order of their names. Scripts that don't compile are left out
+    /// Input: the directory, which doesn't have to exist
+    /// Output: the host, and a message for each script that couldn't be read or compiled
+    pub fn load(directory: &Path) -> (Self, Vec<String>) {
+        let mut paths: Vec<_> = match fs::read_dir(directory) {
+            Ok(entries) => entries
+                .filter_map(|entry| Some(entry.ok()?.path()))
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|extension| extension == "rhai")
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        paths.sort();
+        let mut errors = Vec::new();
+        let sources = paths.into_iter().filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            match fs::read_to_string(&path) {
+                Ok(source) => Some((name, source)),
+                Err(error) => {
+                    errors.push(format!("{}: {error}", path.display()));
+                    None
+                }
+            }
+        });
+        let sources: Vec<_> = sources.collect();
+        let (host, compile_errors) = Self::from_sources(sources);
+        errors.extend(compile_errors);
+        (host, errors)
+    }
+
+    /// Compiles scripts given as text
+    /// Input: the name and source of each script
+    /// Output: the host, and a message for each script that couldn't be compiled
+    fn from_sources(sources: Vec<(String, String)>) -> (Self, Vec<String>) {
+        let context = Rc::new(RefCell::new(ScriptContext {
+            board: Board::new(1, 1),
+            is_changed: false,
+            metrics: Vec::new(),
+        }));
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        register_functions(&mut engine, &context);
+
+        let mut errors = Vec::new();
+        let scripts = sources
+            .into_iter()
+            .filter_map(|(name, source)| match engine.compile(&source) {
+                Ok(ast) => Some(Script {
+                    name,
+                    ast,
+                    scope: Scope::new(),
+                }),
+                Err(error) => {
+                    errors.push(format!("{name}: {error}"));
+                    None
+                }
+            })
+            .collect();
+        let host = ScriptHost {
+            engine,
+            scripts,
+            context,
+            last_generation: None,
+        };
+        (host, errors)
+    }
+
+    /// Checks whether there are any scripts to run
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    /// Runs every script on a generation of a board, unless they already ran on it. Scripts that fail are stopped, so
+    /// they don't fail again on every generation
+    /// Input: the board, and its generation
+    /// Output: whether any cell was changed, and a message for each script that failed
+    pub fn run(&mut self, board: &mut Board, generation: usize) -> (bool, Vec<String>) {
+        if self.last_generation == Some(generation) {
+            return (false, Vec::new());
+        }
+        self.last_generation = Some(generation);
+
+        std::mem::swap(board, &mut self.context.borrow_mut().board);
+        self.context.borrow_mut().is_changed = false;
+        let mut errors = Vec::new();
+        let engine = &self.engine;
+        let context = &self.context;
+        self.scripts.retain_mut(|script| {
+            let (width, height, population) = {
+                let board = &context.borrow().board;
+                (board.width(), board.length(), board.population())
+            };
+            script.scope.set_value("generation", generation as i64);
+            script.scope.set_value("width", width as i64);
+            script.scope.set_value("height", height as i64);
+            script.scope.set_value("population", population as i64);
+            match engine.run_ast_with_scope(&mut script.scope, &script.ast) {
+                Ok(()) => true,
+                Err(error) => {
+                    errors.push(format!("{}: {error}", script.name));
+                    false
+                }
+            }
+        });
+        std::mem::swap(board, &mut self.context.borrow_mut().board);
+        (self.context.borrow().is_changed, errors)
+    }
+
+    /// Returns the values scripts keep track of, with their names
+    pub fn metrics(&self) -> Vec<(String, String)> {
+        self.context.borrow().metrics.clone()
+    }
+}
+
+/// Makes the functions scripts call to reach the board available to them
+/// Input: the engine running the scripts, and what the functions act on
+fn register_functions(engine: &mut Engine, context: &Rc<RefCell<ScriptContext>>) {
+    let reader = Rc::clone(context);
+    engine.register_fn("is_alive", move |x: i64, y: i64| {
+        let board = &reader.borrow().board;
+        board
+            .wrap_coordinates(x as isize, y as isize)
+            .is_some_and(|cell| board[cell] == CellState::Alive)
+    });
+
+    let writer = Rc::clone(context);
+    engine.register_fn("set_alive", move |x: i64, y: i64, is_alive: bool| {
+        let context = &mut *writer.borrow_mut();
+        let state = if is_alive {
+            CellState::Alive
+        } else {
+            CellState::Dead
+        };
+        if let Some(cell) = context.board.wrap_coordinates(x as isize, y as isize)
+            && context.board[cell] != state
+        {
+            context.board[cell] = state;
+            context.is_changed = true;
+        }
+    });
+
+    let placer = Rc::clone(context);
+    engine.register_fn(
+        "place",
+        move |name: &str, x: i64, y: i64| -> Result<(), Box<EvalAltResult>> {
+            let pattern = Pattern::from_library(name)
+                .ok_or_else(|| format!("there's no pattern called '{name}'"))?;
+            let context = &mut *placer.borrow_mut();
+            let alive_cells = (0..pattern.height)
+                .flat_map(|pattern_y| {
+                    (0..pattern.width).map(move |pattern_x| (pattern_x, pattern_y))
+                })
+                .filter(|&(pattern_x, pattern_y)| {
+                    pattern.get((pattern_x, pattern_y)) == CellState::Alive
+                });
+            for (pattern_x, pattern_y) in alive_cells {
+                //Cells past the largest coordinates are off the board, as set_alive takes them
+                let (Some(cell_x), Some(cell_y)) = (
+                    x.checked_add(pattern_x as i64),
+                    y.checked_add(pattern_y as i64),
+                ) else {
+                    continue;
+                };
+                if let Some(cell) = context
+                    .board
+                    .wrap_coordinates(cell_x as isize, cell_y as isize)
+                {
+                    context.board[cell] = CellState::Alive;
+                    context.is_changed = true;
+                }
+            }
+            Ok(())
+        },
+    );
+
+    let recorder = Rc::clone(context);
+    engine.register_fn("metric", move |name: &str, value: Dynamic| {
+        let metrics = &mut recorder.borrow_mut().metrics;
+        let value = value.to_string();
+        match metrics.iter_mut().find(|(metric, _)| metric == name) {
+            Some((_, old_value)) => *old_value = value,
+            None => metrics.push((name.to_string(), value)),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_change_the_board_and_keep_metrics() {
+        let (mut host, errors) = ScriptHost::from_sources(vec![
+            (
+                "placer".to_string(),
+                "if generation == 1 { place(\"Glider\", 2, 2); } metric(\"cells\", population);"
+                    .to_string(),
+            ),
+            ("broken".to_string(), "let = 3;".to_string()),
+            ("failing".to_string(), "place(\"Nothing\", 0, 0);".to_string()),
+            (
+                "counter".to_string(),
+                "if generation == 1 { set_alive(0, 0, true); } else if is_alive(0, 0) { set_alive(0, 0, false); }"
+                    .to_string(),
+            ),
+        ]);
+        assert_eq!(1, errors.len());
+        let mut board = Board::new(10, 10);
+
+        let (is_changed, errors) = host.run(&mut board, 1);
+        assert!(is_changed);
+        assert_eq!(1, errors.len());
+        assert_eq!(6, board.population());
+        assert_eq!(vec![("cells".to_string(), "0".to_string())], host.metrics());
+        //Scripts run once per generation
+        assert_eq!((false, Vec::new()), host.run(&mut board, 1));
+
+        let (is_changed, errors) = host.run(&mut board, 2);
+        assert!(is_changed && errors.is_empty());
+        assert_eq!(5, board.population());
+        assert_eq!(vec![("cells".to_string(), "6".to_string())], host.metrics());
+    }
+
+    #[test]
+    fn patterns_placed_past_the_largest_coordinates_are_left_out() {
+        let (mut host, errors) = ScriptHost::from_sources(vec![(
+            "far".to_string(),
+            "place(\"Glider\", 9223372036854775807, 9223372036854775806);".to_string(),
+        )]);
+        assert!(errors.is_empty());
+        let mut board = Board::new(10, 10);
+        assert_eq!((false, Vec::new()), host.run(&mut board, 1));
+        assert_eq!(0, board.population());
+    }
+}
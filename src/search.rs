@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+use std::thread;
+
+use crate::census::Census;
+use crate::formats::{PatternMeta, write_rle};
+use crate::random::{RandomSource, SplitMix64};
+use crate::{Board, CellState, GolError, Pattern};
+
+/// Width and height of the soups evolved, in cells
+const SOUP_SIDE: usize = 16;
+
+/// Width and height of the board each soup is run on, in its centre, leaving room for it to grow and send out gliders
+const SEARCH_BOARD_SIDE: usize = 96;
+
+/// Soups in each round, and how many of the best ones are kept as they are for the next round
+const POPULATION: usize = 48;
+const ELITES: usize = 8;
+
+/// Rounds of evolution in a search
+const ROUNDS: usize = 30;
+
+/// Chance of each cell of a soup flipping when it's copied into the next round
+const MUTATION_RATE: f64 = 0.02;
+
+/// Share of the cells of the soups of the first round that are alive
+const INITIAL_DENSITY: f64 = 0.4;
+
+/// Generations between the censuses that count the gliders sent out
+const CENSUS_INTERVAL: usize = 50;
+
+/// Points given for each cell of the largest population reached, and for each glider counted
+const GROWTH_WEIGHT: f64 = 1.;
+const GLIDER_WEIGHT: f64 = 100.;
+
+/// Soups written to the search directory once it's over, the best ones
+const SAVED_FINDS: usize = 5;
+
+/// How a soup did once run on its own
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Fitness {
+    /// Generations until the board settled into a cycle, or until they ran out
+    pub longevity: usize,
+    /// Largest population the board reached
+    pub peak_population: usize,
+    /// Most gliders counted at once on the board
+    pub gliders: usize,
+}
+
+impl Fitness {
+    /// Returns the score soups are picked by: a generation for every one the board lasts, plus GROWTH_WEIGHT for each
+    /// cell of its peak population and GLIDER_WEIGHT for each glider
+    pub fn score(&self) -> f64 {
+        self.longevity as f64
+            + GROWTH_WEIGHT * self.peak_population as f64
+            + GLIDER_WEIGHT * self.gliders as f64
+    }
+}
+
+/// Runs a soup on its own in the centre of an empty board with bounded edges, until the board comes back to an earlier
+/// generation or the generations run out
+/// Input: the cells of the soup, row by row, and the most generations to run it for
+/// Output: how the soup did
+pub fn evaluate(soup: &[CellState], max_generations: usize) -> Fitness {
+    let mut board = Board::new(SEARCH_BOARD_SIDE, SEARCH_BOARD_SIDE);
+    let pattern = Pattern {
+        width: SOUP_SIDE,
+        height: SOUP_SIDE,
+        cells: soup.to_vec(),
+    };
+    let corner = (SEARCH_BOARD_SIDE - SOUP_SIDE) / 2;
+    board.paste(&pattern, (corner, corner));
+    let mut census = Census::default();
+    let mut fitness = Fitness {
+        peak_population: board.population(),
+        ..Fitness::default()
+    };
+    //Generation each board was first seen on, by the hash of its cells
+    let mut seen = HashMap::new();
+    for generation in 0..max_generations {
+        let mut hasher = DefaultHasher::new();
+        board.board.hash(&mut hasher);
+        if let Some(first_seen) = seen.insert(hasher.finish(), generation) {
+            //Boards that cycle only count until the cycle began
+            fitness.longevity = first_seen;
+            break;
+        }
+        if generation % CENSUS_INTERVAL == 0 {
+            census.update(&board);
+            fitness.gliders = fitness.gliders.max(census.count("object.glider"));
+        }
+        board.update_board();
+        fitness.longevity = generation + 1;
+        fitness.peak_population = fitness.peak_population.max(board.population());
+    }
+    fitness
+}
+
+/// A genetic algorithm evolving soups towards ones that last long, grow big and send out gliders. Each round, every
+/// soup is run across threads, the ELITES best ones are kept, and the rest of the next round are made by crossing over
+/// and mutating soups picked in tournaments
+pub struct Search {
+    /// Soups of the current round, with how they did once run, best first after a round
+    soups: Vec<(Vec<CellState>, Fitness)>,
+    random: SplitMix64,
+    max_generations: usize,
+    pub round: usize,
+}
+
+impl Search {
+    /// Starts a search with random soups
+    /// Input: the seed of the random numbers, and the most generations each soup is run for
+    pub fn new(seed: u64, max_generations: usize) -> Self {
+        let mut random = SplitMix64::new(seed);
+        let soups = (0..POPULATION)
+            .map(|_| {
+                let soup = (0..SOUP_SIDE * SOUP_SIDE)
+                    .map(|_| {
+                        if random.next_f64() < INITIAL_DENSITY {
+                            CellState::Alive
+                        } else {
+                            CellState::Dead
+                        }
+                    })
+                    .collect();
+                (soup, Fitness::default())
+            })
+            .collect();
+        Search {
+            soups,
+            random,
+            max_generations,
+            round: 0,
+        }
+    }
+
+    /// Runs every soup of the round, spread over as many threads as the computer runs at once, and sorts them best
+    /// first
+    fn evaluate_all(&mut self) {
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+        let chunk_size = self.soups.len().div_ceil(threads);
+        let max_generations = self.max_generations;
+        thread::scope(|scope| {
+            for chunk in self.soups.chunks_mut(chunk_size) {
+                scope.spawn(move || {
+                    for (soup, fitness) in chunk {
+                        *fitness = evaluate(soup, max_generations);
+                    }
+                });
+            }
+        });
+        self.soups
+            .sort_by(|(_, first), (_, second)| second.score().total_cmp(&first.score()));
+    }
+
+    /// Picks the better of two soups at random, out of the current round
+    fn tournament(&mut self) -> &[CellState] {
+        let first = (self.random.next_u64() % self.soups.len() as u64) as usize;
+        let second = (self.random.next_u64() % self.soups.len() as u64) as usize;
+        //Soups are sorted best first
+        &self.soups[first.min(second)].0
+    }
+
+    /// Runs a round: runs every soup, then replaces the round with the best ones and their offspring
+    /// Output: the best soup of the round, with how it did
+    pub fn run_round(&mut self) -> (Vec<CellState>, Fitness) {
+        self.evaluate_all();
+        let best = self.soups[0].clone();
+        let mut next: Vec<_> = self.soups[..ELITES].to_vec();
+        while next.len() < POPULATION {
+            let first = self.tournament().to_vec();
+            let second = self.tournament().to_vec();
+            //Each row of the child comes from one parent or the other, and then cells flip at random
+            let mut child = Vec::with_capacity(first.len());
+            for (first_row, second_row) in first.chunks(SOUP_SIDE).zip(second.chunks(SOUP_SIDE)) {
+                let row = if self.random.next_f64() < 0.5 {
+                    first_row
+                } else {
+                    second_row
+                };
+                child.extend_from_slice(row);
+            }
+            for cell in &mut child {
+                if self.random.next_f64() < MUTATION_RATE {
+                    *cell = match cell {
+                        CellState::Alive => CellState::Dead,
+                        CellState::Dead => CellState::Alive,
+                    };
+                }
+            }
+            next.push((child, Fitness::default()));
+        }
+        self.soups = next;
+        self.round += 1;
+        best
+    }
+
+    /// Runs the soups of the round a last time and returns the best ones, best first
+    /// Input: how many soups to return
+    pub fn finds(&mut self, count: usize) -> Vec<(Vec<CellState>, Fitness)> {
+        self.evaluate_all();
+        self.soups.iter().take(count).cloned().collect()
+    }
+}
+
+/// Writes a soup to an RLE file, with how it did in its comments
+/// Input: the soup, how it did, and the path of the file
+pub fn save_find(soup: &[CellState], fitness: &Fitness, path: &Path) -> Result<(), GolError> {
+    let pattern = Pattern {
+        width: SOUP_SIDE,
+        height: SOUP_SIDE,
+        cells: soup.to_vec(),
+    };
+    let meta = PatternMeta {
+        name: path
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string()),
+        comments: vec![format!(
+            "Lasts {} generations, peaks at {} cells and sends out {} gliders",
+            fitness.longevity, fitness.peak_population, fitness.gliders
+        )],
+        rule: Some("B3/S23".to_string()),
+        ..PatternMeta::default()
+    };
+    fs::write(path, write_rle(&pattern, &meta)).map_err(|source| GolError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Runs a search without a window, printing the best soup of each round, then writes the best soups found as RLE files
+/// to a directory
+/// Input: the directory, created if needed, and the most generations each soup is run for
+/// Output: whether the finds were written, or what kept one from being written
+pub fn run_search(directory: &Path, max_generations: usize) -> Result<(), GolError> {
+    fs::create_dir_all(directory).map_err(|source| GolError::Io {
+        path: directory.to_path_buf(),
+        source,
+    })?;
+    let seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |time| time.as_nanos() as u64);
+    let mut search = Search::new(seed, max_generations);
+    for _ in 0..ROUNDS {
+        let (_, fitness) = search.run_round();
+        println!(
+            "Round {}: best soup scores {:.0}, lasting {} generations, peaking at {} cells, with {} gliders",
+            search.round,
+            fitness.score(),
+            fitness.longevity,
+            fitness.peak_population,
+            fitness.gliders
+        );
+    }
+    for (index, (soup, fitness)) in search.finds(SAVED_FINDS).iter().enumerate() {
+        let path = directory.join(format!("find_{}.rle", index + 1));
+        save_find(soup, fitness, &path)?;
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn soups_are_scored_and_evolved_towards_better_ones() {
+        let mut soup = vec![CellState::Dead; SOUP_SIDE * SOUP_SIDE];
+        assert_eq!(0., evaluate(&soup, 100).score());
+        //A blinker cycles from the start
+        for x in 4..7 {
+            soup[5 * SOUP_SIDE + x] = CellState::Alive;
+        }
+        let blinker = evaluate(&soup, 100);
+        assert_eq!(0, blinker.longevity);
+        assert_eq!(3, blinker.peak_population);
+        //A glider is counted, and keeps the board changing until the generations run out
+        let glider = Pattern::from_library("Glider").unwrap();
+        let mut soup = vec![CellState::Dead; SOUP_SIDE * SOUP_SIDE];
+        for (index, cell) in glider.cells.iter().enumerate() {
+            soup[(index / glider.width) * SOUP_SIDE + index % glider.width] = *cell;
+        }
+        let glider = evaluate(&soup, 60);
+        assert_eq!(1, glider.gliders);
+        assert_eq!(60, glider.longevity);
+
+        //The best soups are kept from round to round, so the best score never drops
+        let mut search = Search::new(1, 5);
+        let first = search.run_round().1.score();
+        let second = search.run_round().1.score();
+        assert!(second >= first);
+        assert_eq!(POPULATION, search.soups.len());
+
+        let (soup, fitness) = search.finds(1).remove(0);
+        let path = std::env::temp_dir().join(format!("find_{}.rle", std::process::id()));
+        save_find(&soup, &fitness, &path).unwrap();
+        let saved = Pattern::load(&path).unwrap();
+        assert_eq!(
+            soup.iter()
+                .filter(|cell| **cell == CellState::Alive)
+                .count(),
+            saved
+                .cells
+                .iter()
+                .filter(|cell| **cell == CellState::Alive)
+                .count()
+        );
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Board, Boundary, LifeLikeRule, Pattern};
+
+/// How a board is written: its rows of # and ., as it's displayed, with the rule and edges it follows. The previous
+/// generation the board keeps isn't part of it, and neither is its neighbourhood, as it can be any type, so boards
+/// are read back with the usual one
+#[derive(Serialize, Deserialize)]
+struct BoardData {
+    rows: Vec<String>,
+    #[serde(default)]
+    rule: LifeLikeRule,
+    #[serde(default = "bounded")]
+    boundary: Boundary,
+}
+
+/// Returns the boundary boards have when it's left out
+fn bounded() -> Boundary {
+    Boundary::Bounded
+}
+
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BoardData {
+            rows: self.to_string().lines().map(str::to_string).collect(),
+            rule: self.rule,
+            boundary: self.boundary,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = BoardData::deserialize(deserializer)?;
+        let mut board: Board = data.rows.join("\n").parse().map_err(D::Error::custom)?;
+        board.rule = data.rule;
+        board.boundary = data.boundary;
+        Ok(board)
+    }
+}
+
+/// Rules are written as their rulestring, such as "B3/S23"
+impl Serialize for LifeLikeRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LifeLikeRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Patterns are written in plaintext format, the same as in pattern files
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_plaintext())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Pattern::parse_plaintext(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellState, StepStats};
+
+    #[test]
+    fn boards_are_written_as_rows_with_their_rule_and_edges() {
+        let mut board: Board = ".#.\n#.#".parse().unwrap();
+        board.rule = "B36/S23".parse().unwrap();
+        board.boundary = Boundary::Toroidal;
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(
+            r##"{"rows":[".#.","#.#"],"rule":"B36/S23","boundary":"toroidal"}"##,
+            json
+        );
+
+        let read: Board = serde_json::from_str(&json).unwrap();
+        assert_eq!(board, read);
+        assert_eq!(board.rule, read.rule);
+        assert_eq!(board.boundary, read.boundary);
+        let read: Board = serde_json::from_str(r##"{"rows":["#."]}"##).unwrap();
+        assert_eq!(
+            (LifeLikeRule::conway(), Boundary::Bounded),
+            (read.rule, read.boundary)
+        );
+        assert!(serde_json::from_str::<Board>(r##"{"rows":["#.","#"]}"##).is_err());
+    }
+
+    #[test]
+    fn cells_rules_and_patterns_read_back_as_written() {
+        let stats = StepStats {
+            births: 3,
+            deaths: 1,
+        };
+        let json = serde_json::to_string(&stats).unwrap();
+        assert_eq!(stats, serde_json::from_str(&json).unwrap());
+        assert_eq!(
+            "\"alive\"",
+            serde_json::to_string(&CellState::Alive).unwrap()
+        );
+        assert!(serde_json::from_str::<LifeLikeRule>("\"B9/S23\"").is_err());
+
+        let glider = Pattern::from_library("Glider").unwrap();
+        let json = serde_json::to_string(&glider).unwrap();
+        assert_eq!("\".O.\\n..O\\nOOO\\n\"", json);
+        assert_eq!(glider, serde_json::from_str(&json).unwrap());
+    }
+}
@@ -0,0 +1,596 @@
+use macroquad::prelude::*;
+
+use crate::Boundary;
+use crate::config::Config;
+use crate::keys::{ACTIONS, Action, KeyBindings, is_bindable, key_name};
+use crate::locale::{tr, tr_with};
+use crate::palette::{PALETTES, Palette};
+use crate::render::CellShape;
+use crate::simulation::SPEEDS;
+use crate::text::{draw_text, measure_text, text_scale};
+use crate::theme::{HIGH_CONTRAST_THEME, THEMES, Theme};
+
+/// Speed boards start with when none was picked before
+const DEFAULT_SPEED_INDEX: usize = 2;
+
+/// Volume of the sounds when none was picked before, out of 100
+const DEFAULT_VOLUME: usize = 50;
+
+/// How much the volume changes on each press
+const VOLUME_STEP: usize = 10;
+
+/// Scales of the interface that can be picked on the settings screen, in percent
+const UI_SCALES: [usize; 6] = [75, 100, 125, 150, 175, 200];
+
+/// Smallest and largest scale of the interface that can be set in the config file, in percent
+pub const MIN_UI_SCALE: usize = 50;
+pub const MAX_UI_SCALE: usize = 300;
+
+/// Settings that can be changed during the game, and are kept between sessions
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Settings {
+    /// Index of the speed of the board, out of SPEEDS
+    pub speed_index: usize,
+    /// Index of the colour theme, out of THEMES
+    pub theme_index: usize,
+    /// Index of the palette of the highlights drawn over the board, out of PALETTES
+    pub palette_index: usize,
+    /// Whether lines are drawn between cells
+    pub show_grid: bool,
+    /// Whether rulers with the indices of rows and columns are drawn along the edges of the board
+    pub show_rulers: bool,
+    /// Shape alive cells are drawn with
+    pub cell_shape: CellShape,
+    /// What lies past the edges of the board
+    pub boundary: Boundary,
+    /// Volume of the sounds, from 0 to 100
+    pub volume: usize,
+    /// Whether the ambient track plays
+    pub play_ambient: bool,
+    /// How large text is drawn, in percent, to keep it readable on big screens or fitting on small windows
+    pub ui_scale: usize,
+    /// Whether text is drawn larger, on top of the scale of the interface
+    pub large_text: bool,
+    /// Whether alive cells are outlined, so they stand out from each other
+    pub cell_outlines: bool,
+    /// Whether animations, like toasts fading out, are left out
+    pub reduced_motion: bool,
+    /// Whether the scripts of the scripts directory run on each generation
+    pub run_scripts: bool,
+    /// Key each action is bound to
+    pub keys: KeyBindings,
+}
+
+/// Rows of the settings screen
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Row {
+    Speed,
+    Theme,
+    Colours,
+    Palette,
+    Grid,
+    Rulers,
+    CellShape,
+    Edges,
+    Volume,
+    Ambient,
+    UiScale,
+    /// Turns every accessibility setting on or off at once, along with the high contrast theme
+    Accessibility,
+    LargeText,
+    CellOutlines,
+    ReducedMotion,
+    Scripts,
+    Back,
+    /// Key an action is bound to, listed under the other rows
+    Key(Action),
+}
+
+const ROWS: [Row; 17] = [
+    Row::Speed,
+    Row::Theme,
+    Row::Colours,
+    Row::Palette,
+    Row::Grid,
+    Row::Rulers,
+    Row::CellShape,
+    Row::Edges,
+    Row::Volume,
+    Row::Ambient,
+    Row::UiScale,
+    Row::Accessibility,
+    Row::LargeText,
+    Row::CellOutlines,
+    Row::ReducedMotion,
+    Row::Scripts,
+    Row::Back,
+];
+
+/// Controls of the game that can't be changed, with what each one does
+const CONTROLS: [(&str, &str); 4] = [
+    ("controls.keys.wheel", "controls.zoom"),
+    ("controls.keys.middle_drag", "controls.pan"),
+    ("Ctrl+T / Ctrl+W / Ctrl+Tab", "controls.tabs"),
+    ("Alt+Enter", "controls.fullscreen"),
+];
+
+/// Returns the row at an index of the settings screen, counting the keys of the actions after the other rows
+fn row_at(index: usize) -> Row {
+    ROWS.get(index)
+        .copied()
+        .unwrap_or_else(|| Row::Key(ACTIONS[index - ROWS.len()]))
+}
+
+impl Settings {
+    /// Takes the settings kept from previous sessions, with the default for the ones that weren't kept
+    pub fn from_config(config: &Config) -> Self {
+        Settings {
+            speed_index: config
+                .speed
+                .filter(|speed_index| *speed_index < SPEEDS.len())
+                .unwrap_or(DEFAULT_SPEED_INDEX),
+            theme_index: config
+                .theme
+                .as_deref()
+                .and_then(Theme::index_of)
+                .unwrap_or(0),
+            palette_index: config
+                .palette
+                .as_deref()
+                .and_then(Palette::index_of)
+                .unwrap_or(0),
+            show_grid: config.show_grid.unwrap_or(false),
+            show_rulers: config.show_rulers.unwrap_or(false),
+            cell_shape: config.cell_shape.unwrap_or(CellShape::Square),
+            boundary: config.boundary.unwrap_or(Boundary::Bounded),
+            volume: config.volume.unwrap_or(DEFAULT_VOLUME).min(100),
+            play_ambient: config.ambient.unwrap_or(false),
+            ui_scale: config
+                .ui_scale
+                .filter(|ui_scale| (MIN_UI_SCALE..=MAX_UI_SCALE).contains(ui_scale))
+                .unwrap_or(100),
+            large_text: config.large_text.unwrap_or(false),
+            cell_outlines: config.cell_outlines.unwrap_or(false),
+            reduced_motion: config.reduced_motion.unwrap_or(false),
+            run_scripts: config.scripts.unwrap_or(false),
+            keys: config
+                .keys
+                .iter()
+                .fold(KeyBindings::default(), |mut keys, (action, key)| {
+                    keys.bind(*action, *key);
+                    keys
+                }),
+        }
+    }
+
+    /// Stores the settings in the config, to keep them for the next sessions
+    pub fn store(&self, config: &mut Config) {
+        config.speed = Some(self.speed_index);
+        config.theme = Some(THEMES[self.theme_index].name.to_string());
+        config.palette = Some(PALETTES[self.palette_index].name.to_string());
+        config.show_grid = Some(self.show_grid);
+        config.show_rulers = Some(self.show_rulers);
+        config.cell_shape = Some(self.cell_shape);
+        config.boundary = Some(self.boundary);
+        config.volume = Some(self.volume);
+        config.ambient = Some(self.play_ambient);
+        config.ui_scale = Some(self.ui_scale);
+        config.large_text = Some(self.large_text);
+        config.cell_outlines = Some(self.cell_outlines);
+        config.reduced_motion = Some(self.reduced_motion);
+        config.scripts = Some(self.run_scripts);
+        config.keys = self.keys.changed().collect();
+    }
+
+    /// Returns whether every accessibility setting is on, along with the high contrast theme
+    fn is_accessible(&self) -> bool {
+        self.large_text
+            && self.cell_outlines
+            && self.reduced_motion
+            && self.theme_index == HIGH_CONTRAST_THEME
+    }
+
+    /// Changes the setting of a row to the next or previous value
+    /// Input: the row, and whether to go to the next value instead of the previous one
+    /// Output: whether the setting changed
+    fn change(&mut self, row: Row, forward: bool) -> bool {
+        match row {
+            Row::Speed => {
+                let speed_index = if forward {
+                    (self.speed_index + 1).min(SPEEDS.len() - 1)
+                } else {
+                    self.speed_index.saturating_sub(1)
+                };
+                let changed = speed_index != self.speed_index;
+                self.speed_index = speed_index;
+                return changed;
+            }
+            Row::Theme => {
+                self.theme_index = if forward {
+                    (self.theme_index + 1) % THEMES.len()
+                } else {
+                    (self.theme_index + THEMES.len() - 1) % THEMES.len()
+                }
+            }
+            Row::Palette => {
+                self.palette_index = if forward {
+                    (self.palette_index + 1) % PALETTES.len()
+                } else {
+                    (self.palette_index + PALETTES.len() - 1) % PALETTES.len()
+                }
+            }
+            Row::Volume => {
+                let volume = if forward {
+                    (self.volume + VOLUME_STEP).min(100)
+                } else {
+                    self.volume.saturating_sub(VOLUME_STEP)
+                };
+                let changed = volume != self.volume;
+                self.volume = volume;
+                return changed;
+            }
+            Row::Ambient => self.play_ambient = !self.play_ambient,
+            //Scales set by hand in the config file can be between the ones listed, so the nearest one is gone to
+            Row::UiScale => {
+                let ui_scale = if forward {
+                    UI_SCALES.into_iter().find(|scale| *scale > self.ui_scale)
+                } else {
+                    UI_SCALES
+                        .into_iter()
+                        .rev()
+                        .find(|scale| *scale < self.ui_scale)
+                };
+                match ui_scale {
+                    Some(ui_scale) => self.ui_scale = ui_scale,
+                    None => return false,
+                }
+            }
+            //Turning accessibility off leaves the high contrast theme for the default one
+            Row::Accessibility => {
+                let is_accessible = !self.is_accessible();
+                self.large_text = is_accessible;
+                self.cell_outlines = is_accessible;
+                self.reduced_motion = is_accessible;
+                if is_accessible {
+                    self.theme_index = HIGH_CONTRAST_THEME;
+                } else if self.theme_index == HIGH_CONTRAST_THEME {
+                    self.theme_index = 0;
+                }
+            }
+            Row::LargeText => self.large_text = !self.large_text,
+            Row::CellOutlines => self.cell_outlines = !self.cell_outlines,
+            Row::ReducedMotion => self.reduced_motion = !self.reduced_motion,
+            Row::Scripts => self.run_scripts = !self.run_scripts,
+            Row::Grid => self.show_grid = !self.show_grid,
+            Row::Rulers => self.show_rulers = !self.show_rulers,
+            //There are only three shapes, so going back is going forward twice
+            Row::CellShape => {
+                self.cell_shape = if forward {
+                    self.cell_shape.next()
+                } else {
+                    self.cell_shape.next().next()
+                }
+            }
+            Row::Edges => {
+                self.boundary = match self.boundary {
+                    Boundary::Bounded => Boundary::Toroidal,
+                    Boundary::Toroidal => Boundary::Bounded,
+                }
+            }
+            Row::Colours | Row::Back | Row::Key(_) => return false,
+        }
+        true
+    }
+
+    /// Returns the text shown for a row
+    fn describe(&self, row: Row) -> String {
+        match row {
+            Row::Speed => tr_with("settings.speed", &[&SPEEDS[self.speed_index].0]),
+            Row::Theme => tr_with("settings.theme", &[&THEMES[self.theme_index].name]),
+            Row::Colours => tr("settings.colours").to_string(),
+            Row::Palette => tr_with(
+                "settings.palette",
+                &[&tr(&PALETTES[self.palette_index].label())],
+            ),
+            Row::Grid => tr_with(
+                "settings.grid",
+                &[&if self.show_grid {
+                    tr("settings.shown")
+                } else {
+                    tr("settings.hidden")
+                }],
+            ),
+            Row::Rulers => tr_with(
+                "settings.rulers",
+                &[&if self.show_rulers {
+                    tr("settings.shown")
+                } else {
+                    tr("settings.hidden")
+                }],
+            ),
+            Row::CellShape => tr_with(
+                "settings.cell_shape",
+                &[&tr(&format!("settings.shape.{}", self.cell_shape))],
+            ),
+            Row::Edges => tr_with(
+                "settings.edges",
+                &[&match self.boundary {
+                    Boundary::Bounded => tr("settings.bounded"),
+                    Boundary::Toroidal => tr("settings.wrap"),
+                }],
+            ),
+            Row::Volume => tr_with("settings.volume", &[&self.volume]),
+            Row::Ambient => tr_with("settings.ambient", &[&on_or_off(self.play_ambient)]),
+            Row::UiScale => tr_with("settings.ui_scale", &[&self.ui_scale]),
+            Row::Accessibility => tr_with(
+                "settings.accessibility",
+                &[&on_or_off(self.is_accessible())],
+            ),
+            Row::LargeText => tr_with("settings.large_text", &[&on_or_off(self.large_text)]),
+            Row::CellOutlines => {
+                tr_with("settings.cell_outlines", &[&on_or_off(self.cell_outlines)])
+            }
+            Row::ReducedMotion => tr_with(
+                "settings.reduced_motion",
+                &[&on_or_off(self.reduced_motion)],
+            ),
+            Row::Scripts => tr_with("settings.scripts", &[&on_or_off(self.run_scripts)]),
+            Row::Back => tr("settings.back").to_string(),
+            Row::Key(action) => tr(action.label()).to_string(),
+        }
+    }
+}
+
+/// Returns the text shown for a setting that's either on or off
+fn on_or_off(is_on: bool) -> &'static str {
+    if is_on {
+        tr("settings.on")
+    } else {
+        tr("settings.off")
+    }
+}
+
+/// What happened on the settings screen on a frame
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SettingsEvent {
+    /// A setting changed, and has to be applied and saved
+    Changed,
+    /// The colour picker has to be opened
+    PickColours,
+}
+
+/// Screen to change the settings during the game, reached from the pause menu
+#[derive(Default)]
+pub struct SettingsScreen {
+    pub is_open: bool,
+    /// Index of the highlighted row, counting the keys of the actions after the other rows
+    selected: usize,
+    /// Action whose key is being changed, which takes the next key pressed
+    rebinding: Option<Action>,
+}
+
+impl SettingsScreen {
+    /// Handles the input of the screen and draws it over an area of the screen. Rows are picked with up and down,
+    /// and changed with left and right, or by clicking on them. The key of an action is changed by picking it and
+    /// pressing the new key. Escape goes back
+    /// Input: the settings to change, the theme to draw the screen with, and the area to draw in
+    /// Output: what happened on this frame, if anything
+    pub fn update(
+        &mut self,
+        settings: &mut Settings,
+        theme: &Theme,
+        area: Rect,
+    ) -> Option<SettingsEvent> {
+        let row_count = ROWS.len() + ACTIONS.len();
+        let row_height = area.h / 22.;
+        //The keys of the actions are listed next to the other rows, followed by the controls that can't be changed,
+        //with lines as tall as fits
+        let controls_x = area.x + area.w * 0.55;
+        let controls_y = area.y + row_height * 2.4;
+        let line_height =
+            ((area.bottom() - controls_y) / (ACTIONS.len() + CONTROLS.len() + 2) as f32).min(22.);
+        let line_rect = |line: usize| {
+            Rect::new(
+                controls_x,
+                controls_y + line_height * (line as f32 + 0.4),
+                area.w * 0.4,
+                line_height,
+            )
+        };
+        let row_rect = |index: usize| match index.checked_sub(ROWS.len()) {
+            Some(key_index) => line_rect(key_index),
+            None => Rect::new(
+                area.x + area.w / 20.,
+                area.y + (index + 2) as f32 * row_height * 1.2,
+                area.w * 0.45,
+                row_height,
+            ),
+        };
+
+        let mut event = None;
+        if let Some(action) = self.rebinding {
+            if is_key_pressed(KeyCode::Escape) {
+                self.rebinding = None;
+            } else if let Some(key) = get_last_key_pressed()
+                && is_bindable(key)
+            {
+                settings.keys.bind(action, key);
+                self.rebinding = None;
+                event = Some(SettingsEvent::Changed);
+            }
+        } else {
+            if is_key_pressed(KeyCode::Down) {
+                self.selected = (self.selected + 1) % row_count;
+            }
+            if is_key_pressed(KeyCode::Up) {
+                self.selected = (self.selected + row_count - 1) % row_count;
+            }
+            let (mouse_x, mouse_y) = mouse_position();
+            let hovered =
+                (0..row_count).find(|index| row_rect(*index).contains(vec2(mouse_x, mouse_y)));
+            if let Some(hovered) = hovered {
+                self.selected = hovered;
+            }
+
+            let row = row_at(self.selected);
+            let is_clicked = hovered.is_some() && is_mouse_button_pressed(MouseButton::Left);
+            let is_picked = is_key_pressed(KeyCode::Enter) || is_clicked;
+            if is_key_pressed(KeyCode::Escape) || (row == Row::Back && is_picked) {
+                self.is_open = false;
+                self.selected = 0;
+            } else if row == Row::Colours && is_picked {
+                event = Some(SettingsEvent::PickColours);
+            } else if let Row::Key(action) = row
+                && is_picked
+            {
+                self.rebinding = Some(action);
+            } else if (is_key_pressed(KeyCode::Right) || is_picked) && settings.change(row, true)
+                || is_key_pressed(KeyCode::Left) && settings.change(row, false)
+            {
+                event = Some(SettingsEvent::Changed);
+            }
+        }
+
+        clear_background(theme.background);
+        draw_text(
+            tr("settings.title"),
+            area.x + area.w / 20.,
+            area.y + row_height * 1.5,
+            48.,
+            theme.text,
+        );
+        for (index, row) in ROWS.iter().enumerate() {
+            let rect = row_rect(index);
+            if index == self.selected {
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme.highlight);
+            }
+            draw_text(
+                &settings.describe(*row),
+                rect.x + 10.,
+                rect.y + rect.h * 0.75,
+                rect.h * 0.75,
+                theme.text,
+            );
+        }
+
+        //Every control is listed, so they can be looked up without going back to the starting menu
+        draw_text(
+            tr("settings.controls"),
+            controls_x,
+            controls_y,
+            line_height * 1.2,
+            theme.text,
+        );
+        //The lines are already as tall as fits, so their text isn't made any larger
+        let font_size = line_height * 0.9 / text_scale();
+        for (key_index, action) in ACTIONS.iter().enumerate() {
+            let index = ROWS.len() + key_index;
+            let rect = row_rect(index);
+            if index == self.selected {
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme.highlight);
+            }
+            let baseline = rect.y + rect.h * 0.75;
+            draw_text(
+                &settings.describe(Row::Key(*action)),
+                rect.x + 5.,
+                baseline,
+                font_size,
+                theme.label,
+            );
+            let key = if self.rebinding == Some(*action) {
+                tr("settings.press_key").to_string()
+            } else {
+                key_name(settings.keys.key(*action))
+            };
+            let key_width = measure_text(&key, None, font_size as u16, 1.).width;
+            draw_text(
+                &key,
+                rect.right() - key_width - 5.,
+                baseline,
+                font_size,
+                theme.text,
+            );
+        }
+        for (index, (keys, action)) in CONTROLS.iter().enumerate() {
+            let rect = line_rect(ACTIONS.len() + 1 + index);
+            let baseline = rect.y + rect.h * 0.75;
+            draw_text(tr(action), rect.x + 5., baseline, font_size, theme.label);
+            let keys = tr(keys);
+            let keys_width = measure_text(keys, None, font_size as u16, 1.).width;
+            draw_text(
+                keys,
+                rect.right() - keys_width - 5.,
+                baseline,
+                font_size,
+                theme.label,
+            );
+        }
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_are_kept_in_the_config() {
+        let mut keys = KeyBindings::default();
+        keys.bind(Action::Faster, KeyCode::Equal);
+        keys.bind(Action::Slower, KeyCode::Minus);
+        let settings = Settings {
+            speed_index: 4,
+            theme_index: 1,
+            palette_index: 2,
+            show_grid: true,
+            show_rulers: true,
+            cell_shape: CellShape::Rounded,
+            boundary: Boundary::Toroidal,
+            volume: 70,
+            play_ambient: true,
+            ui_scale: 125,
+            large_text: true,
+            cell_outlines: false,
+            reduced_motion: true,
+            run_scripts: true,
+            keys,
+        };
+        let mut config = Config::default();
+        settings.store(&mut config);
+        assert_eq!(settings, Settings::from_config(&config));
+    }
+
+    #[test]
+    fn speed_stops_at_the_ends_while_other_settings_cycle() {
+        let mut settings = Settings::from_config(&Config::default());
+        settings.speed_index = SPEEDS.len() - 1;
+        assert!(!settings.change(Row::Speed, true));
+        assert!(settings.change(Row::Speed, false));
+        assert_eq!(SPEEDS.len() - 2, settings.speed_index);
+
+        settings.ui_scale = 110;
+        assert!(settings.change(Row::UiScale, false));
+        assert_eq!(100, settings.ui_scale);
+        settings.ui_scale = 200;
+        assert!(!settings.change(Row::UiScale, true));
+
+        assert!(settings.change(Row::CellShape, false));
+        assert_eq!(CellShape::Circle, settings.cell_shape);
+        assert!(settings.change(Row::Theme, false));
+        assert_eq!(THEMES.len() - 1, settings.theme_index);
+    }
+
+    #[test]
+    fn accessibility_turns_every_setting_on_and_off() {
+        let mut settings = Settings::from_config(&Config::default());
+        settings.large_text = true;
+        assert!(settings.change(Row::Accessibility, true));
+        assert!(settings.is_accessible());
+        assert!(settings.cell_outlines && settings.reduced_motion);
+        assert_eq!(HIGH_CONTRAST_THEME, settings.theme_index);
+
+        assert!(settings.change(Row::Accessibility, false));
+        assert!(!settings.large_text && !settings.cell_outlines && !settings.reduced_motion);
+        assert_eq!(0, settings.theme_index);
+    }
+}
@@ -0,0 +1,830 @@
+use std::collections::VecDeque;
+
+use macroquad::miniquad::date;
+
+use crate::event_log::EventLog;
+use crate::locale::tr_with;
+use crate::pattern_file::PatternFile;
+use crate::patterns::Pattern;
+use crate::rules::{LifeLikeRule, rule_count};
+use crate::view::View;
+use crate::worker::StepWorker;
+use crate::{Board, Boundary, Point, StepStats};
+
+/// Speeds. Each one contains the speed to display when updated, and the update interval for the board
+pub const SPEEDS: [(&str, f64); 5] = [
+    ("0.25x", 0.125),
+    ("0.5x", 0.25),
+    ("1x", 0.5),
+    ("2x", 1.),
+    ("4x", 2.),
+];
+
+/// Longest a generation can take before the board is slowed down, so frames keep being drawn in time
+const FRAME_BUDGET: f64 = 1. / 60.;
+
+/// Largest share of the time that can be spent updating a board that's too slow for the frame budget
+const MAX_SIMULATION_SHARE: f64 = 0.25;
+
+/// How many times closer the second view of a split view starts than the first one
+const SPLIT_VIEW_ZOOM: f32 = 4.;
+
+/// Statistics of the run of a board, gathered as it evolves
+#[derive(Clone, Copy, Default)]
+pub struct RunStats {
+    pub peak_population: usize,
+    pub total_births: usize,
+}
+
+/// Cells of an old generation that could have influenced a cell of the current one
+pub struct Influence {
+    /// The cell whose influence was traced
+    pub cell: Point,
+    /// Cells of the old generation that influenced it
+    pub ancestors: Vec<Point>,
+    /// How many generations back the ancestors are
+    pub generations_back: usize,
+    /// Whether the ancestors are on generation 0, or only as far back as the history goes
+    pub reaches_start: bool,
+}
+
+/// How many past generations are kept to step back to
+const HISTORY_LENGTH: usize = 100;
+
+/// One of the open boards, along with everything needed to run it independently of the others
+pub struct Simulation {
+    pub board: Board,
+    /// Board that runs the same pattern under another rule, when comparing rules side by side
+    pub comparison_board: Option<Board>,
+    pub rule_index: usize,
+    pub comparison_rule_index: usize,
+    pub speed_index: usize,
+    pub is_paused: bool,
+    /// Last time the board was updated
+    pub last_update: f64,
+    pub generation: usize,
+    /// How long the last generation took to compute, in seconds
+    pub last_step_duration: f64,
+    /// Generation at which the board stops on its own, if any
+    pub max_generations: Option<usize>,
+    /// Whether the board already stopped at max_generations, so it can be resumed past it
+    has_stopped_at_max: bool,
+    /// Snapshot of the board that keeps running alongside it, to see how edits made after taking it propagate
+    pub reference_board: Option<Board>,
+    pub stats: RunStats,
+    /// Set when the population dies out, so a summary of the run can be shown
+    pub is_extinct: bool,
+    /// Pattern that pauses the board when it appears, along with its name
+    pub watch_target: Option<(String, Pattern)>,
+    /// Where the pattern being watched for was found on the current generation
+    pub found_target: Option<Point>,
+    /// Messages about what happened on the board, waiting to be shown
+    pub notices: Vec<String>,
+    /// Cells born and dead on the board since they were last taken, to play their sounds
+    pub recent_changes: StepStats,
+    /// Last influence traced, until the board changes
+    pub influence: Option<Influence>,
+    /// Zoom and position of the board on screen
+    pub view: View,
+    /// View the board is drawn with, which eases towards the one above after zooming or jumping around
+    pub camera: View,
+    /// Second view of the board, shown next to the first one while the view is split
+    pub split_view: Option<View>,
+    /// Where births and deaths are logged to, if logging is enabled
+    pub event_log: Option<EventLog>,
+    /// File the board was loaded from, loaded onto it again whenever it changes
+    pub pattern_file: Option<PatternFile>,
+    /// Whether cells were toggled since the board was last saved, loaded or cleared
+    pub has_unsaved_edits: bool,
+    /// Whether the board is run by the host of a shared board instead of here, so it's never stepped on its own
+    pub is_remote: bool,
+    /// Previous generations, oldest first
+    history: VecDeque<HistoryEntry>,
+    /// Board as it was on generation 0, to restart from
+    initial_board: Option<Board>,
+    /// Thread computing the generations, when they aren't computed in the game loop
+    worker: Option<StepWorker>,
+    /// Identifier of the generation being computed by the worker, and when it was requested
+    pending_step: Option<(u64, f64)>,
+    next_step_id: u64,
+}
+
+/// Every board of a simulation that's updated together, at a given generation
+#[derive(Clone)]
+pub struct Snapshot {
+    board: Board,
+    comparison_board: Option<Board>,
+    reference_board: Option<Board>,
+}
+
+impl Snapshot {
+    /// Updates every board one generation
+    /// Output: the updated boards, and how many cells were born and died on the main one
+    pub fn stepped(self) -> (Self, StepStats) {
+        self.stepped_by(1)
+    }
+
+    /// Updates every board several generations at once, without copying them in between
+    /// Input: how many generations
+    /// Output: the updated boards, and how many cells were born and died on the main one over those generations
+    pub fn stepped_by(mut self, generations: usize) -> (Self, StepStats) {
+        let step_stats = self.board.run_generations(generations);
+        if let Some(comparison_board) = &mut self.comparison_board {
+            comparison_board.run_generations(generations);
+        }
+        if let Some(reference_board) = &mut self.reference_board {
+            reference_board.run_generations(generations);
+        }
+        (self, step_stats)
+    }
+}
+
+/// State of every board of a simulation at a past generation
+struct HistoryEntry {
+    boards: Snapshot,
+    stats: RunStats,
+}
+
+impl Simulation {
+    /// Creates a paused simulation with an empty board
+    /// Input: the size of the board, and the index of the preset rule it follows
+    pub fn new(width: usize, length: usize, rule_index: usize) -> Self {
+        let mut board = Board::new(width, length);
+        board.rule = LifeLikeRule::preset(rule_index);
+        Simulation {
+            board,
+            comparison_board: None,
+            rule_index,
+            comparison_rule_index: (rule_index + 1) % rule_count(),
+            speed_index: 2,
+            is_paused: true,
+            last_update: 0.,
+            generation: 0,
+            last_step_duration: 0.,
+            max_generations: None,
+            has_stopped_at_max: false,
+            reference_board: None,
+            stats: RunStats::default(),
+            is_extinct: false,
+            watch_target: None,
+            found_target: None,
+            notices: Vec::new(),
+            recent_changes: StepStats::default(),
+            influence: None,
+            view: View::default(),
+            camera: View::default(),
+            split_view: None,
+            event_log: None,
+            pattern_file: None,
+            has_unsaved_edits: false,
+            is_remote: false,
+            history: VecDeque::new(),
+            initial_board: None,
+            worker: None,
+            pending_step: None,
+            next_step_id: 0,
+        }
+    }
+
+    /// Makes generations be computed on a dedicated thread instead of the game loop, where threads are available
+    pub fn enable_background_stepping(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.worker = Some(StepWorker::spawn());
+        }
+    }
+
+    /// Updates the board if it's playing and enough time has passed since the last update
+    /// Input: the current time, in seconds
+    pub fn update(&mut self, current_time: f64) {
+        if self.is_remote {
+            return;
+        }
+        let Some(worker) = &self.worker else {
+            if !self.is_paused && current_time >= self.last_update + self.update_interval() {
+                self.last_update = current_time;
+                let step_start = date::now();
+                self.step();
+                self.last_step_duration = date::now() - step_start;
+            }
+            return;
+        };
+
+        //Results of generations requested before the boards were edited are thrown away
+        let results: Vec<_> = worker.try_receive().collect();
+        for (id, boards, step_stats) in results {
+            if let Some((pending_id, requested_at)) = self.pending_step
+                && pending_id == id
+            {
+                self.pending_step = None;
+                self.last_step_duration = date::now() - requested_at;
+                self.finish_step(boards, step_stats, 1);
+            }
+        }
+
+        if !self.is_paused
+            && self.pending_step.is_none()
+            && current_time >= self.last_update + self.update_interval()
+        {
+            self.last_update = current_time;
+            self.next_step_id += 1;
+            let boards = self.snapshot();
+            if self
+                .worker
+                .as_ref()
+                .unwrap()
+                .send(self.next_step_id, boards)
+            {
+                self.pending_step = Some((self.next_step_id, date::now()));
+            } else {
+                //If the worker is gone, generations go back to being computed in the game loop
+                self.worker = None;
+            }
+        }
+    }
+
+    /// Returns the time between updates. It's the one of the selected speed, unless generations take longer
+    /// than a frame to compute, in which case it's lengthened so drawing and input handling get most of the time
+    pub fn update_interval(&self) -> f64 {
+        if self.is_throttled() {
+            SPEEDS[self.speed_index]
+                .1
+                .max(self.last_step_duration / MAX_SIMULATION_SHARE)
+        } else {
+            SPEEDS[self.speed_index].1
+        }
+    }
+
+    /// Whether the board is being updated slower than the selected speed, because generations take too long
+    pub fn is_throttled(&self) -> bool {
+        self.last_step_duration > FRAME_BUDGET
+            && self.last_step_duration / MAX_SIMULATION_SHARE > SPEEDS[self.speed_index].1
+    }
+
+    /// Copies every board, to compute the next generation from. The births and deaths of the main board are recorded
+    /// while there's an event log to write them to
+    fn snapshot(&mut self) -> Snapshot {
+        self.board.record_events(self.event_log.is_some());
+        Snapshot {
+            board: self.board.clone(),
+            comparison_board: self.comparison_board.clone(),
+            reference_board: self.reference_board.clone(),
+        }
+    }
+
+    /// Advances the board one generation, storing the current one in the history
+    pub fn step(&mut self) {
+        self.pending_step = None;
+        //Every board is stepped together, so they always show the same generation
+        let (boards, step_stats) = self.snapshot().stepped();
+        self.finish_step(boards, step_stats, 1);
+    }
+
+    /// Advances the board several generations at once, only keeping the one before the last in the history. The
+    /// history is one generation per entry, for periods and traced influences, so what came before the skipped
+    /// generations is dropped. Logged boards are stepped one generation at a time instead, keeping the history, so
+    /// each birth and death is logged under the generation it happened on
+    /// Input: how many generations, at least 1
+    #[cfg(feature = "http-api")]
+    pub fn step_by(&mut self, generations: usize) {
+        if self.event_log.is_some() {
+            for _ in 0..generations {
+                self.step();
+            }
+            return;
+        }
+        if generations > 1 {
+            let (boards, step_stats) = self.snapshot().stepped_by(generations - 1);
+            self.finish_step(boards, step_stats, generations - 1);
+            self.history.clear();
+        }
+        self.step();
+    }
+
+    /// Replaces the boards with the ones some generations later, storing the current one in the history
+    /// Input: the updated boards, how many cells were born and died on the main one, and how many generations later
+    /// they are
+    fn finish_step(&mut self, boards: Snapshot, step_stats: StepStats, generations: usize) {
+        if self.history.len() == HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+        let population = self.board.population();
+        if self.generation == 0 {
+            self.initial_board = Some(self.board.clone());
+        }
+        let previous = Snapshot {
+            board: std::mem::replace(&mut self.board, boards.board),
+            comparison_board: std::mem::replace(
+                &mut self.comparison_board,
+                boards.comparison_board,
+            ),
+            reference_board: std::mem::replace(&mut self.reference_board, boards.reference_board),
+        };
+        self.history.push_back(HistoryEntry {
+            boards: previous,
+            stats: self.stats,
+        });
+
+        self.influence = None;
+        self.generation += generations;
+
+        if let Some(event_log) = &mut self.event_log
+            && let Err(error) = event_log.log_events(self.generation, self.board.drain_events())
+        {
+            eprintln!("Couldn't write to the event log, logging is disabled: {error}");
+            self.event_log = None;
+        }
+
+        let new_population = self.board.population();
+        self.stats.peak_population = self
+            .stats
+            .peak_population
+            .max(population)
+            .max(new_population);
+        self.stats.total_births += step_stats.births;
+        self.recent_changes.births += step_stats.births;
+        self.recent_changes.deaths += step_stats.deaths;
+        //The board is only paused when the pattern shows up, so it can be resumed while the pattern stays put
+        if let Some((name, pattern)) = &self.watch_target {
+            let found_target = self.board.find_pattern(pattern);
+            if let Some(Point { x, y }) = found_target
+                && found_target != self.found_target
+            {
+                self.is_paused = true;
+                self.notices.push(tr_with("game.found", &[&name, &x, &y]));
+            }
+            self.found_target = found_target;
+        }
+        //The limit may have been set at or below the current generation, so it's reached as soon as it's passed
+        if self
+            .max_generations
+            .is_some_and(|max_generations| self.generation >= max_generations)
+            && !self.has_stopped_at_max
+        {
+            self.has_stopped_at_max = true;
+            self.is_paused = true;
+        }
+        //When the last cells die, the board is paused instead of evolving an empty board forever
+        if population > 0 && new_population == 0 {
+            self.is_extinct = true;
+            self.is_paused = true;
+        }
+    }
+
+    /// Finds how many generations it took the board to come back to the current generation, looking through the history
+    /// Output: the period, if the current generation is in the history
+    pub fn period(&self) -> Option<usize> {
+        self.history
+            .iter()
+            .rev()
+            .position(|entry| entry.boards.board == self.board)
+            .map(|index| index + 1)
+    }
+
+    /// Goes back to the previous generation, if it's still in the history
+    /// Output: whether there was a generation to go back to
+    pub fn step_back(&mut self) -> bool {
+        self.pending_step = None;
+        match self.history.pop_back() {
+            Some(entry) => {
+                self.board = entry.boards.board;
+                self.comparison_board = entry.boards.comparison_board;
+                self.reference_board = entry.boards.reference_board;
+                self.stats = entry.stats;
+                self.found_target = None;
+                self.influence = None;
+                self.is_extinct = false;
+                self.generation -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the board, going back to generation 0 with no history. The comparison board and the diff view, if on,
+    /// start over from the new board
+    /// Input: the new board
+    pub fn reset(&mut self, board: Board) {
+        self.pending_step = None;
+        self.board = board;
+        if self.comparison_board.is_some() {
+            let mut comparison_board = self.board.clone();
+            comparison_board.rule = LifeLikeRule::preset(self.comparison_rule_index);
+            self.comparison_board = Some(comparison_board);
+        }
+        if self.reference_board.is_some() {
+            self.reference_board = Some(self.board.clone());
+        }
+        self.history.clear();
+        self.initial_board = None;
+        self.generation = 0;
+        self.has_stopped_at_max = false;
+        self.stats = RunStats::default();
+        self.is_extinct = false;
+        self.found_target = None;
+        self.influence = None;
+        self.is_paused = true;
+        self.has_unsaved_edits = false;
+    }
+
+    /// Goes back to the board as it was on generation 0, paused
+    pub fn restart(&mut self) {
+        match self.initial_board.take() {
+            Some(initial_board) => self.reset(initial_board),
+            None => self.is_paused = true,
+        }
+    }
+
+    /// Traces back which cells of the oldest generation in the history could have influenced a cell of the current one.
+    /// A cell only depends on itself and its neighbours on the previous generation, alive or dead, as a dead neighbour
+    /// changes the neighbour count as much as an alive one, so the influence is followed back through every cell around
+    /// each influencing cell. The history only holds the last HISTORY_LENGTH generations, so older ones aren't reached
+    /// Input: the coordinates of the cell
+    pub fn trace_influence(&mut self, cell: Point) {
+        let (width, length) = (self.board.width, self.board.length);
+        let mut influencing = vec![false; width * length];
+        influencing[cell.y * width + cell.x] = true;
+
+        for entry in self.history.iter().rev() {
+            let mut previous_influencing = vec![false; width * length];
+            for index in (0..influencing.len()).filter(|index| influencing[*index]) {
+                let cell = Point::new(index % width, index / width);
+                let board = &entry.boards.board;
+                let offsets = std::iter::once(&(0, 0)).chain(board.neighborhood.offsets());
+                for (x_offset, y_offset) in offsets {
+                    let (x, y) = cell.offset(*x_offset, *y_offset);
+                    if let Some(neighbour) = board.wrap_coordinates(x, y) {
+                        previous_influencing[neighbour.y * width + neighbour.x] = true;
+                    }
+                }
+            }
+            influencing = previous_influencing;
+        }
+
+        self.influence = Some(Influence {
+            cell,
+            ancestors: (0..influencing.len())
+                .filter(|index| influencing[*index])
+                .map(|index| Point::new(index % width, index / width))
+                .collect(),
+            generations_back: self.history.len(),
+            reaches_start: self.history.len() == self.generation,
+        });
+    }
+
+    /// Returns the size of the board, which each board keeps for itself, as boards of a game can differ in size, like
+    /// joined boards taking the size of the host's
+    /// Output: its width and height, in cells
+    pub fn board_size(&self) -> (usize, usize) {
+        (self.board.width, self.board.length)
+    }
+
+    /// Notes that the board was edited, dropping what was worked out from it before, like a generation being computed
+    pub fn mark_edited(&mut self) {
+        self.pending_step = None;
+        self.is_extinct = false;
+        self.influence = None;
+        self.has_unsaved_edits = true;
+    }
+
+    /// Swaps the state of a cell on the board, and on the comparison board if there is one
+    pub fn toggle_cell_state(&mut self, cell: impl Into<Point>) {
+        let cell = cell.into();
+        self.mark_edited();
+        self.board.toggle_cell_state(cell);
+        if let Some(comparison_board) = &mut self.comparison_board {
+            comparison_board.toggle_cell_state(cell);
+        }
+    }
+
+    /// Turns the side by side comparison on or off. The comparison board starts from the current pattern
+    pub fn toggle_comparison(&mut self) {
+        self.pending_step = None;
+        self.split_view = None;
+        self.comparison_board = match self.comparison_board {
+            Some(_) => None,
+            None => {
+                let mut comparison_board = self.board.clone();
+                comparison_board.rule = LifeLikeRule::preset(self.comparison_rule_index);
+                Some(comparison_board)
+            }
+        };
+    }
+
+    /// Splits the screen between the view of the board and a second one, zoomed in on a point of the board, or goes
+    /// back to a single view. The comparison board takes the same half of the screen, so it's turned off
+    /// Input: the point to zoom in on, in cells from the top left corner of the board, the size of the area the
+    /// second view is drawn in, and the size of its cells with the whole board in view
+    pub fn toggle_split_view(&mut self, center: (f32, f32), area: (f32, f32), fit_cell_size: f32) {
+        self.split_view = match self.split_view {
+            Some(_) => None,
+            None => {
+                self.comparison_board = None;
+                let mut split_view = View::default();
+                split_view.zoom_at(self.view.zoom * SPLIT_VIEW_ZOOM, (0., 0.));
+                split_view.center_on(center, area, fit_cell_size);
+                Some(split_view)
+            }
+        };
+    }
+
+    /// Turns the diff view on or off. Turning it on takes a snapshot of the current board to compare against
+    pub fn toggle_diff_view(&mut self) {
+        self.pending_step = None;
+        self.reference_board = match self.reference_board {
+            Some(_) => None,
+            None => Some(self.board.clone()),
+        };
+    }
+
+    /// Changes what lies past the edges of every board
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.pending_step = None;
+        self.board.boundary = boundary;
+        for board in [&mut self.comparison_board, &mut self.reference_board]
+            .into_iter()
+            .flatten()
+        {
+            board.boundary = boundary;
+        }
+    }
+
+    /// Changes the rule followed by the comparison board
+    /// Input: the index of the preset rule
+    pub fn set_comparison_rule(&mut self, rule_index: usize) {
+        self.pending_step = None;
+        self.comparison_rule_index = rule_index;
+        if let Some(comparison_board) = &mut self.comparison_board {
+            comparison_board.rule = LifeLikeRule::preset(rule_index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CellState;
+
+    #[test]
+    fn stepping_back_restores_previous_generation() {
+        let mut simulation = Simulation::new(3, 3, 0);
+        simulation.toggle_cell_state((1, 0));
+        simulation.toggle_cell_state((1, 1));
+        simulation.toggle_cell_state((1, 2));
+
+        simulation.step();
+        assert_eq!(CellState::Dead, simulation.board[(1, 0)]);
+
+        assert!(simulation.step_back());
+        assert_eq!(0, simulation.generation);
+        assert_eq!(CellState::Alive, simulation.board[(1, 0)]);
+        assert!(!simulation.step_back());
+    }
+
+    #[test]
+    fn restarting_goes_back_to_generation_zero() {
+        let mut simulation = Simulation::new(3, 3, 0);
+        simulation.toggle_cell_state((1, 0));
+        simulation.toggle_cell_state((1, 1));
+        simulation.toggle_cell_state((1, 2));
+        for _ in 0..150 {
+            simulation.step();
+        }
+
+        simulation.restart();
+        assert_eq!(0, simulation.generation);
+        assert_eq!(CellState::Alive, simulation.board[(1, 0)]);
+        assert!(!simulation.step_back());
+    }
+
+    #[test]
+    fn comparison_board_is_stepped_with_the_board() {
+        let mut simulation = Simulation::new(3, 3, 0);
+        simulation.toggle_comparison();
+        simulation.toggle_cell_state((0, 0));
+        simulation.toggle_cell_state((0, 1));
+        simulation.toggle_cell_state((1, 0));
+
+        simulation.step();
+        let comparison_board = simulation.comparison_board.unwrap();
+        assert_eq!(CellState::Alive, comparison_board[(1, 1)]);
+    }
+
+    #[test]
+    fn split_view_and_comparison_take_turns() {
+        let mut simulation = Simulation::new(10, 10, 0);
+        simulation.toggle_comparison();
+        simulation.toggle_split_view((5., 5.), (100., 100.), 10.);
+        assert!(simulation.comparison_board.is_none());
+        let split_view = simulation.split_view.unwrap();
+        assert_eq!(SPLIT_VIEW_ZOOM, split_view.zoom);
+        assert_eq!((5, 5), split_view.cell_at((50., 50.), 10.));
+
+        simulation.toggle_comparison();
+        assert!(simulation.split_view.is_none());
+    }
+
+    #[test]
+    fn single_cell_perturbation_shows_up_as_difference() {
+        let mut simulation = Simulation::new(5, 5, 0);
+        simulation.toggle_cell_state((1, 2));
+        simulation.toggle_cell_state((2, 2));
+        simulation.toggle_diff_view();
+        simulation.toggle_cell_state((3, 2));
+
+        simulation.step();
+        let reference_board = simulation.reference_board.as_ref().unwrap();
+        let differences: Vec<(usize, usize)> = simulation
+            .board
+            .differences(reference_board)
+            .map(Into::into)
+            .collect();
+        assert_eq!(vec![(2, 1), (2, 2), (2, 3)], differences);
+    }
+
+    #[test]
+    fn extinction_pauses_the_simulation() {
+        let mut simulation = Simulation::new(3, 3, 0);
+        simulation.toggle_cell_state((0, 0));
+        simulation.toggle_cell_state((1, 1));
+        simulation.is_paused = false;
+
+        simulation.step();
+        assert!(simulation.is_extinct);
+        assert!(simulation.is_paused);
+        assert_eq!(2, simulation.stats.peak_population);
+        assert_eq!(0, simulation.stats.total_births);
+    }
+
+    #[test]
+    fn simulation_stops_at_max_generations() {
+        let mut simulation = Simulation::new(3, 3, 0);
+        simulation.toggle_cell_state((0, 1));
+        simulation.toggle_cell_state((1, 1));
+        simulation.toggle_cell_state((2, 1));
+        simulation.max_generations = Some(2);
+        simulation.is_paused = false;
+
+        simulation.update(1.);
+        assert!(!simulation.is_paused);
+        simulation.update(2.);
+        assert!(simulation.is_paused);
+        assert_eq!(2, simulation.generation);
+
+        //It only stops once, and can be resumed past the limit
+        simulation.is_paused = false;
+        simulation.update(3.);
+        assert!(!simulation.is_paused);
+        assert_eq!(3, simulation.generation);
+
+        //A limit set below the current generation stops the board on its next one
+        let mut simulation = Simulation::new(3, 3, 0);
+        simulation.toggle_cell_state((1, 1));
+        simulation.toggle_cell_state((2, 1));
+        simulation.toggle_cell_state((1, 2));
+        simulation.toggle_cell_state((2, 2));
+        simulation.is_paused = false;
+        simulation.update(1.);
+        simulation.update(2.);
+        simulation.max_generations = Some(1);
+        simulation.update(3.);
+        assert!(simulation.is_paused);
+        assert_eq!(3, simulation.generation);
+    }
+
+    #[test]
+    fn simulation_pauses_when_watched_pattern_appears() {
+        let mut simulation = Simulation::new(5, 5, 0);
+        simulation.watch_target =
+            Some(("Block".to_string(), Pattern::from_library("Block").unwrap()));
+        simulation.toggle_cell_state((1, 1));
+        simulation.toggle_cell_state((2, 1));
+        simulation.toggle_cell_state((1, 2));
+        simulation.is_paused = false;
+
+        simulation.step();
+        assert!(simulation.is_paused);
+        assert_eq!(Some(Point::new(1, 1)), simulation.found_target);
+        assert_eq!(vec!["Found a Block at (1, 1)"], simulation.notices);
+
+        simulation.is_paused = false;
+        simulation.step();
+        assert!(!simulation.is_paused);
+    }
+
+    #[test]
+    fn influence_is_traced_back_through_every_neighbour() {
+        let mut simulation = Simulation::new(7, 5, 0);
+        //A blinker, and a block far enough to not influence it
+        for (x, y) in [(1, 2), (2, 2), (3, 2), (5, 0), (6, 0), (5, 1), (6, 1)] {
+            simulation.toggle_cell_state((x, y));
+        }
+        simulation.step();
+        simulation.step();
+
+        //Dead cells influence it as much as alive ones, up to two cells away after two generations
+        simulation.trace_influence(Point::new(2, 2));
+        let influence = simulation.influence.take().unwrap();
+        assert_eq!(2, influence.generations_back);
+        assert!(influence.reaches_start);
+        assert_eq!(25, influence.ancestors.len());
+        assert!(influence.ancestors.contains(&Point::new(0, 0)));
+        assert!(influence.ancestors.iter().all(|ancestor| ancestor.x <= 4));
+
+        //Past the length of the history, the influence is only traced as far back as it goes
+        for _ in 0..HISTORY_LENGTH {
+            simulation.step();
+        }
+        simulation.trace_influence(Point::new(2, 2));
+        let influence = simulation.influence.unwrap();
+        assert_eq!(HISTORY_LENGTH, influence.generations_back);
+        assert!(!influence.reaches_start);
+    }
+
+    #[test]
+    fn slow_generations_lengthen_the_update_interval() {
+        let mut simulation = Simulation::new(3, 3, 0);
+        assert!(!simulation.is_throttled());
+
+        simulation.last_step_duration = 0.5;
+        assert!(simulation.is_throttled());
+        assert_eq!(2., simulation.update_interval());
+    }
+
+    #[test]
+    fn background_stepping_computes_generations_on_worker() {
+        let mut simulation = Simulation::new(3, 3, 0);
+        simulation.enable_background_stepping();
+        simulation.toggle_cell_state((0, 1));
+        simulation.toggle_cell_state((1, 1));
+        simulation.toggle_cell_state((2, 1));
+        simulation.is_paused = false;
+
+        //The worker is given time to answer between updates, up to a deadline
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let mut current_time = 1.;
+        while simulation.generation == 0 {
+            simulation.update(current_time);
+            current_time += 1.;
+            assert!(
+                std::time::Instant::now() < deadline,
+                "the worker never answered"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(CellState::Alive, simulation.board[(1, 0)]);
+    }
+
+    #[test]
+    fn edits_are_unsaved_until_the_board_is_replaced() {
+        let mut simulation = Simulation::new(3, 3, 0);
+        assert!(!simulation.has_unsaved_edits);
+        simulation.toggle_cell_state((1, 1));
+        simulation.step();
+        assert!(simulation.has_unsaved_edits);
+        simulation.reset(Board::new(3, 3));
+        assert!(!simulation.has_unsaved_edits);
+    }
+
+    #[test]
+    fn edits_discard_generations_being_computed() {
+        let mut simulation = Simulation::new(3, 3, 0);
+        simulation.enable_background_stepping();
+        simulation.toggle_cell_state((1, 1));
+        simulation.is_paused = false;
+        simulation.update(1.);
+
+        simulation.toggle_cell_state((0, 0));
+        simulation.is_paused = true;
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        simulation.update(2.);
+        assert_eq!(0, simulation.generation);
+        assert_eq!(CellState::Alive, simulation.board[(1, 1)]);
+    }
+
+    #[cfg(feature = "http-api")]
+    #[test]
+    fn logged_boards_log_each_generation_when_stepped_by_several() {
+        let path = std::env::temp_dir().join(format!("step_by_{}.csv", std::process::id()));
+        let mut simulation = Simulation::new(3, 3, 0);
+        simulation.toggle_cell_state((1, 0));
+        simulation.toggle_cell_state((1, 1));
+        simulation.toggle_cell_state((1, 2));
+        simulation.event_log = Some(EventLog::create(&path).unwrap());
+
+        simulation.step_by(2);
+        let log = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(2, simulation.generation);
+        //A blinker has two births and two deaths on every generation
+        for generation in ["1,", "2,"] {
+            assert_eq!(
+                4,
+                log.lines()
+                    .filter(|line| line.starts_with(generation))
+                    .count(),
+                "{log}"
+            );
+        }
+    }
+}
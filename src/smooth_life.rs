@@ -0,0 +1,312 @@
+use macroquad::miniquad::date;
+use macroquad::prelude::*;
+
+use crate::keys::{Action, KeyBindings};
+use crate::locale::{tr, tr_with};
+use crate::random::{RandomSource, SplitMix64};
+use crate::simulation::SPEEDS;
+use crate::theme::Theme;
+use crate::tutorial::draw_banner;
+
+/// Width and length of the grid. Each cell adds up the hundreds of cells around it, so bigger grids step too slowly
+/// to watch
+const SMOOTH_SIZE: (usize, usize) = (128, 128);
+
+/// Squares filled at random when the grid starts, with the side of each one in outer radii
+const SPLATS: usize = 24;
+const SPLAT_SIDE: f32 = 1.;
+
+/// Space left above the grid for the banner with the generation, in pixels
+const BANNER_MARGIN: f32 = 100.;
+
+/// How cells of a SmoothLife grid live and die. Each cell compares how alive the disk around it is with how alive
+/// the ring around that disk is, both between 0 and 1, and smooth steps between the birth and survival intervals
+/// turn those into its next value
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SmoothRule {
+    /// Radius of the ring around each cell, in cells. The disk inside it has a third of it
+    pub outer_radius: f32,
+    /// Ring values a dead cell is born within
+    pub birth: (f32, f32),
+    /// Ring values a live cell survives within
+    pub survival: (f32, f32),
+    /// Widths of the smooth steps over the ring values and over the disk values
+    pub ring_smoothness: f32,
+    pub disk_smoothness: f32,
+}
+
+impl Default for SmoothRule {
+    /// Returns the rule of the original SmoothLife paper, whose blobs glide across the grid
+    fn default() -> Self {
+        SmoothRule {
+            outer_radius: 8.,
+            birth: (0.278, 0.365),
+            survival: (0.267, 0.445),
+            ring_smoothness: 0.028,
+            disk_smoothness: 0.147,
+        }
+    }
+}
+
+/// Smooth step from 0 to 1 around a threshold
+/// Input: the value, the threshold, and the width of the step
+fn sigmoid(value: f32, threshold: f32, width: f32) -> f32 {
+    1. / (1. + (-(value - threshold) * 4. / width).exp())
+}
+
+impl SmoothRule {
+    /// Works out the next value of a cell
+    /// Input: how alive the ring and the disk around the cell are, between 0 and 1
+    pub fn transition(&self, ring: f32, disk: f32) -> f32 {
+        //How alive the cell is picks between the birth and survival intervals, smoothly
+        let aliveness = sigmoid(disk, 0.5, self.disk_smoothness);
+        let low = self.birth.0 * (1. - aliveness) + self.survival.0 * aliveness;
+        let high = self.birth.1 * (1. - aliveness) + self.survival.1 * aliveness;
+        sigmoid(ring, low, self.ring_smoothness) * (1. - sigmoid(ring, high, self.ring_smoothness))
+    }
+
+    /// Returns the cells around a cell that make up its disk and its ring, as their offsets with how much of each one
+    /// is in the disk and in the ring. Cells on the edges of both count in part, so shapes move smoothly
+    fn kernel(&self) -> Vec<(isize, isize, f32, f32)> {
+        let inner_radius = self.outer_radius / 3.;
+        let reach = self.outer_radius.ceil() as isize + 1;
+        let mut kernel = Vec::new();
+        for y in -reach..=reach {
+            for x in -reach..=reach {
+                let distance = ((x * x + y * y) as f32).sqrt();
+                let disk = (inner_radius + 0.5 - distance).clamp(0., 1.);
+                let ring = (self.outer_radius + 0.5 - distance).clamp(0., 1.) - disk;
+                if disk > 0. || ring > 0. {
+                    kernel.push((x, y, disk, ring));
+                }
+            }
+        }
+        kernel
+    }
+}
+
+/// A grid of cells with values between 0, dead, and 1, alive, whose edges wrap around
+pub struct SmoothBoard {
+    width: usize,
+    length: usize,
+    /// Value of each cell, row by row
+    cells: Vec<f32>,
+    pub rule: SmoothRule,
+    /// Cells making up the disk and ring of the rule, worked out once
+    kernel: Vec<(isize, isize, f32, f32)>,
+    pub generation: usize,
+}
+
+impl SmoothBoard {
+    /// Returns a grid with every cell dead
+    pub fn new(width: usize, length: usize, rule: SmoothRule) -> Self {
+        SmoothBoard {
+            width,
+            length,
+            cells: vec![0.; width * length],
+            rule,
+            kernel: rule.kernel(),
+            generation: 0,
+        }
+    }
+
+    /// Returns a grid with squares of live cells at random places, the size of the ring of the rule
+    /// Input: the size of the grid, the rule, and the seed of the random places
+    pub fn random(width: usize, length: usize, rule: SmoothRule, seed: u64) -> Self {
+        let mut board = SmoothBoard::new(width, length, rule);
+        let mut random = SplitMix64::new(seed);
+        let side = (rule.outer_radius * SPLAT_SIDE) as usize;
+        for _ in 0..SPLATS {
+            let corner_x = (random.next_f64() * width as f64) as usize;
+            let corner_y = (random.next_f64() * length as f64) as usize;
+            for y in corner_y..corner_y + side {
+                for x in corner_x..corner_x + side {
+                    board.cells[(y % length) * width + x % width] = 1.;
+                }
+            }
+        }
+        board
+    }
+
+    /// Brings cells to life within a circle
+    /// Input: the centre of the circle and its radius, in cells
+    pub fn splat(&mut self, (x, y): (usize, usize), radius: f32) {
+        let reach = radius.ceil() as isize;
+        for y_offset in -reach..=reach {
+            for x_offset in -reach..=reach {
+                if ((x_offset * x_offset + y_offset * y_offset) as f32).sqrt() <= radius {
+                    let cell_x = (x as isize + x_offset).rem_euclid(self.width as isize) as usize;
+                    let cell_y = (y as isize + y_offset).rem_euclid(self.length as isize) as usize;
+                    self.cells[cell_y * self.width + cell_x] = 1.;
+                }
+            }
+        }
+    }
+
+    /// Adds up how alive cells are over the whole grid
+    pub fn mass(&self) -> f32 {
+        self.cells.iter().sum()
+    }
+
+    /// Advances every cell one generation at once
+    pub fn step(&mut self) {
+        let disk_area: f32 = self.kernel.iter().map(|(_, _, disk, _)| disk).sum();
+        let ring_area: f32 = self.kernel.iter().map(|(_, _, _, ring)| ring).sum();
+        let (width, length) = (self.width as isize, self.length as isize);
+        let mut next = Vec::with_capacity(self.cells.len());
+        for y in 0..length {
+            for x in 0..width {
+                let (mut disk, mut ring) = (0., 0.);
+                for (x_offset, y_offset, disk_share, ring_share) in &self.kernel {
+                    let neighbour_x = (x + x_offset).rem_euclid(width);
+                    let neighbour_y = (y + y_offset).rem_euclid(length);
+                    let value = self.cells[(neighbour_y * width + neighbour_x) as usize];
+                    disk += value * disk_share;
+                    ring += value * ring_share;
+                }
+                next.push(self.rule.transition(ring / ring_area, disk / disk_area));
+            }
+        }
+        self.cells = next;
+        self.generation += 1;
+    }
+
+    /// Draws every cell on an image with one pixel per cell, blending the colours of dead and live cells by its value
+    /// Input: the image, as big as the grid, and the theme to draw with
+    fn paint(&self, image: &mut Image, theme: &Theme) {
+        for (index, value) in self.cells.iter().enumerate() {
+            let color = Color::new(
+                theme.dead.r + (theme.alive.r - theme.dead.r) * value,
+                theme.dead.g + (theme.alive.g - theme.dead.g) * value,
+                theme.dead.b + (theme.alive.b - theme.dead.b) * value,
+                1.,
+            );
+            image.set_pixel(
+                (index % self.width) as u32,
+                (index / self.width) as u32,
+                color,
+            );
+        }
+    }
+}
+
+/// Runs SmoothLife on the window until Escape goes back to the starting menu. The keys of the game pause it and
+/// change its speed, clicking brings a circle of cells to life, and Enter starts it over on other random cells
+/// Input: the theme to draw with, the keys of the game and the index of the speed the grid starts at, out of SPEEDS
+/// Output: whether closing the window was asked for
+pub async fn run_smooth_life(theme: &Theme, keys: &KeyBindings, mut speed_index: usize) -> bool {
+    let (width, length) = SMOOTH_SIZE;
+    let seed = || date::now().to_bits();
+    let rule = SmoothRule::default();
+    let mut board = SmoothBoard::random(width, length, rule, seed());
+    let mut image = Image::gen_image_color(width as u16, length as u16, theme.dead);
+    let texture = Texture2D::from_image(&image);
+    texture.set_filter(FilterMode::Linear);
+    let mut is_paused = false;
+    let mut last_update = get_time();
+    loop {
+        if is_quit_requested() {
+            return true;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            return false;
+        }
+        if is_key_pressed(KeyCode::Enter) {
+            board = SmoothBoard::random(width, length, rule, seed());
+        }
+        if keys.is_pressed(Action::Pause) {
+            is_paused = !is_paused;
+        }
+        if keys.is_pressed(Action::Slower) {
+            speed_index = speed_index.saturating_sub(1);
+        }
+        if keys.is_pressed(Action::Faster) {
+            speed_index = (speed_index + 1).min(SPEEDS.len() - 1);
+        }
+        if !is_paused && get_time() - last_update >= SPEEDS[speed_index].1 {
+            last_update = get_time();
+            board.step();
+        }
+
+        //The grid is as big as fits under the banner, centred
+        let cell_size = (screen_width() / width as f32)
+            .min((screen_height() - BANNER_MARGIN - 30.) / length as f32);
+        let corner = (
+            (screen_width() - cell_size * width as f32) / 2.,
+            BANNER_MARGIN
+                + (screen_height() - BANNER_MARGIN - 30. - cell_size * length as f32) / 2.,
+        );
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let (mouse_x, mouse_y) = mouse_position();
+            let (x, y) = (
+                ((mouse_x - corner.0) / cell_size).floor(),
+                ((mouse_y - corner.1) / cell_size).floor(),
+            );
+            if x >= 0. && y >= 0. && (x as usize) < width && (y as usize) < length {
+                board.splat((x as usize, y as usize), rule.outer_radius / 2.);
+            }
+        }
+
+        clear_background(theme.background);
+        board.paint(&mut image, theme);
+        texture.update(&image);
+        draw_texture_ex(
+            &texture,
+            corner.0,
+            corner.1,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(cell_size * width as f32, cell_size * length as f32)),
+                ..Default::default()
+            },
+        );
+        draw_banner(
+            &tr_with(
+                "smooth_life.generation",
+                &[&board.generation, &format!("{:.0}", board.mass())],
+            ),
+            theme,
+        );
+        draw_text(
+            tr("smooth_life.hint"),
+            20.,
+            screen_height() - 10.,
+            18.,
+            theme.text,
+        );
+        next_frame().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smooth_cells_are_born_and_die_like_the_rule_says() {
+        let rule = SmoothRule::default();
+        //Dead cells are born with their ring in the birth interval, and live ones die when it's crowded
+        assert!(rule.transition(0.32, 0.) > 0.9);
+        assert!(rule.transition(0.1, 0.) < 0.1);
+        assert!(rule.transition(0.35, 1.) > 0.9);
+        assert!(rule.transition(0.6, 1.) < 0.1);
+
+        let kernel = rule.kernel();
+        let disk_area: f32 = kernel.iter().map(|(_, _, disk, _)| disk).sum();
+        let ring_area: f32 = kernel.iter().map(|(_, _, _, ring)| ring).sum();
+        //The disk and ring are about as big as circles of their radii
+        assert!((disk_area - std::f32::consts::PI * (8. / 3_f32).powi(2)).abs() < 3.);
+        assert!((disk_area + ring_area - std::f32::consts::PI * 64.).abs() < 3.);
+
+        //A grid with nothing alive stays all but dead, and circles are brought to life on it
+        let mut board = SmoothBoard::new(32, 32, rule);
+        board.step();
+        assert!(board.mass() < 1e-6);
+        let mut board = SmoothBoard::new(32, 32, rule);
+        board.splat((16, 16), 4.);
+        assert_eq!(1., board.cells[12 * 32 + 16]);
+        assert_eq!(0., board.cells[11 * 32 + 16]);
+        let board = SmoothBoard::random(64, 64, rule, 3);
+        assert!(board.mass() > 0.);
+    }
+}
@@ -0,0 +1,403 @@
+use std::f32::consts::TAU;
+
+use macroquad::audio::{
+    PlaySoundParams, Sound, load_sound_from_bytes, play_sound, set_sound_volume, stop_sound,
+};
+
+use crate::{Board, CellState, StepStats};
+
+/// Samples per second of the sounds, which are made by the game instead of being loaded from files
+const SAMPLE_RATE: u32 = 22050;
+
+/// Shortest time between two sounds of births or deaths, in seconds. Changes in between are played together, so fast
+/// boards don't play a sound on top of another on every generation
+const CHANGES_INTERVAL: f64 = 0.15;
+
+/// Length of the ambient track, in seconds. Every frequency in it goes through a whole number of cycles in this
+/// time, so it loops without a seam
+const AMBIENT_DURATION: f32 = 8.;
+
+/// Notes of the pentatonic scale boards are played with, as semitones above the first note of each octave. Any of
+/// them sound fine together, however many cells are born where
+const SCALE: [u32; 5] = [0, 2, 4, 7, 9];
+
+/// Notes births are played with, over three octaves. Each one plays the births of a band of columns of the board,
+/// from the left, with the lowest one, to the right
+const NOTE_COUNT: usize = 3 * SCALE.len();
+
+/// Notes the population is played with, an octave under the lowest note of the births
+const BASS_NOTE_COUNT: usize = SCALE.len();
+
+/// Most notes played at once for the births, taken from the bands of columns with the most of them
+const MAX_CHORD: usize = 3;
+
+/// Time between two chords when the board is played as music, in seconds
+const SONIFICATION_INTERVAL: f64 = 0.25;
+
+/// Sounds of the game, played with the volume of the settings
+pub struct Sounds {
+    birth: Sound,
+    death: Sound,
+    pause: Sound,
+    resume: Sound,
+    ambient: Sound,
+    /// Notes of the scale the board is played with, from the lowest
+    notes: Vec<Sound>,
+    bass_notes: Vec<Sound>,
+    is_ambient_playing: bool,
+    changes: PendingChanges,
+    sonification: Sonification,
+}
+
+impl Sounds {
+    /// Makes every sound of the game
+    /// Output: the sounds, or a message explaining why they couldn't be loaded
+    pub async fn load() -> Result<Self, String> {
+        let load = async |samples: Vec<f32>| {
+            load_sound_from_bytes(&wav(&samples))
+                .await
+                .map_err(|error| error.to_string())
+        };
+        Ok(Sounds {
+            birth: load(sweep(660., 990., 0.08)).await?,
+            death: load(sweep(330., 220., 0.1)).await?,
+            pause: load([sweep(440., 440., 0.07), sweep(330., 330., 0.1)].concat()).await?,
+            resume: load([sweep(330., 330., 0.07), sweep(440., 440., 0.1)].concat()).await?,
+            ambient: load(ambient()).await?,
+            notes: {
+                let mut notes = Vec::new();
+                for index in 0..NOTE_COUNT {
+                    notes.push(load(pluck(note_frequency(220., index))).await?);
+                }
+                notes
+            },
+            bass_notes: {
+                let mut notes = Vec::new();
+                for index in 0..BASS_NOTE_COUNT {
+                    notes.push(load(pluck(note_frequency(110., index))).await?);
+                }
+                notes
+            },
+            is_ambient_playing: false,
+            changes: PendingChanges::default(),
+            sonification: Sonification::default(),
+        })
+    }
+
+    /// Adds the births and deaths of new generations, to be played along with the ones not played yet
+    pub fn add_changes(&mut self, step_stats: StepStats) {
+        self.changes.births += step_stats.births;
+        self.changes.deaths += step_stats.deaths;
+    }
+
+    /// Plays the births and deaths not played yet, if it's time to, and starts or stops the ambient track
+    /// Input: the volume, from 0 to 1, whether the ambient track plays, and the current time
+    pub fn update(&mut self, volume: f32, is_ambient_on: bool, time: f64) {
+        if let Some(step_stats) = self.changes.take(time) {
+            for (sound, count) in [
+                (&self.birth, step_stats.births),
+                (&self.death, step_stats.deaths),
+            ] {
+                if count > 0 {
+                    play_sound(
+                        sound,
+                        PlaySoundParams {
+                            looped: false,
+                            volume: volume * loudness(count),
+                        },
+                    );
+                }
+            }
+        }
+
+        let is_ambient_on = is_ambient_on && volume > 0.;
+        if is_ambient_on && !self.is_ambient_playing {
+            play_sound(
+                &self.ambient,
+                PlaySoundParams {
+                    looped: true,
+                    volume,
+                },
+            );
+        } else if !is_ambient_on && self.is_ambient_playing {
+            stop_sound(&self.ambient);
+        }
+        if is_ambient_on {
+            set_sound_volume(&self.ambient, volume);
+        }
+        self.is_ambient_playing = is_ambient_on;
+    }
+
+    /// Plays a board as music, if it's time to: the births since the last chord are played as notes, higher the
+    /// further right they were, and the population as a bass note, higher the bigger it is, whenever it changes
+    /// Input: the board, the volume, from 0 to 1, and the current time
+    pub fn sonify(&mut self, board: &Board, volume: f32, time: f64) {
+        if time - self.sonification.last_played < SONIFICATION_INTERVAL {
+            return;
+        }
+        //A board that wasn't played for a while starts over, instead of playing everything that changed meanwhile
+        if time - self.sonification.last_played > 4. * SONIFICATION_INTERVAL {
+            self.sonification.cells.clear();
+        }
+        self.sonification.last_played = time;
+        let Some(births) = self.sonification.births_by_band(board) else {
+            return;
+        };
+        for (band, count) in loudest_bands(&births) {
+            play_sound(
+                &self.notes[band],
+                PlaySoundParams {
+                    looped: false,
+                    volume: volume * loudness(count),
+                },
+            );
+        }
+        let bass_note = population_note(board.population());
+        if bass_note != self.sonification.bass_note
+            && let Some(bass_note) = bass_note
+        {
+            play_sound(
+                &self.bass_notes[bass_note],
+                PlaySoundParams {
+                    looped: false,
+                    volume: volume * 0.6,
+                },
+            );
+        }
+        self.sonification.bass_note = bass_note;
+    }
+
+    /// Plays the sound of pausing or unpausing the game
+    /// Input: whether the game was paused, and the volume, from 0 to 1
+    pub fn play_pause(&self, is_paused: bool, volume: f32) {
+        let sound = if is_paused { &self.pause } else { &self.resume };
+        play_sound(
+            sound,
+            PlaySoundParams {
+                looped: false,
+                volume,
+            },
+        );
+    }
+}
+
+/// Births and deaths waiting to be played, with when the last ones were
+#[derive(Default)]
+struct PendingChanges {
+    births: usize,
+    deaths: usize,
+    last_played: f64,
+}
+
+impl PendingChanges {
+    /// Takes the changes to play, if there are any and enough time went by since the last ones
+    /// Input: the current time
+    fn take(&mut self, time: f64) -> Option<StepStats> {
+        if self.births + self.deaths == 0 || time - self.last_played < CHANGES_INTERVAL {
+            return None;
+        }
+        self.last_played = time;
+        Some(StepStats {
+            births: std::mem::take(&mut self.births),
+            deaths: std::mem::take(&mut self.deaths),
+        })
+    }
+}
+
+/// What was last played of a board played as music
+#[derive(Default)]
+struct Sonification {
+    /// Cells of the board when the last chord was played
+    cells: Vec<CellState>,
+    last_played: f64,
+    /// Index of the last bass note played, out of the bass notes
+    bass_note: Option<usize>,
+}
+
+impl Sonification {
+    /// Counts the cells born since the last chord in each band of columns of a board, and keeps its cells for the
+    /// next one
+    /// Output: the births of each band, from the left, or nothing if the board changed size since the last chord
+    fn births_by_band(&mut self, board: &Board) -> Option<[usize; NOTE_COUNT]> {
+        if self.cells.len() != board.board.len() {
+            self.cells.clone_from(&board.board);
+            return None;
+        }
+        let mut births = [0; NOTE_COUNT];
+        for (index, (previous, cell)) in self.cells.iter().zip(&board.board).enumerate() {
+            if *previous == CellState::Dead && *cell == CellState::Alive {
+                births[index % board.width * NOTE_COUNT / board.width] += 1;
+            }
+        }
+        self.cells.clone_from(&board.board);
+        Some(births)
+    }
+}
+
+/// Picks the bands of columns with the most births, which are the ones played
+/// Output: the index and births of each band, with the most births first
+fn loudest_bands(births: &[usize]) -> Vec<(usize, usize)> {
+    let mut bands: Vec<(usize, usize)> = births
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    bands.sort_by(|(_, first), (_, second)| second.cmp(first));
+    bands.truncate(MAX_CHORD);
+    bands
+}
+
+/// Returns the bass note a population is played with, a note higher for every eightfold of cells
+fn population_note(population: usize) -> Option<usize> {
+    (population > 0).then(|| (population.ilog2() as usize / 3).min(BASS_NOTE_COUNT - 1))
+}
+
+/// Returns the frequency of a note of the scale
+/// Input: the frequency of the lowest note, in hertz, and the index of the note, counting up from it
+fn note_frequency(lowest: f32, index: usize) -> f32 {
+    let semitones = 12 * (index / SCALE.len()) as u32 + SCALE[index % SCALE.len()];
+    lowest * 2_f32.powf(semitones as f32 / 12.)
+}
+
+/// Returns how loud the sound of a number of births or deaths is, from 0 to 1. Each tenfold of cells is a bit
+/// louder, so big boards don't drown everything else out
+fn loudness(count: usize) -> f32 {
+    if count == 0 {
+        return 0.;
+    }
+    (0.25 + (count as f32).log10() / 4.).min(1.)
+}
+
+/// Makes a tone that slides from a frequency to another, fading in and out so it doesn't click
+/// Input: the frequencies it starts and ends at, in hertz, and its length, in seconds
+fn sweep(start_frequency: f32, end_frequency: f32, duration: f32) -> Vec<f32> {
+    let length = (duration * SAMPLE_RATE as f32) as usize;
+    let fade = length / 5;
+    let mut phase = 0.;
+    (0..length)
+        .map(|index| {
+            let progress = index as f32 / length as f32;
+            let frequency = start_frequency + (end_frequency - start_frequency) * progress;
+            phase += TAU * frequency / SAMPLE_RATE as f32;
+            let envelope = (index.min(length - index) as f32 / fade as f32).min(1.);
+            phase.sin() * envelope * 0.5
+        })
+        .collect()
+}
+
+/// Makes a note that starts at once and slowly dies out, like a plucked string
+/// Input: the frequency of the note, in hertz
+fn pluck(frequency: f32) -> Vec<f32> {
+    let length = (0.6 * SAMPLE_RATE as f32) as usize;
+    (0..length)
+        .map(|index| {
+            let time = index as f32 / SAMPLE_RATE as f32;
+            let attack = (time / 0.005).min(1.);
+            (TAU * frequency * time).sin() * attack * (-time * 6.).exp() * 0.5
+        })
+        .collect()
+}
+
+/// Makes the ambient track, a soft chord that slowly swells and fades
+fn ambient() -> Vec<f32> {
+    let length = (AMBIENT_DURATION * SAMPLE_RATE as f32) as usize;
+    let frequencies = [110., 165., 220., 277.5];
+    (0..length)
+        .map(|index| {
+            let time = index as f32 / SAMPLE_RATE as f32;
+            let chord: f32 = frequencies
+                .iter()
+                .map(|frequency| (TAU * frequency * time).sin())
+                .sum();
+            let swell = 0.6 + 0.4 * (TAU * time * 2. / AMBIENT_DURATION).sin();
+            chord / frequencies.len() as f32 * swell * 0.3
+        })
+        .collect()
+}
+
+/// Writes samples from -1 to 1 as a 16 bit mono WAV file
+fn wav(samples: &[f32]) -> Vec<u8> {
+    let data_length = samples.len() as u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_length as usize);
+    bytes.extend(b"RIFF");
+    bytes.extend((36 + data_length).to_le_bytes());
+    bytes.extend(b"WAVEfmt ");
+    bytes.extend(16u32.to_le_bytes());
+    //PCM, with a single channel
+    bytes.extend(1u16.to_le_bytes());
+    bytes.extend(1u16.to_le_bytes());
+    bytes.extend(SAMPLE_RATE.to_le_bytes());
+    bytes.extend((SAMPLE_RATE * 2).to_le_bytes());
+    bytes.extend(2u16.to_le_bytes());
+    bytes.extend(16u16.to_le_bytes());
+    bytes.extend(b"data");
+    bytes.extend(data_length.to_le_bytes());
+    for sample in samples {
+        bytes.extend(((sample.clamp(-1., 1.) * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_are_played_together_once_in_a_while() {
+        let mut changes = PendingChanges::default();
+        assert_eq!(None, changes.take(1.));
+        changes.births = 3;
+        changes.deaths = 2;
+        assert_eq!(
+            Some(StepStats {
+                births: 3,
+                deaths: 2
+            }),
+            changes.take(1.)
+        );
+        changes.births = 5;
+        assert_eq!(None, changes.take(1.1));
+        changes.deaths = 1;
+        assert_eq!(
+            Some(StepStats {
+                births: 5,
+                deaths: 1
+            }),
+            changes.take(1.2)
+        );
+    }
+
+    #[test]
+    fn births_are_played_by_column_and_population_by_pitch() {
+        let mut sonification = Sonification::default();
+        let mut board = Board::new(30, 10);
+        assert_eq!(None, sonification.births_by_band(&board));
+        board.toggle_cell_state((0, 0));
+        board.toggle_cell_state((1, 5));
+        board.toggle_cell_state((29, 9));
+        let births = sonification.births_by_band(&board).unwrap();
+        assert_eq!(2, births[0]);
+        assert_eq!(1, births[NOTE_COUNT - 1]);
+        assert_eq!(vec![(0, 2), (NOTE_COUNT - 1, 1)], loudest_bands(&births));
+        assert_eq!(Some([0; NOTE_COUNT]), sonification.births_by_band(&board));
+
+        assert_eq!(None, population_note(0));
+        assert_eq!(Some(0), population_note(7));
+        assert_eq!(Some(1), population_note(8));
+        assert_eq!(Some(BASS_NOTE_COUNT - 1), population_note(1_000_000));
+        assert_eq!(440., note_frequency(220., SCALE.len()));
+    }
+
+    #[test]
+    fn sounds_get_louder_with_more_cells_up_to_full_volume() {
+        assert_eq!(0., loudness(0));
+        assert!(loudness(1) < loudness(10));
+        assert!(loudness(10) < loudness(100));
+        assert_eq!(1., loudness(1_000_000));
+
+        let bytes = wav(&sweep(440., 440., 0.1));
+        assert_eq!(b"RIFF", &bytes[..4]);
+        assert_eq!(44 + 2 * 2205, bytes.len());
+    }
+}
@@ -0,0 +1,224 @@
+use std::io::{self, ErrorKind};
+use std::net::{IpAddr, TcpListener, TcpStream, ToSocketAddrs};
+
+use tungstenite::handshake::server::{NoCallback, ServerHandshake};
+use tungstenite::handshake::{HandshakeError, MidHandshake};
+use tungstenite::{Error, WebSocket};
+
+use crate::locale::tr_with;
+use crate::network::{CONNECT_TIMEOUT, Message, SentState, full_board, next_update};
+use crate::simulation::Simulation;
+
+/// Someone watching a streamed board, who may still be opening the connection
+enum Viewer {
+    Handshaking(MidHandshake<ServerHandshake<TcpStream, NoCallback>>),
+    Watching(WebSocket<TcpStream>),
+}
+
+/// Streams a board over WebSocket, so it can be watched live from a browser, with web/spectator.html, or from
+/// another game with --spectate. Viewers get the whole board when they connect and then how it changes, as text
+/// messages holding the lines shared boards are sent with
+pub struct Broadcast {
+    listener: TcpListener,
+    viewers: Vec<(String, Viewer)>,
+    sent: Option<SentState>,
+}
+
+impl Broadcast {
+    /// Starts streaming a board, waiting for viewers on a port
+    /// Input: the address of the network interface to listen on, like 127.0.0.1 to only stream to this computer, and
+    /// the port
+    /// Output: the broadcast, or why the port couldn't be listened on
+    pub fn start(address: IpAddr, port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind((address, port))?;
+        listener.set_nonblocking(true)?;
+        Ok(Broadcast {
+            listener,
+            viewers: Vec::new(),
+            sent: None,
+        })
+    }
+
+    /// Takes in new viewers and sends every viewer how the board changed since the last call. Nothing viewers send
+    /// changes the board
+    /// Input: the simulation running the streamed board
+    /// Output: messages about viewers connecting and leaving, to show
+    pub fn send(&mut self, simulation: &Simulation) -> Vec<String> {
+        let mut notices = Vec::new();
+        while let Ok((stream, address)) = self.listener.accept() {
+            if stream.set_nonblocking(true).is_ok() && stream.set_nodelay(true).is_ok() {
+                let handshake = ServerHandshake::start(stream, NoCallback, None);
+                self.viewers
+                    .push((address.to_string(), Viewer::Handshaking(handshake)));
+            }
+        }
+
+        let update = next_update(&mut self.sent, simulation);
+        for (address, viewer) in std::mem::take(&mut self.viewers) {
+            let viewer = match viewer {
+                //Handshakes go on where they stopped until the browser has all it needs
+                Viewer::Handshaking(handshake) => match handshake.handshake() {
+                    Ok(mut socket) => {
+                        if send_line(&mut socket, &full_board(simulation)).is_err() {
+                            continue;
+                        }
+                        notices.push(tr_with("streaming.connected", &[&address]));
+                        Viewer::Watching(socket)
+                    }
+                    Err(HandshakeError::Interrupted(handshake)) => Viewer::Handshaking(handshake),
+                    Err(HandshakeError::Failure(_)) => continue,
+                },
+                Viewer::Watching(mut socket) => {
+                    if keep_watching(&mut socket, update.as_ref()).is_err() {
+                        notices.push(tr_with("streaming.disconnected", &[&address]));
+                        continue;
+                    }
+                    Viewer::Watching(socket)
+                }
+            };
+            self.viewers.push((address, viewer));
+        }
+        notices
+    }
+}
+
+/// Sends a viewer how the board changed, if it did, along with what couldn't be sent before
+/// Input: the connection to the viewer, and the message to send
+/// Output: an error if the viewer is gone
+fn keep_watching(socket: &mut WebSocket<TcpStream>, update: Option<&Message>) -> Result<(), Error> {
+    match update {
+        Some(update) => send_line(socket, update)?,
+        None => socket.flush().or_else(ignore_would_block)?,
+    }
+    //Viewers only send pings and closing messages, which have to be read to be answered
+    loop {
+        if let Err(error) = socket.read() {
+            return ignore_would_block(error);
+        }
+    }
+}
+
+/// Sends a message as a WebSocket text message, queueing what can't be written without waiting
+/// Input: the connection, and the message
+/// Output: an error if the other side is gone
+fn send_line(socket: &mut WebSocket<TcpStream>, message: &Message) -> Result<(), Error> {
+    let line = message.to_line();
+    socket
+        .send(tungstenite::Message::text(line.trim_end()))
+        .or_else(ignore_would_block)
+}
+
+/// Takes connections that would have to wait as working ones, since what they hold is sent or read later
+fn ignore_would_block(error: Error) -> Result<(), Error> {
+    match error {
+        Error::Io(error) if error.kind() == ErrorKind::WouldBlock => Ok(()),
+        error => Err(error),
+    }
+}
+
+/// A connection to a streamed board, watched from another game
+pub(crate) struct Spectator {
+    socket: WebSocket<TcpStream>,
+}
+
+impl Spectator {
+    /// Connects to a streamed board
+    /// Input: the address of the game streaming it, such as "192.168.1.20:7879"
+    /// Output: the connection, or why the board couldn't be reached
+    pub(crate) fn connect(address: &str) -> io::Result<Self> {
+        let socket_address = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "the address has no host"))?;
+        let stream = TcpStream::connect_timeout(&socket_address, CONNECT_TIMEOUT)?;
+        stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+        let (socket, _) = tungstenite::client(format!("ws://{address}/"), stream)
+            .map_err(|error| io::Error::other(error.to_string()))?;
+        socket.get_ref().set_nonblocking(true)?;
+        Ok(Spectator { socket })
+    }
+
+    /// Reads every message that arrived since the last call
+    /// Output: the messages, or an error if the connection was lost or the board sent something unreadable
+    pub(crate) fn receive(&mut self) -> io::Result<Vec<Message>> {
+        let mut messages = Vec::new();
+        loop {
+            match self.socket.read() {
+                Ok(tungstenite::Message::Text(text)) => {
+                    for line in text.lines() {
+                        messages.push(Message::parse(line).map_err(io::Error::other)?);
+                    }
+                }
+                Ok(_) => {}
+                Err(Error::Io(error)) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) => return Err(io::Error::other(error.to_string())),
+            }
+        }
+        Ok(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CellState;
+    use crate::network::Session;
+    use std::time::Duration;
+
+    /// Keeps streaming a board to a spectator until it shows the board as it is, giving up after a while
+    fn watch_until_shown(
+        broadcast: &mut Broadcast,
+        simulation: &Simulation,
+        session: &mut Session,
+        watched: &mut Simulation,
+    ) -> bool {
+        for _ in 0..200 {
+            broadcast.send(simulation);
+            session.sync(watched);
+            if watched.board == simulation.board && watched.generation == simulation.generation {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        false
+    }
+
+    #[test]
+    fn spectators_watch_the_board_change() {
+        let mut broadcast = Broadcast::start(IpAddr::from([127, 0, 0, 1]), 0).unwrap();
+        let port = broadcast.listener.local_addr().unwrap().port();
+        let mut simulation = Simulation::new(6, 6, 0);
+        simulation.board = ".#....\n..#...\n###...\n......\n......\n......"
+            .parse()
+            .unwrap();
+
+        //The broadcast has to answer the handshake, so the spectator connects on a thread of its own
+        let connecting =
+            std::thread::spawn(move || Session::spectate(&format!("127.0.0.1:{port}")));
+        while !connecting.is_finished() {
+            broadcast.send(&simulation);
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        let mut session = connecting.join().unwrap().unwrap();
+        let mut watched = Simulation::new(3, 3, 0);
+        assert!(watch_until_shown(
+            &mut broadcast,
+            &simulation,
+            &mut session,
+            &mut watched
+        ));
+        assert!(watched.is_remote);
+
+        //Spectators see the board change, and their own edits are undone
+        simulation.step();
+        simulation.board[(5, 5)] = CellState::Alive;
+        watched.toggle_cell_state((0, 0));
+        assert!(watch_until_shown(
+            &mut broadcast,
+            &simulation,
+            &mut session,
+            &mut watched
+        ));
+        assert_eq!(1, broadcast.viewers.len());
+    }
+}
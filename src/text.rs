@@ -0,0 +1,55 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use macroquad::prelude::{Color, Font, TextDimensions};
+
+/// How much larger text is drawn with large text on, on top of the scale of the interface
+const LARGE_TEXT_SCALE: f32 = 1.4;
+
+/// How much larger than asked text is drawn, shared by everything that draws text. It's kept as the bits of an f32,
+/// as there are no atomic floats
+static TEXT_SCALE: AtomicU32 = AtomicU32::new(0x3f80_0000);
+
+/// Sets how large text is drawn, for every text drawn afterwards
+/// Input: the scale of the interface, in percent, and whether large text is on
+pub fn set_text_scale(ui_scale: usize, is_large: bool) {
+    let scale = ui_scale as f32 / 100. * if is_large { LARGE_TEXT_SCALE } else { 1. };
+    TEXT_SCALE.store(scale.to_bits(), Ordering::Relaxed);
+}
+
+/// Returns how much larger than asked text is drawn
+pub fn text_scale() -> f32 {
+    f32::from_bits(TEXT_SCALE.load(Ordering::Relaxed))
+}
+
+/// Draws text like macroquad does, scaled like the rest of the interface
+/// Input: the text, the screen position of the start of its baseline, its font size, and its colour
+/// Output: the size of the text drawn
+pub fn draw_text(text: &str, x: f32, y: f32, font_size: f32, color: Color) -> TextDimensions {
+    macroquad::text::draw_text(text, x, y, font_size * text_scale(), color)
+}
+
+/// Measures text like macroquad does, scaled like the rest of the interface, so it matches what draw_text draws
+/// Input: the text, its font, its font size, and the scale of the font
+/// Output: the size of the text
+pub fn measure_text(
+    text: &str,
+    font: Option<&Font>,
+    font_size: u16,
+    font_scale: f32,
+) -> TextDimensions {
+    macroquad::text::measure_text(text, font, font_size, font_scale * text_scale())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn large_text_adds_to_the_scale_of_the_interface() {
+        assert_eq!(1., text_scale());
+        set_text_scale(150, true);
+        assert_eq!(1.5 * LARGE_TEXT_SCALE, text_scale());
+        set_text_scale(100, false);
+        assert_eq!(1., text_scale());
+    }
+}
@@ -0,0 +1,166 @@
+use macroquad::prelude::*;
+
+use crate::text::{draw_text, measure_text};
+use crate::theme::Theme;
+
+/// Field to type a number into, with a cursor that can be moved around its digits
+#[derive(Clone, PartialEq, Debug)]
+pub struct NumberField {
+    digits: String,
+    /// Position of the cursor, as how many digits are before it
+    cursor: usize,
+    /// Most digits the number can have
+    max_digits: usize,
+}
+
+impl NumberField {
+    /// Creates a field with a number already typed in, and the cursor after it
+    /// Input: the number, and the most digits the field takes
+    pub fn new(value: usize, max_digits: usize) -> Self {
+        let digits = value.to_string();
+        NumberField {
+            cursor: digits.len(),
+            digits,
+            max_digits,
+        }
+    }
+
+    /// Returns the number typed in, with an empty field being 0
+    pub fn value(&self) -> usize {
+        self.digits.parse().unwrap_or(0)
+    }
+
+    /// Types a character at the cursor, as long as it's a digit and there's room for it
+    /// Output: whether the character was typed in
+    pub fn insert(&mut self, character: char) -> bool {
+        if !character.is_ascii_digit() || self.digits.len() >= self.max_digits {
+            return false;
+        }
+        self.digits.insert(self.cursor, character);
+        self.cursor += 1;
+        true
+    }
+
+    /// Erases the digit before the cursor
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.digits.remove(self.cursor);
+        }
+    }
+
+    /// Erases the digit after the cursor
+    pub fn delete(&mut self) {
+        if self.cursor < self.digits.len() {
+            self.digits.remove(self.cursor);
+        }
+    }
+
+    /// Moves the cursor, stopping at both ends of the number
+    /// Input: how many digits to move it by, to the right if positive and to the left if negative
+    pub fn move_cursor(&mut self, offset: isize) {
+        self.cursor = self
+            .cursor
+            .saturating_add_signed(offset)
+            .min(self.digits.len());
+    }
+
+    /// Handles the keys used to edit the number: digits, from the number row or the numpad, backspace, delete,
+    /// left and right, and home and end
+    /// Input: the characters typed on this frame, in order
+    pub fn handle_input(&mut self, typed: &[char]) {
+        for character in typed {
+            self.insert(*character);
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            self.backspace();
+        }
+        if is_key_pressed(KeyCode::Delete) {
+            self.delete();
+        }
+        if is_key_pressed(KeyCode::Left) {
+            self.move_cursor(-1);
+        }
+        if is_key_pressed(KeyCode::Right) {
+            self.move_cursor(1);
+        }
+        if is_key_pressed(KeyCode::Home) {
+            self.cursor = 0;
+        }
+        if is_key_pressed(KeyCode::End) {
+            self.cursor = self.digits.len();
+        }
+    }
+
+    /// Draws the number, highlighted and with the cursor if the field is the one being typed in
+    /// Input: the screen position of the start of the text, its font size, whether the field is selected, and the
+    /// theme to draw it with
+    pub fn draw(&self, (x, y): (f32, f32), font_size: f32, is_selected: bool, theme: &Theme) {
+        let size = measure_text(&self.digits, None, font_size as u16, 1.);
+        if is_selected {
+            draw_rectangle(
+                x - 2.,
+                y - font_size * 0.75,
+                size.width.max(font_size / 2.) + 4.,
+                font_size,
+                theme.highlight,
+            );
+            let cursor_x =
+                x + measure_text(&self.digits[..self.cursor], None, font_size as u16, 1.).width;
+            draw_line(
+                cursor_x,
+                y - font_size * 0.7,
+                cursor_x,
+                y + font_size * 0.2,
+                2.,
+                theme.text,
+            );
+        }
+        draw_text(&self.digits, x, y, font_size, theme.text);
+    }
+}
+
+/// Takes every character typed since the last frame, in the order they were typed
+pub fn typed_characters() -> Vec<char> {
+    let mut typed: Vec<_> = std::iter::from_fn(get_char_pressed).collect();
+    //Characters are taken starting from the last one typed
+    typed.reverse();
+    typed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits_are_typed_at_the_cursor() {
+        let mut field = NumberField::new(15, 4);
+        field.move_cursor(-1);
+        assert!(field.insert('2'));
+        assert_eq!(125, field.value());
+        assert!(!field.insert('x'));
+        assert!(field.insert('9'));
+        assert!(!field.insert('9'));
+        assert_eq!(1295, field.value());
+    }
+
+    #[test]
+    fn digits_are_erased_around_the_cursor() {
+        let mut field = NumberField::new(1234, 4);
+        field.move_cursor(-2);
+        field.backspace();
+        assert_eq!(134, field.value());
+        field.delete();
+        assert_eq!(14, field.value());
+        field.move_cursor(-10);
+        field.backspace();
+        assert_eq!(14, field.value());
+    }
+
+    #[test]
+    fn empty_fields_are_zero() {
+        let mut field = NumberField::new(7, 4);
+        field.backspace();
+        assert_eq!(0, field.value());
+    }
+}
@@ -0,0 +1,118 @@
+use macroquad::color::{BLACK, Color, DARKGRAY, LIGHTGRAY, WHITE, YELLOW};
+
+/// Colours the game is drawn with
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Theme {
+    pub name: &'static str,
+    /// Colour of alive cells
+    pub alive: Color,
+    /// Colour of dead cells
+    pub dead: Color,
+    /// Colour behind the board and the menu
+    pub background: Color,
+    /// Colour of the menu text
+    pub text: Color,
+    /// Colour of the labels and messages drawn over the board
+    pub label: Color,
+    /// Colour of the big messages drawn over the board, like the one shown while paused, which shouldn't hide it
+    pub faded_text: Color,
+    /// Colour of the lines between cells
+    pub grid: Color,
+    /// Colour behind the value being modified on the menu
+    pub highlight: Color,
+}
+
+/// Built-in themes, the first one being the default
+pub const THEMES: [Theme; 5] = [
+    Theme {
+        name: "Classic",
+        alive: BLACK,
+        dead: WHITE,
+        background: LIGHTGRAY,
+        text: BLACK,
+        label: DARKGRAY,
+        faded_text: LIGHTGRAY,
+        grid: Color::new(0.6, 0.6, 0.6, 0.5),
+        highlight: YELLOW,
+    },
+    Theme {
+        name: "Dark",
+        alive: Color::from_hex(0xe6e6e6),
+        dead: Color::from_hex(0x141419),
+        background: Color::from_hex(0x26262b),
+        text: Color::from_hex(0xe6e6e6),
+        label: Color::from_hex(0xb3b3b3),
+        faded_text: Color::from_hex(0x59595e),
+        grid: Color::new(0.4, 0.4, 0.4, 0.5),
+        highlight: Color::from_hex(0x8c7319),
+    },
+    Theme {
+        name: "Solarized dark",
+        alive: Color::from_hex(0xeee8d5),
+        dead: Color::from_hex(0x002b36),
+        background: Color::from_hex(0x073642),
+        text: Color::from_hex(0x93a1a1),
+        label: Color::from_hex(0x839496),
+        faded_text: Color::from_hex(0x586e75),
+        grid: Color::new(0.35, 0.43, 0.46, 0.5),
+        highlight: Color::from_hex(0xb58900),
+    },
+    Theme {
+        name: "Solarized light",
+        alive: Color::from_hex(0x073642),
+        dead: Color::from_hex(0xfdf6e3),
+        background: Color::from_hex(0xeee8d5),
+        text: Color::from_hex(0x586e75),
+        label: Color::from_hex(0x657b83),
+        faded_text: Color::from_hex(0x93a1a1),
+        grid: Color::new(0.58, 0.63, 0.63, 0.5),
+        highlight: Color::from_hex(0xb58900),
+    },
+    //Pure colours with as much contrast as there can be, for players with low vision
+    Theme {
+        name: "High contrast",
+        alive: YELLOW,
+        dead: BLACK,
+        background: BLACK,
+        text: WHITE,
+        label: WHITE,
+        faded_text: Color::from_hex(0x808080),
+        grid: Color::new(1., 1., 1., 0.6),
+        highlight: Color::from_hex(0x0000c0),
+    },
+];
+
+/// Index of the high contrast theme in THEMES, which the accessibility setting switches to
+pub const HIGH_CONTRAST_THEME: usize = 4;
+
+impl Theme {
+    /// Finds one of the built-in themes
+    /// Input: the name of the theme, ignoring case
+    /// Output: the index of the theme in THEMES, if there's one with that name
+    pub fn index_of(name: &str) -> Option<usize> {
+        THEMES
+            .iter()
+            .position(|theme| theme.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn themes_are_found_ignoring_case() {
+        assert_eq!(Some(0), Theme::index_of("classic"));
+        assert_eq!(Some(2), Theme::index_of("SOLARIZED DARK"));
+        assert_eq!(Some(HIGH_CONTRAST_THEME), Theme::index_of("high contrast"));
+        assert_eq!(None, Theme::index_of("Neon"));
+    }
+
+    #[test]
+    fn cells_stand_out_in_every_theme() {
+        for theme in THEMES {
+            assert_ne!(theme.alive, theme.dead);
+            assert_ne!(theme.text, theme.background);
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use macroquad::prelude::*;
+
+use crate::lessons::LESSONS;
+use crate::locale::{tr, tr_with};
+use crate::patterns::LIBRARY;
+use crate::theme::Theme;
+use crate::tutorial::draw_banner;
+use crate::{Board, CellState, Pattern, Point};
+
+/// Dead cells left around the pattern to build on every side of the board
+const MARGIN: usize = 3;
+
+/// Space left above the board for the banner with the clock, in pixels
+const BANNER_MARGIN: f32 = 100.;
+
+/// Pattern to build, from the library or the lessons
+pub struct Target {
+    /// Name the best times of the pattern are kept under, the same whatever the language
+    id: &'static str,
+    /// Name the pattern is shown with
+    name: String,
+    pattern: Pattern,
+}
+
+/// Returns every pattern that can be built: the ones of the library, then the ones of the lessons, such as the
+/// Gosper glider gun
+pub fn targets() -> Vec<Target> {
+    let library = LIBRARY.iter().map(|(name, cells)| Target {
+        id: name,
+        name: name.to_string(),
+        pattern: Pattern::parse_plaintext(cells).expect("library patterns are valid"),
+    });
+    let lessons = LESSONS.iter().map(|lesson| Target {
+        id: lesson.name,
+        name: tr(lesson.name).to_string(),
+        pattern: lesson.pattern(),
+    });
+    library.chain(lessons).collect()
+}
+
+/// A race to build a pattern cell by cell, timed from the first click until the board holds the pattern and nothing
+/// else
+pub struct TimeAttack {
+    /// Board the pattern is built on, with MARGIN cells around it
+    pub board: Board,
+    /// When the first cell was placed, in seconds
+    started_at: Option<f64>,
+    /// Seconds it took to build the pattern, once it's built
+    pub finish_time: Option<f64>,
+}
+
+impl TimeAttack {
+    /// Starts a race, on an empty board just big enough for the pattern
+    /// Input: the pattern to build
+    pub fn new(target: &Target) -> Self {
+        TimeAttack {
+            board: Board::new(
+                target.pattern.width + 2 * MARGIN,
+                target.pattern.height + 2 * MARGIN,
+            ),
+            started_at: None,
+            finish_time: None,
+        }
+    }
+
+    /// Places a cell, or removes one, starting the clock on the first one and stopping it once the pattern is built
+    /// Input: the cell, the pattern to build, and the time, in seconds
+    /// Output: whether the cell changed
+    pub fn toggle(&mut self, cell: Point, target: &Target, time: f64) -> bool {
+        if self.finish_time.is_some() {
+            return false;
+        }
+        let started_at = *self.started_at.get_or_insert(time);
+        self.board.toggle_cell_state(cell);
+        let pattern_population = target
+            .pattern
+            .cells
+            .iter()
+            .filter(|cell| **cell == CellState::Alive)
+            .count();
+        //Copies of the pattern with stray cells elsewhere on the board aren't finished yet
+        if self.board.population() == pattern_population
+            && self.board.find_pattern(&target.pattern).is_some()
+        {
+            self.finish_time = Some(time - started_at);
+        }
+        true
+    }
+
+    /// Returns the seconds since the first cell was placed, stopping once the pattern is built
+    /// Input: the time, in seconds
+    pub fn elapsed(&self, time: f64) -> f64 {
+        match (self.finish_time, self.started_at) {
+            (Some(finish_time), _) => finish_time,
+            (None, Some(started_at)) => time - started_at,
+            (None, None) => 0.,
+        }
+    }
+}
+
+/// Best time each pattern was built in, kept across sessions
+#[derive(Default, PartialEq, Debug)]
+pub struct BestTimes {
+    /// Milliseconds each pattern took, with the name of the pattern
+    times: Vec<(u64, String)>,
+}
+
+impl BestTimes {
+    /// Reads the best times file, with no times if it doesn't exist yet or can't be read. Lines that can't be read
+    /// are skipped
+    /// Input: the path of the file
+    pub fn load(path: &Path) -> Self {
+        let times = fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let (milliseconds, id) = line.trim().split_once(' ')?;
+                Some((milliseconds.parse().ok()?, id.to_string()))
+            })
+            .collect();
+        BestTimes { times }
+    }
+
+    /// Writes the best times file, creating its directory if needed
+    /// Input: the path of the file
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let text: String = self
+            .times
+            .iter()
+            .map(|(milliseconds, id)| format!("{milliseconds} {id}\n"))
+            .collect();
+        fs::write(path, text)
+    }
+
+    /// Returns the best time a pattern was built in, in seconds
+    pub fn best(&self, id: &str) -> Option<f64> {
+        self.times
+            .iter()
+            .find(|(_, timed_id)| timed_id == id)
+            .map(|(milliseconds, _)| *milliseconds as f64 / 1000.)
+    }
+
+    /// Keeps a time if it's the best one of its pattern
+    /// Input: the seconds the pattern took, and the name it's kept under
+    /// Output: whether it's the best time
+    pub fn add(&mut self, seconds: f64, id: &str) -> bool {
+        let milliseconds = (seconds * 1000.).round() as u64;
+        match self.times.iter_mut().find(|(_, timed_id)| timed_id == id) {
+            Some((best, _)) if *best <= milliseconds => false,
+            Some((best, _)) => {
+                *best = milliseconds;
+                true
+            }
+            None => {
+                self.times.push((milliseconds, id.to_string()));
+                true
+            }
+        }
+    }
+}
+
+/// Draws a board as big as fits in an area, centred in it
+/// Input: the board, the area, and the theme to draw with
+/// Output: the top left corner of the board and the size of its cells, in pixels
+fn draw_board(board: &Board, area: Rect, theme: &Theme) -> ((f32, f32), f32) {
+    let (width, length) = (board.width(), board.length());
+    let cell_size = (area.w / width as f32).min(area.h / length as f32);
+    let corner = (
+        area.x + (area.w - cell_size * width as f32) / 2.,
+        area.y + (area.h - cell_size * length as f32) / 2.,
+    );
+    draw_rectangle(
+        corner.0,
+        corner.1,
+        cell_size * width as f32,
+        cell_size * length as f32,
+        theme.dead,
+    );
+    for cell in board.live_cells() {
+        draw_rectangle(
+            corner.0 + cell.x as f32 * cell_size,
+            corner.1 + cell.y as f32 * cell_size,
+            cell_size,
+            cell_size,
+            theme.alive,
+        );
+    }
+    (corner, cell_size)
+}
+
+/// Runs time attacks on the window until Escape goes back to the starting menu. The player clicks on the board to
+/// build the pattern shown next to it, Left and Right pick another pattern, and Enter starts over
+/// Input: the theme to draw with, and the path of the best times file, if it's known
+/// Output: whether closing the window was asked for
+pub async fn run_time_attack(theme: &Theme, best_times_path: Option<PathBuf>) -> bool {
+    let mut best_times = best_times_path
+        .as_deref()
+        .map(BestTimes::load)
+        .unwrap_or_default();
+    let targets = targets();
+    let mut target_index = 0;
+    let mut time_attack = TimeAttack::new(&targets[0]);
+    //Whether the last pattern built beat the best time
+    let mut is_record = false;
+    loop {
+        if is_quit_requested() {
+            return true;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            return false;
+        }
+        let picked = if is_key_pressed(KeyCode::Right) {
+            Some((target_index + 1) % targets.len())
+        } else if is_key_pressed(KeyCode::Left) {
+            Some((target_index + targets.len() - 1) % targets.len())
+        } else {
+            is_key_pressed(KeyCode::Enter).then_some(target_index)
+        };
+        if let Some(index) = picked {
+            target_index = index;
+            time_attack = TimeAttack::new(&targets[target_index]);
+            is_record = false;
+        }
+        let target = &targets[target_index];
+
+        //The board takes two thirds of the window under the banner, and the pattern to build the rest
+        let board_area = Rect::new(
+            20.,
+            BANNER_MARGIN,
+            (screen_width() - 40.) * 2. / 3.,
+            screen_height() - BANNER_MARGIN - 50.,
+        );
+        let target_area = Rect::new(
+            board_area.right() + 20.,
+            BANNER_MARGIN + 30.,
+            screen_width() - board_area.right() - 40.,
+            board_area.h - 30.,
+        );
+        clear_background(theme.background);
+        let (corner, cell_size) = draw_board(&time_attack.board, board_area, theme);
+        let mut target_board = Board::new(target.pattern.width, target.pattern.height);
+        target_board.paste(&target.pattern, (0, 0));
+        draw_board(&target_board, target_area, theme);
+        draw_text(
+            &tr_with("time_attack.target", &[&target.name]),
+            target_area.x,
+            target_area.y - 10.,
+            20.,
+            theme.text,
+        );
+
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let (mouse_x, mouse_y) = mouse_position();
+            let (x, y) = (
+                ((mouse_x - corner.0) / cell_size).floor(),
+                ((mouse_y - corner.1) / cell_size).floor(),
+            );
+            if x >= 0.
+                && y >= 0.
+                && (x as usize) < time_attack.board.width()
+                && (y as usize) < time_attack.board.length()
+                && time_attack.toggle(Point::new(x as usize, y as usize), target, get_time())
+                && let Some(finish_time) = time_attack.finish_time
+            {
+                is_record = best_times.add(finish_time, target.id);
+                if let (true, Some(path)) = (is_record, &best_times_path)
+                    && let Err(error) = best_times.save(path)
+                {
+                    eprintln!("Couldn't save the best times: {error}");
+                }
+            }
+        }
+
+        let seconds = format!("{:.2}", time_attack.elapsed(get_time()));
+        let banner = match time_attack.finish_time {
+            None if time_attack.started_at.is_none() => {
+                tr_with("time_attack.waiting", &[&target.name])
+            }
+            None => tr_with("time_attack.building", &[&target.name, &seconds]),
+            Some(_) if is_record => tr_with("time_attack.record", &[&target.name, &seconds]),
+            Some(_) => tr_with("time_attack.built", &[&target.name, &seconds]),
+        };
+        draw_banner(&banner, theme);
+        if let Some(best) = best_times.best(target.id) {
+            draw_text(
+                &tr_with("time_attack.best", &[&format!("{best:.2}")]),
+                20.,
+                screen_height() - 30.,
+                18.,
+                theme.text,
+            );
+        }
+        draw_text(
+            tr("time_attack.hint"),
+            20.,
+            screen_height() - 10.,
+            18.,
+            theme.text,
+        );
+        next_frame().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_clock_stops_once_only_the_pattern_is_on_the_board() {
+        let targets = targets();
+        let glider = targets.iter().find(|target| target.id == "Glider").unwrap();
+        assert!(targets.iter().any(|target| target.id == "lesson.gun"));
+
+        let mut time_attack = TimeAttack::new(glider);
+        assert_eq!(3 + 2 * MARGIN, time_attack.board.width());
+        assert_eq!(0., time_attack.elapsed(5.));
+        //A stray cell, taken back at the end
+        time_attack.toggle(Point::new(0, 0), glider, 10.);
+        for (x, y) in [(4, 3), (5, 4), (3, 5), (4, 5), (5, 5)] {
+            time_attack.toggle(Point::new(x, y), glider, 11.);
+        }
+        assert_eq!(None, time_attack.finish_time);
+        assert_eq!(2., time_attack.elapsed(12.));
+        time_attack.toggle(Point::new(0, 0), glider, 13.5);
+        assert_eq!(Some(3.5), time_attack.finish_time);
+        assert!(!time_attack.toggle(Point::new(1, 1), glider, 14.));
+        assert_eq!(3.5, time_attack.elapsed(20.));
+
+        let mut best_times = BestTimes::default();
+        assert!(best_times.add(3.5, "Glider"));
+        assert!(!best_times.add(4., "Glider"));
+        assert!(best_times.add(2.25, "Glider"));
+        assert_eq!(Some(2.25), best_times.best("Glider"));
+        assert_eq!(None, best_times.best("Block"));
+
+        let path = std::env::temp_dir().join(format!("best_times_{}", std::process::id()));
+        best_times.save(&path).unwrap();
+        assert_eq!(best_times, BestTimes::load(&path));
+        fs::remove_file(path).unwrap();
+    }
+}
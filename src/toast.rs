@@ -0,0 +1,126 @@
+use std::collections::VecDeque;
+
+use macroquad::prelude::*;
+
+use crate::text::draw_text;
+use crate::theme::Theme;
+
+/// How long toasts are shown for, in seconds, including their fade out
+const TOAST_DURATION: f64 = 2.;
+
+/// How long toasts take to fade out at the end, in seconds
+const FADE_DURATION: f64 = 0.5;
+
+/// Most toasts shown at once. Past that, the oldest ones are dropped
+const MAX_TOASTS: usize = 5;
+
+/// Short message shown for a moment over the game
+#[derive(Clone, PartialEq, Debug)]
+struct Toast {
+    text: String,
+    /// What the message is about, so a newer message about the same thing replaces it
+    topic: Option<&'static str>,
+    /// Time the toast was pushed at
+    shown_at: f64,
+}
+
+/// Queue of toasts, stacked on top of each other with the newest one at the bottom
+#[derive(Default)]
+pub struct Toasts {
+    toasts: VecDeque<Toast>,
+}
+
+impl Toasts {
+    /// Shows a message for a moment
+    pub fn push(&mut self, text: impl Into<String>) {
+        self.push_at(text.into(), None, get_time());
+    }
+
+    /// Shows a message for a moment, replacing the one shown about the same topic, so changing something quickly
+    /// doesn't fill the screen with messages
+    /// Input: the topic, and the message
+    pub fn replace(&mut self, topic: &'static str, text: impl Into<String>) {
+        self.push_at(text.into(), Some(topic), get_time());
+    }
+
+    fn push_at(&mut self, text: String, topic: Option<&'static str>, time: f64) {
+        if topic.is_some() {
+            self.toasts.retain(|toast| toast.topic != topic);
+        }
+        self.toasts.push_back(Toast {
+            text,
+            topic,
+            shown_at: time,
+        });
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+    }
+
+    /// Drops the toasts that were shown for long enough
+    /// Input: the current time
+    fn expire(&mut self, time: f64) {
+        self.toasts
+            .retain(|toast| time < toast.shown_at + TOAST_DURATION);
+    }
+
+    /// Draws the toasts that are still shown, fading out the ones about to go away
+    /// Input: the screen position of the bottom left corner of the stack, the theme to draw them with, and whether
+    /// they fade out, instead of going away all at once
+    pub fn draw(&mut self, (x, y): (f32, f32), theme: &Theme, is_fading: bool) {
+        let time = get_time();
+        self.expire(time);
+        for (index, toast) in self.toasts.iter().rev().enumerate() {
+            let opacity = if is_fading {
+                opacity(toast.shown_at, time)
+            } else {
+                1.
+            };
+            let color = Color {
+                a: theme.label.a * opacity,
+                ..theme.label
+            };
+            draw_text(&toast.text, x, y - index as f32 * 34., 30., color);
+        }
+    }
+}
+
+/// Finds how visible a toast is, fully until it starts fading out
+/// Input: the time the toast was shown at, and the current time
+/// Output: the opacity, from 1 when fully visible to 0 when gone
+fn opacity(shown_at: f64, time: f64) -> f32 {
+    ((shown_at + TOAST_DURATION - time) / FADE_DURATION).clamp(0., 1.) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toasts_fade_out_and_expire() {
+        let mut toasts = Toasts::default();
+        toasts.push_at("Board saved".to_string(), None, 10.);
+        assert_eq!(1., opacity(10., 11.));
+        assert_eq!(0.5, opacity(10., 11.75));
+        toasts.expire(11.9);
+        assert_eq!(1, toasts.toasts.len());
+        toasts.expire(12.);
+        assert!(toasts.toasts.is_empty());
+    }
+
+    #[test]
+    fn toasts_about_the_same_topic_replace_each_other() {
+        let mut toasts = Toasts::default();
+        toasts.push_at("Board saved".to_string(), None, 0.);
+        toasts.push_at("Speed: 1".to_string(), Some("speed"), 0.);
+        toasts.push_at("Speed: 2".to_string(), Some("speed"), 0.);
+        let texts: Vec<_> = toasts.toasts.iter().map(|toast| &toast.text).collect();
+        assert_eq!(vec!["Board saved", "Speed: 2"], texts);
+
+        for index in 0..MAX_TOASTS {
+            toasts.push_at(index.to_string(), None, 0.);
+        }
+        assert_eq!(MAX_TOASTS, toasts.toasts.len());
+        assert_eq!("0", toasts.toasts[0].text);
+    }
+}
@@ -0,0 +1,200 @@
+use macroquad::input::{Touch, TouchPhase};
+use macroquad::math::Vec2;
+
+/// Farthest a finger can move before lifting it for the touch to still be a tap
+const TAP_DISTANCE: f32 = 10.;
+
+/// Longest distance between the points painted along a drag, so no cell is skipped when the finger moves fast
+const PAINT_STEP: f32 = 4.;
+
+/// What the fingers on a touchscreen did on a frame
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Gesture {
+    /// A finger touched the screen and was lifted without moving, at this position
+    Tap(Vec2),
+    /// A single finger is being dragged over this position
+    Paint(Vec2),
+    /// Two fingers moved closer or apart, changing the distance between them by the factor
+    Pinch { center: Vec2, factor: f32 },
+    /// Two fingers moved together, by this many pixels
+    Pan(Vec2),
+}
+
+/// Follows the fingers on a touchscreen across frames, turning what they do into gestures
+#[derive(Default)]
+pub struct Gestures {
+    /// Position of each finger on the screen on the previous frame
+    fingers: Vec<(u64, Vec2)>,
+    /// Where the first finger of the current touch landed
+    start: Option<Vec2>,
+    is_painting: bool,
+    /// Whether a second finger landed since the first one did, which stops the touch from being a tap or painting
+    is_multi_touch: bool,
+}
+
+impl Gestures {
+    /// Takes the touches of a frame
+    /// Input: the touches, as macroquad gives them
+    /// Output: the gestures they make, in order
+    pub fn update(&mut self, touches: &[Touch]) -> Vec<Gesture> {
+        let mut gestures = Vec::new();
+        let fingers: Vec<(u64, Vec2)> = touches
+            .iter()
+            .filter(|touch| !matches!(touch.phase, TouchPhase::Ended | TouchPhase::Cancelled))
+            .map(|touch| (touch.id, touch.position))
+            .collect();
+        let start = *self
+            .start
+            .get_or_insert_with(|| touches.first().map_or(Vec2::ZERO, |touch| touch.position));
+
+        if fingers.len() > 1 {
+            self.is_multi_touch = true;
+            self.is_painting = false;
+            let previous: Vec<Vec2> = fingers[..2]
+                .iter()
+                .filter_map(|(id, _)| self.position_of(*id))
+                .collect();
+            if let [previous_first, previous_second] = previous[..] {
+                let (first, second) = (fingers[0].1, fingers[1].1);
+                let previous_center = (previous_first + previous_second) / 2.;
+                let center = (first + second) / 2.;
+                gestures.push(Gesture::Pan(center - previous_center));
+                let previous_distance = previous_first.distance(previous_second);
+                if previous_distance > 0. {
+                    gestures.push(Gesture::Pinch {
+                        center,
+                        factor: first.distance(second) / previous_distance,
+                    });
+                }
+            }
+        } else if let [(id, position)] = fingers[..]
+            && !self.is_multi_touch
+        {
+            if self.is_painting {
+                let previous = self.position_of(id).unwrap_or(position);
+                gestures.extend(points_between(previous, position).map(Gesture::Paint));
+            } else if position.distance(start) > TAP_DISTANCE {
+                self.is_painting = true;
+                gestures.push(Gesture::Paint(start));
+                gestures.extend(points_between(start, position).map(Gesture::Paint));
+            }
+        }
+
+        //The touch is over once every finger is lifted
+        if fingers.is_empty() {
+            if let Some(lifted) = touches.first()
+                && !self.is_multi_touch
+                && !self.is_painting
+            {
+                gestures.push(Gesture::Tap(lifted.position));
+            }
+            self.start = None;
+            self.is_painting = false;
+            self.is_multi_touch = false;
+        }
+        self.fingers = fingers;
+        gestures
+    }
+
+    /// Returns whether a finger is being dragged to paint cells
+    pub fn is_painting(&self) -> bool {
+        self.is_painting
+    }
+
+    fn position_of(&self, id: u64) -> Option<Vec2> {
+        self.fingers
+            .iter()
+            .find(|(finger, _)| *finger == id)
+            .map(|(_, position)| *position)
+    }
+}
+
+/// Finds points evenly spread on the way from a point to another, PAINT_STEP apart at most
+/// Output: the points, including the last one but not the first
+fn points_between(from: Vec2, to: Vec2) -> impl Iterator<Item = Vec2> {
+    let steps = (from.distance(to) / PAINT_STEP).ceil().max(1.) as usize;
+    (1..=steps).map(move |step| from.lerp(to, step as f32 / steps as f32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macroquad::math::vec2;
+
+    fn touch(id: u64, phase: TouchPhase, (x, y): (f32, f32)) -> Touch {
+        Touch {
+            id,
+            phase,
+            position: vec2(x, y),
+        }
+    }
+
+    #[test]
+    fn lifting_a_finger_that_didnt_move_taps() {
+        let mut gestures = Gestures::default();
+        assert!(
+            gestures
+                .update(&[touch(0, TouchPhase::Started, (50., 50.))])
+                .is_empty()
+        );
+        assert!(
+            gestures
+                .update(&[touch(0, TouchPhase::Moved, (53., 52.))])
+                .is_empty()
+        );
+        assert_eq!(
+            vec![Gesture::Tap(vec2(53., 52.))],
+            gestures.update(&[touch(0, TouchPhase::Ended, (53., 52.))])
+        );
+        assert!(gestures.update(&[]).is_empty());
+    }
+
+    #[test]
+    fn dragging_a_finger_paints_along_the_way() {
+        let mut gestures = Gestures::default();
+        gestures.update(&[touch(0, TouchPhase::Started, (0., 0.))]);
+        let painted = gestures.update(&[touch(0, TouchPhase::Moved, (20., 0.))]);
+        assert_eq!(Some(&Gesture::Paint(vec2(0., 0.))), painted.first());
+        assert_eq!(Some(&Gesture::Paint(vec2(20., 0.))), painted.last());
+        assert_eq!(6, painted.len());
+        assert!(gestures.is_painting());
+        assert!(
+            gestures
+                .update(&[touch(0, TouchPhase::Ended, (20., 0.))])
+                .is_empty()
+        );
+        assert!(!gestures.is_painting());
+    }
+
+    #[test]
+    fn two_fingers_pan_and_pinch_without_tapping() {
+        let mut gestures = Gestures::default();
+        gestures.update(&[touch(0, TouchPhase::Started, (0., 0.))]);
+        gestures.update(&[
+            touch(0, TouchPhase::Stationary, (0., 0.)),
+            touch(1, TouchPhase::Started, (10., 0.)),
+        ]);
+        assert_eq!(
+            vec![
+                Gesture::Pan(vec2(5., 10.)),
+                Gesture::Pinch {
+                    center: vec2(10., 10.),
+                    factor: 2.
+                }
+            ],
+            gestures.update(&[
+                touch(0, TouchPhase::Moved, (0., 10.)),
+                touch(1, TouchPhase::Moved, (20., 10.)),
+            ])
+        );
+        gestures.update(&[
+            touch(1, TouchPhase::Ended, (20., 10.)),
+            touch(0, TouchPhase::Moved, (0., 10.)),
+        ]);
+        assert!(
+            gestures
+                .update(&[touch(0, TouchPhase::Ended, (0., 10.))])
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use macroquad::prelude::*;
+
+use crate::census::Census;
+use crate::keys::{Action, KeyBindings, key_name};
+use crate::locale::{tr, tr_with};
+use crate::simulation::Simulation;
+use crate::text::{draw_text, measure_text};
+use crate::theme::Theme;
+
+/// Steps of the tutorial, in order
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Step {
+    Play,
+    Pause,
+    ToggleCell,
+    BuildBlinker,
+    RunBlinker,
+    BuildGlider,
+    RunGlider,
+    ChangeSpeed,
+    Done,
+}
+
+impl Step {
+    /// Returns the instructions shown for the step
+    /// Input: the keys of the actions, which the instructions name
+    fn prompt(self, keys: &KeyBindings) -> String {
+        let pause = key_name(keys.key(Action::Pause));
+        match self {
+            Step::Play => tr_with("tutorial.play", &[&pause]),
+            Step::Pause => tr_with("tutorial.pause", &[&pause]),
+            Step::ToggleCell => tr("tutorial.toggle_cell").to_string(),
+            Step::BuildBlinker => tr("tutorial.build_blinker").to_string(),
+            Step::RunBlinker => tr_with("tutorial.run_blinker", &[&pause]),
+            Step::BuildGlider => tr("tutorial.build_glider").to_string(),
+            Step::RunGlider => tr_with("tutorial.run_glider", &[&pause]),
+            Step::ChangeSpeed => tr_with(
+                "tutorial.change_speed",
+                &[
+                    &key_name(keys.key(Action::Slower)),
+                    &key_name(keys.key(Action::Faster)),
+                ],
+            ),
+            Step::Done => tr_with("tutorial.done", &[&key_name(keys.key(Action::Tutorial))]),
+        }
+    }
+
+    /// Returns the step after this one
+    fn next(self) -> Self {
+        match self {
+            Step::Play => Step::Pause,
+            Step::Pause => Step::ToggleCell,
+            Step::ToggleCell => Step::BuildBlinker,
+            Step::BuildBlinker => Step::RunBlinker,
+            Step::RunBlinker => Step::BuildGlider,
+            Step::BuildGlider => Step::RunGlider,
+            Step::RunGlider => Step::ChangeSpeed,
+            Step::ChangeSpeed | Step::Done => Step::Done,
+        }
+    }
+}
+
+/// Guided tour of the game, that shows what to do next and moves on once it's done
+pub struct Tutorial {
+    step: Step,
+    /// Generation and speed of the board when the step started, to tell when it ran or its speed changed
+    step_generation: usize,
+    step_speed_index: usize,
+    /// Used to recognise the objects the user is asked to build
+    census: Census,
+    pub is_hidden: bool,
+}
+
+impl Tutorial {
+    /// Starts the tutorial on a board
+    pub fn new(simulation: &Simulation) -> Self {
+        Tutorial {
+            step: Step::Play,
+            step_generation: simulation.generation,
+            step_speed_index: simulation.speed_index,
+            census: Census::default(),
+            is_hidden: false,
+        }
+    }
+
+    /// Checks whether the current step was done on the board, moving on to the next one if it was
+    pub fn update(&mut self, simulation: &Simulation) {
+        let is_done = match self.step {
+            Step::Play => !simulation.is_paused,
+            Step::Pause => simulation.is_paused,
+            Step::RunBlinker => {
+                simulation.is_paused && simulation.generation >= self.step_generation + 2
+            }
+            Step::ToggleCell => simulation.board.population() > 0,
+            Step::BuildBlinker => {
+                self.census.update(&simulation.board);
+                simulation.is_paused && self.census.count("object.blinker") > 0
+            }
+            Step::BuildGlider => {
+                self.census.update(&simulation.board);
+                simulation.is_paused && self.census.count("object.glider") > 0
+            }
+            Step::RunGlider => simulation.generation >= self.step_generation + 8,
+            Step::ChangeSpeed => simulation.speed_index != self.step_speed_index,
+            Step::Done => false,
+        };
+        if is_done {
+            self.step = self.step.next();
+            self.step_generation = simulation.generation;
+            self.step_speed_index = simulation.speed_index;
+        }
+    }
+
+    /// Draws the instructions of the current step near the top of the window
+    /// Input: the keys of the actions, and the theme to draw them with
+    pub fn draw(&self, keys: &KeyBindings, theme: &Theme) {
+        if self.is_hidden {
+            return;
+        }
+        draw_banner(&self.step.prompt(keys), theme);
+    }
+}
+
+/// Draws a line of text in a box near the top of the window, centered, to guide the user
+/// Input: the text, and the theme to draw it with
+pub fn draw_banner(text: &str, theme: &Theme) {
+    let width = measure_text(text, None, 22, 1.).width + 30.;
+    let rect = Rect::new((screen_width() - width) / 2., 50., width, 36.);
+    draw_rectangle(
+        rect.x,
+        rect.y,
+        rect.w,
+        rect.h,
+        Color {
+            a: 0.9,
+            ..theme.background
+        },
+    );
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2., theme.highlight);
+    draw_text(text, rect.x + 15., rect.y + 24., 22., theme.text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_move_on_once_done() {
+        let mut simulation = Simulation::new(10, 10, 0);
+        let mut tutorial = Tutorial::new(&simulation);
+        tutorial.update(&simulation);
+        assert_eq!(Step::Play, tutorial.step);
+
+        simulation.is_paused = false;
+        tutorial.update(&simulation);
+        simulation.step();
+        simulation.step();
+        simulation.is_paused = true;
+        tutorial.update(&simulation);
+        assert_eq!(Step::ToggleCell, tutorial.step);
+
+        for x in 3..6 {
+            simulation.toggle_cell_state((x, 4));
+        }
+        tutorial.update(&simulation);
+        tutorial.update(&simulation);
+        assert_eq!(Step::RunBlinker, tutorial.step);
+    }
+
+    #[test]
+    fn gliders_are_recognised_in_any_orientation() {
+        let mut simulation = Simulation::new(10, 10, 0);
+        let mut tutorial = Tutorial::new(&simulation);
+        tutorial.step = Step::BuildGlider;
+        for (x, y) in [(4, 3), (5, 4), (3, 5), (4, 5), (5, 5)].map(|(x, y)| (9 - x, y)) {
+            simulation.toggle_cell_state((x, y));
+        }
+        tutorial.update(&simulation);
+        assert_eq!(Step::RunGlider, tutorial.step);
+    }
+}
@@ -0,0 +1,408 @@
+use std::cmp::Ordering;
+
+use macroquad::prelude::*;
+
+use crate::locale::{tr, tr_with};
+use crate::palette::Palette;
+use crate::simulation::SPEEDS;
+use crate::theme::Theme;
+use crate::tutorial::draw_banner;
+use crate::{Board, CellState, Point};
+
+/// Cells each player places before the board evolves
+const CELLS_PER_PLAYER: usize = 20;
+
+/// Generations the board evolves for before the cells of each player are counted
+const VERSUS_GENERATIONS: usize = 200;
+
+/// Space left above the board for the banner telling whose turn it is, in pixels
+const BANNER_MARGIN: f32 = 100.;
+
+/// Generations the computer looks ahead to score each cell it could place
+const LOOKAHEAD: usize = 6;
+
+/// Most cells the computer scores before placing one, as each one is simulated on a copy of the board
+const MAX_CANDIDATES: usize = 60;
+
+/// Seconds the computer waits before placing its cell, so the player sees the turn pass
+const COMPUTER_DELAY: f64 = 0.4;
+
+/// One of the two players of a versus game
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    /// Returns the player playing against this one
+    fn other(self) -> Self {
+        match self {
+            Player::One => Player::Two,
+            Player::Two => Player::One,
+        }
+    }
+
+    /// Returns the position of the player in arrays holding something for each player
+    fn index(self) -> usize {
+        match self {
+            Player::One => 0,
+            Player::Two => 1,
+        }
+    }
+}
+
+/// What a versus game is doing
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Phase {
+    /// A player is placing a cell
+    Placing(Player),
+    /// The board is evolving on its own
+    Evolving,
+    /// The board evolved for every generation, and the cells left are counted
+    Over,
+}
+
+/// A game for two players, each owning a colour, who take turns placing a cell each until both placed
+/// CELLS_PER_PLAYER. The board then evolves for VERSUS_GENERATIONS generations, with cells keeping their colour while
+/// they survive and being born with the colour most of the cells giving birth to them have. The player with the most
+/// cells left wins
+#[derive(Clone)]
+pub struct Versus {
+    pub board: Board,
+    /// Player each cell belongs to, row by row. Cells born from as many cells of each player belong to nobody
+    owners: Vec<Option<Player>>,
+    /// Cells each player placed
+    placed: [usize; 2],
+    pub phase: Phase,
+    pub generation: usize,
+}
+
+impl Versus {
+    /// Starts a game on a board, with player one placing the first cell
+    /// Input: the board, with the rule and edges it follows, which is emptied
+    pub fn new(mut board: Board) -> Self {
+        for cell in board.live_cells().collect::<Vec<_>>() {
+            board[cell] = CellState::Dead;
+        }
+        Versus {
+            owners: vec![None; board.width() * board.length()],
+            board,
+            placed: [0; 2],
+            phase: Phase::Placing(Player::One),
+            generation: 0,
+        }
+    }
+
+    /// Returns the player a cell belongs to, if it's alive and belongs to one
+    pub fn owner(&self, cell: Point) -> Option<Player> {
+        self.owners[cell.y * self.board.width() + cell.x]
+    }
+
+    /// Returns how many cells a player can still place
+    pub fn cells_left(&self, player: Player) -> usize {
+        CELLS_PER_PLAYER - self.placed[player.index()]
+    }
+
+    /// Places a cell of the player whose turn it is, passing the turn to the other player. Once both placed all their
+    /// cells, the board starts evolving
+    /// Input: the cell, which has to be dead
+    /// Output: whether the cell was placed
+    pub fn place(&mut self, cell: Point) -> bool {
+        let Phase::Placing(player) = self.phase else {
+            return false;
+        };
+        if self.board[cell] == CellState::Alive {
+            return false;
+        }
+        self.board[cell] = CellState::Alive;
+        self.owners[cell.y * self.board.width() + cell.x] = Some(player);
+        self.placed[player.index()] += 1;
+        self.phase = if self.cells_left(player.other()) > 0 {
+            Phase::Placing(player.other())
+        } else if self.cells_left(player) > 0 {
+            Phase::Placing(player)
+        } else {
+            Phase::Evolving
+        };
+        true
+    }
+
+    /// Advances the board one generation. Surviving cells keep their player, and cells born take the player most of
+    /// their alive neighbours belong to
+    pub fn step(&mut self) {
+        if self.phase != Phase::Evolving {
+            return;
+        }
+        self.evolve();
+        self.generation += 1;
+        if self.generation >= VERSUS_GENERATIONS || self.board.population() == 0 {
+            self.phase = Phase::Over;
+        }
+    }
+
+    /// Advances the board and the players of its cells one generation, whatever the phase
+    fn evolve(&mut self) {
+        let previous = self.board.clone();
+        self.board.update_board();
+        let offsets = previous.neighborhood.offsets();
+        let mut owners = vec![None; self.owners.len()];
+        for cell in self.board.live_cells() {
+            let index = cell.y * self.board.width() + cell.x;
+            if previous[cell] == CellState::Alive {
+                owners[index] = self.owners[index];
+                continue;
+            }
+            let mut votes = [0; 2];
+            for (x_offset, y_offset) in offsets {
+                if let Some(neighbour) = previous
+                    .wrap_coordinates(cell.x as isize + x_offset, cell.y as isize + y_offset)
+                    && previous[neighbour] == CellState::Alive
+                    && let Some(player) = self.owner(neighbour)
+                {
+                    votes[player.index()] += 1;
+                }
+            }
+            owners[index] = match votes[0].cmp(&votes[1]) {
+                Ordering::Greater => Some(Player::One),
+                Ordering::Less => Some(Player::Two),
+                Ordering::Equal => None,
+            };
+        }
+        self.owners = owners;
+    }
+
+    /// Counts the alive cells of each player, which is their score
+    pub fn scores(&self) -> [usize; 2] {
+        let mut scores = [0; 2];
+        for player in self.owners.iter().flatten() {
+            scores[player.index()] += 1;
+        }
+        scores
+    }
+
+    /// Picks the cell the computer places for a player. Each dead cell next to a live one is tried on a copy of the
+    /// board evolved LOOKAHEAD generations ahead, and the one leaving the most cells of the player wins, then the one
+    /// leaving the fewest cells of the other player. On an empty board, the centre is picked
+    /// Input: the player the computer places a cell for
+    /// Output: the cell, if there's a dead one
+    pub fn computer_move(&self, player: Player) -> Option<Point> {
+        let (width, length) = (self.board.width(), self.board.length());
+        let mut is_candidate = vec![false; width * length];
+        for cell in self.board.live_cells() {
+            for y in cell.y.saturating_sub(1)..(cell.y + 2).min(length) {
+                for x in cell.x.saturating_sub(1)..(cell.x + 2).min(width) {
+                    is_candidate[y * width + x] = self.board[Point::new(x, y)] == CellState::Dead;
+                }
+            }
+        }
+        let mut candidates: Vec<Point> = (0..width * length)
+            .filter(|index| is_candidate[*index])
+            .map(|index| Point::new(index % width, index / width))
+            .collect();
+        if candidates.is_empty() {
+            //With no live cells to play around, the centre is as good as anywhere, and otherwise any dead cell is
+            let centre = Point::new(width / 2, length / 2);
+            return std::iter::once(centre)
+                .chain((0..width * length).map(|index| Point::new(index % width, index / width)))
+                .find(|cell| self.board[*cell] == CellState::Dead);
+        }
+        //Crowded boards have too many cells to try them all, so they're tried evenly spread
+        let spacing = candidates.len().div_ceil(MAX_CANDIDATES);
+        candidates = candidates.into_iter().step_by(spacing).collect();
+        candidates.into_iter().max_by_key(|cell| {
+            let mut future = self.clone();
+            future.phase = Phase::Placing(player);
+            future.place(*cell);
+            for _ in 0..LOOKAHEAD {
+                future.evolve();
+            }
+            let scores = future.scores();
+            //Ties go to the first cell tried
+            (
+                scores[player.index()],
+                std::cmp::Reverse(scores[player.other().index()]),
+                std::cmp::Reverse(cell.y * width + cell.x),
+            )
+        })
+    }
+}
+
+/// Runs versus games on the window until Escape goes back to the starting menu. Players click on the board to place
+/// their cells, C switches player two between a person and the computer, and Enter starts a new game once one is over
+/// Input: the board games are played on, with the rule and edges they follow, the theme and palette to draw with,
+/// the latter giving the colours of the players, and the index of the speed the board evolves at, out of SPEEDS
+/// Output: whether closing the window was asked for
+pub async fn run_versus(
+    board: Board,
+    theme: &Theme,
+    palette: &Palette,
+    speed_index: usize,
+) -> bool {
+    //The highlights of the palette are see-through, and player cells are drawn solid
+    let colors = [palette.board_only, palette.snapshot_only].map(|color| Color { a: 1., ..color });
+    let mut versus = Versus::new(board.clone());
+    let mut last_update = get_time();
+    let mut is_computer_playing = false;
+    //When the last cell was placed, which the computer waits a while after
+    let mut last_placed = get_time();
+    loop {
+        if is_quit_requested() {
+            return true;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            return false;
+        }
+        if versus.phase == Phase::Over && is_key_pressed(KeyCode::Enter) {
+            versus = Versus::new(board.clone());
+        }
+        if is_key_pressed(KeyCode::C) {
+            is_computer_playing = !is_computer_playing;
+        }
+        let is_computer_turn = is_computer_playing && versus.phase == Phase::Placing(Player::Two);
+        if is_computer_turn
+            && get_time() - last_placed >= COMPUTER_DELAY
+            && let Some(cell) = versus.computer_move(Player::Two)
+        {
+            versus.place(cell);
+            last_placed = get_time();
+        }
+        if versus.phase == Phase::Evolving && get_time() - last_update >= SPEEDS[speed_index].1 {
+            last_update = get_time();
+            versus.step();
+        }
+
+        //The board is as big as fits under the banner, centred
+        let (width, length) = (versus.board.width(), versus.board.length());
+        let cell_size =
+            (screen_width() / width as f32).min((screen_height() - BANNER_MARGIN) / length as f32);
+        let corner = (
+            (screen_width() - cell_size * width as f32) / 2.,
+            BANNER_MARGIN + (screen_height() - BANNER_MARGIN - cell_size * length as f32) / 2.,
+        );
+        if is_mouse_button_pressed(MouseButton::Left) && !is_computer_turn {
+            let (mouse_x, mouse_y) = mouse_position();
+            let (x, y) = (
+                ((mouse_x - corner.0) / cell_size).floor(),
+                ((mouse_y - corner.1) / cell_size).floor(),
+            );
+            if x >= 0.
+                && y >= 0.
+                && (x as usize) < width
+                && (y as usize) < length
+                && versus.place(Point::new(x as usize, y as usize))
+            {
+                last_placed = get_time();
+            }
+        }
+
+        clear_background(theme.background);
+        draw_rectangle(
+            corner.0,
+            corner.1,
+            cell_size * width as f32,
+            cell_size * length as f32,
+            theme.dead,
+        );
+        for cell in versus.board.live_cells() {
+            let color = match versus.owner(cell) {
+                Some(player) => colors[player.index()],
+                None => theme.alive,
+            };
+            draw_rectangle(
+                corner.0 + cell.x as f32 * cell_size,
+                corner.1 + cell.y as f32 * cell_size,
+                cell_size,
+                cell_size,
+                color,
+            );
+        }
+        let [one, two] = versus.scores();
+        let banner = match versus.phase {
+            Phase::Placing(Player::Two) if is_computer_playing => tr_with(
+                "versus.computer_placing",
+                &[&versus.cells_left(Player::Two)],
+            ),
+            Phase::Placing(player) => tr_with(
+                "versus.placing",
+                &[&(player.index() + 1), &versus.cells_left(player)],
+            ),
+            Phase::Evolving => tr_with(
+                "versus.evolving",
+                &[&versus.generation, &VERSUS_GENERATIONS, &one, &two],
+            ),
+            Phase::Over if one == two => tr_with("versus.tie", &[&one, &two]),
+            Phase::Over => {
+                let winner = if one > two { 1 } else { 2 };
+                tr_with("versus.won", &[&winner, &one.max(two), &one.min(two)])
+            }
+        };
+        draw_banner(&banner, theme);
+        draw_text(
+            tr("versus.hint"),
+            20.,
+            screen_height() - 10.,
+            18.,
+            theme.text,
+        );
+        next_frame().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn players_take_turns_and_cells_are_born_with_the_most_common_colour() {
+        let mut versus = Versus::new(Board::new(10, 10));
+        assert!(versus.place(Point::new(1, 1)));
+        assert_eq!(Phase::Placing(Player::Two), versus.phase);
+        assert!(!versus.place(Point::new(1, 1)));
+        assert!(versus.place(Point::new(3, 3)));
+        assert_eq!(Some(Player::One), versus.owner(Point::new(1, 1)));
+        assert_eq!(Some(Player::Two), versus.owner(Point::new(3, 3)));
+        assert_eq!(CELLS_PER_PLAYER - 1, versus.cells_left(Player::Two));
+
+        //A blinker with player two in the middle: the cells born on its sides have two neighbours of player one
+        let mut versus = Versus::new(Board::new(5, 5));
+        for (x, y, player) in [
+            (2, 1, Player::One),
+            (2, 2, Player::Two),
+            (2, 3, Player::One),
+        ] {
+            versus.phase = Phase::Placing(player);
+            versus.place(Point::new(x, y));
+        }
+        versus.phase = Phase::Evolving;
+        versus.step();
+        assert_eq!(
+            ".....\n.....\n.###.\n.....\n.....",
+            versus.board.to_string()
+        );
+        assert_eq!(Some(Player::Two), versus.owner(Point::new(2, 2)));
+        assert_eq!(Some(Player::One), versus.owner(Point::new(1, 2)));
+        assert_eq!(Some(Player::One), versus.owner(Point::new(3, 2)));
+        assert_eq!([2, 1], versus.scores());
+    }
+
+    #[test]
+    fn the_computer_places_cells_that_keep_its_colour_alive() {
+        let mut versus = Versus::new(Board::new(12, 12));
+        assert_eq!(Some(Point::new(6, 6)), versus.computer_move(Player::One));
+
+        //Two cells die on their own, but a third one next to them can make a blinker or a block, which live on
+        for (x, y, player) in [(5, 5, Player::Two), (5, 6, Player::Two)] {
+            versus.phase = Phase::Placing(player);
+            versus.place(Point::new(x, y));
+        }
+        let cell = versus.computer_move(Player::Two).unwrap();
+        assert_eq!(CellState::Dead, versus.board[cell]);
+        versus.phase = Phase::Placing(Player::Two);
+        versus.place(cell);
+        for _ in 0..LOOKAHEAD {
+            versus.evolve();
+        }
+        assert!(versus.scores()[1] >= 3);
+    }
+}
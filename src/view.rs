@@ -0,0 +1,309 @@
+use macroquad::math::Rect;
+
+/// Closest the view can get to the board, as how many times bigger cells are drawn than with the whole board in view
+const MAX_ZOOM: f32 = 100.;
+
+/// How much each step of the mouse wheel zooms in or out
+pub const ZOOM_STEP: f32 = 1.2;
+
+/// How many pixels the view moves on each frame a panning key is held down
+pub const PAN_STEP: f32 = 10.;
+
+/// How long the view takes to get most of the way to where it's going after zooming or jumping, in seconds
+const EASING_TIME: f32 = 0.08;
+
+/// Share of the width or height of the area the alive cells take when framing them, leaving a margin around them
+const FRAMING_SHARE: f32 = 0.8;
+
+/// Part of the board that's shown on screen. Without zoom, the whole board fits the area it's drawn in
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct View {
+    /// How many times bigger cells are drawn than with the whole board in view
+    pub zoom: f32,
+    /// Position of the top left corner of the board, relative to the top left corner of the area it's drawn in
+    pub offset: (f32, f32),
+}
+
+/// Finds the biggest size with square cells at which a board fits in a part of the screen, centered in it
+/// Input: the width and height of the board, in cells, and the part of the screen it has to fit in
+/// Output: the area the board takes, and the size of its cells
+pub fn fit_board((board_width, board_height): (usize, usize), space: Rect) -> (Rect, f32) {
+    let cell_size = (space.w / board_width as f32).min(space.h / board_height as f32);
+    let width = board_width as f32 * cell_size;
+    let height = board_height as f32 * cell_size;
+    (
+        Rect::new(
+            space.x + (space.w - width) / 2.,
+            space.y + (space.h - height) / 2.,
+            width,
+            height,
+        ),
+        cell_size,
+    )
+}
+
+impl Default for View {
+    fn default() -> Self {
+        View {
+            zoom: 1.,
+            offset: (0., 0.),
+        }
+    }
+}
+
+impl View {
+    /// Returns the size cells are drawn at
+    /// Input: the size of the cells with the whole board in view
+    pub fn cell_size(&self, fit_cell_size: f32) -> f32 {
+        fit_cell_size * self.zoom
+    }
+
+    /// Zooms in or out, keeping the point of the board under the cursor in place
+    /// Input: how much to multiply the zoom by, and the cursor position relative to the area the board is drawn in
+    pub fn zoom_at(&mut self, factor: f32, (cursor_x, cursor_y): (f32, f32)) {
+        let zoom = (self.zoom * factor).clamp(1., MAX_ZOOM);
+        let scale = zoom / self.zoom;
+        self.offset = (
+            cursor_x - (cursor_x - self.offset.0) * scale,
+            cursor_y - (cursor_y - self.offset.1) * scale,
+        );
+        self.zoom = zoom;
+    }
+
+    /// Moves the view over the board
+    /// Input: how many pixels to move the board by on screen
+    pub fn pan(&mut self, (delta_x, delta_y): (f32, f32)) {
+        self.offset = (self.offset.0 + delta_x, self.offset.1 + delta_y);
+    }
+
+    /// Keeps the view within what makes sense to show after zooming or panning. The edges of bounded boards can't be
+    /// moved inside the area they're drawn in, while toroidal boards can be moved freely, as they repeat forever
+    /// Input: the size of the board with the whole of it in view, and whether its edges wrap around
+    pub fn constrain(&mut self, (board_width, board_height): (f32, f32), wraps_around: bool) {
+        let zoomed_width = board_width * self.zoom;
+        let zoomed_height = board_height * self.zoom;
+        self.offset = if wraps_around {
+            // Moving a whole board over shows the same cells, so the offset is kept small
+            (self.offset.0 % zoomed_width, self.offset.1 % zoomed_height)
+        } else {
+            (
+                self.offset.0.clamp(board_width - zoomed_width, 0.),
+                self.offset.1.clamp(board_height - zoomed_height, 0.),
+            )
+        };
+    }
+
+    /// Moves the view so a point of the board is in the middle of the area it's drawn in
+    /// Input: the point, in cells from the top left corner of the board, the size of the area, and the size of the
+    /// cells with the whole board in view
+    pub fn center_on(
+        &mut self,
+        (x, y): (f32, f32),
+        (area_width, area_height): (f32, f32),
+        fit_cell_size: f32,
+    ) {
+        let cell_size = self.cell_size(fit_cell_size);
+        self.offset = (
+            area_width / 2. - x * cell_size,
+            area_height / 2. - y * cell_size,
+        );
+    }
+
+    /// Zooms and moves the view so a part of the board fills most of the area it's drawn in, centered in it
+    /// Input: the position and size of the part, in cells from the top left corner of the board, the size of the area,
+    /// and the size of the cells with the whole board in view
+    pub fn frame(
+        &mut self,
+        (x, y, width, height): (f32, f32, f32, f32),
+        (area_width, area_height): (f32, f32),
+        fit_cell_size: f32,
+    ) {
+        let zoom =
+            (area_width / (width * fit_cell_size)).min(area_height / (height * fit_cell_size));
+        self.zoom = (zoom * FRAMING_SHARE).clamp(1., MAX_ZOOM);
+        self.center_on(
+            (x + width / 2., y + height / 2.),
+            (area_width, area_height),
+            fit_cell_size,
+        );
+    }
+
+    /// Moves the view part of the way to another one, slowing down as it gets closer, so zooming and jumping around
+    /// the board are animated instead of instant. Once close enough, it's snapped to the other view
+    /// Input: the view to go to, the time since the last frame, in seconds, the size of the board with the whole of it
+    /// in view, and whether its edges wrap around
+    pub fn ease_towards(
+        &mut self,
+        target: View,
+        elapsed: f32,
+        (board_width, board_height): (f32, f32),
+        wraps_around: bool,
+    ) {
+        let mut delta = (
+            target.offset.0 - self.offset.0,
+            target.offset.1 - self.offset.1,
+        );
+        // Toroidal boards repeat, so the view goes to the nearest copy of the target instead of across the board
+        if wraps_around {
+            let (period_x, period_y) = (board_width * target.zoom, board_height * target.zoom);
+            delta = (
+                delta.0 - period_x * (delta.0 / period_x).round(),
+                delta.1 - period_y * (delta.1 / period_y).round(),
+            );
+        }
+        if delta.0.abs() < 0.5 && delta.1.abs() < 0.5 && (target.zoom - self.zoom).abs() < 0.001 {
+            *self = target;
+            return;
+        }
+        let progress = 1. - (-elapsed / EASING_TIME).exp();
+        self.offset = (
+            self.offset.0 + delta.0 * progress,
+            self.offset.1 + delta.1 * progress,
+        );
+        self.zoom += (target.zoom - self.zoom) * progress;
+    }
+
+    /// Finds the part of the board that's in view
+    /// Input: the size of the area the board is drawn in, and the size of the cells with the whole board in view
+    /// Output: the position and size of the visible part, in cells from the top left corner of the board
+    pub fn visible_cells(
+        &self,
+        (area_width, area_height): (f32, f32),
+        fit_cell_size: f32,
+    ) -> (f32, f32, f32, f32) {
+        let cell_size = self.cell_size(fit_cell_size);
+        (
+            -self.offset.0 / cell_size,
+            -self.offset.1 / cell_size,
+            area_width / cell_size,
+            area_height / cell_size,
+        )
+    }
+
+    /// Finds the cell under a point of the screen
+    /// Input: the point, relative to the area the board is drawn in, and the size of the cells with the whole board in view
+    /// Output: the coordinates of the cell, which can be past the edges of the board
+    pub fn cell_at(&self, (x, y): (f32, f32), fit_cell_size: f32) -> (isize, isize) {
+        let cell_size = self.cell_size(fit_cell_size);
+        (
+            ((x - self.offset.0) / cell_size).floor() as isize,
+            ((y - self.offset.1) / cell_size).floor() as isize,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boards_are_centered_with_square_cells() {
+        let (area, cell_size) = fit_board((10, 5), Rect::new(100., 0., 200., 200.));
+        assert_eq!(20., cell_size);
+        assert_eq!(Rect::new(100., 50., 200., 100.), area);
+    }
+
+    #[test]
+    fn zooming_keeps_the_cell_under_the_cursor() {
+        let mut view = View::default();
+        let cursor = (35., 55.);
+        assert_eq!((3, 5), view.cell_at(cursor, 10.));
+
+        view.zoom_at(4., cursor);
+        assert_eq!(4., view.zoom);
+        assert_eq!((3, 5), view.cell_at(cursor, 10.));
+    }
+
+    #[test]
+    fn zooming_out_past_the_whole_board_is_not_allowed() {
+        let mut view = View::default();
+        view.zoom_at(2., (100., 100.));
+        view.zoom_at(0.25, (0., 0.));
+        view.constrain((100., 100.), false);
+        assert_eq!(View::default(), view);
+    }
+
+    #[test]
+    fn bounded_boards_are_kept_covering_their_area() {
+        let mut view = View::default();
+        view.zoom_at(2., (0., 0.));
+        view.pan((50., -500.));
+        view.constrain((100., 100.), false);
+        assert_eq!((0., -100.), view.offset);
+    }
+
+    #[test]
+    fn toroidal_boards_can_be_panned_freely() {
+        let mut view = View::default();
+        view.zoom_at(2., (0., 0.));
+        view.pan((50., -500.));
+        view.constrain((100., 100.), true);
+        assert_eq!((50., -100.), view.offset);
+    }
+
+    #[test]
+    fn centering_puts_the_point_in_the_middle_of_the_view() {
+        let mut view = View::default();
+        view.zoom_at(4., (0., 0.));
+        view.center_on((5., 5.), (100., 100.), 10.);
+        assert_eq!(
+            (3.75, 3.75, 2.5, 2.5),
+            view.visible_cells((100., 100.), 10.)
+        );
+    }
+
+    #[test]
+    fn framing_fits_the_part_with_a_margin() {
+        let mut view = View::default();
+        view.frame((10., 20., 5., 5.), (100., 100.), 2.);
+        assert_eq!(8., view.zoom);
+        assert_eq!((12.5, 22.5), {
+            let (x, y, width, height) = view.visible_cells((100., 100.), 2.);
+            (x + width / 2., y + height / 2.)
+        });
+    }
+
+    #[test]
+    fn easing_slows_down_and_lands_on_the_target() {
+        let mut view = View::default();
+        let target = View {
+            zoom: 3.,
+            offset: (-100., -50.),
+        };
+        view.ease_towards(target, EASING_TIME, (100., 100.), false);
+        assert!(view.zoom > 1. && view.zoom < 3.);
+        assert!(view.offset.0 < -50. && view.offset.0 > -100.);
+        for _ in 0..30 {
+            view.ease_towards(target, EASING_TIME, (100., 100.), false);
+        }
+        assert_eq!(target, view);
+    }
+
+    #[test]
+    fn easing_on_toroidal_boards_goes_the_short_way() {
+        let mut view = View {
+            zoom: 1.,
+            offset: (-95., 0.),
+        };
+        view.ease_towards(
+            View {
+                zoom: 1.,
+                offset: (3., 0.),
+            },
+            EASING_TIME,
+            (100., 100.),
+            true,
+        );
+        assert!(view.offset.0 < -95.);
+    }
+
+    #[test]
+    fn points_past_the_edges_have_negative_coordinates() {
+        let view = View {
+            zoom: 2.,
+            offset: (-10., 5.),
+        };
+        assert_eq!((1, -1), view.cell_at((20., 0.), 10.));
+        assert_eq!((1, 0), view.cell_at((20., 5.), 10.));
+    }
+}
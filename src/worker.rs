@@ -0,0 +1,42 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::StepStats;
+use crate::simulation::Snapshot;
+
+/// Computes generations on a dedicated thread, so heavy ones don't freeze drawing and input handling.
+/// Boards are double buffered: the game keeps drawing the current generation while the worker computes the next one
+/// from a copy, which replaces the current one once it's done
+pub struct StepWorker {
+    jobs: Sender<(u64, Snapshot)>,
+    results: Receiver<(u64, Snapshot, StepStats)>,
+}
+
+impl StepWorker {
+    /// Starts the thread. It finishes on its own once the worker is dropped
+    pub fn spawn() -> Self {
+        let (jobs, job_receiver) = mpsc::channel::<(u64, Snapshot)>();
+        let (result_sender, results) = mpsc::channel();
+        thread::spawn(move || {
+            for (id, boards) in job_receiver {
+                let (boards, step_stats) = boards.stepped();
+                if result_sender.send((id, boards, step_stats)).is_err() {
+                    break;
+                }
+            }
+        });
+        StepWorker { jobs, results }
+    }
+
+    /// Asks for the next generation of some boards
+    /// Input: an identifier for the request, which comes back along with the result, and the boards to update
+    /// Output: whether the thread is still running to compute it
+    pub fn send(&self, id: u64, boards: Snapshot) -> bool {
+        self.jobs.send((id, boards)).is_ok()
+    }
+
+    /// Returns the generations computed since the last call, without waiting for the ones still being computed
+    pub fn try_receive(&self) -> impl Iterator<Item = (u64, Snapshot, StepStats)> + '_ {
+        self.results.try_iter()
+    }
+}